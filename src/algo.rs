@@ -0,0 +1,664 @@
+// src/algo.rs - Graph algorithms written once over any adjacency storage
+use crate::density::DensityStrategy;
+use crate::error::GraphError;
+use crate::shortest_path::SearchStats;
+use crate::Graph;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// Distances from a source and the predecessor of every vertex on its shortest path
+pub type SearchTree<T> = (Vec<Option<T>>, Vec<Option<usize>>);
+
+/// Read-only access to a graph with vertices numbered 0..vertex_count()
+///
+/// Every algorithm in this module is generic over this trait, so a new storage
+/// strategy only has to implement these methods to get all of them.
+pub trait GraphRef {
+    /// Iterator over the indices of the vertices adjacent to a vertex
+    type Neighbors<'a>: Iterator<Item = usize> + 'a
+    where
+        Self: 'a;
+
+    /// Returns the number of vertices
+    fn vertex_count(&self) -> usize;
+
+    /// Returns the label of the vertex at an index
+    fn label(&self, idx: usize) -> &str;
+
+    /// Returns the index of the vertex with a label, if there is one
+    fn index_of(&self, label: &str) -> Option<usize>;
+
+    /// Iterates over the vertices an edge leads to from idx, in no particular order
+    fn neighbors(&self, idx: usize) -> Self::Neighbors<'_>;
+
+    /// Checks whether there is an edge from i to j
+    fn has_edge(&self, i: usize, j: usize) -> bool;
+
+    /// Returns the weight of the edge from i to j; storage without weights reports 1.0
+    fn weight(&self, _i: usize, _j: usize) -> f64 {
+        1.0
+    }
+}
+
+/// Neighbors of a vertex read off one row of an adjacency matrix
+pub struct MatrixNeighbors<'a> {
+    row: &'a [i32],
+    pos: usize,
+}
+
+impl Iterator for MatrixNeighbors<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.pos < self.row.len() {
+            let idx = self.pos;
+            self.pos += 1;
+            if self.row[idx] == 1 {
+                return Some(idx);
+            }
+        }
+        None
+    }
+}
+
+impl GraphRef for Graph {
+    type Neighbors<'a> = MatrixNeighbors<'a>;
+
+    fn vertex_count(&self) -> usize {
+        self.n_vertices
+    }
+
+    fn label(&self, idx: usize) -> &str {
+        &self.vertices[idx]
+    }
+
+    fn index_of(&self, label: &str) -> Option<usize> {
+        self.find_vertex_idx(label)
+    }
+
+    fn neighbors(&self, idx: usize) -> MatrixNeighbors<'_> {
+        MatrixNeighbors {
+            row: &self.adj_matrix[idx][..self.n_vertices],
+            pos: 0,
+        }
+    }
+
+    fn has_edge(&self, i: usize, j: usize) -> bool {
+        self.adj_matrix[i][j] == 1
+    }
+
+    fn weight(&self, i: usize, j: usize) -> f64 {
+        self.weight_at(i, j)
+    }
+}
+
+/// Returns the vertex indices in alphabetical order of their labels
+pub fn sorted_vertices<G: GraphRef>(g: &G) -> Vec<usize> {
+    let mut idx: Vec<usize> = (0..g.vertex_count()).collect();
+    // Stable, so equal labels keep their input order
+    idx.sort_by(|&a, &b| g.label(a).cmp(g.label(b)));
+    idx
+}
+
+/// Returns the position of every vertex in alphabetical order, indexed by vertex index
+pub fn alphabetical_ranks<G: GraphRef>(g: &G) -> Vec<usize> {
+    let mut ranks = vec![0; g.vertex_count()];
+    for (rank, idx) in sorted_vertices(g).into_iter().enumerate() {
+        ranks[idx] = rank;
+    }
+    ranks
+}
+
+/// Returns the neighbors of a vertex in alphabetical order of their labels
+pub fn sorted_neighbors<G: GraphRef>(g: &G, idx: usize) -> Vec<usize> {
+    let mut neighbors: Vec<usize> = g.neighbors(idx).collect();
+    neighbors.sort_by(|&a, &b| g.label(a).cmp(g.label(b)));
+    neighbors
+}
+
+/// Follows a predecessor array back from target and returns the indices from the source to target
+pub fn reconstruct_path(pred: &[Option<usize>], target: usize) -> Vec<usize> {
+    let mut path = vec![target];
+    let mut current = target;
+    while let Some(previous) = pred[current] {
+        path.push(previous);
+        current = previous;
+    }
+    path.reverse();
+    path
+}
+
+/// Computes hop distances and the BFS tree from a start, visiting neighbors alphabetically
+pub fn bfs_tree<G: GraphRef>(g: &G, start: usize) -> SearchTree<usize> {
+    let mut dist = vec![None; g.vertex_count()];
+    let mut pred = vec![None; g.vertex_count()];
+    let mut queue = VecDeque::new();
+
+    dist[start] = Some(0);
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        let next_dist = dist[current].map(|d| d + 1);
+        for neighbor in sorted_neighbors(g, current) {
+            if dist[neighbor].is_none() {
+                dist[neighbor] = next_dist;
+                pred[neighbor] = Some(current);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    (dist, pred)
+}
+
+/// Returns the vertices in the order a breadth-first search from start visits them,
+/// taking neighbors alphabetically
+pub fn bfs<G: GraphRef>(g: &G, start: usize) -> Vec<usize> {
+    let mut visited = vec![false; g.vertex_count()];
+    let mut queue = VecDeque::from([start]);
+    let mut order = Vec::new();
+    visited[start] = true;
+
+    while let Some(current) = queue.pop_front() {
+        order.push(current);
+        for neighbor in sorted_neighbors(g, current) {
+            if !visited[neighbor] {
+                visited[neighbor] = true;
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    order
+}
+
+/// Returns the same order as bfs by scanning the vertices not visited yet for the
+/// ones current has an edge to, rather than sorting the neighbors of current
+///
+/// Every vertex scanned either leaves the list or is one current has no edge to,
+/// so the search takes time proportional to the vertices plus the missing edges:
+/// fast on dense graphs, where the complement is sparse.
+pub fn bfs_by_complement<G: GraphRef>(g: &G, start: usize) -> Vec<usize> {
+    let mut unvisited: Vec<usize> = sorted_vertices(g).into_iter().filter(|&v| v != start).collect();
+    let mut queue = VecDeque::from([start]);
+    let mut order = Vec::new();
+
+    while let Some(current) = queue.pop_front() {
+        order.push(current);
+        unvisited.retain(|&v| {
+            let reached = g.has_edge(current, v);
+            if reached {
+                queue.push_back(v);
+            }
+            !reached
+        });
+    }
+
+    order
+}
+
+/// Runs bfs or bfs_by_complement, which return the same order
+pub fn bfs_with<G: GraphRef>(g: &G, start: usize, strategy: DensityStrategy) -> Vec<usize> {
+    match strategy {
+        DensityStrategy::Direct => bfs(g, start),
+        DensityStrategy::Complement => bfs_by_complement(g, start),
+    }
+}
+
+/// Appends the vertices in the order a depth-first search from start visits them,
+/// taking neighbors alphabetically and skipping vertices already marked as visited
+pub fn dfs<G: GraphRef>(g: &G, start: usize, visited: &mut [bool], order: &mut Vec<usize>) {
+    visited[start] = true;
+    order.push(start);
+
+    for neighbor in sorted_neighbors(g, start) {
+        if !visited[neighbor] {
+            dfs(g, neighbor, visited, order);
+        }
+    }
+}
+
+/// Assigns every vertex the number of its connected component, following edges in
+/// either direction; components are numbered in order of their smallest label
+pub fn component_ids<G: GraphRef>(g: &G) -> Vec<usize> {
+    let n = g.vertex_count();
+    let mut ids = vec![usize::MAX; n];
+    let mut n_components = 0;
+
+    for start in sorted_vertices(g) {
+        if ids[start] != usize::MAX {
+            continue;
+        }
+
+        ids[start] = n_components;
+        let mut queue = VecDeque::from([start]);
+        while let Some(current) = queue.pop_front() {
+            for (i, id) in ids.iter_mut().enumerate() {
+                let linked = g.has_edge(current, i) || g.has_edge(i, current);
+                if linked && *id == usize::MAX {
+                    *id = n_components;
+                    queue.push_back(i);
+                }
+            }
+        }
+        n_components += 1;
+    }
+
+    ids
+}
+
+/// Returns the same numbering as component_ids by scanning the vertices not reached
+/// yet, as bfs_by_complement does, in time proportional to the vertices plus the
+/// pairs joined in neither direction
+pub fn component_ids_by_complement<G: GraphRef>(g: &G) -> Vec<usize> {
+    let mut ids = vec![usize::MAX; g.vertex_count()];
+    let mut unreached = VecDeque::from(sorted_vertices(g));
+    let mut n_components = 0;
+
+    while let Some(start) = unreached.pop_front() {
+        ids[start] = n_components;
+        let mut queue = VecDeque::from([start]);
+        while let Some(current) = queue.pop_front() {
+            unreached.retain(|&v| {
+                let linked = g.has_edge(current, v) || g.has_edge(v, current);
+                if linked {
+                    ids[v] = n_components;
+                    queue.push_back(v);
+                }
+                !linked
+            });
+        }
+        n_components += 1;
+    }
+
+    ids
+}
+
+/// Runs component_ids or component_ids_by_complement, which return the same numbering
+pub fn component_ids_with<G: GraphRef>(g: &G, strategy: DensityStrategy) -> Vec<usize> {
+    match strategy {
+        DensityStrategy::Direct => component_ids(g),
+        DensityStrategy::Complement => component_ids_by_complement(g),
+    }
+}
+
+/// Checks that a start index names a vertex of the graph
+fn check_start<G: GraphRef>(g: &G, start: usize) -> Result<(), GraphError> {
+    if start < g.vertex_count() {
+        Ok(())
+    } else {
+        Err(GraphError::InvalidParameter {
+            name: "start",
+            reason: format!("index {} is out of range for {} vertices", start, g.vertex_count()),
+        })
+    }
+}
+
+/// Checks every edge weight against a predicate and reports the first edge that fails it
+pub fn validate_weights<G: GraphRef>(
+    g: &G,
+    is_valid: impl Fn(f64) -> bool,
+    reason: &'static str,
+) -> Result<(), GraphError> {
+    for i in 0..g.vertex_count() {
+        for j in g.neighbors(i) {
+            if !is_valid(g.weight(i, j)) {
+                return Err(GraphError::InvalidWeight {
+                    from: g.label(i).to_string(),
+                    to: g.label(j).to_string(),
+                    weight: g.weight(i, j),
+                    reason,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs 0/1 BFS from a start and returns the distances and predecessors
+/// A start that is not a vertex index is an InvalidParameter error
+pub fn zero_one_bfs_tree<G: GraphRef>(g: &G, start: usize) -> Result<SearchTree<u64>, GraphError> {
+    check_start(g, start)?;
+    validate_weights(g, |w| w == 0.0 || w == 1.0, "0-1 BFS only accepts weights 0 and 1")?;
+
+    let mut dist: Vec<Option<u64>> = vec![None; g.vertex_count()];
+    let mut pred = vec![None; g.vertex_count()];
+    let mut deque = VecDeque::new();
+
+    dist[start] = Some(0);
+    deque.push_back(start);
+
+    while let Some(current) = deque.pop_front() {
+        let current_dist = dist[current].unwrap_or(0);
+        for neighbor in sorted_neighbors(g, current) {
+            let weight = g.weight(current, neighbor) as u64;
+            let candidate = current_dist + weight;
+            // Only strict improvements replace a predecessor, so earlier
+            // (alphabetically smaller) discoveries win ties
+            if !matches!(dist[neighbor], Some(d) if d <= candidate) {
+                dist[neighbor] = Some(candidate);
+                pred[neighbor] = Some(current);
+                // Zero-weight edges keep the vertex on the current level
+                if weight == 0 {
+                    deque.push_front(neighbor);
+                } else {
+                    deque.push_back(neighbor);
+                }
+            }
+        }
+    }
+
+    Ok((dist, pred))
+}
+
+/// Priority queue entry ordered so that BinaryHeap pops the cheapest,
+/// then alphabetically smallest, vertex first
+struct QueueEntry {
+    /// Priority of the entry: the distance plus any heuristic estimate
+    cost: f64,
+    distance: f64,
+    rank: usize,
+    idx: usize,
+}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueueEntry {}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.rank.cmp(&self.rank))
+    }
+}
+
+/// Runs a best-first search guided by a heuristic estimate of the remaining cost
+/// With a zero heuristic this is uniform-cost search (Dijkstra's algorithm)
+/// Returns the cost and the indices along the path, or None if target is unreachable
+pub fn best_first_search<G: GraphRef>(
+    g: &G,
+    start: usize,
+    target: usize,
+    heuristic: impl Fn(usize) -> f64,
+) -> (Option<(f64, Vec<usize>)>, SearchStats) {
+    let ranks = alphabetical_ranks(g);
+    let mut dist: Vec<Option<f64>> = vec![None; g.vertex_count()];
+    let mut pred = vec![None; g.vertex_count()];
+    let mut heap = BinaryHeap::new();
+    let mut stats = SearchStats::default();
+
+    dist[start] = Some(0.0);
+    heap.push(QueueEntry {
+        cost: heuristic(start),
+        distance: 0.0,
+        rank: ranks[start],
+        idx: start,
+    });
+
+    while let Some(QueueEntry { distance, idx, .. }) = heap.pop() {
+        // Skip entries superseded by a cheaper path found later
+        if matches!(dist[idx], Some(d) if d < distance) {
+            continue;
+        }
+
+        if idx == target {
+            return (Some((distance, reconstruct_path(&pred, target))), stats);
+        }
+        stats.expansions += 1;
+
+        for neighbor in sorted_neighbors(g, idx) {
+            let candidate = distance + g.weight(idx, neighbor);
+            if !matches!(dist[neighbor], Some(d) if d <= candidate) {
+                dist[neighbor] = Some(candidate);
+                pred[neighbor] = Some(idx);
+                heap.push(QueueEntry {
+                    cost: candidate + heuristic(neighbor),
+                    distance: candidate,
+                    rank: ranks[neighbor],
+                    idx: neighbor,
+                });
+            }
+        }
+    }
+
+    (None, stats)
+}
+
+/// Runs Dijkstra's algorithm from a start and returns the distances and predecessors
+/// of every vertex; weights must be nonnegative
+///
+/// Vertices of equal distance leave the queue alphabetically and a predecessor is only
+/// replaced by a strictly shorter path, so ties resolve the same way on every run.
+pub fn dijkstra_tree<G: GraphRef>(g: &G, start: usize) -> SearchTree<f64> {
+    let ranks = alphabetical_ranks(g);
+    let mut dist: Vec<Option<f64>> = vec![None; g.vertex_count()];
+    let mut pred = vec![None; g.vertex_count()];
+    let mut done = vec![false; g.vertex_count()];
+    let mut heap = BinaryHeap::new();
+
+    dist[start] = Some(0.0);
+    heap.push(QueueEntry {
+        cost: 0.0,
+        distance: 0.0,
+        rank: ranks[start],
+        idx: start,
+    });
+
+    while let Some(QueueEntry { distance, idx, .. }) = heap.pop() {
+        if std::mem::replace(&mut done[idx], true) {
+            continue;
+        }
+        for neighbor in sorted_neighbors(g, idx) {
+            let candidate = distance + g.weight(idx, neighbor);
+            if !done[neighbor] && !matches!(dist[neighbor], Some(d) if d <= candidate) {
+                dist[neighbor] = Some(candidate);
+                pred[neighbor] = Some(idx);
+                heap.push(QueueEntry {
+                    cost: candidate,
+                    distance: candidate,
+                    rank: ranks[neighbor],
+                    idx: neighbor,
+                });
+            }
+        }
+    }
+
+    (dist, pred)
+}
+
+/// Runs Bellman-Ford from a start and returns the distances and predecessors,
+/// or the negative cycle reachable from the start
+/// A start that is not a vertex index is an InvalidParameter error
+pub fn bellman_ford_tree<G: GraphRef>(g: &G, start: usize) -> Result<SearchTree<f64>, GraphError> {
+    check_start(g, start)?;
+    let n = g.vertex_count();
+    let order = sorted_vertices(g);
+    let mut dist: Vec<Option<f64>> = vec![None; n];
+    let mut pred = vec![None; n];
+    dist[start] = Some(0.0);
+
+    // Relax every edge n-1 times, in alphabetical order so predecessors are deterministic
+    for _ in 1..n {
+        let mut changed = false;
+        for &u in &order {
+            let Some(du) = dist[u] else { continue };
+            for v in sorted_neighbors(g, u) {
+                let candidate = du + g.weight(u, v);
+                if !matches!(dist[v], Some(d) if d <= candidate) {
+                    dist[v] = Some(candidate);
+                    pred[v] = Some(u);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    // Any edge that can still be relaxed leads back into a negative cycle
+    for &u in &order {
+        let Some(du) = dist[u] else { continue };
+        for v in sorted_neighbors(g, u) {
+            if matches!(dist[v], Some(d) if du + g.weight(u, v) < d) {
+                pred[v] = Some(u);
+                return Err(GraphError::NegativeCycle {
+                    cycle: extract_cycle(g, &pred, v),
+                });
+            }
+        }
+    }
+
+    Ok((dist, pred))
+}
+
+/// Walks predecessors from a vertex known to lead into a cycle and returns the cycle's labels
+fn extract_cycle<G: GraphRef>(g: &G, pred: &[Option<usize>], from: usize) -> Vec<String> {
+    // After n steps back the walk is guaranteed to be inside the cycle
+    let mut inside = from;
+    for _ in 0..g.vertex_count() {
+        inside = pred[inside].unwrap_or(inside);
+    }
+
+    let mut cycle = vec![inside];
+    let mut current = pred[inside].unwrap_or(inside);
+    while current != inside {
+        cycle.push(current);
+        current = pred[current].unwrap_or(inside);
+    }
+    cycle.reverse();
+    cycle.into_iter().map(|i| g.label(i).to_string()).collect()
+}
+
+/// Checks that every vertex label of h also appears in g
+pub fn all_vertices_exist<G: GraphRef, H: GraphRef>(g: &G, h: &H) -> bool {
+    (0..h.vertex_count()).all(|i| g.index_of(h.label(i)).is_some())
+}
+
+/// Checks that every edge of h joins two labels that are joined by an edge in g
+pub fn all_edges_exist<G: GraphRef, H: GraphRef>(g: &G, h: &H) -> bool {
+    (0..h.vertex_count()).all(|i| {
+        h.neighbors(i).all(|j| {
+            match (g.index_of(h.label(i)), g.index_of(h.label(j))) {
+                (Some(gi), Some(gj)) => g.has_edge(gi, gj),
+                _ => false,
+            }
+        })
+    })
+}
+
+/// Determines whether h is a subgraph of g, matching vertices by label
+pub fn is_subgraph<G: GraphRef, H: GraphRef>(g: &G, h: &H) -> bool {
+    all_vertices_exist(g, h) && all_edges_exist(g, h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Adjacency lists whose vertices and neighbors come in no alphabetical order,
+    /// so the algorithms only see the GraphRef methods and must sort for themselves
+    struct AdjacencyLists {
+        labels: Vec<&'static str>,
+        lists: Vec<Vec<(usize, f64)>>,
+    }
+
+    impl AdjacencyLists {
+        fn new(labels: &[&'static str], edges: &[(&str, &str, f64)]) -> Self {
+            let mut graph = AdjacencyLists {
+                labels: labels.to_vec(),
+                lists: vec![Vec::new(); labels.len()],
+            };
+            for &(u, v, w) in edges {
+                let (i, j) = (graph.index_of(u).unwrap(), graph.index_of(v).unwrap());
+                graph.lists[i].insert(0, (j, w));
+                graph.lists[j].insert(0, (i, w));
+            }
+            graph
+        }
+    }
+
+    impl GraphRef for AdjacencyLists {
+        type Neighbors<'a> = std::iter::Map<std::slice::Iter<'a, (usize, f64)>, fn(&(usize, f64)) -> usize>;
+
+        fn vertex_count(&self) -> usize {
+            self.labels.len()
+        }
+
+        fn label(&self, idx: usize) -> &str {
+            self.labels[idx]
+        }
+
+        fn index_of(&self, label: &str) -> Option<usize> {
+            self.labels.iter().position(|&l| l == label)
+        }
+
+        fn neighbors(&self, idx: usize) -> Self::Neighbors<'_> {
+            self.lists[idx].iter().map(|&(j, _)| j)
+        }
+
+        fn has_edge(&self, i: usize, j: usize) -> bool {
+            self.lists[i].iter().any(|&(k, _)| k == j)
+        }
+
+        fn weight(&self, i: usize, j: usize) -> f64 {
+            self.lists[i].iter().find(|&&(k, _)| k == j).map_or(1.0, |&(_, w)| w)
+        }
+    }
+
+    fn kite() -> AdjacencyLists {
+        AdjacencyLists::new(
+            &["D", "B", "A", "C", "E"],
+            &[("A", "B", 2.0), ("B", "C", 1.0), ("A", "C", 5.0), ("C", "D", 1.0)],
+        )
+    }
+
+    fn labels(g: &AdjacencyLists, order: &[usize]) -> Vec<&'static str> {
+        order.iter().map(|&i| g.labels[i]).collect()
+    }
+
+    #[test]
+    fn searches_run_on_other_storage() {
+        let g = kite();
+        let a = g.index_of("A").unwrap();
+        assert_eq!(labels(&g, &sorted_vertices(&g)), ["A", "B", "C", "D", "E"]);
+        assert_eq!(labels(&g, &bfs(&g, a)), ["A", "B", "C", "D"]);
+
+        let mut visited = vec![false; g.vertex_count()];
+        let mut order = Vec::new();
+        dfs(&g, a, &mut visited, &mut order);
+        assert_eq!(labels(&g, &order), ["A", "B", "C", "D"]);
+
+        assert_eq!(component_ids(&g), [0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn weighted_search_reads_the_storage_weights() {
+        let g = kite();
+        let (dist, pred) = bellman_ford_tree(&g, g.index_of("A").unwrap()).unwrap();
+        assert_eq!(dist, [Some(4.0), Some(2.0), Some(0.0), Some(3.0), None]);
+        assert_eq!(labels(&g, &reconstruct_path(&pred, 0)), ["A", "B", "C", "D"]);
+    }
+
+    #[test]
+    fn subgraph_check_matches_labels_across_storage() {
+        let g = kite();
+        let path = AdjacencyLists::new(&["C", "B"], &[("B", "C", 1.0)]);
+        let stray = AdjacencyLists::new(&["B", "E"], &[("B", "E", 1.0)]);
+        assert!(is_subgraph(&g, &path));
+        assert!(!is_subgraph(&g, &stray));
+        assert!(!is_subgraph(&path, &g));
+    }
+}
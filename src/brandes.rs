@@ -0,0 +1,75 @@
+// src/brandes.rs - Brandes' algorithm, shared by the centrality report and the metrics table
+//
+// Kept apart from centrality.rs, which sits behind the unstable-algorithms
+// feature, because the betweenness column of vertex_metrics is stable.
+use crate::algo::GraphRef;
+use crate::Graph;
+use std::collections::VecDeque;
+
+impl Graph {
+    /// Counts the shortest paths from one source to every vertex by BFS
+    ///
+    /// Returns the number of paths and the distance of every vertex, None when it is
+    /// unreachable, and the vertices in the order BFS reached them.
+    pub(crate) fn shortest_path_counts(&self, out: &[Vec<usize>], source: usize) -> (Vec<f64>, Vec<Option<usize>>, Vec<usize>) {
+        let n = self.n_vertices;
+        let mut sigma = vec![0.0; n];
+        let mut dist: Vec<Option<usize>> = vec![None; n];
+        let mut order = Vec::with_capacity(n);
+        let mut queue = VecDeque::new();
+        sigma[source] = 1.0;
+        dist[source] = Some(0);
+        queue.push_back(source);
+
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            let next = dist[v].map(|d| d + 1);
+            for &w in &out[v] {
+                if dist[w].is_none() {
+                    dist[w] = next;
+                    queue.push_back(w);
+                }
+                if dist[w] == next {
+                    sigma[w] += sigma[v];
+                }
+            }
+        }
+        (sigma, dist, order)
+    }
+
+    /// Adds the dependencies of every vertex on the shortest paths from one source
+    fn accumulate_dependencies(&self, out: &[Vec<usize>], incoming: &[Vec<usize>], source: usize, scores: &mut [f64]) {
+        let n = self.n_vertices;
+        let (sigma, dist, order) = self.shortest_path_counts(out, source);
+
+        // The predecessors of w are the vertices joined to it from one level above
+        let mut delta = vec![0.0; n];
+        for &w in order.iter().rev() {
+            for &v in &incoming[w] {
+                if dist[v].is_some_and(|d| Some(d + 1) == dist[w]) {
+                    delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]);
+                }
+            }
+            if w != source {
+                scores[w] += delta[w];
+            }
+        }
+    }
+
+    /// Runs Brandes from the given sources and returns the scaled score of every vertex,
+    /// in input order
+    pub(crate) fn brandes_scores(&self, sources: &[usize], scale: f64) -> Vec<f64> {
+        let n = self.n_vertices;
+        let out: Vec<Vec<usize>> = (0..n).map(|v| self.neighbors(v).collect()).collect();
+        let incoming: Vec<Vec<usize>> = (0..n)
+            .map(|w| (0..n).filter(|&v| self.adj_matrix[v][w] == 1).collect())
+            .collect();
+        let mut scores = vec![0.0; n];
+        for &source in sources {
+            self.accumulate_dependencies(&out, &incoming, source, &mut scores);
+        }
+        let undirected = self.direction_profile().one_way_pairs == 0;
+        let scale = if undirected { scale / 2.0 } else { scale };
+        scores.iter().map(|score| score * scale).collect()
+    }
+}
@@ -0,0 +1,174 @@
+// src/budget.rs - Node, wall-clock and cancellation limits for exponential searches
+use crate::error::GraphError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Number of search steps between two checks of the clock and the cancel flag
+const CHECK_INTERVAL: u64 = 1024;
+
+/// Limits on a potentially exponential search; an absent limit does not apply
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    /// Maximum number of search nodes to explore
+    pub nodes: Option<u64>,
+    /// Point in time after which the search gives up
+    pub deadline: Option<Instant>,
+    /// Flag another thread can set to abort the search
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl Budget {
+    /// A budget without any limit
+    pub fn unlimited() -> Self {
+        Budget::default()
+    }
+
+    /// A budget of the given number of search nodes
+    pub fn nodes(nodes: u64) -> Self {
+        Budget {
+            nodes: Some(nodes),
+            ..Budget::default()
+        }
+    }
+
+    /// A budget that runs out the given time from now
+    pub fn timeout(timeout: Duration) -> Self {
+        Budget {
+            deadline: Some(Instant::now() + timeout),
+            ..Budget::default()
+        }
+    }
+
+    /// Adds a cancellation flag to the budget
+    pub fn with_cancel(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Starts counting the search steps taken against this budget
+    pub(crate) fn meter(&self) -> BudgetMeter<'_> {
+        BudgetMeter {
+            budget: self,
+            explored: 0,
+            started: Instant::now(),
+        }
+    }
+}
+
+/// Running count of the steps a search has taken against its budget
+pub(crate) struct BudgetMeter<'a> {
+    budget: &'a Budget,
+    explored: u64,
+    started: Instant,
+}
+
+impl BudgetMeter<'_> {
+    /// Counts one search step; returns false once the budget is used up
+    /// The clock and the cancel flag are only read every CHECK_INTERVAL steps
+    pub(crate) fn tick(&mut self) -> bool {
+        if matches!(self.budget.nodes, Some(nodes) if self.explored >= nodes) {
+            return false;
+        }
+        self.explored += 1;
+        if self.explored % CHECK_INTERVAL != 1 {
+            return true;
+        }
+        self.in_time()
+    }
+
+    /// Counts one expensive step, reading the clock and the cancel flag every time
+    pub(crate) fn tick_checked(&mut self) -> bool {
+        if matches!(self.budget.nodes, Some(nodes) if self.explored >= nodes) {
+            return false;
+        }
+        self.explored += 1;
+        self.in_time()
+    }
+
+    /// Checks that the search was not cancelled and the deadline has not passed
+    fn in_time(&self) -> bool {
+        if matches!(&self.budget.cancel, Some(flag) if flag.load(Ordering::Relaxed)) {
+            return false;
+        }
+        !matches!(self.budget.deadline, Some(deadline) if Instant::now() >= deadline)
+    }
+
+    /// The error reported when the search ran out of budget
+    pub(crate) fn exceeded(&self) -> GraphError {
+        GraphError::BudgetExceeded {
+            explored: self.explored,
+            elapsed: self.started.elapsed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::Dialect;
+    use crate::Graph;
+    use std::thread;
+
+    fn graph_from(text: &str) -> Graph {
+        let mut graph = Graph::new();
+        graph.read_input_text_with(text, Dialect::Strict).unwrap();
+        graph
+    }
+
+    /// Fourteen isolated vertices A..N, then `rest` on U..Z
+    fn isolated_and(rest: &str) -> Graph {
+        let isolated: String = ('A'..='N').map(|c| format!("{} -1\n", c)).collect();
+        graph_from(&format!("20\n{}{}", isolated, rest))
+    }
+
+    /// Two graphs the isomorphism search cannot tell apart until the end: a 6-cycle
+    /// against two triangles, after the 14! ways to map the isolated vertices
+    fn hard_pair() -> (Graph, Graph) {
+        let cycle = isolated_and("U V Z -1\nV U W -1\nW V X -1\nX W Y -1\nY X Z -1\nZ Y U -1\n");
+        let triangles = isolated_and("U V W -1\nV U W -1\nW U V -1\nX Y Z -1\nY X Z -1\nZ X Y -1\n");
+        (cycle, triangles)
+    }
+
+    #[test]
+    fn deadline_stops_a_long_search() {
+        let (g, h) = hard_pair();
+        let started = Instant::now();
+        let result = g.is_isomorphic_within(&h, &Budget::timeout(Duration::from_millis(50)));
+        let elapsed = started.elapsed();
+        match result {
+            Err(GraphError::BudgetExceeded { explored, elapsed: reported }) => {
+                assert!(explored > 0);
+                assert!(reported >= Duration::from_millis(50), "{:?}", reported);
+            }
+            other => panic!("expected BudgetExceeded, got {:?}", other),
+        }
+        assert!(elapsed < Duration::from_secs(2), "took {:?}", elapsed);
+    }
+
+    #[test]
+    fn cancel_flag_stops_a_search_from_another_thread() {
+        let (g, h) = hard_pair();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&cancel);
+        let canceller = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            flag.store(true, Ordering::Relaxed);
+        });
+        let started = Instant::now();
+        let result = g.is_isomorphic_within(&h, &Budget::unlimited().with_cancel(cancel));
+        canceller.join().unwrap();
+        assert!(matches!(result, Err(GraphError::BudgetExceeded { .. })), "{:?}", result);
+        assert!(started.elapsed() < Duration::from_secs(2), "took {:?}", started.elapsed());
+    }
+
+    #[test]
+    fn node_budget_counts_steps() {
+        let (g, h) = hard_pair();
+        match g.is_isomorphic_within(&h, &Budget::nodes(5_000)) {
+            Err(GraphError::BudgetExceeded { explored, .. }) => assert_eq!(explored, 5_000),
+            other => panic!("expected BudgetExceeded, got {:?}", other),
+        }
+        assert_eq!(h.is_isomorphic_within(&h, &Budget::nodes(5_000)).unwrap().map(|map| map.len()), Some(20));
+    }
+}
@@ -0,0 +1,175 @@
+// src/cache.rs - Cached results of repeated structural queries
+use crate::error::GraphError;
+use crate::gomory_hu::CutTree;
+use crate::Graph;
+use std::sync::OnceLock;
+use std::collections::VecDeque;
+
+/// Graphs with more vertices than this never cache all-pairs hop distances
+pub const ALL_PAIRS_CACHE_LIMIT: usize = 2_000;
+
+/// Lazily filled results of queries that depend only on the graph structure
+///
+/// Every mutating method of Graph clears the cache through invalidate_cache.
+/// Code that edits the public fields directly must call invalidate_cache itself.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisCache {
+    sorted: OnceLock<Vec<usize>>,
+    component_ids: OnceLock<Vec<usize>>,
+    degrees: OnceLock<Vec<usize>>,
+    hop_distances: OnceLock<Option<Vec<Vec<Option<usize>>>>>,
+    cut_tree: OnceLock<Result<CutTree, GraphError>>,
+}
+
+impl Graph {
+    /// Drops every cached analysis result so the next query recomputes it
+    pub fn invalidate_cache(&mut self) {
+        self.cache = AnalysisCache::default();
+    }
+
+    /// Returns the vertex indices in alphabetical order of their labels
+    pub(crate) fn cached_sorted(&self) -> &[usize] {
+        self.cache.sorted.get_or_init(|| self.compute_sorted_vertices())
+    }
+
+    /// Returns the component number of every vertex
+    pub(crate) fn cached_component_ids(&self) -> &[usize] {
+        self.cache.component_ids.get_or_init(|| self.compute_component_ids())
+    }
+
+    /// Returns the degree of every vertex, in input order
+    pub(crate) fn cached_degrees(&self) -> &[usize] {
+        self.cache.degrees.get_or_init(|| self.compute_degrees())
+    }
+
+    /// Returns the number of edges on a shortest path between every ordered pair of
+    /// vertices, indexed by vertex index, or None for graphs above ALL_PAIRS_CACHE_LIMIT
+    pub fn all_pairs_hops(&self) -> Option<&Vec<Vec<Option<usize>>>> {
+        self.cache
+            .hop_distances
+            .get_or_init(|| {
+                if self.n_vertices > ALL_PAIRS_CACHE_LIMIT {
+                    None
+                } else {
+                    Some(self.compute_all_pairs_hops())
+                }
+            })
+            .as_ref()
+    }
+
+    /// Returns the Gomory-Hu tree in parent form, or the error that kept it from being built
+    pub(crate) fn cached_cut_tree(&self) -> Result<&CutTree, GraphError> {
+        self.cache
+            .cut_tree
+            .get_or_init(|| self.compute_cut_tree())
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    /// Computes the degree table from adj_count
+    pub(crate) fn compute_degrees(&self) -> Vec<usize> {
+        self.adj_count[..self.n_vertices].iter().map(|&d| d.max(0) as usize).collect()
+    }
+
+    /// Runs a BFS from every vertex over the adjacency matrix
+    pub(crate) fn compute_all_pairs_hops(&self) -> Vec<Vec<Option<usize>>> {
+        (0..self.n_vertices)
+            .map(|start| {
+                let mut hops = vec![None; self.n_vertices];
+                hops[start] = Some(0);
+                let mut queue = VecDeque::from([start]);
+                while let Some(current) = queue.pop_front() {
+                    let next = hops[current].map(|h| h + 1);
+                    for (i, &cell) in self.adj_matrix[current][..self.n_vertices].iter().enumerate() {
+                        if cell == 1 && hops[i].is_none() {
+                            hops[i] = next;
+                            queue.push_back(i);
+                        }
+                    }
+                }
+                hops
+            })
+            .collect()
+    }
+
+    /// Lists the cached results that no longer match a fresh computation
+    pub(crate) fn stale_cache_entries(&self) -> Vec<&'static str> {
+        let mut stale = Vec::new();
+        if matches!(self.cache.sorted.get(), Some(v) if *v != self.compute_sorted_vertices()) {
+            stale.push("sorted vertex order");
+        }
+        if matches!(self.cache.component_ids.get(), Some(v) if *v != self.compute_component_ids()) {
+            stale.push("components");
+        }
+        if matches!(self.cache.degrees.get(), Some(v) if *v != self.compute_degrees()) {
+            stale.push("degree table");
+        }
+        if matches!(self.cache.hop_distances.get(), Some(Some(v)) if *v != self.compute_all_pairs_hops()) {
+            stale.push("all-pairs distances");
+        }
+        if matches!(self.cache.cut_tree.get(), Some(tree) if *tree != self.compute_cut_tree()) {
+            stale.push("Gomory-Hu tree");
+        }
+        stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::NewVertexPolicy;
+    use crate::fixtures;
+
+    /// Fills every cache entry
+    fn fill(graph: &Graph) {
+        graph.cached_sorted();
+        graph.cached_component_ids();
+        graph.cached_degrees();
+        graph.all_pairs_hops();
+        let _ = graph.cached_cut_tree();
+    }
+
+    #[test]
+    fn components_follow_an_added_edge() {
+        let mut graph = fixtures::disconnected_pair();
+        assert_eq!(graph.connected_components().len(), 2);
+        graph.add_edge("B", "C").unwrap();
+        assert_eq!(graph.connected_components().len(), 1);
+        assert_eq!(graph.all_pairs_hops().unwrap()[0][3], Some(3));
+    }
+
+    #[test]
+    fn no_mutation_leaves_a_stale_entry() {
+        type Mutation = fn(&mut Graph);
+        let mutations: [(&str, Mutation); 10] = [
+            ("set_edge_weight", |g| g.set_edge_weight("D", "E", 5.0).unwrap()),
+            ("set_coordinates", |g| g.set_coordinates("A", 1.0, 2.0).unwrap()),
+            ("set_edge_weight_by_id", |g| {
+                let id = g.find_edge("A", "B").unwrap();
+                assert!(g.set_edge_weight_by_id(id, 0.5));
+            }),
+            ("set_edge_attr", |g| {
+                let id = g.find_edge("A", "C").unwrap();
+                assert!(g.set_edge_attr(id, "color", "red"));
+            }),
+            ("add_vertex", |g| {
+                g.add_vertex("I").unwrap();
+            }),
+            ("add_edge", |g| g.add_edge("H", "I").unwrap()),
+            ("extend_edges", |g| {
+                let edges = [("A", "J"), ("J", "I")].map(|(u, v)| (u.to_string(), v.to_string()));
+                g.extend_edges(edges, NewVertexPolicy::Create).unwrap();
+            }),
+            ("remove_edge", |g| assert!(g.remove_edge("D", "E").unwrap())),
+            ("split_vertex", |g| g.split_vertex("E", "E2", &["F"], false).unwrap()),
+            ("remove_vertex", |g| g.remove_vertex("J").unwrap()),
+        ];
+        let mut graph = fixtures::two_cliques_bridge();
+        for (name, mutate) in mutations {
+            fill(&graph);
+            mutate(&mut graph);
+            assert_eq!(graph.stale_cache_entries(), Vec::<&str>::new(), "after {}", name);
+            graph.check_invariants().unwrap();
+        }
+    }
+}
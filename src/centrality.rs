@@ -0,0 +1,403 @@
+// src/centrality.rs - Betweenness centrality, exact and estimated from sampled sources
+//
+// Both run Brandes' algorithm, in brandes.rs: one BFS per source counts the
+// shortest paths, and walking the BFS order backwards accumulates how much each
+// vertex lies on them. Exact betweenness takes every vertex as a source, O(n·m) in all; the estimate
+// takes a seeded sample of k sources and scales their sum by n/k.
+//
+// Paths follow edges in their direction and ignore weights. On an undirected graph
+// each pair is counted once, so the scores are half the sums over ordered pairs.
+// explain_centrality keeps the path counts of every BFS to say where one score
+// comes from.
+use crate::algo::{self, GraphRef};
+use crate::rng::Rng;
+use crate::error::GraphError;
+use crate::Graph;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+
+/// Largest graph the centrality report scores exactly; larger ones are sampled
+pub const EXACT_BETWEENNESS_LIMIT: usize = 2_000;
+/// Sources the centrality subcommand samples when the graph is too large to score exactly
+pub const DEFAULT_BETWEENNESS_SAMPLES: usize = 1_000;
+/// Source-target pairs an explanation lists, those relying most on the vertex first
+pub const EXPLAIN_TOP_PAIRS: usize = 5;
+
+/// The shortest paths of one source-target pair that pass through the explained vertex
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairShare {
+    pub source: String,
+    pub target: String,
+    /// Shortest source-target paths through the vertex
+    pub through: f64,
+    /// All shortest source-target paths
+    pub total: f64,
+}
+
+impl PairShare {
+    /// The share of the pair's shortest paths through the vertex, its term in the betweenness
+    pub fn share(&self) -> f64 {
+        self.through / self.total
+    }
+}
+
+/// Why a vertex scores as it does, from Graph::explain_centrality
+#[derive(Debug, Clone, PartialEq)]
+pub struct CentralityExplanation {
+    pub label: String,
+    pub n_vertices: usize,
+    pub degree: usize,
+    /// 1 for the highest degree; tied vertices share the better rank
+    pub degree_rank: usize,
+    /// Exact betweenness, as betweenness reports it
+    pub betweenness: f64,
+    /// 1 for the highest betweenness; tied vertices share the better rank
+    pub betweenness_rank: usize,
+    /// Shortest paths between other vertices that pass through this one
+    pub paths_through: f64,
+    /// Pairs with at least one shortest path through the vertex
+    pub pairs_relying: usize,
+    /// At most EXPLAIN_TOP_PAIRS pairs, by share of their paths through the vertex
+    pub top_pairs: Vec<PairShare>,
+    /// Sizes of the components its neighbors fall into once it is removed, largest
+    /// first; more than one means the vertex is an articulation point
+    pub parts_without: Vec<usize>,
+}
+
+impl CentralityExplanation {
+    /// Checks whether removing the vertex disconnects its neighbors from each other
+    pub fn is_articulation_point(&self) -> bool {
+        self.parts_without.len() > 1
+    }
+}
+
+/// 1 plus the number of values greater than the one at idx
+fn rank_of(values: &[f64], idx: usize) -> usize {
+    1 + values.iter().filter(|&&value| value > values[idx] + 1e-9).count()
+}
+
+impl Graph {
+    /// Pairs every label with its score, alphabetically
+    fn labeled_scores(&self, scores: &[f64]) -> Vec<(String, f64)> {
+        self.sort_vertices()
+            .into_iter()
+            .map(|idx| (self.vertices[idx].clone(), scores[idx]))
+            .collect()
+    }
+
+    /// Exact betweenness of every vertex, as (label, score) in alphabetical order
+    ///
+    /// The score of v sums, over the pairs s, t other than v, the share of the
+    /// shortest s-t paths passing through v.
+    pub fn betweenness(&self) -> Vec<(String, f64)> {
+        let sources: Vec<usize> = (0..self.n_vertices).collect();
+        self.labeled_scores(&self.brandes_scores(&sources, 1.0))
+    }
+
+    /// Betweenness estimated from a seeded sample of sources, as (label, score) in
+    /// alphabetical order
+    ///
+    /// `samples` distinct sources are drawn with the seed and their dependencies scaled
+    /// by n/samples, which makes every estimate unbiased. The same seed draws the same
+    /// sources, so the estimates repeat exactly. With samples >= n every vertex is a
+    /// source and the scores are exact; with no samples (or no vertices) they are all 0.
+    ///
+    /// Error: one source adds at most n-2 to a score, so by Hoeffding's inequality an
+    /// estimate misses the exact score by more than ε·n·(n-2) (half that on an undirected
+    /// graph) with probability at most 2·exp(-2·samples·ε²). For 1000 samples that is
+    /// ε = 0.043 at 95% confidence, about 4% of the largest possible score, whatever
+    /// the size of the graph.
+    pub fn betweenness_sampled(&self, samples: usize, seed: u64) -> Vec<(String, f64)> {
+        let n = self.n_vertices;
+        let mut sources: Vec<usize> = (0..n).collect();
+        Rng::new(seed).shuffle(&mut sources);
+        sources.truncate(samples);
+        let scale = if sources.is_empty() { 0.0 } else { n as f64 / sources.len() as f64 };
+        self.labeled_scores(&self.brandes_scores(&sources, scale))
+    }
+
+    /// Explains the centrality of a vertex: its degree and betweenness with their ranks,
+    /// the shortest paths through it, the pairs relying on it most, and what its
+    /// removal would split apart
+    ///
+    /// A pair s, t relies on v when a shortest s-t path passes through v; the paths
+    /// through v number sigma(s,v)·sigma(v,t) of the sigma(s,t) in all, and their share
+    /// is the term the pair adds to the betweenness. On an undirected graph each pair
+    /// is counted once. Scores are exact, one BFS per vertex, so this is meant for
+    /// graphs the centrality report scores exactly. Fails with GraphError::UnknownVertex
+    /// for a label that is not a vertex.
+    pub fn explain_centrality(&self, label: &str) -> Result<CentralityExplanation, GraphError> {
+        let v = self.require_vertex_idx(label)?;
+        let n = self.n_vertices;
+        let out: Vec<Vec<usize>> = (0..n).map(|u| self.neighbors(u).collect()).collect();
+        let counts: Vec<(Vec<f64>, Vec<Option<usize>>)> = (0..n)
+            .map(|s| {
+                let (sigma, dist, _) = self.shortest_path_counts(&out, s);
+                (sigma, dist)
+            })
+            .collect();
+        let undirected = self.direction_profile().one_way_pairs == 0;
+
+        let (sigma_v, dist_v) = &counts[v];
+        let mut pairs = Vec::new();
+        for (s, (sigma_s, dist_s)) in counts.iter().enumerate() {
+            let Some(d_sv) = dist_s[v].filter(|_| s != v) else {
+                continue;
+            };
+            for t in 0..n {
+                if t == v || t == s || (undirected && t < s) {
+                    continue;
+                }
+                if let (Some(d_vt), Some(d_st)) = (dist_v[t], dist_s[t]) {
+                    if d_sv + d_vt == d_st {
+                        let (source, target) = match undirected && self.vertices[t] < self.vertices[s] {
+                            true => (t, s),
+                            false => (s, t),
+                        };
+                        pairs.push(PairShare {
+                            source: self.vertices[source].clone(),
+                            target: self.vertices[target].clone(),
+                            through: sigma_s[v] * sigma_v[t],
+                            total: sigma_s[t],
+                        });
+                    }
+                }
+            }
+        }
+        let paths_through = pairs.iter().fold(0.0, |sum, pair| sum + pair.through);
+        let pairs_relying = pairs.len();
+        pairs.sort_by(|a, b| {
+            b.share()
+                .total_cmp(&a.share())
+                .then(b.through.total_cmp(&a.through))
+                .then_with(|| (&a.source, &a.target).cmp(&(&b.source, &b.target)))
+        });
+        pairs.truncate(EXPLAIN_TOP_PAIRS);
+
+        // The components of the neighbors once v is masked out, as what_if_remove sees them
+        let view = self
+            .view()
+            .without_vertices(&[label])
+            .expect("only the label of a vertex is masked");
+        let ids = algo::component_ids_with(&view, self.density_strategy());
+        let mut sizes = vec![0; ids.iter().map(|&id| id + 1).max().unwrap_or(0)];
+        let mut touched = vec![false; sizes.len()];
+        for (u, &id) in ids.iter().enumerate() {
+            sizes[id] += 1;
+            let w = view.graph_index(u);
+            if self.adj_matrix[v][w] == 1 || self.adj_matrix[w][v] == 1 {
+                touched[id] = true;
+            }
+        }
+        let mut parts_without: Vec<usize> = (0..sizes.len()).filter(|&id| touched[id]).map(|id| sizes[id]).collect();
+        parts_without.sort_by(|a, b| b.cmp(a));
+
+        let degrees: Vec<f64> = self.adj_count[..n].iter().map(|&count| count as f64).collect();
+        let sources: Vec<usize> = (0..n).collect();
+        let scores = self.brandes_scores(&sources, 1.0);
+        Ok(CentralityExplanation {
+            label: self.vertices[v].clone(),
+            n_vertices: n,
+            degree: self.adj_count[v] as usize,
+            degree_rank: rank_of(&degrees, v),
+            betweenness: scores[v],
+            betweenness_rank: rank_of(&scores, v),
+            paths_through,
+            pairs_relying,
+            top_pairs: pairs,
+            parts_without,
+        })
+    }
+
+    /// To create/write an output file of the betweenness of every vertex
+    ///
+    /// Graphs of up to EXACT_BETWEENNESS_LIMIT vertices are scored exactly, larger ones
+    /// from `samples` sources drawn with `seed`. The first line records the method
+    /// and the number of sources used, then one `label score` line per vertex follows
+    /// in alphabetical order.
+    pub fn produce_output_file_centrality(
+        &self,
+        base_name: &str,
+        samples: usize,
+        seed: u64,
+    ) -> Result<(), GraphError> {
+        let n = self.n_vertices;
+        let exact = n <= EXACT_BETWEENNESS_LIMIT || samples >= n;
+        let (header, scores) = if exact {
+            (format!("method: exact, {} of {} sources", n, n), self.betweenness())
+        } else {
+            (
+                format!("method: sampled, {} of {} sources, seed {}", samples, n, seed),
+                self.betweenness_sampled(samples, seed),
+            )
+        };
+
+        let output_name = Self::get_output_filename(base_name, "-CENTRALITY");
+        let mut fp = File::create(output_name)?;
+        writeln!(fp, "{}", header)?;
+        for (label, score) in scores {
+            writeln!(fp, "{} {:.4}", label, score)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for CentralityExplanation {
+    /// One paragraph, then one indented line per pair relying on the vertex most
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} has degree {} (rank {} of {}) and betweenness {:.4} (rank {} of {}). ",
+            self.label,
+            self.degree,
+            self.degree_rank,
+            self.n_vertices,
+            self.betweenness,
+            self.betweenness_rank,
+            self.n_vertices
+        )?;
+        write!(
+            f,
+            "{} shortest paths between {} pairs of other vertices pass through it. ",
+            self.paths_through, self.pairs_relying
+        )?;
+        let mut parts: Vec<String> = self.parts_without.iter().map(usize::to_string).collect();
+        match self.parts_without.len() {
+            0 => writeln!(f, "It has no neighbors, so removing it disconnects nothing.")?,
+            1 => writeln!(
+                f,
+                "It is not an articulation point: its neighbors stay in one component of {} vertices without it.",
+                parts[0]
+            )?,
+            count => {
+                let last = parts.pop().unwrap_or_default();
+                writeln!(
+                    f,
+                    "It is an articulation point: removing it splits its component into {} parts of {} and {} vertices.",
+                    count,
+                    parts.join(", "),
+                    last
+                )?
+            }
+        }
+        if !self.top_pairs.is_empty() {
+            writeln!(f, "Pairs relying on it most:")?;
+        }
+        for pair in &self.top_pairs {
+            writeln!(
+                f,
+                "  {}-{}: {} of {} shortest paths ({:.1}%)",
+                pair.source,
+                pair.target,
+                pair.through,
+                pair.total,
+                pair.share() * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::Dialect;
+    use crate::fixtures::{self, scratch_dir};
+    use std::fs;
+
+    /// Triangles A-B-C and D-E-F, joined through the bridge vertex M
+    fn barbell() -> Graph {
+        let mut graph = Graph::new();
+        graph
+            .read_input_text_with(
+                "7\nA B C -1\nB A C -1\nC A B M -1\nM C D -1\nD E F M -1\nE D F -1\nF D E -1\n",
+                Dialect::Strict,
+            )
+            .unwrap();
+        graph
+    }
+
+    fn score(scores: &[(String, f64)], label: &str) -> f64 {
+        scores.iter().find(|(l, _)| l == label).map(|&(_, s)| s).unwrap()
+    }
+
+    #[test]
+    fn exact_betweenness_of_the_barbell() {
+        let scores = barbell().betweenness();
+        // Every path between the triangles crosses C, M and D; C and D also carry M's
+        assert_eq!(score(&scores, "M"), 9.0);
+        assert_eq!(score(&scores, "C"), 2.0 * 4.0);
+        assert_eq!(score(&scores, "A"), 0.0);
+    }
+
+    #[test]
+    fn sampling_every_source_is_exact_and_seeds_repeat() {
+        let graph = fixtures::two_cliques_bridge();
+        assert_eq!(graph.betweenness_sampled(graph.n_vertices, 3), graph.betweenness());
+        assert_eq!(graph.betweenness_sampled(3, 11), graph.betweenness_sampled(3, 11));
+        assert!(graph.betweenness_sampled(0, 11).iter().all(|&(_, s)| s == 0.0));
+    }
+
+    #[test]
+    fn sampled_estimate_stays_near_the_exact_score() {
+        let graph = crate::generators::barabasi_albert(200, 2, 5).unwrap();
+        let (n, samples) = (200.0, 100);
+        let exact = graph.betweenness();
+        // The documented Hoeffding bound at 99% confidence, on an undirected graph
+        let epsilon = ((2.0_f64 / 0.01).ln() / (2.0 * samples as f64)).sqrt();
+        let bound = epsilon * n * (n - 2.0) / 2.0;
+        let mut mean = vec![0.0; exact.len()];
+        for seed in 0..20 {
+            let sampled = graph.betweenness_sampled(samples, seed);
+            for (k, ((label, s), (_, estimate))) in exact.iter().zip(&sampled).enumerate() {
+                assert!((estimate - s).abs() < bound, "{} (seed {}): {} vs {}", label, seed, estimate, s);
+                mean[k] += estimate / 20.0;
+            }
+        }
+        // Averaged over the seeds, the unbiased estimates close in on the exact scores
+        for (k, (label, s)) in exact.iter().enumerate() {
+            assert!((mean[k] - s).abs() < 0.05 * bound, "{}: {} vs {}", label, mean[k], s);
+        }
+    }
+
+    #[test]
+    fn report_records_the_method_and_sources() {
+        let dir = scratch_dir("centrality", "report");
+        let base = dir.join("g").display().to_string();
+        let small = crate::generators::barabasi_albert(200, 2, 5).unwrap();
+        small.produce_output_file_centrality(&base, 50, 7).unwrap();
+        let report = fs::read_to_string(format!("{}-CENTRALITY.TXT", base)).unwrap();
+        assert_eq!(report.lines().next(), Some("method: exact, 200 of 200 sources"));
+        assert_eq!(report.lines().count(), 201);
+
+        let large = crate::generators::barabasi_albert(EXACT_BETWEENNESS_LIMIT + 100, 2, 5).unwrap();
+        large.produce_output_file_centrality(&base, 50, 7).unwrap();
+        let report = fs::read_to_string(format!("{}-CENTRALITY.TXT", base)).unwrap();
+        assert_eq!(report.lines().next(), Some("method: sampled, 50 of 2100 sources, seed 7"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn bridge_vertex_explanation_names_both_sides() {
+        let explanation = barbell().explain_centrality("M").unwrap();
+        assert_eq!(explanation.parts_without, vec![3, 3]);
+        assert!(explanation.is_articulation_point());
+        assert_eq!((explanation.betweenness, explanation.betweenness_rank), (9.0, 1));
+        assert_eq!(explanation.pairs_relying, 9);
+        assert_eq!(explanation.top_pairs.len(), EXPLAIN_TOP_PAIRS);
+        assert!(explanation.top_pairs.iter().all(|pair| pair.share() == 1.0));
+        let text = explanation.to_string();
+        assert!(text.contains("splits its component into 2 parts of 3 and 3 vertices"), "{}", text);
+    }
+
+    #[test]
+    fn clique_vertex_is_no_articulation_point() {
+        let explanation = barbell().explain_centrality("A").unwrap();
+        assert_eq!(explanation.parts_without, vec![6]);
+        assert!(!explanation.is_articulation_point());
+        assert_eq!(explanation.paths_through, 0.0);
+        assert!(barbell().explain_centrality("Z").is_err());
+    }
+}
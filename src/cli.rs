@@ -0,0 +1,2023 @@
+// src/cli.rs - Non-interactive subcommands of the binary
+use graph_analysis::shortest_path::{PathAlgorithm, Route};
+use crate::completions::{bash_script, fish_script, help_json, zsh_script};
+use crate::repl::{Flow, Repl};
+use crate::serve::serve;
+use graph_analysis::budget::Budget;
+#[cfg(feature = "unstable-algorithms")]
+use graph_analysis::centrality::DEFAULT_BETWEENNESS_SAMPLES;
+use graph_analysis::require::Requirement;
+#[cfg(feature = "unstable-algorithms")]
+use graph_analysis::examples::generate_corpus;
+#[cfg(feature = "unstable-algorithms")]
+use graph_analysis::generators;
+use graph_analysis::formats::{labels_path, GraphFormat};
+use graph_analysis::graph6::{analyze_graph6_stream, StreamConfig, StreamMetric};
+#[cfg(feature = "unstable-algorithms")]
+use graph_analysis::communities::read_partition;
+use graph_analysis::compare::compare_outputs;
+use graph_analysis::dedupe::{dedupe_edge_list_with, DEFAULT_MEM_BUDGET_BYTES};
+use graph_analysis::dialect::{Dialect, Encoding};
+use graph_analysis::digest::DigestMismatch;
+use graph_analysis::direction::Direction;
+use graph_analysis::edge_list::is_edge_list_path;
+use graph_analysis::error::{file_error, json_string, GraphError};
+use graph_analysis::lint::{fix_text, lint, lint_text};
+use graph_analysis::metrics::VertexMetric;
+use graph_analysis::output::{OutputConfig, SECTIONS};
+use graph_analysis::profile::{DialectProfile, BUILT_IN_PROFILES};
+use graph_analysis::suggest::DEFAULT_SUGGESTIONS;
+use graph_analysis::weights::MissingEdgePolicy;
+use graph_analysis::Graph;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// What an operand or the value of a flag names, for completion and help-json
+#[derive(Debug, Clone, Copy)]
+pub enum Value {
+    /// An existing adjacency file, offered from the .txt files
+    InputFile,
+    /// Any file, read or written
+    File,
+    Directory,
+    Vertex,
+    /// One of a fixed list of words
+    Choice(&'static [&'static str]),
+    /// A format of the convert subcommand
+    Format,
+    /// A registered generator family
+    #[cfg(feature = "unstable-algorithms")]
+    Generator,
+    /// SECTION=N of --limit, offered as the section names
+    Section,
+    /// Anything else, such as a number or a list; never completed
+    Text,
+}
+
+impl Value {
+    /// Name of the kind of value in help-json and the completion scripts
+    pub fn kind(self) -> &'static str {
+        match self {
+            Value::InputFile => "input",
+            Value::File => "file",
+            Value::Directory => "directory",
+            Value::Vertex => "vertex",
+            Value::Choice(_) => "choice",
+            Value::Format => "format",
+            #[cfg(feature = "unstable-algorithms")]
+            Value::Generator => "generator",
+            Value::Section => "section",
+            Value::Text => "text",
+        }
+    }
+
+    /// The words the value is one of, None when it is a path or free text
+    pub fn words(self) -> Option<Vec<String>> {
+        let words = match self {
+            Value::Choice(words) => words.iter().map(|word| word.to_string()).collect(),
+            Value::Format => GraphFormat::ALL.iter().map(|format| format.name().to_string()).collect(),
+            #[cfg(feature = "unstable-algorithms")]
+            Value::Generator => generators::registered()
+                .iter()
+                .map(|generator| generator.name.to_string())
+                .collect(),
+            Value::Section => SECTIONS.iter().map(|section| format!("{}=", section)).collect(),
+            Value::InputFile | Value::File | Value::Directory | Value::Vertex | Value::Text => return None,
+        };
+        Some(words)
+    }
+}
+
+/// A flag of a subcommand
+#[derive(Debug, Clone, Copy)]
+pub struct Flag {
+    pub name: &'static str,
+    /// What the argument after the flag names, None for a switch
+    pub value: Option<Value>,
+    /// Whether the flag may be given more than once
+    pub repeats: bool,
+}
+
+/// A flag without a value
+const fn switch(name: &'static str) -> Flag {
+    Flag {
+        name,
+        value: None,
+        repeats: false,
+    }
+}
+
+/// A flag taking a value, given at most once
+const fn option(name: &'static str, value: Value) -> Flag {
+    Flag {
+        name,
+        value: Some(value),
+        repeats: false,
+    }
+}
+
+/// A flag taking a value, given any number of times
+const fn repeated(name: &'static str, value: Value) -> Flag {
+    Flag {
+        name,
+        value: Some(value),
+        repeats: true,
+    }
+}
+
+const WEIGHTS: Flag = option("--weights", Value::File);
+const MISSING_EDGES: Flag = option("--missing-edges", Value::Choice(&["error", "ignore", "add"]));
+const TIMEOUT: Flag = option("--timeout", Value::Text);
+const OUT: Flag = option("--out", Value::File);
+const LIMIT: Flag = repeated("--limit", Value::Section);
+/// A built-in output dialect, or a profile file of key=value lines
+const DIALECT: Flag = option("--dialect", Value::Choice(BUILT_IN_PROFILES));
+/// Read the graph with this direction instead of inferring it, see take_direction
+const DIRECTED: Flag = switch("--directed");
+const UNDIRECTED: Flag = switch("--undirected");
+
+/// Set by --lossy-encoding: graph files are read with invalid UTF-8 replaced, not refused
+static LOSSY_ENCODING: AtomicBool = AtomicBool::new(false);
+
+/// Flags every subcommand accepts
+pub static GLOBAL_FLAGS: &[Flag] = &[switch("--json"), switch("--lossy-encoding")];
+
+/// Shells the completions subcommand writes scripts for
+const SHELLS: &[&str] = &["bash", "zsh", "fish"];
+
+/// A subcommand of the binary, also offered by the interactive menu
+pub struct Command {
+    pub name: &'static str,
+    /// Arguments after the name, as shown in the usage summary
+    pub args: &'static str,
+    /// What the command does; each line is printed aligned under the previous one
+    pub summary: &'static str,
+    /// What each operand names, in order
+    pub operands: &'static [Value],
+    pub flags: &'static [Flag],
+    run: fn(&[String]) -> Result<(), CliError>,
+}
+
+impl Command {
+    /// Runs the command with its arguments, returning the error message on failure
+    pub fn execute(&self, args: &[String]) -> Result<(), String> {
+        (self.run)(args).map_err(|error| error.to_string())
+    }
+}
+
+/// Every subcommand, in the order of the usage summary
+///
+/// Dispatch, the usage summary, the completion scripts, help-json and the tools
+/// menu all read this table, so a command missing from it cannot be run at all.
+static COMMANDS: &[Command] = &[
+    Command {
+        name: "path",
+        args: "INPUT.TXT FROM TO [--algorithm bfs|0-1-bfs|uniform-cost|bellman-ford] [--astar --coordinates C.CSV] [--weights W.CSV]",
+        summary: "shortest path between two vertices",
+        operands: &[Value::InputFile, Value::Vertex, Value::Vertex],
+        flags: &[
+            option("--algorithm", Value::Choice(&["bfs", "0-1-bfs", "uniform-cost", "bellman-ford"])),
+            switch("--astar"),
+            option("--coordinates", Value::File),
+            WEIGHTS,
+            MISSING_EDGES,
+        ],
+        run: run_path,
+    },
+    Command {
+        name: "shortest",
+        args: "INPUT.TXT START [--algorithm bfs|0-1-bfs|uniform-cost|bellman-ford] [--weights W.CSV]",
+        summary: "distance and path from START to every vertex, written to -SHORTPATH.TXT\n\
+                  under the name of the algorithm, by default the cheapest the weights allow",
+        operands: &[Value::InputFile, Value::Vertex],
+        flags: &[
+            option("--algorithm", Value::Choice(&["bfs", "0-1-bfs", "uniform-cost", "bellman-ford"])),
+            WEIGHTS,
+            MISSING_EDGES,
+        ],
+        run: run_shortest,
+    },
+    Command {
+        name: "layers",
+        args: "INPUT.TXT START",
+        summary: "BFS layers of START as sets and a level diagram, written to -LAYERS.TXT",
+        operands: &[Value::InputFile, Value::Vertex],
+        flags: &[],
+        run: run_layers,
+    },
+    Command {
+        name: "disjoint-paths",
+        args: "INPUT.TXT A B --vertex|--edge",
+        summary: "count and list disjoint paths",
+        operands: &[Value::InputFile, Value::Vertex, Value::Vertex],
+        flags: &[switch("--vertex"), switch("--edge")],
+        run: run_disjoint_paths,
+    },
+    Command {
+        name: "subgraph",
+        args: "G.TXT H.TXT [--swap-if-larger]",
+        summary: "check whether H is a subgraph of G, written to G-H-SUBGRAPH.TXT",
+        operands: &[Value::InputFile, Value::InputFile],
+        flags: &[switch("--swap-if-larger")],
+        run: run_subgraph,
+    },
+    Command {
+        name: "check",
+        args: "INPUT.TXT --require SPEC [--require SPEC ...]",
+        summary: "fail listing every unmet requirement: connected, acyclic, bipartite,\n\
+                  max-degree=N, min-degree=N, vertex-count=MIN..MAX, contains=A,B",
+        operands: &[Value::InputFile],
+        flags: &[repeated("--require", Value::Text)],
+        run: run_check,
+    },
+    Command {
+        name: "convert",
+        args: "INPUT OUTPUT [--from FORMAT] [--to FORMAT]",
+        summary: "convert between adjacency (.txt), dimacs (.dimacs, .col) and metis (.metis, .graph)",
+        operands: &[Value::File, Value::File],
+        flags: &[option("--from", Value::Format), option("--to", Value::Format)],
+        run: run_convert,
+    },
+    Command {
+        name: "lint",
+        args: "INPUT.TXT [--infer-count] [--fix [--out FIXED.TXT]]",
+        summary: "report file problems; --fix writes -FIXED.TXT with the safe fixes",
+        operands: &[Value::InputFile],
+        flags: &[switch("--infer-count"), switch("--fix"), OUT],
+        run: run_lint,
+    },
+    Command {
+        name: "doctor",
+        args: "INPUT.TXT",
+        summary: "check that the adjacency matrix, lists and degree counts agree; names the\n\
+                  first vertex where they do not",
+        operands: &[Value::InputFile],
+        flags: &[],
+        run: run_doctor,
+    },
+    Command {
+        name: "normalize",
+        args: "INPUT.TXT [--out NORM.TXT | --write]",
+        summary: "canonical form: sorted vertices and neighbors, single spaces, LF",
+        operands: &[Value::InputFile],
+        flags: &[OUT, switch("--write")],
+        run: run_normalize,
+    },
+    Command {
+        name: "explain",
+        args: "INPUT.TXT A B",
+        summary: "why the edge A-B does or does not appear in each output",
+        operands: &[Value::InputFile, Value::Vertex, Value::Vertex],
+        flags: &[],
+        run: run_explain,
+    },
+    Command {
+        name: "minimize",
+        args: "INPUT.TXT --holds [not-]SPEC [--holds ...] [--out MIN.TXT] [--timeout 5s]",
+        summary: "smallest graph found on which the check SPECs still hold (or fail)",
+        operands: &[Value::InputFile],
+        flags: &[repeated("--holds", Value::Text), OUT, TIMEOUT],
+        run: run_minimize,
+    },
+    #[cfg(feature = "unstable-algorithms")]
+    Command {
+        name: "generate",
+        args: "NAME [P1 P2 ...] [--out F.TXT]",
+        summary: "graph of a named family: hypercube D, wheel N, petersen, complete-bipartite A B,\n\
+                  binary-tree DEPTH, barabasi-albert N M SEED",
+        operands: &[Value::Generator],
+        flags: &[OUT],
+        run: run_generate,
+    },
+    #[cfg(feature = "unstable-algorithms")]
+    Command {
+        name: "examples",
+        args: "OUTDIR",
+        summary: "write example input files covering the input format, described in OUTDIR/README.TXT",
+        operands: &[Value::Directory],
+        flags: &[],
+        run: run_examples,
+    },
+    Command {
+        name: "nearest",
+        args: "INPUT.TXT --sources A,B,C",
+        summary: "distance to and name of the nearest source per vertex, in -NEAREST.TXT",
+        operands: &[Value::InputFile],
+        flags: &[option("--sources", Value::Text)],
+        run: run_nearest,
+    },
+    Command {
+        name: "bipartite",
+        args: "INPUT.TXT",
+        summary: "the two sides of the graph, or an odd cycle, written to -BIPARTITE.TXT",
+        operands: &[Value::InputFile],
+        flags: &[],
+        run: run_bipartite,
+    },
+    #[cfg(feature = "unstable-algorithms")]
+    Command {
+        name: "centrality",
+        args: "INPUT.TXT [--samples 1000] [--seed 0]",
+        summary: "betweenness of every vertex in -CENTRALITY.TXT, sampled from --samples sources\n\
+                  past 2000 vertices",
+        operands: &[Value::InputFile],
+        flags: &[option("--samples", Value::Text), option("--seed", Value::Text)],
+        run: run_centrality,
+    },
+    #[cfg(feature = "unstable-algorithms")]
+    Command {
+        name: "explain-centrality",
+        args: "INPUT.TXT X",
+        summary: "why X scores as it does: degree and betweenness ranks, the shortest paths\n\
+                  through it, the pairs relying on it most, and what its removal splits",
+        operands: &[Value::InputFile, Value::Vertex],
+        flags: &[],
+        run: run_explain_centrality,
+    },
+    Command {
+        name: "metrics",
+        args: "INPUT.TXT [--metrics degree,coreness,...] [--weights W.CSV]",
+        summary: "one CSV row of per-vertex metrics per vertex, in -METRICS.csv; strength needs --weights",
+        operands: &[Value::InputFile],
+        flags: &[option("--metrics", Value::Text), WEIGHTS, MISSING_EDGES],
+        run: run_metrics,
+    },
+    Command {
+        name: "mst",
+        args: "INPUT.TXT START [--weights W.CSV]",
+        summary: "minimum spanning forest grown from START by Prim's algorithm,\n\
+                  written to -MST.TXT with its total weight",
+        operands: &[Value::InputFile, Value::Vertex],
+        flags: &[WEIGHTS, MISSING_EDGES],
+        run: run_mst,
+    },
+    Command {
+        name: "dag-layers",
+        args: "INPUT.TXT [--weights W.CSV] [--directed|--undirected]",
+        summary: "longest-path layers of a directed acyclic graph and its critical path,\n\
+                  written to -LAYERS-DAG.TXT; lines list only the successors",
+        operands: &[Value::InputFile],
+        flags: &[WEIGHTS, MISSING_EDGES, DIRECTED, UNDIRECTED],
+        run: run_dag_layers,
+    },
+    Command {
+        name: "topo",
+        args: "INPUT.TXT [--directed|--undirected]",
+        summary: "topological order of a directed acyclic graph, alphabetical among ties,\n\
+                  written to -TOPO.TXT; lines list only the successors",
+        operands: &[Value::InputFile],
+        flags: &[DIRECTED, UNDIRECTED],
+        run: run_topo,
+    },
+    Command {
+        name: "schedule",
+        args: "INPUT.TXT [--directed|--undirected]",
+        summary: "steps of a parallel schedule of a directed acyclic graph, its makespan and width,\n\
+                  written to -SCHEDULE.TXT; lines list only the successors",
+        operands: &[Value::InputFile],
+        flags: &[DIRECTED, UNDIRECTED],
+        run: run_schedule,
+    },
+    Command {
+        name: "dedupe-edges",
+        args: "EDGES.TXT --out CLEAN.TXT [--mem SIZE] [--undirected]",
+        summary: "sort an edge list and drop repeated edges within a memory budget (default 64M),\n\
+                  spilling sorted runs to temporary files; --undirected also drops v u after u v",
+        operands: &[Value::InputFile],
+        flags: &[OUT, option("--mem", Value::Text), switch("--undirected")],
+        run: run_dedupe_edges,
+    },
+    Command {
+        name: "dot",
+        args: "INPUT.TXT [--weights W.CSV] [--directed|--undirected]",
+        summary: "the graph in the Graphviz DOT language, written to -GRAPH.dot; one-way\n\
+                  lines make it a digraph",
+        operands: &[Value::InputFile],
+        flags: &[WEIGHTS, MISSING_EDGES, DIRECTED, UNDIRECTED],
+        run: run_dot,
+    },
+    Command {
+        name: "graphml",
+        args: "INPUT.TXT [--weights W.CSV] [--directed|--undirected]",
+        summary: "the graph as a GraphML document, written to -GRAPH.graphml; one-way\n\
+                  lines make its edges directed",
+        operands: &[Value::InputFile],
+        flags: &[WEIGHTS, MISSING_EDGES, DIRECTED, UNDIRECTED],
+        run: run_graphml,
+    },
+    Command {
+        name: "tgf",
+        args: "INPUT.TXT [--weights W.CSV] [--directed|--undirected]",
+        summary: "the graph in the Trivial Graph Format for yEd, written to -GRAPH.tgf; ids\n\
+                  are 1..n in input order",
+        operands: &[Value::InputFile],
+        flags: &[WEIGHTS, MISSING_EDGES, DIRECTED, UNDIRECTED],
+        run: run_tgf,
+    },
+    Command {
+        name: "csv",
+        args: "INPUT.TXT [--weights W.CSV] [--no-header] [--directed|--undirected]",
+        summary: "the edges as source,target[,weight] CSV rows, written to -EDGES.csv",
+        operands: &[Value::InputFile],
+        flags: &[WEIGHTS, MISSING_EDGES, switch("--no-header"), DIRECTED, UNDIRECTED],
+        run: run_csv,
+    },
+    Command {
+        name: "json",
+        args: "INPUT.TXT [--directed|--undirected]",
+        summary: "the graph as a node-link JSON document, written to -GRAPH.json",
+        operands: &[Value::InputFile],
+        flags: &[DIRECTED, UNDIRECTED],
+        run: run_json,
+    },
+    Command {
+        name: "motifs",
+        args: "INPUT.TXT",
+        summary: "counts of the connected 3- and 4-vertex induced subgraphs, written to\n\
+                  -MOTIFS.TXT with a check against triangle_count",
+        operands: &[Value::InputFile],
+        flags: &[],
+        run: run_motifs,
+    },
+    Command {
+        name: "compare-outputs",
+        args: "OLD_DIR NEW_DIR",
+        summary: "semantic differences between two directories of output files",
+        operands: &[Value::Directory, Value::Directory],
+        flags: &[],
+        run: run_compare_outputs,
+    },
+    Command {
+        name: "stats",
+        args: "INPUT.TXT [--what-if A,B]",
+        summary: "structural summary, also written to -STATS.TXT; --what-if previews removing vertices",
+        operands: &[Value::InputFile],
+        flags: &[option("--what-if", Value::Text)],
+        run: run_stats,
+    },
+    Command {
+        name: "orbits",
+        args: "INPUT.TXT [--timeout 5s] [--limit orbits=N]",
+        summary: "vertex orbits under automorphisms, also written to -ORBITS.TXT",
+        operands: &[Value::InputFile],
+        flags: &[TIMEOUT, LIMIT],
+        run: run_orbits,
+    },
+    Command {
+        name: "mis",
+        args: "INPUT.TXT [--timeout 5s]",
+        summary: "greedy and exact maximum independent set, also written to -MIS.TXT",
+        operands: &[Value::InputFile],
+        flags: &[TIMEOUT],
+        run: run_mis,
+    },
+    Command {
+        name: "gomory-hu",
+        args: "INPUT.TXT [--between A,B] [--weights W.CSV]",
+        summary: "Gomory-Hu cut tree, also written to -GOMORYHU.TXT; --between prints one min cut",
+        operands: &[Value::InputFile],
+        flags: &[option("--between", Value::Text), WEIGHTS, MISSING_EDGES],
+        run: run_gomory_hu,
+    },
+    #[cfg(feature = "unstable-algorithms")]
+    Command {
+        name: "coarsen",
+        args: "INPUT.TXT --communities louvain|file PART.TXT [--out Q.TXT]",
+        summary: "quotient graph with one vertex per community",
+        operands: &[Value::InputFile, Value::File],
+        flags: &[option("--communities", Value::Choice(&["louvain", "file"])), OUT],
+        run: run_coarsen,
+    },
+    Command {
+        name: "outputs",
+        args: "INPUT.TXT [--dialect default|course-ps2|PROFILE.TXT]",
+        summary: "the -SET, -DEGREE, -LIST, -LIST-SORTED and -MATRIX files, laid out as\n\
+                  the dialect says",
+        operands: &[Value::InputFile],
+        flags: &[DIALECT],
+        run: run_outputs,
+    },
+    Command {
+        name: "traverse",
+        args: "INPUT.TXT --start START [--dialect default|course-ps2|PROFILE.TXT] [--components]",
+        summary: "the files the interactive traversal writes, without prompting: the core\n\
+                  outputs, -BFS.TXT and -DFS.TXT from START, and with --components\n\
+                  -COMPONENTS.TXT",
+        operands: &[Value::InputFile],
+        flags: &[option("--start", Value::Vertex), DIALECT, switch("--components")],
+        run: run_traverse,
+    },
+    Command {
+        name: "traverse-all",
+        args: "INPUT.TXT [--distinct-starts] [--limit bfs-all=N] [--limit dfs-all=N]",
+        summary: "BFS/DFS from every start into -BFS-ALL.TXT and -DFS-ALL.TXT",
+        operands: &[Value::InputFile],
+        flags: &[switch("--distinct-starts"), LIMIT],
+        run: run_traverse_all,
+    },
+    Command {
+        name: "graph6",
+        args: "INPUT.G6 [--out OUTPUT.CSV] [--metrics order,size,connected,girth,min-degree,max-degree,mean-degree] [--cache N]",
+        summary: "one CSV row of metrics per graph6 line; repeated graphs reuse the metrics of\n\
+                  the N most recently used ones (default 4096, 0 turns it off)",
+        operands: &[Value::File],
+        flags: &[OUT, option("--metrics", Value::Text), option("--cache", Value::Text)],
+        run: run_graph6,
+    },
+    Command {
+        name: "repl",
+        args: "INPUT.TXT [--script FILE]",
+        summary: "answer queries about a graph interactively, or run the commands of a\n\
+                  script; it stops at a failed command unless it says set onerror continue",
+        operands: &[Value::InputFile],
+        flags: &[WEIGHTS, MISSING_EDGES, option("--script", Value::File)],
+        run: run_repl,
+    },
+    Command {
+        name: "serve",
+        args: "INPUT.TXT [--port 7070] [--host 127.0.0.1]",
+        summary: "answer the read-only REPL commands of TCP clients, a blank line ending each answer",
+        operands: &[Value::InputFile],
+        flags: &[option("--port", Value::Text), option("--host", Value::Text), WEIGHTS, MISSING_EDGES],
+        run: run_serve,
+    },
+    Command {
+        name: "completions",
+        args: "bash|zsh|fish [--program NAME]",
+        summary: "completion script for a shell, for the binary installed as NAME (default main)",
+        operands: &[Value::Choice(SHELLS)],
+        flags: &[option("--program", Value::Text)],
+        run: run_completions,
+    },
+    Command {
+        name: "help-json",
+        args: "",
+        summary: "every subcommand with its operands and flags, as JSON",
+        operands: &[],
+        flags: &[],
+        run: run_help_json,
+    },
+    Command {
+        name: "help",
+        args: "",
+        summary: "this summary",
+        operands: &[],
+        flags: &[],
+        run: run_help,
+    },
+];
+
+/// Finds a subcommand by name
+pub fn find_command(name: &str) -> Option<&'static Command> {
+    COMMANDS.iter().find(|command| command.name == name)
+}
+
+/// Column at which the summaries of the usage lines start
+const SUMMARY_COLUMN: usize = 36;
+
+/// Prints the usage summary of the subcommands
+fn print_usage() {
+    eprintln!("Usage:");
+    eprintln!("  {:<w$}(interactive menu)", "main", w = SUMMARY_COLUMN - 2);
+    for command in COMMANDS {
+        let synopsis = format!("  main {} {}", command.name, command.args).trim_end().to_string();
+        let mut lines = command.summary.lines();
+        let first = lines.next().unwrap_or("");
+        // A short synopsis shares its line with a one-line summary
+        if synopsis.len() < SUMMARY_COLUMN - 1 && command.summary.lines().count() == 1 {
+            eprintln!("{:<w$}{}", synopsis, first, w = SUMMARY_COLUMN);
+        } else {
+            eprintln!("{}", synopsis);
+            eprintln!("{:w$}{}", "", first, w = SUMMARY_COLUMN);
+        }
+        for line in lines {
+            eprintln!("{:w$}{}", "", line, w = SUMMARY_COLUMN);
+        }
+    }
+    eprintln!();
+    eprintln!("Vertices can be given by label or as #N, the vertex at index N (from 0) in input order");
+    eprintln!("--limit SECTION=N stops an enumeration output after N items and ends it with a truncation line");
+    eprintln!("--weights W.CSV (path, repl, serve) attaches a,b,weight rows to the edges; --missing-edges error|ignore|add");
+    eprintln!("handles rows naming edges the graph lacks");
+    eprintln!("With --json, errors are printed to stderr as JSON objects with a stable \"code\" field");
+    eprintln!("With --lossy-encoding, invalid UTF-8 in graph files is replaced with U+FFFD and reported");
+}
+
+/// Why a subcommand failed: a mistake in its arguments or an error from the library
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Graph(GraphError),
+    /// The given number of requirements of the check subcommand failed
+    RequirementsFailed(usize),
+    /// The lint subcommand found the given number of errors
+    LintErrors(usize),
+    /// The compare-outputs subcommand found the given number of differences
+    OutputsDiffer(usize),
+    /// A command of the given repl script failed
+    ScriptFailed(String),
+    /// The doctor subcommand found the representations disagreeing at the given vertex
+    Inconsistent(String),
+}
+
+impl CliError {
+    /// Formats the error as a JSON object; usage mistakes have the code E_USAGE
+    fn to_json(&self) -> String {
+        match self {
+            CliError::Usage(message) => format!(
+                "{{\"code\":\"E_USAGE\",\"message\":{}}}",
+                json_string(message)
+            ),
+            CliError::Graph(error) => error.to_json(),
+            CliError::RequirementsFailed(count) => format!(
+                "{{\"code\":\"E_REQUIREMENTS_FAILED\",\"message\":{},\"failures\":{}}}",
+                json_string(&self.to_string()),
+                count
+            ),
+            CliError::LintErrors(count) => format!(
+                "{{\"code\":\"E_LINT_ERRORS\",\"message\":{},\"errors\":{}}}",
+                json_string(&self.to_string()),
+                count
+            ),
+            CliError::OutputsDiffer(count) => format!(
+                "{{\"code\":\"E_OUTPUTS_DIFFER\",\"message\":{},\"differences\":{}}}",
+                json_string(&self.to_string()),
+                count
+            ),
+            CliError::ScriptFailed(script) => format!(
+                "{{\"code\":\"E_SCRIPT_FAILED\",\"message\":{},\"script\":{}}}",
+                json_string(&self.to_string()),
+                json_string(script)
+            ),
+            CliError::Inconsistent(vertex) => format!(
+                "{{\"code\":\"E_INCONSISTENT\",\"message\":{},\"vertex\":{}}}",
+                json_string(&self.to_string()),
+                json_string(vertex)
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::Graph(error) => write!(f, "{}", error),
+            CliError::RequirementsFailed(count) => write!(f, "requirement checks failed: {}", count),
+            CliError::LintErrors(count) => write!(f, "lint errors: {}", count),
+            CliError::OutputsDiffer(count) => write!(f, "output differences: {}", count),
+            CliError::ScriptFailed(script) => write!(f, "script {} had failed commands", script),
+            CliError::Inconsistent(vertex) => write!(f, "representations disagree at vertex {}", vertex),
+        }
+    }
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        CliError::Usage(message)
+    }
+}
+
+impl From<GraphError> for CliError {
+    fn from(error: GraphError) -> Self {
+        CliError::Graph(error)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(error: io::Error) -> Self {
+        CliError::Graph(error.into())
+    }
+}
+
+/// How graph files are decoded: lossily when --lossy-encoding was given
+fn encoding() -> Encoding {
+    if LOSSY_ENCODING.load(AtomicOrdering::Relaxed) {
+        Encoding::Lossy
+    } else {
+        Encoding::Strict
+    }
+}
+
+/// Reads a graph file in strict mode for a subcommand
+fn load_graph(str_input_filename: &str) -> Result<Graph, CliError> {
+    load_graph_with(str_input_filename, Dialect::Strict)
+}
+
+/// Reads a graph file under a dialect for a subcommand, printing its warnings to stderr
+/// Invalid UTF-8 is an error unless --lossy-encoding was given; a *.edges file is
+/// read as an edge list, whatever the dialect
+fn load_graph_with(str_input_filename: &str, dialect: Dialect) -> Result<Graph, CliError> {
+    let encoding = encoding();
+    let mut graph = Graph::new();
+    if is_edge_list_path(str_input_filename) {
+        for warning in graph.read_edge_list_with_encoding(str_input_filename, encoding)? {
+            eprintln!("Warning: {}", warning);
+        }
+        return Ok(graph);
+    }
+    let warnings = graph
+        .read_input_file_with_encoding(str_input_filename, dialect, encoding)
+        .map_err(|e| match e {
+            // Malformed and inconsistent files keep the E_INVALID_FORMAT code scripts know,
+            // naming the file
+            GraphError::Parse { .. } | GraphError::Inconsistent(_) => GraphError::InvalidFormat {
+                format: "adjacency",
+                reason: format!("{}: {}", str_input_filename, e),
+            },
+            // AutoDirection gives up on a file between directed and undirected
+            GraphError::InvalidFormat {
+                format: "adjacency",
+                reason,
+            } if dialect == Dialect::AutoDirection && reason.ends_with("choose the direction explicitly") => {
+                GraphError::InvalidFormat {
+                    format: "adjacency",
+                    reason: format!("{}: {} with --directed or --undirected", str_input_filename, reason),
+                }
+            }
+            GraphError::InvalidFormat {
+                format: "adjacency",
+                reason,
+            } => GraphError::InvalidFormat {
+                format: "adjacency",
+                reason: format!("{}: {}", str_input_filename, reason),
+            },
+            e => e,
+        })?;
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(graph)
+}
+
+/// The file given with --weights and the policy for its rows naming missing edges
+type WeightsArg = Option<(String, MissingEdgePolicy)>;
+
+/// Removes --weights W.CSV and --missing-edges error|ignore|add from the arguments
+fn take_weights(args: &[String]) -> Result<(Vec<String>, WeightsArg), CliError> {
+    let (args, weights) = take_flag(args, "--weights")?;
+    let (args, missing) = take_flag(&args, "--missing-edges")?;
+    let policy = match missing {
+        Some(name) => MissingEdgePolicy::from_name(&name)
+            .ok_or_else(|| format!("unknown --missing-edges {} (expected error, ignore or add)", name))?,
+        None => MissingEdgePolicy::Error,
+    };
+    Ok((args, weights.map(|path| (path, policy))))
+}
+
+/// Loads a graph and attaches the weights of a --weights file, printing its report to stderr
+fn load_weighted_graph(
+    str_input_filename: &str,
+    weights: WeightsArg,
+) -> Result<Graph, CliError> {
+    let mut graph = load_graph(str_input_filename)?;
+    if let Some((path, policy)) = weights {
+        let report = graph.apply_weights_csv_with_encoding(&path, policy, encoding())?;
+        eprint!("{}", report);
+    }
+    Ok(graph)
+}
+
+/// Splits the arguments into positional values and the value of a --name flag
+fn take_flag(args: &[String], name: &str) -> Result<(Vec<String>, Option<String>), String> {
+    let mut positional = Vec::new();
+    let mut value = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == name {
+            let flag_value = iter.next().ok_or_else(|| format!("{} expects a value", name))?;
+            value = Some(flag_value.clone());
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+    Ok((positional, value))
+}
+
+/// Splits the arguments into positional values and the values of every --name flag
+fn take_flag_all(args: &[String], name: &str) -> Result<(Vec<String>, Vec<String>), String> {
+    let mut positional = Vec::new();
+    let mut values = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == name {
+            let flag_value = iter.next().ok_or_else(|| format!("{} expects a value", name))?;
+            values.push(flag_value.clone());
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+    Ok((positional, values))
+}
+
+/// Removes a boolean --name switch from the arguments and reports whether it was present
+fn take_switch(args: &[String], name: &str) -> (Vec<String>, bool) {
+    let present = args.iter().any(|arg| arg == name);
+    let rest = args.iter().filter(|arg| *arg != name).cloned().collect();
+    (rest, present)
+}
+
+/// Removes --directed or --undirected from the arguments, giving the dialect to read with
+/// Without either the direction is inferred with Dialect::AutoDirection
+fn take_direction(args: &[String]) -> Result<(Vec<String>, Dialect), CliError> {
+    let (args, directed) = take_switch(args, "--directed");
+    let (args, undirected) = take_switch(&args, "--undirected");
+    let dialect = match (directed, undirected) {
+        (true, true) => {
+            return Err(CliError::Usage("--directed and --undirected cannot be given together".to_string()))
+        }
+        (true, false) => Dialect::Directed,
+        (false, true) => Dialect::Undirected,
+        (false, false) => Dialect::AutoDirection,
+    };
+    Ok((args, dialect))
+}
+
+/// Parses a duration such as 5s, 500ms or 2m; a bare number is read as seconds
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(pos) => value.split_at(pos),
+        None => (value, "s"),
+    };
+    let amount: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid timeout {}; expected e.g. 5s, 500ms or 2m", value))?;
+    let seconds = match unit {
+        "ms" => amount / 1000.0,
+        "s" => amount,
+        "m" => amount * 60.0,
+        _ => return Err(format!("invalid timeout unit in {}; use ms, s or m", value)),
+    };
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+/// Parses a size in bytes such as 512K, 64M or 2G; a bare number is read as bytes
+fn parse_size(value: &str) -> Result<usize, String> {
+    let invalid = || format!("invalid size {}; expected e.g. 4096, 512K, 64M or 2G", value);
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(pos) => value.split_at(pos),
+        None => (value, ""),
+    };
+    let amount: usize = number.parse().map_err(|_| invalid())?;
+    let scale: usize = match unit {
+        "" | "B" => 1,
+        "K" | "k" => 1 << 10,
+        "M" | "m" => 1 << 20,
+        "G" | "g" => 1 << 30,
+        _ => return Err(invalid()),
+    };
+    amount.checked_mul(scale).ok_or_else(invalid)
+}
+
+/// Builds the search budget given by a --timeout value, unlimited when there is none
+fn timeout_budget(timeout: Option<String>) -> Result<Budget, CliError> {
+    match timeout {
+        Some(value) => Ok(Budget::timeout(parse_timeout(&value)?)),
+        None => Ok(Budget::unlimited()),
+    }
+}
+
+/// Removes every --limit SECTION=N option and collects them into an OutputConfig
+fn take_limits(args: &[String]) -> Result<(Vec<String>, OutputConfig), CliError> {
+    let (args, specs) = take_flag_all(args, "--limit")?;
+    let mut config = OutputConfig::new();
+    for spec in &specs {
+        config.apply_limit_spec(spec)?;
+    }
+    Ok((args, config))
+}
+
+/// Resolves a label or #N index given on the command line to the vertex's label
+fn resolve_label(graph: &Graph, input: &str) -> Result<String, CliError> {
+    let (vertex, warning) = graph.parse_vertex_ref(input);
+    if let Some(warning) = warning {
+        eprintln!("Warning: {}", warning);
+    }
+    let idx = graph.resolve_vertex(vertex)?;
+    Ok(graph.vertices[idx].clone())
+}
+
+/// Prints the shortest path between two vertices and the algorithm chosen for the weights
+fn run_path(args: &[String]) -> Result<(), CliError> {
+    let (args, use_astar) = take_switch(args, "--astar");
+    let (args, coordinates) = take_flag(&args, "--coordinates")?;
+    let (args, weights) = take_weights(&args)?;
+    let (positional, algorithm_name) = take_flag(&args, "--algorithm")?;
+    let [str_input_filename, from, to] = positional.as_slice() else {
+        return Err(CliError::Usage(
+            "path expects INPUT.TXT FROM TO [--algorithm NAME | --astar --coordinates C.CSV] [--weights W.CSV]"
+                .to_string(),
+        ));
+    };
+
+    let mut graph = load_weighted_graph(str_input_filename, weights)?;
+    if let Some(path) = coordinates {
+        graph.apply_coordinates_csv_with_encoding(&path, encoding())?;
+    }
+    let from = resolve_label(&graph, from)?;
+    let to = resolve_label(&graph, to)?;
+    let (from, to) = (from.as_str(), to.as_str());
+
+    if use_astar {
+        let heuristic = graph.euclidean_heuristic(to)?;
+        let (route, stats) = graph
+            .astar_with_stats(from, to, heuristic)?;
+        println!("algorithm: A* (Euclidean heuristic, {} expansions)", stats.expansions);
+        print_route(from, to, route);
+        return Ok(());
+    }
+
+    let algorithm = path_algorithm(&graph, algorithm_name)?;
+    let route = graph
+        .shortest_path_with(from, to, algorithm)?;
+
+    println!("algorithm: {}", algorithm);
+    print_route(from, to, route);
+    Ok(())
+}
+
+/// The algorithm an --algorithm flag names, or the cheapest one the weights allow without it
+fn path_algorithm(graph: &Graph, name: Option<String>) -> Result<PathAlgorithm, CliError> {
+    match name {
+        Some(name) => PathAlgorithm::from_name(&name).ok_or_else(|| {
+            format!(
+                "unknown algorithm {} (expected bfs, 0-1-bfs, uniform-cost or bellman-ford)",
+                name
+            )
+            .into()
+        }),
+        None => Ok(graph.choose_path_algorithm()),
+    }
+}
+
+/// Writes the shortest routes from one start to every vertex to the -SHORTPATH file
+fn run_shortest(args: &[String]) -> Result<(), CliError> {
+    let (args, weights) = take_weights(args)?;
+    let (args, algorithm_name) = take_flag(&args, "--algorithm")?;
+    let [str_input_filename, start] = args.as_slice() else {
+        return Err(CliError::Usage(
+            "shortest expects INPUT.TXT START [--algorithm NAME] [--weights W.CSV]".to_string(),
+        ));
+    };
+
+    let graph = load_weighted_graph(str_input_filename, weights)?;
+    let start = resolve_label(&graph, start)?;
+    let algorithm = path_algorithm(&graph, algorithm_name)?;
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_shortest(&str_base_filename, &start, algorithm)?;
+    println!("Wrote {}-SHORTPATH.TXT", str_base_filename);
+    Ok(())
+}
+
+/// Writes the BFS layers of one start to the -LAYERS file
+fn run_layers(args: &[String]) -> Result<(), CliError> {
+    let [str_input_filename, start] = args else {
+        return Err(CliError::Usage("layers expects INPUT.TXT START".to_string()));
+    };
+
+    let graph = load_graph(str_input_filename)?;
+    let start = resolve_label(&graph, start)?;
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_layers(&str_base_filename, &start)?;
+    println!("Wrote {}-LAYERS.TXT", str_base_filename);
+    Ok(())
+}
+
+/// Prints the usage summary
+fn run_help(_args: &[String]) -> Result<(), CliError> {
+    print_usage();
+    Ok(())
+}
+
+/// Prints the completion script of a shell, built from the command table
+fn run_completions(args: &[String]) -> Result<(), CliError> {
+    let (args, program) = take_flag(args, "--program")?;
+    let program = program.as_deref().unwrap_or("main");
+    let script = match args.as_slice() {
+        [shell] if shell == "bash" => bash_script(program, COMMANDS),
+        [shell] if shell == "zsh" => zsh_script(program, COMMANDS),
+        [shell] if shell == "fish" => fish_script(program, COMMANDS),
+        _ => {
+            return Err(CliError::Usage(format!(
+                "completions expects {} [--program NAME]",
+                SHELLS.join("|")
+            )))
+        }
+    };
+    print!("{}", script);
+    Ok(())
+}
+
+/// Prints the command table as JSON, for tools wrapping the binary
+fn run_help_json(args: &[String]) -> Result<(), CliError> {
+    if !args.is_empty() {
+        return Err(CliError::Usage("help-json expects no arguments".to_string()));
+    }
+    println!("{}", help_json("main", COMMANDS));
+    Ok(())
+}
+
+/// Prints the cost and labels of a path, or that the target cannot be reached
+fn print_route(from: &str, to: &str, route: Option<Route>) {
+    match route {
+        Some((cost, path)) => {
+            println!("cost: {}", cost);
+            println!("path: {}", path.join("->"));
+        }
+        None => println!("{} is unreachable from {}", to, from),
+    }
+}
+
+/// Prints the number of edge- or vertex-disjoint paths between two vertices and the paths themselves
+fn run_disjoint_paths(args: &[String]) -> Result<(), CliError> {
+    let (args, vertex) = take_switch(args, "--vertex");
+    let (positional, edge) = take_switch(&args, "--edge");
+    let [str_input_filename, a, b] = positional.as_slice() else {
+        return Err(CliError::Usage("disjoint-paths expects INPUT.TXT A B --vertex|--edge".to_string()));
+    };
+    if vertex == edge {
+        return Err(CliError::Usage("disjoint-paths expects exactly one of --vertex or --edge".to_string()));
+    }
+
+    let graph = load_graph(str_input_filename)?;
+    let a = resolve_label(&graph, a)?;
+    let b = resolve_label(&graph, b)?;
+    let (a, b) = (a.as_str(), b.as_str());
+    let paths = if vertex {
+        graph.vertex_disjoint_paths_with_paths(a, b)
+    } else {
+        graph.edge_disjoint_paths_with_paths(a, b)
+    }?;
+
+    let kind = if vertex { "vertex" } else { "edge" };
+    println!("{}-disjoint paths from {} to {}: {}", kind, a, b, paths.len());
+    for path in &paths {
+        println!("{}", path.join("->"));
+    }
+    Ok(())
+}
+
+/// Checks whether H is a subgraph of G and writes the -SUBGRAPH report
+/// With --swap-if-larger, a G smaller than H is checked against H instead
+fn run_subgraph(args: &[String]) -> Result<(), CliError> {
+    let (args, swap_if_larger) = take_switch(args, "--swap-if-larger");
+    let [str_file_g, str_file_h] = args.as_slice() else {
+        return Err(CliError::Usage("subgraph expects G.TXT H.TXT [--swap-if-larger]".to_string()));
+    };
+
+    let graph_g = load_graph(str_file_g)?;
+    let graph_h = load_graph(str_file_h)?;
+    for label in &graph_h.vertices[..graph_h.n_vertices] {
+        if graph_g.find_vertex_idx(label).is_none() {
+            let suggestions = graph_g.closest_labels(label, DEFAULT_SUGGESTIONS);
+            if !suggestions.is_empty() {
+                eprintln!("Note: {} is not in {}; did you mean {}?", label, str_file_g, suggestions.join(", "));
+            }
+        }
+    }
+    let swapped = swap_if_larger && graph_g.suggest_swap(&graph_h);
+    graph_g
+        .produce_subgraph_output_with(str_file_g, str_file_h, &graph_h, swapped)?;
+
+    let (container, candidate) = if swapped {
+        (&graph_h, &graph_g)
+    } else {
+        (&graph_g, &graph_h)
+    };
+    if container.check_is_subgraph(candidate) {
+        println!("subgraph: yes");
+    } else {
+        println!("subgraph: no (matched {})", container.subgraph_report(candidate));
+    }
+    if swapped {
+        println!("(inputs swapped: {} checked against {})", str_file_g, str_file_h);
+    }
+    Ok(())
+}
+
+/// Picks the format given by a --from/--to flag, or guesses it from the file extension
+fn format_of(flag: Option<String>, path: &str) -> Result<GraphFormat, CliError> {
+    match flag {
+        Some(name) => GraphFormat::from_name(&name)
+            .ok_or_else(|| CliError::Usage(format!("unknown format {} (expected adjacency, dimacs, metis or matrix)", name))),
+        None => GraphFormat::from_path(path).ok_or_else(|| {
+            CliError::Usage(format!("cannot tell the format of {}; use --from/--to", path))
+        }),
+    }
+}
+
+/// Converts a graph file between the adjacency, DIMACS and METIS formats
+/// DIMACS and METIS files get a sidecar .labels file that keeps the vertex labels
+fn run_convert(args: &[String]) -> Result<(), CliError> {
+    let (args, from) = take_flag(args, "--from")?;
+    let (args, to) = take_flag(&args, "--to")?;
+    let [str_input_filename, str_output_filename] = args.as_slice() else {
+        return Err(CliError::Usage("convert expects INPUT OUTPUT [--from FORMAT] [--to FORMAT]".to_string()));
+    };
+    let from = format_of(from, str_input_filename)?;
+    let to = format_of(to, str_output_filename)?;
+
+    let graph = if from == GraphFormat::Adjacency {
+        load_graph(str_input_filename)?
+    } else {
+        let (graph, warnings) = Graph::read_file_as(from, str_input_filename, encoding())?;
+        for warning in &warnings {
+            eprintln!("Warning: {}", warning);
+        }
+        graph
+    };
+
+    std::fs::write(str_output_filename, graph.write_as(to))?;
+    println!("Wrote {} ({})", str_output_filename, to);
+    if to.uses_label_sidecar() {
+        let sidecar = labels_path(str_output_filename);
+        std::fs::write(&sidecar, graph.labels_text())?;
+        println!("Wrote {}", sidecar);
+    }
+    Ok(())
+}
+
+/// Explains how an edge is represented and which outputs show it
+/// The file is read leniently, since the problems being explained would fail the strict checks
+fn run_explain(args: &[String]) -> Result<(), CliError> {
+    let [str_input_filename, a, b] = args else {
+        return Err(CliError::Usage("explain expects INPUT.TXT A B".to_string()));
+    };
+    let mut graph = Graph::new();
+    let warnings = graph.read_input_file_with(str_input_filename, Dialect::InferCount)?;
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    print!("{}", graph.explain_edge(a, b));
+    Ok(())
+}
+
+/// Checks structural requirements and lists every failure
+fn run_check(args: &[String]) -> Result<(), CliError> {
+    let (args, specs) = take_flag_all(args, "--require")?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("check expects INPUT.TXT --require SPEC [--require SPEC ...]".to_string()));
+    };
+    if specs.is_empty() {
+        return Err(CliError::Usage("check expects at least one --require".to_string()));
+    }
+
+    let requirements = specs
+        .iter()
+        .map(|spec| Requirement::parse(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    let graph = load_graph(str_input_filename)?;
+    match graph.require(&requirements) {
+        Ok(()) => {
+            println!("ok: {} requirements hold", requirements.len());
+            Ok(())
+        }
+        Err(failures) => {
+            for failure in &failures {
+                println!("FAIL: {}", failure);
+            }
+            Err(CliError::RequirementsFailed(failures.len()))
+        }
+    }
+}
+
+/// Shrinks a graph to a small one on which the --holds conditions still hold
+fn run_minimize(args: &[String]) -> Result<(), CliError> {
+    let (args, specs) = take_flag_all(args, "--holds")?;
+    let (args, output) = take_flag(&args, "--out")?;
+    let (args, timeout) = take_flag(&args, "--timeout")?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage(
+            "minimize expects INPUT.TXT --holds [not-]SPEC [--holds ...] [--out MIN.TXT] [--timeout DURATION]".to_string(),
+        ));
+    };
+    if specs.is_empty() {
+        return Err(CliError::Usage("minimize expects at least one --holds".to_string()));
+    }
+
+    // A not- prefix asks for the requirement to fail, like not-bipartite
+    let conditions = specs
+        .iter()
+        .map(|spec| match spec.strip_prefix("not-") {
+            Some(spec) => Requirement::parse(spec).map(|requirement| (requirement, false)),
+            None => Requirement::parse(spec).map(|requirement| (requirement, true)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let holds = |graph: &Graph| {
+        conditions
+            .iter()
+            .all(|(requirement, expected)| graph.require(std::slice::from_ref(requirement)).is_ok() == *expected)
+    };
+
+    let budget = timeout_budget(timeout)?;
+    let graph = load_graph(str_input_filename)?;
+    if !holds(&graph) {
+        return Err(CliError::Usage(format!(
+            "the conditions do not hold on {}, so there is nothing to minimize",
+            str_input_filename
+        )));
+    }
+    let minimal = graph.minimize(holds, budget);
+
+    let output = output.unwrap_or_else(|| format!("{}-MIN.TXT", Graph::get_base_filename(str_input_filename)));
+    std::fs::write(&output, minimal.to_input_text())?;
+    println!(
+        "Wrote {} ({} of {} vertices, {} of {} edges)",
+        output,
+        minimal.n_vertices,
+        graph.n_vertices,
+        minimal.edge_count(),
+        graph.edge_count()
+    );
+    Ok(())
+}
+
+/// Rewrites a graph file in canonical form, to --out, in place with --write, or to stdout
+fn run_normalize(args: &[String]) -> Result<(), CliError> {
+    let (args, output) = take_flag(args, "--out")?;
+    let (args, in_place) = take_switch(&args, "--write");
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("normalize expects INPUT.TXT [--out NORM.TXT | --write]".to_string()));
+    };
+    if in_place && output.is_some() {
+        return Err(CliError::Usage("normalize expects at most one of --out or --write".to_string()));
+    }
+
+    let text = load_graph(str_input_filename)?.to_canonical_text();
+    match output.or_else(|| in_place.then(|| str_input_filename.clone())) {
+        Some(output) => {
+            std::fs::write(&output, text)?;
+            println!("Wrote {}", output);
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+/// Builds a graph of a registered generator family, printing it or writing it to --out
+#[cfg(feature = "unstable-algorithms")]
+fn run_generate(args: &[String]) -> Result<(), CliError> {
+    let (args, output) = take_flag(args, "--out")?;
+    let Some((name, params)) = args.split_first() else {
+        let names: Vec<&str> = generators::registered().iter().map(|generator| generator.name).collect();
+        return Err(CliError::Usage(format!(
+            "generate expects NAME [P1 P2 ...] [--out F.TXT], NAME one of {}",
+            names.join(", ")
+        )));
+    };
+    let params = params
+        .iter()
+        .map(|param| {
+            param
+                .parse::<usize>()
+                .map_err(|_| CliError::Usage(format!("generate expects whole-number parameters, got {}", param)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let text = generators::by_name(name, &params)?.to_input_text();
+    match output {
+        Some(output) => {
+            std::fs::write(&output, text)?;
+            println!("Wrote {}", output);
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+/// Writes the example corpus into a directory
+#[cfg(feature = "unstable-algorithms")]
+fn run_examples(args: &[String]) -> Result<(), CliError> {
+    let [dir] = args else {
+        return Err(CliError::Usage("examples expects OUTDIR".to_string()));
+    };
+    for path in generate_corpus(Path::new(dir))? {
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+/// Writes the nearest of several sources for every vertex to the -NEAREST file
+fn run_nearest(args: &[String]) -> Result<(), CliError> {
+    let (args, sources) = take_flag(args, "--sources")?;
+    let ([str_input_filename], Some(sources)) = (args.as_slice(), sources) else {
+        return Err(CliError::Usage("nearest expects INPUT.TXT --sources A,B,C".to_string()));
+    };
+
+    let graph = load_graph(str_input_filename)?;
+    let sources = sources
+        .split(',')
+        .map(|source| resolve_label(&graph, source.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let sources: Vec<&str> = sources.iter().map(String::as_str).collect();
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_nearest(&str_base_filename, &sources)?;
+    println!("Wrote {}-NEAREST.TXT", str_base_filename);
+    Ok(())
+}
+
+/// Writes the two sides of the graph, or an odd cycle when there are none
+fn run_bipartite(args: &[String]) -> Result<(), CliError> {
+    let [str_input_filename] = args else {
+        return Err(CliError::Usage("bipartite expects INPUT.TXT".to_string()));
+    };
+
+    let graph = load_graph(str_input_filename)?;
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_bipartite(&str_base_filename)?;
+    println!("Wrote {}-BIPARTITE.TXT", str_base_filename);
+    Ok(())
+}
+
+/// Writes the betweenness report, exact or sampled as the size of the graph decides
+#[cfg(feature = "unstable-algorithms")]
+fn run_centrality(args: &[String]) -> Result<(), CliError> {
+    let (args, samples) = take_flag(args, "--samples")?;
+    let (args, seed) = take_flag(&args, "--seed")?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("centrality expects INPUT.TXT [--samples N] [--seed S]".to_string()));
+    };
+    let samples = match samples {
+        Some(samples) => samples
+            .parse()
+            .map_err(|_| format!("--samples expects a whole number, got {}", samples))?,
+        None => DEFAULT_BETWEENNESS_SAMPLES,
+    };
+    let seed = match seed {
+        Some(seed) => seed
+            .parse()
+            .map_err(|_| format!("--seed expects a whole number, got {}", seed))?,
+        None => 0,
+    };
+
+    let graph = load_graph(str_input_filename)?;
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_centrality(&str_base_filename, samples, seed)?;
+    println!("Wrote {}-CENTRALITY.TXT", str_base_filename);
+    Ok(())
+}
+
+/// Prints why one vertex ranks where it does in the centrality report
+#[cfg(feature = "unstable-algorithms")]
+fn run_explain_centrality(args: &[String]) -> Result<(), CliError> {
+    let [str_input_filename, label] = args else {
+        return Err(CliError::Usage("explain-centrality expects INPUT.TXT X".to_string()));
+    };
+
+    let graph = load_graph(str_input_filename)?;
+    print!("{}", graph.explain_centrality(label)?);
+    Ok(())
+}
+
+/// Writes the table of per-vertex metrics, every one but strength unless weights are given
+fn run_metrics(args: &[String]) -> Result<(), CliError> {
+    let (args, weights) = take_weights(args)?;
+    let (args, metrics) = take_flag(&args, "--metrics")?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage(
+            "metrics expects INPUT.TXT [--metrics LIST] [--weights W.CSV]".to_string(),
+        ));
+    };
+
+    let which: Vec<VertexMetric> = match metrics {
+        Some(names) => names
+            .split(',')
+            .map(|name| {
+                VertexMetric::from_name(name.trim()).ok_or_else(|| format!("unknown metric {}", name.trim()))
+            })
+            .collect::<Result<_, _>>()?,
+        None => VertexMetric::ALL
+            .into_iter()
+            .filter(|&metric| metric != VertexMetric::Strength || weights.is_some())
+            .collect(),
+    };
+
+    let graph = load_weighted_graph(str_input_filename, weights)?;
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_metrics(&str_base_filename, &which)?;
+    println!("Wrote {}-METRICS.csv", str_base_filename);
+    Ok(())
+}
+
+/// Writes the minimum spanning forest grown from a vertex
+fn run_mst(args: &[String]) -> Result<(), CliError> {
+    let (args, weights) = take_weights(args)?;
+    let [str_input_filename, start] = args.as_slice() else {
+        return Err(CliError::Usage("mst expects INPUT.TXT START [--weights W.CSV]".to_string()));
+    };
+
+    let graph = load_weighted_graph(str_input_filename, weights)?;
+    let start = resolve_label(&graph, start)?;
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_mst(&str_base_filename, &start)?;
+    println!("Wrote {}-MST.TXT", str_base_filename);
+    Ok(())
+}
+
+/// Writes the graph as a node-link JSON document, directed when the file lists one-way edges
+fn run_json(args: &[String]) -> Result<(), CliError> {
+    let (args, dialect) = take_direction(args)?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("json expects INPUT.TXT [--directed|--undirected]".to_string()));
+    };
+
+    let graph = load_graph_with(str_input_filename, dialect)?;
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_json(&str_base_filename)?;
+    println!("Wrote {}-GRAPH.json", str_base_filename);
+    Ok(())
+}
+
+/// Writes the motif spectrum of the graph
+fn run_motifs(args: &[String]) -> Result<(), CliError> {
+    let [str_input_filename] = args else {
+        return Err(CliError::Usage("motifs expects INPUT.TXT".to_string()));
+    };
+
+    let graph = load_graph(str_input_filename)?;
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_motifs(&str_base_filename)?;
+    println!("Wrote {}-MOTIFS.TXT", str_base_filename);
+    Ok(())
+}
+
+/// Writes the longest-path layers and the critical path of a directed acyclic graph
+/// The direction is inferred from the file, so lines may list only the successors
+fn run_dag_layers(args: &[String]) -> Result<(), CliError> {
+    let (args, weights) = take_weights(args)?;
+    let (args, dialect) = take_direction(&args)?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("dag-layers expects INPUT.TXT [--weights W.CSV] [--directed|--undirected]".to_string()));
+    };
+
+    let mut graph = load_graph_with(str_input_filename, dialect)?;
+    if let Some((path, policy)) = weights {
+        let report = graph.apply_weights_csv_with_encoding(&path, policy, encoding())?;
+        eprint!("{}", report);
+    }
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_layers_dag(&str_base_filename)?;
+    println!("Wrote {}-LAYERS-DAG.TXT", str_base_filename);
+    Ok(())
+}
+
+/// Writes the topological order of a directed acyclic graph
+/// The direction is inferred from the file, as for dag-layers
+fn run_topo(args: &[String]) -> Result<(), CliError> {
+    let (args, dialect) = take_direction(args)?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("topo expects INPUT.TXT [--directed|--undirected]".to_string()));
+    };
+
+    let graph = load_graph_with(str_input_filename, dialect)?;
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_topo(&str_base_filename)?;
+    println!("Wrote {}-TOPO.TXT", str_base_filename);
+    Ok(())
+}
+
+/// Writes the generations of a directed acyclic graph as the steps of a schedule
+/// The direction is inferred from the file, as for dag-layers
+fn run_schedule(args: &[String]) -> Result<(), CliError> {
+    let (args, dialect) = take_direction(args)?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("schedule expects INPUT.TXT [--directed|--undirected]".to_string()));
+    };
+
+    let graph = load_graph_with(str_input_filename, dialect)?;
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_schedule(&str_base_filename)?;
+    println!("Wrote {}-SCHEDULE.TXT", str_base_filename);
+    Ok(())
+}
+
+/// Writes an edge list sorted and without repeated edges, sorting externally within --mem
+fn run_dedupe_edges(args: &[String]) -> Result<(), CliError> {
+    let (args, output) = take_flag(args, "--out")?;
+    let (args, mem) = take_flag(&args, "--mem")?;
+    let (args, undirected) = take_switch(&args, "--undirected");
+    let ([str_input_filename], Some(output)) = (args.as_slice(), output) else {
+        return Err(CliError::Usage(
+            "dedupe-edges expects EDGES.TXT --out CLEAN.TXT [--mem SIZE] [--undirected]".to_string(),
+        ));
+    };
+    let budget = match mem {
+        Some(mem) => parse_size(&mem)?,
+        None => DEFAULT_MEM_BUDGET_BYTES,
+    };
+    let direction = if undirected { Direction::Undirected } else { Direction::Directed };
+
+    let stats = dedupe_edge_list_with(Path::new(str_input_filename), Path::new(&output), budget, direction)?;
+    eprint!("{}", stats);
+    println!("Wrote {}", output);
+    Ok(())
+}
+
+/// Writes the graph in the DOT language, directed when the file lists one-way edges
+fn run_dot(args: &[String]) -> Result<(), CliError> {
+    let (args, weights) = take_weights(args)?;
+    let (args, dialect) = take_direction(&args)?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("dot expects INPUT.TXT [--weights W.CSV] [--directed|--undirected]".to_string()));
+    };
+
+    let mut graph = load_graph_with(str_input_filename, dialect)?;
+    if let Some((path, policy)) = weights {
+        let report = graph.apply_weights_csv_with_encoding(&path, policy, encoding())?;
+        eprint!("{}", report);
+    }
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_dot(&str_base_filename)?;
+    println!("Wrote {}-GRAPH.dot", str_base_filename);
+    Ok(())
+}
+
+/// Writes the graph as GraphML, with directed edges when the file lists one-way edges
+fn run_graphml(args: &[String]) -> Result<(), CliError> {
+    let (args, weights) = take_weights(args)?;
+    let (args, dialect) = take_direction(&args)?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("graphml expects INPUT.TXT [--weights W.CSV] [--directed|--undirected]".to_string()));
+    };
+
+    let mut graph = load_graph_with(str_input_filename, dialect)?;
+    if let Some((path, policy)) = weights {
+        let report = graph.apply_weights_csv_with_encoding(&path, policy, encoding())?;
+        eprint!("{}", report);
+    }
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_graphml(&str_base_filename)?;
+    println!("Wrote {}-GRAPH.graphml", str_base_filename);
+    Ok(())
+}
+
+/// Writes the graph in the Trivial Graph Format, every arc listed when the file lists one-way edges
+fn run_tgf(args: &[String]) -> Result<(), CliError> {
+    let (args, weights) = take_weights(args)?;
+    let (args, dialect) = take_direction(&args)?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("tgf expects INPUT.TXT [--weights W.CSV] [--directed|--undirected]".to_string()));
+    };
+
+    let mut graph = load_graph_with(str_input_filename, dialect)?;
+    if let Some((path, policy)) = weights {
+        let report = graph.apply_weights_csv_with_encoding(&path, policy, encoding())?;
+        eprint!("{}", report);
+    }
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_output_file_tgf(&str_base_filename)?;
+    println!("Wrote {}-GRAPH.tgf", str_base_filename);
+    Ok(())
+}
+
+/// Loads a graph and reports whether its matrix, lists and degree counts agree
+fn run_doctor(args: &[String]) -> Result<(), CliError> {
+    let [str_input_filename] = args else {
+        return Err(CliError::Usage("doctor expects INPUT.TXT".to_string()));
+    };
+
+    let graph = load_graph_with(str_input_filename, Dialect::InferCount)?;
+    let digest = graph.representation_digest();
+    print!("{}", digest);
+    match digest.first_mismatch() {
+        None => Ok(()),
+        Some(DigestMismatch::Neighbors { vertex, .. } | DigestMismatch::Degree { vertex, .. }) => {
+            Err(CliError::Inconsistent(vertex))
+        }
+    }
+}
+
+/// Writes the edges as a CSV edge list, one-way arcs kept when the file lists one-way edges
+fn run_csv(args: &[String]) -> Result<(), CliError> {
+    let (args, weights) = take_weights(args)?;
+    let (args, dialect) = take_direction(&args)?;
+    let (args, no_header) = take_switch(&args, "--no-header");
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("csv expects INPUT.TXT [--weights W.CSV] [--no-header] [--directed|--undirected]".to_string()));
+    };
+
+    let mut graph = load_graph_with(str_input_filename, dialect)?;
+    if let Some((path, policy)) = weights {
+        let report = graph.apply_weights_csv_with_encoding(&path, policy, encoding())?;
+        eprint!("{}", report);
+    }
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    let output = format!("{}-EDGES.csv", str_base_filename);
+    if no_header {
+        graph.write_csv_with(BufWriter::new(File::create(&output)?), false)?;
+    } else {
+        graph.produce_output_file_csv(&str_base_filename)?;
+    }
+    println!("Wrote {}", output);
+    Ok(())
+}
+
+/// Reports the problems of an input file; with --fix, writes a copy with the safe fixes applied
+fn run_lint(args: &[String]) -> Result<(), CliError> {
+    let (args, fix) = take_switch(args, "--fix");
+    let (args, infer_count) = take_switch(&args, "--infer-count");
+    let (args, output) = take_flag(&args, "--out")?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("lint expects INPUT.TXT [--infer-count] [--fix [--out FIXED.TXT]]".to_string()));
+    };
+    let dialect = if infer_count { Dialect::InferCount } else { Dialect::Strict };
+
+    let mut report = lint(Path::new(str_input_filename), dialect)?;
+    print!("{}", report);
+    if fix {
+        let text = std::fs::read_to_string(str_input_filename).map_err(file_error(str_input_filename))?;
+        let output =
+            output.unwrap_or_else(|| format!("{}-FIXED.TXT", Graph::get_base_filename(str_input_filename)));
+        let fixed = fix_text(&text, dialect);
+        std::fs::write(&output, &fixed)?;
+        report = lint_text(&fixed, Dialect::Strict);
+        println!("Wrote {}; {} findings left", output, report.findings.len());
+        print!("{}", report);
+    }
+    match report.error_count() {
+        0 => Ok(()),
+        errors => Err(CliError::LintErrors(errors)),
+    }
+}
+
+/// Compares two directories of output files by meaning and lists the differences
+fn run_compare_outputs(args: &[String]) -> Result<(), CliError> {
+    let [dir_a, dir_b] = args else {
+        return Err(CliError::Usage("compare-outputs expects OLD_DIR NEW_DIR".to_string()));
+    };
+
+    let comparison = compare_outputs(Path::new(dir_a), Path::new(dir_b))?;
+    print!("{}", comparison);
+    match comparison.findings.len() + comparison.only_in_a.len() + comparison.only_in_b.len() {
+        0 => Ok(()),
+        differences => Err(CliError::OutputsDiffer(differences)),
+    }
+}
+
+/// Prints the structural summary of a graph and writes it to the -STATS file
+fn run_stats(args: &[String]) -> Result<(), CliError> {
+    let (args, what_if) = take_flag(args, "--what-if")?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("stats expects INPUT.TXT [--what-if A,B]".to_string()));
+    };
+
+    let graph = load_graph(str_input_filename)?;
+    print!("{}", graph.stats());
+    if let Some(labels) = what_if {
+        let labels: Vec<&str> = labels.split(',').map(str::trim).collect();
+        print!("{}", graph.what_if_remove(&labels));
+    }
+    graph
+        .produce_output_file_stats(&Graph::get_base_filename(str_input_filename))?;
+    Ok(())
+}
+
+/// Prints the vertex orbits of a graph and writes them to the -ORBITS file
+fn run_orbits(args: &[String]) -> Result<(), CliError> {
+    let (args, timeout) = take_flag(args, "--timeout")?;
+    let (args, config) = take_limits(&args)?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("orbits expects INPUT.TXT [--timeout DURATION] [--limit orbits=N]".to_string()));
+    };
+
+    let budget = timeout_budget(timeout)?;
+    let graph = load_graph(str_input_filename)?;
+    let orbits = graph.produce_output_file_orbits_with(
+        &Graph::get_base_filename(str_input_filename),
+        &budget,
+        &config,
+    )?;
+    for orbit in orbits {
+        println!("{{{}}}", orbit.join(","));
+    }
+    Ok(())
+}
+
+/// Prints the greedy and the exact maximum independent set and writes them to the -MIS file
+fn run_mis(args: &[String]) -> Result<(), CliError> {
+    let (args, timeout) = take_flag(args, "--timeout")?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("mis expects INPUT.TXT [--timeout DURATION]".to_string()));
+    };
+
+    let budget = timeout_budget(timeout)?;
+    let graph = load_graph(str_input_filename)?;
+    let greedy = graph.maximal_independent_set();
+    let exact = graph.produce_output_file_mis(&Graph::get_base_filename(str_input_filename), budget)?;
+    println!("greedy: {{{}}} (size {})", greedy.join(","), greedy.len());
+    println!("exact: {}", exact);
+    Ok(())
+}
+
+/// Writes the Gomory-Hu tree of a graph to the -GOMORYHU file, or prints one pairwise minimum cut
+fn run_gomory_hu(args: &[String]) -> Result<(), CliError> {
+    let (args, between) = take_flag(args, "--between")?;
+    let (args, weights) = take_weights(&args)?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage("gomory-hu expects INPUT.TXT [--between A,B] [--weights W.CSV]".to_string()));
+    };
+
+    let graph = load_weighted_graph(str_input_filename, weights)?;
+    if let Some(between) = between {
+        let Some((a, b)) = between.split_once(',') else {
+            return Err(CliError::Usage(format!("--between expects A,B, got {}", between)));
+        };
+        let (a, b) = (resolve_label(&graph, a.trim())?, resolve_label(&graph, b.trim())?);
+        println!("min cut {}-{}: {}", a, b, graph.min_cut_between(&a, &b)?);
+    }
+    graph.produce_output_file_gomory_hu(&Graph::get_base_filename(str_input_filename))?;
+    for edge in graph.gomory_hu()?.ordered_edges() {
+        println!("{} {} {}", edge.source, edge.target, edge.weight.unwrap_or(0.0));
+    }
+    Ok(())
+}
+
+/// Writes the quotient graph over Louvain communities or the blocks of a partition file
+#[cfg(feature = "unstable-algorithms")]
+fn run_coarsen(args: &[String]) -> Result<(), CliError> {
+    let (args, communities) = take_flag(args, "--communities")?;
+    let (args, output) = take_flag(&args, "--out")?;
+    let usage = "coarsen expects INPUT.TXT --communities louvain|file PART.TXT [--out Q.TXT]";
+
+    let (str_input_filename, partition) = match (communities.as_deref(), args.as_slice()) {
+        (Some("louvain"), [input]) => (input, None),
+        (Some("file"), [input, part]) => (input, Some(read_partition(part)?)),
+        _ => return Err(CliError::Usage(usage.to_string())),
+    };
+
+    let graph = load_graph(str_input_filename)?;
+    let partition = match partition {
+        Some(partition) => partition,
+        None => graph.louvain_communities()?,
+    };
+    let quotient = graph.quotient(&partition, None)?;
+
+    let output = output
+        .unwrap_or_else(|| format!("{}-QUOTIENT.TXT", Graph::get_base_filename(str_input_filename)));
+    std::fs::write(&output, quotient.to_input_text())?;
+    for block in &partition {
+        println!("{{{}}}", block.join(","));
+    }
+    println!("modularity: {:.4}", graph.modularity(&partition)?);
+    println!("Wrote {} ({} vertices)", output, quotient.n_vertices);
+    Ok(())
+}
+
+/// Writes the core output files, in the layout of a built-in or loaded dialect
+fn run_outputs(args: &[String]) -> Result<(), CliError> {
+    let (args, dialect) = take_flag(args, "--dialect")?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage(
+            "outputs expects INPUT.TXT [--dialect default|course-ps2|PROFILE.TXT]".to_string(),
+        ));
+    };
+
+    let mut config = OutputConfig::new();
+    if let Some(spec) = dialect {
+        config = config.with_dialect(DialectProfile::load(&spec)?);
+    }
+    let graph = load_graph(str_input_filename)?;
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_core_outputs(&str_base_filename, &config)?;
+    println!(
+        "Wrote the -SET, -DEGREE, -LIST, -LIST-SORTED and -MATRIX files of {} ({} dialect)",
+        str_base_filename,
+        config.dialect().name
+    );
+    Ok(())
+}
+
+/// Writes what the interactive graph traversal writes, the start given by --start
+/// The start is checked before any file is written, so an unknown one leaves none behind
+fn run_traverse(args: &[String]) -> Result<(), CliError> {
+    let (args, start) = take_flag(args, "--start")?;
+    let (args, dialect) = take_flag(&args, "--dialect")?;
+    let (args, components) = take_switch(&args, "--components");
+    let ([str_input_filename], Some(str_start_vertex)) = (args.as_slice(), start) else {
+        return Err(CliError::Usage(
+            "traverse expects INPUT.TXT --start START [--dialect default|course-ps2|PROFILE.TXT] [--components]"
+                .to_string(),
+        ));
+    };
+
+    let mut config = OutputConfig::new();
+    if let Some(spec) = dialect {
+        config = config.with_dialect(DialectProfile::load(&spec)?);
+    }
+    let graph = load_graph(str_input_filename)?;
+    // The start may be a label or #N for the vertex at index N in input order
+    let (start, warning) = graph.parse_vertex_ref(&str_start_vertex);
+    if let Some(warning) = warning {
+        eprintln!("Warning: {}", warning);
+    }
+    graph.resolve_vertex(start)?;
+
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_core_outputs(&str_base_filename, &config)?;
+    if components {
+        graph.produce_output_file_components(&str_base_filename)?;
+    }
+    graph.produce_traversal_files(&str_base_filename, start)?;
+    println!("All output files of {} written", str_base_filename);
+    Ok(())
+}
+
+/// Writes BFS and DFS traversals from every start, or one start per orbit
+fn run_traverse_all(args: &[String]) -> Result<(), CliError> {
+    let (args, distinct_starts) = take_switch(args, "--distinct-starts");
+    let (args, config) = take_limits(&args)?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage(
+            "traverse-all expects INPUT.TXT [--distinct-starts] [--limit bfs-all=N] [--limit dfs-all=N]".to_string(),
+        ));
+    };
+
+    let graph = load_graph(str_input_filename)?;
+    let str_base_filename = Graph::get_base_filename(str_input_filename);
+    graph.produce_all_traversals_with(&str_base_filename, distinct_starts, &config)?;
+    println!(
+        "Wrote {}-BFS-ALL.TXT and {}-DFS-ALL.TXT",
+        str_base_filename, str_base_filename
+    );
+    Ok(())
+}
+
+/// Streams a file of graph6 strings into a CSV of per-graph metrics
+fn run_graph6(args: &[String]) -> Result<(), CliError> {
+    let (args, output) = take_flag(args, "--out")?;
+    let (args, metrics) = take_flag(&args, "--metrics")?;
+    let (args, cache) = take_flag(&args, "--cache")?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage(
+            "graph6 expects INPUT.G6 [--out OUTPUT.CSV] [--metrics LIST] [--cache N]".to_string(),
+        ));
+    };
+
+    let output = output
+        .unwrap_or_else(|| format!("{}-GRAPH6.CSV", Graph::get_base_filename(str_input_filename)));
+    let mut cfg = StreamConfig::new(&output);
+    cfg.encoding = encoding();
+    if let Some(names) = metrics {
+        cfg.metrics = names
+            .split(',')
+            .map(|name| {
+                StreamMetric::from_name(name.trim())
+                    .ok_or_else(|| format!("unknown metric {}", name.trim()))
+            })
+            .collect::<Result<_, _>>()?;
+    }
+    if let Some(entries) = cache {
+        cfg.cache_entries = entries
+            .parse()
+            .map_err(|_| CliError::Usage(format!("--cache expects a whole number of graphs, got {}", entries)))?;
+    }
+
+    let file = File::open(str_input_filename).map_err(file_error(str_input_filename))?;
+    let summary = analyze_graph6_stream(BufReader::new(file), &cfg)?;
+
+    for (line, reason) in &summary.malformed {
+        eprintln!("Warning: line {} skipped: {}", line, reason);
+    }
+    println!(
+        "Analyzed {} graphs into {} (cache: {} hits, {} misses)",
+        summary.graphs, output, summary.cache_hits, summary.cache_misses
+    );
+    Ok(())
+}
+
+/// Loads a graph and answers commands about it from stdin, or from a --script file
+fn run_repl(args: &[String]) -> Result<(), CliError> {
+    let (args, weights) = take_weights(args)?;
+    let (args, script) = take_flag(&args, "--script")?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage(
+            "repl expects INPUT.TXT [--weights W.CSV] [--script FILE]".to_string(),
+        ));
+    };
+
+    let graph = load_weighted_graph(str_input_filename, weights)?;
+    println!("{}", graph.summary_line(str_input_filename));
+    let mut repl = Repl::new(&graph);
+    if let Some(script) = script {
+        if repl.run_script(&script, &mut io::stdout())? == Flow::Failed {
+            return Err(CliError::ScriptFailed(script));
+        }
+        return Ok(());
+    }
+    let stdin = io::stdin();
+    repl.run(&mut stdin.lock(), &mut io::stdout())?;
+    Ok(())
+}
+
+/// Serves REPL queries about one loaded graph to TCP clients
+fn run_serve(args: &[String]) -> Result<(), CliError> {
+    let (args, port) = take_flag(args, "--port")?;
+    let (args, host) = take_flag(&args, "--host")?;
+    let (args, weights) = take_weights(&args)?;
+    let [str_input_filename] = args.as_slice() else {
+        return Err(CliError::Usage(
+            "serve expects INPUT.TXT [--port 7070] [--host 127.0.0.1] [--weights W.CSV]".to_string(),
+        ));
+    };
+    let port: u16 = match port {
+        Some(port) => port
+            .parse()
+            .map_err(|_| CliError::Usage(format!("--port expects a port number, got {}", port)))?,
+        None => 7070,
+    };
+    let host = host.unwrap_or_else(|| "127.0.0.1".to_string());
+
+    let graph = load_weighted_graph(str_input_filename, weights)?;
+    let listener = TcpListener::bind((host.as_str(), port))?;
+    println!("{}", graph.summary_line(str_input_filename));
+    println!("Listening on {}", listener.local_addr()?);
+    serve(Arc::new(graph), listener)?;
+    Ok(())
+}
+
+/// Runs the subcommand named by the first argument and returns the process exit code
+/// With --json anywhere in the arguments, errors are printed to stderr as JSON objects
+pub fn run(args: &[String]) -> i32 {
+    let (args, json) = take_switch(args, "--json");
+    let (args, lossy) = take_switch(&args, "--lossy-encoding");
+    LOSSY_ENCODING.store(lossy, AtomicOrdering::Relaxed);
+    let Some(command) = args.first() else {
+        print_usage();
+        return 1;
+    };
+    // --help and -h are spellings of the help subcommand
+    let name = match command.as_str() {
+        "--help" | "-h" => "help",
+        name => name,
+    };
+    let result = match find_command(name) {
+        Some(command) => (command.run)(&args[1..]),
+        None => Err(CliError::Usage(format!("unknown command {}", name))),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(error) if json => {
+            eprintln!("{}", error.to_json());
+            1
+        }
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn every_registered_command_dispatches_to_its_entry() {
+        for (i, command) in COMMANDS.iter().enumerate() {
+            assert!(
+                COMMANDS[..i].iter().all(|earlier| earlier.name != command.name),
+                "{} is registered twice",
+                command.name
+            );
+            let found = find_command(command.name).expect("registered commands are found");
+            assert!(std::ptr::eq(found, command), "{} dispatches elsewhere", command.name);
+            for (j, flag) in command.flags.iter().enumerate() {
+                assert!(flag.name.starts_with("--"), "{} {}", command.name, flag.name);
+                assert!(
+                    command.flags[..j].iter().all(|earlier| earlier.name != flag.name),
+                    "{} lists {} twice",
+                    command.name,
+                    flag.name
+                );
+            }
+        }
+        assert!(find_command("nope").is_none());
+        assert_eq!(run(&args(&["nope"])), 1);
+        assert_eq!(run(&args(&["--help"])), 0);
+    }
+
+    #[test]
+    fn completions_and_help_json_list_every_command() {
+        let json = help_json("main", COMMANDS);
+        let bash = bash_script("main", COMMANDS);
+        let zsh = zsh_script("main", COMMANDS);
+        let fish = fish_script("main", COMMANDS);
+        for command in COMMANDS {
+            assert!(json.contains(&format!("{{\"name\":{},", json_string(command.name))), "{}", command.name);
+            for script in [&bash, &zsh, &fish] {
+                assert!(script.contains(command.name), "{} missing from a script", command.name);
+            }
+        }
+        assert_eq!(json.matches(",\"args\":").count(), COMMANDS.len());
+    }
+}
@@ -0,0 +1,299 @@
+// src/cliques.rs - Maximum cliques and independent sets
+//
+// Edges are taken as undirected (a matrix cell in either direction joins two
+// vertices) and self-loops are ignored. The exact searches are Bron–Kerbosch
+// with pivoting over vertex bitsets of 64 vertices per word; an independent set of a graph is a clique of
+// its complement, so both problems share the one search.
+use crate::budget::{Budget, BudgetMeter};
+use crate::density::DensityStrategy;
+use crate::error::GraphError;
+use crate::Graph;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+
+/// The best vertex set an exact search found, and whether it is proven optimal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetSearch {
+    /// Labels in alphabetical order
+    pub vertices: Vec<String>,
+    /// False when the budget ran out before the search finished
+    pub optimal: bool,
+}
+
+impl fmt::Display for SetSearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{{}}} (size {}", self.vertices.join(","), self.vertices.len())?;
+        if self.optimal {
+            write!(f, ")")
+        } else {
+            write!(f, ", best found before the budget ran out)")
+        }
+    }
+}
+
+/// A set of vertex indices, one bit per vertex
+#[derive(Clone, PartialEq, Eq)]
+struct VertexSet {
+    words: Vec<u64>,
+}
+
+impl VertexSet {
+    fn empty(n: usize) -> Self {
+        VertexSet {
+            words: vec![0; n / 64 + usize::from(n & 63 != 0)],
+        }
+    }
+
+    /// The vertices 0..n
+    fn full(n: usize) -> Self {
+        let mut set = VertexSet::empty(n);
+        for v in 0..n {
+            set.insert(v);
+        }
+        set
+    }
+
+    fn insert(&mut self, v: usize) {
+        self.words[v / 64] |= 1 << (v % 64);
+    }
+
+    fn remove(&mut self, v: usize) {
+        self.words[v / 64] &= !(1 << (v % 64));
+    }
+
+    fn contains(&self, v: usize) -> bool {
+        self.words[v / 64] >> (v % 64) & 1 == 1
+    }
+
+    fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    fn intersection(&self, other: &VertexSet) -> VertexSet {
+        VertexSet {
+            words: self.words.iter().zip(&other.words).map(|(a, b)| a & b).collect(),
+        }
+    }
+
+    fn difference(&self, other: &VertexSet) -> VertexSet {
+        VertexSet {
+            words: self.words.iter().zip(&other.words).map(|(a, b)| a & !b).collect(),
+        }
+    }
+
+    fn union(&self, other: &VertexSet) -> VertexSet {
+        VertexSet {
+            words: self.words.iter().zip(&other.words).map(|(a, b)| a | b).collect(),
+        }
+    }
+
+    /// Counts the members shared with other without building the intersection
+    fn common(&self, other: &VertexSet) -> usize {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+
+    /// Members in increasing order
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.words.len() * 64).filter(move |&v| self.contains(v))
+    }
+}
+
+/// Bron–Kerbosch state: the neighbor bitsets and the best clique so far
+struct CliqueSearch<'a, 'm> {
+    graph: &'a Graph,
+    neighbors: Vec<VertexSet>,
+    best: Vec<String>,
+    meter: &'a mut BudgetMeter<'m>,
+    exhausted: bool,
+}
+
+impl CliqueSearch<'_, '_> {
+    /// Keeps the clique if it is larger than the best, or as large and alphabetically smaller
+    fn offer(&mut self, clique: &VertexSet) {
+        let mut labels: Vec<String> = clique.iter().map(|v| self.graph.vertices[v].clone()).collect();
+        labels.sort();
+        if labels.len() > self.best.len() || (labels.len() == self.best.len() && labels < self.best) {
+            self.best = labels;
+        }
+    }
+
+    fn expand(&mut self, clique: VertexSet, mut candidates: VertexSet, mut excluded: VertexSet) {
+        if self.exhausted {
+            return;
+        }
+        if !self.meter.tick() {
+            self.exhausted = true;
+            return;
+        }
+        if candidates.is_empty() && excluded.is_empty() {
+            self.offer(&clique);
+            return;
+        }
+        // Ties with the best must still be reached, so only strictly smaller branches are cut
+        if clique.len() + candidates.len() < self.best.len() {
+            return;
+        }
+
+        let pivot = candidates
+            .union(&excluded)
+            .iter()
+            .max_by_key(|&u| candidates.common(&self.neighbors[u]))
+            .unwrap_or(0);
+        let branches = candidates.difference(&self.neighbors[pivot]);
+        for v in branches.iter() {
+            let mut grown = clique.clone();
+            grown.insert(v);
+            let next_candidates = candidates.intersection(&self.neighbors[v]);
+            let next_excluded = excluded.intersection(&self.neighbors[v]);
+            self.expand(grown, next_candidates, next_excluded);
+            candidates.remove(v);
+            excluded.insert(v);
+        }
+    }
+}
+
+impl Graph {
+    /// Checks whether u and v are joined in either direction; a vertex is never adjacent to itself
+    fn adjacent(&self, u: usize, v: usize) -> bool {
+        u != v && (self.adj_matrix[u][v] == 1 || self.adj_matrix[v][u] == 1)
+    }
+
+    /// Returns the complement: the same vertices, joined exactly where this graph is not
+    pub fn complement(&self) -> Graph {
+        let n = self.n_vertices;
+        let rows: Vec<(String, Vec<String>)> = (0..n)
+            .map(|u| {
+                let neighbors = (0..n)
+                    .filter(|&v| v != u && !self.adjacent(u, v))
+                    .map(|v| self.vertices[v].clone())
+                    .collect();
+                (self.vertices[u].clone(), neighbors)
+            })
+            .collect();
+        let mut graph = Graph::new();
+        graph.rebuild_from_rows(&rows);
+        graph
+    }
+
+    /// Finds a largest clique, the alphabetically smallest among equally large ones
+    /// Each search node counts as one step against the budget
+    pub fn maximum_clique_within(&self, budget: &Budget) -> SetSearch {
+        self.maximum_clique_from(budget, Vec::new())
+    }
+
+    /// Runs the clique search starting from a known clique, which is returned if nothing beats it
+    fn maximum_clique_from(&self, budget: &Budget, known: Vec<String>) -> SetSearch {
+        let n = self.n_vertices;
+        let neighbors: Vec<VertexSet> = (0..n)
+            .map(|u| {
+                let mut set = VertexSet::empty(n);
+                for v in (0..n).filter(|&v| self.adjacent(u, v)) {
+                    set.insert(v);
+                }
+                set
+            })
+            .collect();
+        let mut meter = budget.meter();
+        let mut search = CliqueSearch {
+            graph: self,
+            neighbors,
+            best: known,
+            meter: &mut meter,
+            exhausted: false,
+        };
+        search.expand(VertexSet::empty(n), VertexSet::full(n), VertexSet::empty(n));
+        SetSearch {
+            vertices: search.best,
+            optimal: !search.exhausted,
+        }
+    }
+
+    /// Finds a largest independent set as a largest clique of the complement
+    /// Ties go to the alphabetically smallest set. The search starts from the greedy set, so
+    /// even when the budget runs out (SetSearch::optimal is false) the result is no worse
+    pub fn maximum_independent_set_exact(&self, budget: Budget) -> SetSearch {
+        self.complement().maximum_clique_from(&budget, self.maximal_independent_set())
+    }
+
+    /// Builds an independent set greedily, always taking a vertex of least remaining degree
+    /// Ties go to the alphabetically first vertex; the labels are returned in alphabetical order
+    /// Dense graphs count the degrees through the complement; see density_strategy
+    pub fn maximal_independent_set(&self) -> Vec<String> {
+        self.maximal_independent_set_with(self.density_strategy())
+    }
+
+    /// Builds the set of maximal_independent_set with the given strategy
+    ///
+    /// The complement-based form lists the vertices each one is not adjacent to, in
+    /// one pass over the matrix, and takes the remaining degree as the remaining
+    /// vertices less those, so a round costs the missing edges of the remaining
+    /// vertices rather than the square of their number.
+    pub fn maximal_independent_set_with(&self, strategy: DensityStrategy) -> Vec<String> {
+        if strategy == DensityStrategy::Complement {
+            return self.maximal_independent_set_by_complement();
+        }
+        let mut remaining: Vec<usize> = self.sort_vertices();
+        let mut chosen = Vec::new();
+        while !remaining.is_empty() {
+            let degree = |u: usize| remaining.iter().filter(|&&v| self.adjacent(u, v)).count();
+            let Some(&pick) = remaining.iter().min_by_key(|&&u| degree(u)) else {
+                break;
+            };
+            chosen.push(self.vertices[pick].clone());
+            remaining.retain(|&v| v != pick && !self.adjacent(pick, v));
+        }
+        chosen.sort();
+        chosen
+    }
+
+    /// The greedy set of maximal_independent_set, counting degrees through the complement
+    fn maximal_independent_set_by_complement(&self) -> Vec<String> {
+        let n = self.n_vertices;
+        let non_neighbors: Vec<Vec<usize>> = (0..n)
+            .map(|u| (0..n).filter(|&v| v != u && !self.adjacent(u, v)).collect())
+            .collect();
+        let mut remaining: Vec<usize> = self.sort_vertices();
+        let mut in_remaining = vec![true; n];
+        let mut chosen = Vec::new();
+        while !remaining.is_empty() {
+            let others = remaining.len() - 1;
+            let degree = |u: usize| others - non_neighbors[u].iter().filter(|&&v| in_remaining[v]).count();
+            let Some(&pick) = remaining.iter().min_by_key(|&&u| degree(u)) else {
+                break;
+            };
+            chosen.push(self.vertices[pick].clone());
+            // Only the vertices pick is not adjacent to stay
+            let mut stays = vec![false; n];
+            for &v in &non_neighbors[pick] {
+                stays[v] = in_remaining[v];
+            }
+            remaining.retain(|&v| stays[v]);
+            in_remaining = stays;
+        }
+        chosen.sort();
+        chosen
+    }
+
+    /// To create/write an output file comparing the greedy and the exact maximum independent set
+    pub fn produce_output_file_mis(&self, base_name: &str, budget: Budget) -> Result<SetSearch, GraphError> {
+        let greedy = self.maximal_independent_set();
+        let exact = self.maximum_independent_set_exact(budget);
+        let output_name = Self::get_output_filename(base_name, "-MIS");
+        let mut fp = File::create(output_name)?;
+
+        writeln!(fp, "greedy: {{{}}} (size {})", greedy.join(","), greedy.len())?;
+        writeln!(fp, "exact: {}", exact)?;
+        writeln!(fp, "gap: {}", exact.vertices.len().saturating_sub(greedy.len()))?;
+
+        Ok(exact)
+    }
+}
@@ -0,0 +1,261 @@
+// src/communities.rs - Vertex partitions: modularity, Louvain communities and quotient graphs
+use crate::error::{file_error, GraphError};
+use crate::weights::WeightProfile;
+use crate::Graph;
+use std::fs;
+
+/// Symmetric weight matrix of the undirected graph underlying the adjacency matrix
+type WeightMatrix = Vec<Vec<f64>>;
+
+/// Sums the entries of a weight matrix
+fn total_weight(w: &WeightMatrix) -> f64 {
+    w.iter().map(|row| row.iter().sum::<f64>()).sum()
+}
+
+/// Moves single nodes between communities while that raises modularity
+/// Returns the community of each node, numbered from 0 in order of first appearance
+fn louvain_level(w: &WeightMatrix) -> Vec<usize> {
+    let n = w.len();
+    let m2 = total_weight(w);
+    let strength: Vec<f64> = w.iter().map(|row| row.iter().sum()).collect();
+    let mut community: Vec<usize> = (0..n).collect();
+    let mut total: Vec<f64> = strength.clone();
+
+    let mut moved = true;
+    while moved {
+        moved = false;
+        for i in 0..n {
+            let current = community[i];
+            total[current] -= strength[i];
+
+            let mut links = vec![0.0; n];
+            for j in (0..n).filter(|&j| j != i) {
+                links[community[j]] += w[i][j];
+            }
+            // Staying put wins ties, so the passes always come to an end
+            let gain = |c: usize| links[c] - total[c] * strength[i] / m2;
+            let mut best = current;
+            for (c, &link) in links.iter().enumerate() {
+                if link > 0.0 && gain(c) > gain(best) + 1e-12 {
+                    best = c;
+                }
+            }
+
+            total[best] += strength[i];
+            if best != current {
+                community[i] = best;
+                moved = true;
+            }
+        }
+    }
+
+    let mut renumber: Vec<Option<usize>> = vec![None; n];
+    let mut next = 0;
+    community
+        .iter()
+        .map(|&c| {
+            *renumber[c].get_or_insert_with(|| {
+                next += 1;
+                next - 1
+            })
+        })
+        .collect()
+}
+
+/// Reads a partition file: each non-empty line lists the vertices of one block
+pub fn read_partition(path: &str) -> Result<Vec<Vec<String>>, GraphError> {
+    let text = fs::read_to_string(path).map_err(file_error(path))?;
+    Ok(text
+        .lines()
+        .map(|line| line.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+        .filter(|block| !block.is_empty())
+        .collect())
+}
+
+impl Graph {
+    /// Builds the symmetric weight matrix used by the community routines
+    fn community_weights(&self) -> Result<WeightMatrix, GraphError> {
+        let n = self.n_vertices;
+        let mut w = vec![vec![0.0; n]; n];
+        for (i, row) in w.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                let weight = if self.adj_matrix[i][j] == 1 {
+                    self.weight_at(i, j)
+                } else if self.adj_matrix[j][i] == 1 {
+                    self.weight_at(j, i)
+                } else {
+                    continue;
+                };
+                if weight < 0.0 {
+                    return Err(GraphError::InvalidWeight {
+                        from: self.vertices[i].clone(),
+                        to: self.vertices[j].clone(),
+                        weight,
+                        reason: "communities need nonnegative weights",
+                    });
+                }
+                *cell = weight;
+            }
+        }
+        Ok(w)
+    }
+
+    /// Checks that a partition puts every vertex in exactly one nonempty block
+    /// Returns the block index of each vertex
+    pub fn partition_blocks(&self, partition: &[Vec<String>]) -> Result<Vec<usize>, GraphError> {
+        let mut block_of: Vec<Option<usize>> = vec![None; self.n_vertices];
+        for (b, block) in partition.iter().enumerate() {
+            if block.is_empty() {
+                return Err(GraphError::InvalidPartition(format!("block {} is empty", b + 1)));
+            }
+            for label in block {
+                let idx = self.require_vertex_idx(label)?;
+                if block_of[idx].is_some() {
+                    return Err(GraphError::InvalidPartition(format!(
+                        "vertex {} appears in more than one block",
+                        label
+                    )));
+                }
+                block_of[idx] = Some(b);
+            }
+        }
+
+        block_of
+            .iter()
+            .enumerate()
+            .map(|(idx, block)| {
+                block.ok_or_else(|| {
+                    GraphError::InvalidPartition(format!(
+                        "vertex {} is in no block",
+                        self.vertices[idx]
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Computes the modularity of a partition of the vertices
+    /// Edge weights count as edge multiplicities; a graph without edges has modularity 0
+    pub fn modularity(&self, partition: &[Vec<String>]) -> Result<f64, GraphError> {
+        let block_of = self.partition_blocks(partition)?;
+        let w = self.community_weights()?;
+        let m2 = total_weight(&w);
+        if m2 == 0.0 {
+            return Ok(0.0);
+        }
+
+        let strength: Vec<f64> = w.iter().map(|row| row.iter().sum()).collect();
+        let mut q = 0.0;
+        for i in 0..self.n_vertices {
+            for j in 0..self.n_vertices {
+                if block_of[i] == block_of[j] {
+                    q += w[i][j] - strength[i] * strength[j] / m2;
+                }
+            }
+        }
+        Ok(q / m2)
+    }
+
+    /// Detects communities with the Louvain method, visiting vertices in alphabetical order
+    /// Each community is sorted alphabetically and communities are ordered by their smallest label
+    pub fn louvain_communities(&self) -> Result<Vec<Vec<String>>, GraphError> {
+        let sorted = self.sort_vertices();
+        let full = self.community_weights()?;
+        // Work on the vertices in alphabetical order so the result does not depend on input order
+        let mut w: WeightMatrix = sorted
+            .iter()
+            .map(|&i| sorted.iter().map(|&j| full[i][j]).collect())
+            .collect();
+        let mut community_of: Vec<usize> = (0..sorted.len()).collect();
+
+        if total_weight(&w) > 0.0 {
+            loop {
+                let level = louvain_level(&w);
+                let count = level.iter().max().map_or(0, |&c| c + 1);
+                if count == w.len() {
+                    break;
+                }
+                for c in community_of.iter_mut() {
+                    *c = level[*c];
+                }
+                // Collapse every community into one node for the next level
+                let mut coarse = vec![vec![0.0; count]; count];
+                for (i, row) in w.iter().enumerate() {
+                    for (j, &weight) in row.iter().enumerate() {
+                        coarse[level[i]][level[j]] += weight;
+                    }
+                }
+                w = coarse;
+            }
+        }
+
+        let count = community_of.iter().max().map_or(0, |&c| c + 1);
+        let mut communities: Vec<Vec<String>> = vec![Vec::new(); count];
+        for (pos, &i) in sorted.iter().enumerate() {
+            communities[community_of[pos]].push(self.vertices[i].clone());
+        }
+        communities.sort();
+        Ok(communities)
+    }
+
+    /// Builds the quotient graph with one vertex per block of the partition
+    /// Two blocks are adjacent whenever an edge joins a vertex of one to a vertex of the other.
+    /// Blocks are named by labels when given, otherwise by their alphabetically first vertex.
+    /// When this graph has weights, each quotient edge weighs the number of edges it stands for.
+    pub fn quotient(&self, partition: &[Vec<String>], labels: Option<&[String]>) -> Result<Graph, GraphError> {
+        let block_of = self.partition_blocks(partition)?;
+        let names: Vec<String> = match labels {
+            Some(labels) if labels.len() != partition.len() => {
+                return Err(GraphError::InvalidPartition(format!(
+                    "{} labels given for {} blocks",
+                    labels.len(),
+                    partition.len()
+                )));
+            }
+            Some(labels) => {
+                if let Some(pos) = (1..labels.len()).find(|&i| labels[..i].contains(&labels[i])) {
+                    return Err(GraphError::InvalidPartition(format!(
+                        "label {} is given to more than one block",
+                        labels[pos]
+                    )));
+                }
+                labels.to_vec()
+            }
+            None => partition
+                .iter()
+                .map(|block| block.iter().min().cloned().unwrap_or_default())
+                .collect(),
+        };
+
+        let k = partition.len();
+        let mut crossing = vec![vec![0usize; k]; k];
+        for i in 0..self.n_vertices {
+            for j in 0..self.n_vertices {
+                if self.adj_matrix[i][j] == 1 && block_of[i] != block_of[j] {
+                    crossing[block_of[i]][block_of[j]] += 1;
+                }
+            }
+        }
+
+        let rows: Vec<(String, Vec<String>)> = (0..k)
+            .map(|a| {
+                let neighbors = (0..k)
+                    .filter(|&b| crossing[a][b] > 0)
+                    .map(|b| names[b].clone())
+                    .collect();
+                (names[a].clone(), neighbors)
+            })
+            .collect();
+
+        let mut coarse = Graph::new();
+        coarse.rebuild_from_rows(&rows);
+        if self.weight_profile() != WeightProfile::Unweighted {
+            for (a, row) in crossing.iter().enumerate() {
+                for (b, &count) in row.iter().enumerate().filter(|(_, &count)| count > 0) {
+                    coarse.store_weight(a, b, count as f64);
+                }
+            }
+        }
+        Ok(coarse)
+    }
+}
@@ -0,0 +1,407 @@
+// src/compare.rs - Semantic comparison of two directories of output files
+//
+// Files are paired by name and parsed according to their suffix, so that two runs
+// of different versions can be compared by meaning instead of by bytes: -SET as
+// sets of vertices and edges, -DEGREE and -MATRIX as tables keyed by label, and the
+// traversal outputs token by token. Line endings, trailing blank lines and the
+// amount of padding between columns are ignored everywhere.
+use crate::error::{file_error, GraphError};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// How an output file is parsed for comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputKind {
+    /// -SET: the V(...) and E(...) sets
+    Set,
+    /// -DEGREE: one label and degree per line
+    Degree,
+    /// -MATRIX: a header row of labels, then one labeled row of cells per vertex
+    Matrix,
+    /// -BFS, -DFS and their -ALL variants: lines of vertex tokens
+    Traversal,
+    /// Anything else, compared line by line
+    Lines,
+}
+
+impl OutputKind {
+    /// Picks the parser for an output file from the suffix of its name
+    pub fn from_file_name(file_name: &str) -> OutputKind {
+        let stem = file_name.strip_suffix(".TXT").unwrap_or(file_name);
+        if stem.ends_with("-SET") {
+            OutputKind::Set
+        } else if stem.ends_with("-DEGREE") {
+            OutputKind::Degree
+        } else if stem.ends_with("-MATRIX") {
+            OutputKind::Matrix
+        } else if ["-BFS", "-DFS", "-BFS-ALL", "-DFS-ALL", "-TOPO"].iter().any(|suffix| stem.ends_with(suffix)) {
+            OutputKind::Traversal
+        } else {
+            OutputKind::Lines
+        }
+    }
+}
+
+/// One semantic difference between the two versions of an output file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputFinding {
+    pub file: String,
+    pub message: String,
+}
+
+/// The result of compare_outputs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputComparison {
+    /// Files present in both directories, by name
+    pub compared: Vec<String>,
+    pub only_in_a: Vec<String>,
+    pub only_in_b: Vec<String>,
+    pub findings: Vec<OutputFinding>,
+}
+
+impl OutputComparison {
+    /// Checks whether both directories hold the same files with the same meaning
+    pub fn is_identical(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty() && self.findings.is_empty()
+    }
+}
+
+/// Lists the output files (.TXT) of a directory, by name
+fn output_files(dir: &Path) -> Result<BTreeSet<String>, GraphError> {
+    let mut names = BTreeSet::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if entry.file_type()?.is_file() && name.ends_with(".TXT") {
+            names.insert(name);
+        }
+    }
+    Ok(names)
+}
+
+/// Splits text into lines without line endings or trailing whitespace, dropping trailing blank lines
+fn content_lines(text: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// The items between the braces of a `V(g)={...}` or `E(g)={...}` line
+///
+/// Edges are unordered pairs, so each is keyed with its endpoints in text order:
+/// (B,A) in one file and (A,B) in the other are the same edge.
+fn set_items(line: &str) -> (String, BTreeSet<String>) {
+    let name = line
+        .find('(')
+        .zip(line.find(")="))
+        .map(|(open, close)| line[open + 1..close].to_string())
+        .unwrap_or_default();
+    let inner = line
+        .find("={")
+        .map(|start| line[start + 2..].trim_end_matches('}'))
+        .unwrap_or("");
+    let mut items = BTreeSet::new();
+    if line.starts_with('E') {
+        // Edges hold a comma themselves, so split on the closing parentheses
+        for edge in inner.split(')') {
+            let edge = edge.trim_start_matches(',').trim();
+            let Some((u, v)) = edge.strip_prefix('(').and_then(|pair| pair.split_once(',')) else {
+                if !edge.is_empty() {
+                    items.insert(format!("{})", edge));
+                }
+                continue;
+            };
+            let (u, v) = (u.trim(), v.trim());
+            items.insert(format!("({},{})", u.min(v), u.max(v)));
+        }
+    } else {
+        items.extend(inner.split(',').map(str::trim).filter(|v| !v.is_empty()).map(String::from));
+    }
+    (name, items)
+}
+
+fn compare_sets(a: &str, b: &str, findings: &mut Vec<String>) {
+    let (lines_a, lines_b) = (content_lines(a), content_lines(b));
+    for (what, prefix) in [("vertex", 'V'), ("edge", 'E')] {
+        let find = |lines: &[&str]| {
+            lines
+                .iter()
+                .find(|line| line.starts_with(prefix))
+                .map(|line| set_items(line))
+                .unwrap_or_default()
+        };
+        let ((name_a, items_a), (name_b, items_b)) = (find(&lines_a), find(&lines_b));
+        if what == "vertex" && name_a != name_b {
+            findings.push(format!("graph name differs: {} vs {}", name_a, name_b));
+        }
+        for item in items_a.difference(&items_b) {
+            findings.push(format!("{} {} present only in A", what, item));
+        }
+        for item in items_b.difference(&items_a) {
+            findings.push(format!("{} {} present only in B", what, item));
+        }
+    }
+}
+
+/// Reads the `label degree` lines of a -DEGREE file
+fn degree_table(text: &str) -> BTreeMap<String, String> {
+    content_lines(text)
+        .iter()
+        .filter_map(|line| {
+            let mut tokens = line.split_whitespace();
+            Some((tokens.next()?.to_string(), tokens.next().unwrap_or("").to_string()))
+        })
+        .collect()
+}
+
+fn compare_degrees(a: &str, b: &str, findings: &mut Vec<String>) {
+    let (table_a, table_b) = (degree_table(a), degree_table(b));
+    for (label, degree_a) in &table_a {
+        match table_b.get(label) {
+            None => findings.push(format!("vertex {} present only in A", label)),
+            Some(degree_b) if degree_b != degree_a => {
+                findings.push(format!("degree of {} differs: {} vs {}", label, degree_a, degree_b))
+            }
+            Some(_) => {}
+        }
+    }
+    for label in table_b.keys().filter(|label| !table_a.contains_key(*label)) {
+        findings.push(format!("vertex {} present only in B", label));
+    }
+}
+
+/// Reads the cells of a -MATRIX file, keyed by (row label, column label)
+fn matrix_table(text: &str) -> (BTreeSet<String>, BTreeMap<(String, String), String>) {
+    let lines = content_lines(text);
+    let Some((header, rows)) = lines.split_first() else {
+        return Default::default();
+    };
+    let columns: Vec<&str> = header.split_whitespace().collect();
+    let mut labels: BTreeSet<String> = columns.iter().map(|label| label.to_string()).collect();
+    let mut cells = BTreeMap::new();
+    for row in rows {
+        let mut tokens = row.split_whitespace();
+        let Some(label) = tokens.next() else {
+            continue;
+        };
+        labels.insert(label.to_string());
+        for (column, cell) in columns.iter().zip(tokens) {
+            cells.insert((label.to_string(), column.to_string()), cell.to_string());
+        }
+    }
+    (labels, cells)
+}
+
+fn compare_matrices(a: &str, b: &str, findings: &mut Vec<String>) {
+    let ((labels_a, cells_a), (labels_b, cells_b)) = (matrix_table(a), matrix_table(b));
+    for label in labels_a.difference(&labels_b) {
+        findings.push(format!("vertex {} present only in A", label));
+    }
+    for label in labels_b.difference(&labels_a) {
+        findings.push(format!("vertex {} present only in B", label));
+    }
+    // A cell missing on one side is only reported when both of its vertices are
+    // shared; otherwise the vertex finding above already covers it
+    let shared = |(row, column): &(String, String)| {
+        [row, column].iter().all(|label| labels_a.contains(*label) && labels_b.contains(*label))
+    };
+    for (key, cell_a) in &cells_a {
+        match cells_b.get(key) {
+            Some(cell_b) if cell_a != cell_b => {
+                findings.push(format!("cell [{}][{}] differs: {} vs {}", key.0, key.1, cell_a, cell_b))
+            }
+            None if shared(key) => findings.push(format!("cell [{}][{}] present only in A", key.0, key.1)),
+            _ => {}
+        }
+    }
+    for key in cells_b.keys().filter(|key| !cells_a.contains_key(*key) && shared(key)) {
+        findings.push(format!("cell [{}][{}] present only in B", key.0, key.1));
+    }
+}
+
+fn compare_traversals(a: &str, b: &str, findings: &mut Vec<String>) {
+    let (lines_a, lines_b) = (content_lines(a), content_lines(b));
+    for (i, (line_a, line_b)) in lines_a.iter().zip(&lines_b).enumerate() {
+        let tokens_a: Vec<&str> = line_a.split_whitespace().collect();
+        let tokens_b: Vec<&str> = line_b.split_whitespace().collect();
+        let position = tokens_a.iter().zip(&tokens_b).position(|(x, y)| x != y);
+        match position {
+            Some(k) => findings.push(format!(
+                "line {}: order differs at position {}: {} vs {}",
+                i + 1,
+                k + 1,
+                tokens_a[k],
+                tokens_b[k]
+            )),
+            None if tokens_a.len() != tokens_b.len() => findings.push(format!(
+                "line {}: {} tokens vs {}",
+                i + 1,
+                tokens_a.len(),
+                tokens_b.len()
+            )),
+            None => {}
+        }
+    }
+    push_extra_lines(&lines_a, &lines_b, findings);
+}
+
+fn compare_lines(a: &str, b: &str, findings: &mut Vec<String>) {
+    let (lines_a, lines_b) = (content_lines(a), content_lines(b));
+    for (i, (line_a, line_b)) in lines_a.iter().zip(&lines_b).enumerate() {
+        let words_a: Vec<&str> = line_a.split_whitespace().collect();
+        let words_b: Vec<&str> = line_b.split_whitespace().collect();
+        if words_a != words_b {
+            findings.push(format!("line {} differs: \"{}\" vs \"{}\"", i + 1, line_a.trim(), line_b.trim()));
+        }
+    }
+    push_extra_lines(&lines_a, &lines_b, findings);
+}
+
+/// Reports the lines one file has past the end of the other
+fn push_extra_lines(lines_a: &[&str], lines_b: &[&str], findings: &mut Vec<String>) {
+    let common = lines_a.len().min(lines_b.len());
+    for (i, line) in lines_a.iter().enumerate().skip(common) {
+        findings.push(format!("line {} present only in A: \"{}\"", i + 1, line.trim()));
+    }
+    for (i, line) in lines_b.iter().enumerate().skip(common) {
+        findings.push(format!("line {} present only in B: \"{}\"", i + 1, line.trim()));
+    }
+}
+
+/// Compares the texts of two versions of one output file
+pub fn compare_output_texts(kind: OutputKind, a: &str, b: &str) -> Vec<String> {
+    let mut findings = Vec::new();
+    match kind {
+        OutputKind::Set => compare_sets(a, b, &mut findings),
+        OutputKind::Degree => compare_degrees(a, b, &mut findings),
+        OutputKind::Matrix => compare_matrices(a, b, &mut findings),
+        OutputKind::Traversal => compare_traversals(a, b, &mut findings),
+        OutputKind::Lines => compare_lines(a, b, &mut findings),
+    }
+    findings
+}
+
+/// Compares the output files of two directories, e.g. written by two versions of the tool
+///
+/// Files are paired by name; a file in only one directory is listed as such. Each
+/// pair is parsed by the OutputKind of its suffix and compared by meaning, so a
+/// reordered -SET or a differently padded -DEGREE file raises no finding.
+pub fn compare_outputs(dir_a: &Path, dir_b: &Path) -> Result<OutputComparison, GraphError> {
+    let (files_a, files_b) = (output_files(dir_a)?, output_files(dir_b)?);
+    let mut comparison = OutputComparison {
+        only_in_a: files_a.difference(&files_b).cloned().collect(),
+        only_in_b: files_b.difference(&files_a).cloned().collect(),
+        ..OutputComparison::default()
+    };
+
+    for file in files_a.intersection(&files_b) {
+        let (path_a, path_b) = (dir_a.join(file).display().to_string(), dir_b.join(file).display().to_string());
+        let text_a = String::from_utf8_lossy(&fs::read(&path_a).map_err(file_error(&path_a))?).into_owned();
+        let text_b = String::from_utf8_lossy(&fs::read(&path_b).map_err(file_error(&path_b))?).into_owned();
+        let kind = OutputKind::from_file_name(file);
+        comparison.findings.extend(
+            compare_output_texts(kind, &text_a, &text_b)
+                .into_iter()
+                .map(|message| OutputFinding {
+                    file: file.clone(),
+                    message,
+                }),
+        );
+        comparison.compared.push(file.clone());
+    }
+    Ok(comparison)
+}
+
+impl fmt::Display for OutputFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.file, self.message)
+    }
+}
+
+impl fmt::Display for OutputComparison {
+    /// Writes one line per finding, then a summary line
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for file in &self.only_in_a {
+            writeln!(f, "{}: present only in A", file)?;
+        }
+        for file in &self.only_in_b {
+            writeln!(f, "{}: present only in B", file)?;
+        }
+        for finding in &self.findings {
+            writeln!(f, "{}", finding)?;
+        }
+        writeln!(
+            f,
+            "{} files compared, {} findings, {} only in A, {} only in B",
+            self.compared.len(),
+            self.findings.len(),
+            self.only_in_a.len(),
+            self.only_in_b.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MATRIX: &str = "          A         B         C         \nA         0         1         1         \n\
+                          B         1         0         0         \nC         1         0         0         \n";
+
+    #[test]
+    fn kinds_follow_the_suffix() {
+        assert_eq!(OutputKind::from_file_name("g-SET.TXT"), OutputKind::Set);
+        assert_eq!(OutputKind::from_file_name("g-DFS-ALL.TXT"), OutputKind::Traversal);
+        assert_eq!(OutputKind::from_file_name("g-LIST.TXT"), OutputKind::Lines);
+    }
+
+    #[test]
+    fn reordered_and_reoriented_sets_are_equal() {
+        let a = "V(g)={A,B,C}\nE(g)={(A,B),(B,C)}\n";
+        let b = "V(g)={C,A,B}\r\nE(g)={(C,B),(B,A)}\r\n\r\n";
+        assert!(compare_output_texts(OutputKind::Set, a, b).is_empty());
+        assert_eq!(
+            compare_output_texts(OutputKind::Set, a, "V(h)={A,B,C}\nE(h)={(A,B),(A,C)}\n"),
+            vec!["graph name differs: g vs h", "edge (B,C) present only in A", "edge (A,C) present only in B"]
+        );
+    }
+
+    #[test]
+    fn degree_tables_ignore_padding() {
+        let a = "A         1\nB         2\n";
+        assert!(compare_output_texts(OutputKind::Degree, a, "A 1\nB 2\n").is_empty());
+        assert_eq!(
+            compare_output_texts(OutputKind::Degree, a, "A 1\nB 3\nC 0\n"),
+            vec!["degree of B differs: 2 vs 3", "vertex C present only in B"]
+        );
+    }
+
+    #[test]
+    fn matrices_report_cells_and_vertices() {
+        assert!(compare_output_texts(OutputKind::Matrix, MATRIX, MATRIX).is_empty());
+        let changed = MATRIX.replacen("B         1         0         0", "B         1         0         1", 1);
+        assert_eq!(
+            compare_output_texts(OutputKind::Matrix, MATRIX, &changed),
+            vec!["cell [B][C] differs: 0 vs 1"]
+        );
+        let short_row = MATRIX.replacen("C         1         0         0", "C         1         0", 1);
+        assert_eq!(
+            compare_output_texts(OutputKind::Matrix, MATRIX, &short_row),
+            vec!["cell [C][C] present only in A"]
+        );
+        let smaller = "     A    B\nA    0    1\nB    1    0\n";
+        assert_eq!(compare_output_texts(OutputKind::Matrix, MATRIX, smaller), vec!["vertex C present only in A"]);
+    }
+
+    #[test]
+    fn lines_past_the_end_are_reported() {
+        assert_eq!(
+            compare_output_texts(OutputKind::Lines, "x\ny\n", "x\n"),
+            vec!["line 2 present only in A: \"y\""]
+        );
+    }
+}
@@ -0,0 +1,289 @@
+// src/completions.rs - Shell completion scripts and help-json, built from the command table
+//
+// Nothing here names a subcommand or a flag: every script is generated from the
+// operands and flags of the Command entries, so a command added to the table is
+// completed everywhere at once. Word lists that depend on the build, such as the
+// generator families, are read when the script is generated.
+use crate::cli::{Command, Flag, Value, GLOBAL_FLAGS};
+use graph_analysis::error::json_string;
+use std::fmt::Write;
+
+/// Name of the shell function of a program, without characters a function name cannot hold
+fn function_name(program: &str) -> String {
+    let name: String = program
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("_{}", name)
+}
+
+/// Quotes text for the shell in single quotes
+fn single_quoted(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// The first line of a summary, enough to pick a command by
+fn first_line(summary: &str) -> &str {
+    summary.lines().next().unwrap_or("")
+}
+
+/// Every flag a command accepts, its own first
+fn all_flags(command: &Command) -> impl Iterator<Item = &Flag> {
+    command.flags.iter().chain(GLOBAL_FLAGS)
+}
+
+/// The bash statements completing a value into COMPREPLY
+fn bash_action(function: &str, value: Value) -> String {
+    match value.words() {
+        Some(words) => {
+            let nospace = if matches!(value, Value::Section) { "compopt -o nospace 2>/dev/null; " } else { "" };
+            format!("{}COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", nospace, words.join(" "))
+        }
+        None => format!("{}_value {}", function, value.kind()),
+    }
+}
+
+/// Writes a bash completion script, registered with `complete -F`
+pub fn bash_script(program: &str, commands: &[Command]) -> String {
+    let function = function_name(program);
+    let names: Vec<&str> = commands.iter().map(|command| command.name).collect();
+    let mut script = String::new();
+    let _ = writeln!(script, "# bash completion for {}, generated by `{} completions bash`", program, program);
+    let _ = writeln!(script);
+    let _ = writeln!(script, "# Completes a path value: input (.txt files, not the .TXT outputs), file or directory");
+    let _ = writeln!(script, "{}_value() {{", function);
+    let _ = writeln!(script, "    case \"$1\" in");
+    let _ = writeln!(
+        script,
+        "        input) COMPREPLY=($(compgen -f -X '!*.txt' -- \"$cur\") $(compgen -d -- \"$cur\")) ;;"
+    );
+    let _ = writeln!(script, "        file) COMPREPLY=($(compgen -f -- \"$cur\")) ;;");
+    let _ = writeln!(script, "        directory) COMPREPLY=($(compgen -d -- \"$cur\")) ;;");
+    let _ = writeln!(script, "    esac");
+    let _ = writeln!(script, "}}");
+    let _ = writeln!(script);
+    let _ = writeln!(script, "# Counts the operands before the cursor, skipping the flags in $1 and their values");
+    let _ = writeln!(script, "{}_operand() {{", function);
+    let _ = writeln!(script, "    local i n=0");
+    let _ = writeln!(script, "    for ((i = 2; i < COMP_CWORD; i++)); do");
+    let _ = writeln!(script, "        case \" $1 \" in *\" ${{COMP_WORDS[i]}} \"*) ((i++)); continue ;; esac");
+    let _ = writeln!(script, "        [[ ${{COMP_WORDS[i]}} == -* ]] || ((n++))");
+    let _ = writeln!(script, "    done");
+    let _ = writeln!(script, "    echo $n");
+    let _ = writeln!(script, "}}");
+    let _ = writeln!(script);
+    let _ = writeln!(script, "{}() {{", function);
+    let _ = writeln!(script, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\" prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"");
+    let _ = writeln!(script, "    COMPREPLY=()");
+    let _ = writeln!(script, "    if [[ $COMP_CWORD -eq 1 ]]; then");
+    let _ = writeln!(script, "        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", names.join(" "));
+    let _ = writeln!(script, "        return");
+    let _ = writeln!(script, "    fi");
+    let _ = writeln!(script, "    case \"${{COMP_WORDS[1]}}\" in");
+    for command in commands {
+        let valued: Vec<&str> = all_flags(command)
+            .filter(|flag| flag.value.is_some())
+            .map(|flag| flag.name)
+            .collect();
+        let flags: Vec<&str> = all_flags(command).map(|flag| flag.name).collect();
+        let _ = writeln!(script, "        {})", command.name);
+        if !valued.is_empty() {
+            let _ = writeln!(script, "            case \"$prev\" in");
+            for flag in all_flags(command) {
+                if let Some(value) = flag.value {
+                    let _ = writeln!(script, "                {}) {}; return ;;", flag.name, bash_action(&function, value));
+                }
+            }
+            let _ = writeln!(script, "            esac");
+        }
+        let _ = writeln!(script, "            if [[ $cur == -* ]]; then");
+        let _ = writeln!(script, "                COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", flags.join(" "));
+        let _ = writeln!(script, "                return");
+        let _ = writeln!(script, "            fi");
+        let operands: Vec<(usize, Value)> = command
+            .operands
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| !matches!(value, Value::Vertex | Value::Text))
+            .map(|(i, &value)| (i, value))
+            .collect();
+        if !operands.is_empty() {
+            let _ = writeln!(script, "            case $({}_operand \"{}\") in", function, valued.join(" "));
+            for (i, value) in operands {
+                let _ = writeln!(script, "                {}) {} ;;", i, bash_action(&function, value));
+            }
+            let _ = writeln!(script, "            esac");
+        }
+        let _ = writeln!(script, "            ;;");
+    }
+    let _ = writeln!(script, "    esac");
+    let _ = writeln!(script, "}}");
+    let _ = writeln!(script, "complete -F {} {}", function, program);
+    script
+}
+
+/// The zsh _arguments action completing a value
+fn zsh_action(value: Value) -> String {
+    match (value, value.words()) {
+        (Value::Section, Some(words)) => format!("{{compadd -S \"\" -- {}}}", words.join(" ")),
+        (_, Some(words)) => format!("({})", words.join(" ")),
+        (Value::InputFile, None) => "_files -g \"*.txt\"".to_string(),
+        (Value::File, None) => "_files".to_string(),
+        (Value::Directory, None) => "_files -/".to_string(),
+        _ => " ".to_string(),
+    }
+}
+
+/// Writes a zsh completion script, to be saved as _NAME on $fpath
+pub fn zsh_script(program: &str, commands: &[Command]) -> String {
+    let function = function_name(program);
+    let mut script = String::new();
+    let _ = writeln!(script, "#compdef {}", program);
+    let _ = writeln!(script, "# zsh completion for {}, generated by `{} completions zsh`", program, program);
+    let _ = writeln!(script);
+    let _ = writeln!(script, "{}() {{", function);
+    let _ = writeln!(script, "    local -a commands");
+    let _ = writeln!(script, "    commands=(");
+    for command in commands {
+        let entry = format!("{}:{}", command.name, first_line(command.summary).replace(':', "\\:"));
+        let _ = writeln!(script, "        {}", single_quoted(&entry));
+    }
+    let _ = writeln!(script, "    )");
+    let _ = writeln!(script, "    if (( CURRENT == 2 )); then");
+    let _ = writeln!(script, "        _describe -t commands command commands");
+    let _ = writeln!(script, "        return");
+    let _ = writeln!(script, "    fi");
+    let _ = writeln!(script, "    local command=$words[2]");
+    let _ = writeln!(script, "    shift words");
+    let _ = writeln!(script, "    (( CURRENT-- ))");
+    let _ = writeln!(script, "    case $command in");
+    for command in commands {
+        let mut specs = Vec::new();
+        for flag in all_flags(command) {
+            let repeats = if flag.repeats { "*" } else { "" };
+            let spec = match flag.value {
+                Some(value) => format!("{}{}:{}:{}", repeats, flag.name, value.kind(), zsh_action(value)),
+                None => format!("{}{}", repeats, flag.name),
+            };
+            specs.push(single_quoted(&spec));
+        }
+        for (i, &value) in command.operands.iter().enumerate() {
+            specs.push(single_quoted(&format!("{}:{}:{}", i + 1, value.kind(), zsh_action(value))));
+        }
+        let _ = writeln!(script, "        {})", command.name);
+        let _ = writeln!(script, "            _arguments {}", specs.join(" \\\n                "));
+        let _ = writeln!(script, "            ;;");
+    }
+    let _ = writeln!(script, "    esac");
+    let _ = writeln!(script, "}}");
+    let _ = writeln!(script);
+    let _ = writeln!(script, "{} \"$@\"", function);
+    script
+}
+
+/// The fish complete options offering a value
+fn fish_action(value: Value) -> String {
+    match value.words() {
+        Some(words) => format!("-a {}", single_quoted(&words.join(" "))),
+        None => match value {
+            Value::InputFile => "-a '(__fish_complete_suffix .txt)'".to_string(),
+            Value::File => "-F".to_string(),
+            Value::Directory => "-a '(__fish_complete_directories)'".to_string(),
+            _ => String::new(),
+        },
+    }
+}
+
+/// Writes a fish completion script, to be saved as NAME.fish in a completions directory
+///
+/// fish cannot tell operands apart by position, so every operand kind of a
+/// command is offered at every position.
+pub fn fish_script(program: &str, commands: &[Command]) -> String {
+    let mut script = String::new();
+    let _ = writeln!(script, "# fish completion for {}, generated by `{} completions fish`", program, program);
+    let _ = writeln!(script, "complete -c {} -f", program);
+    for flag in GLOBAL_FLAGS {
+        let _ = writeln!(script, "complete -c {} -l {}", program, flag.name.trim_start_matches('-'));
+    }
+    for command in commands {
+        let _ = writeln!(
+            script,
+            "complete -c {} -n __fish_use_subcommand -a {} -d {}",
+            program,
+            command.name,
+            single_quoted(first_line(command.summary))
+        );
+    }
+    for command in commands {
+        let condition = format!("-n '__fish_seen_subcommand_from {}'", command.name);
+        for flag in command.flags {
+            let name = flag.name.trim_start_matches('-');
+            let line = match flag.value {
+                Some(value) => format!("complete -c {} {} -l {} -r {}", program, condition, name, fish_action(value)),
+                None => format!("complete -c {} {} -l {}", program, condition, name),
+            };
+            let _ = writeln!(script, "{}", line.trim_end());
+        }
+        let mut offered: Vec<String> = Vec::new();
+        for &value in command.operands {
+            let action = fish_action(value);
+            if !action.is_empty() && !offered.contains(&action) {
+                let _ = writeln!(script, "complete -c {} {} {}", program, condition, action);
+                offered.push(action);
+            }
+        }
+    }
+    script
+}
+
+/// Formats a value as a JSON object with its kind and, when it is one of a list, the words
+fn value_json(value: Value) -> String {
+    match value.words() {
+        Some(words) => {
+            let words: Vec<String> = words.iter().map(|word| json_string(word)).collect();
+            format!("{{\"kind\":{},\"words\":[{}]}}", json_string(value.kind()), words.join(","))
+        }
+        None => format!("{{\"kind\":{}}}", json_string(value.kind())),
+    }
+}
+
+/// Formats a flag as a JSON object; a switch has a null value
+fn flag_json(flag: &Flag) -> String {
+    let value = flag.value.map_or_else(|| "null".to_string(), value_json);
+    format!(
+        "{{\"name\":{},\"value\":{},\"repeats\":{}}}",
+        json_string(flag.name),
+        value,
+        flag.repeats
+    )
+}
+
+/// Formats the command table as one JSON object
+///
+/// The object holds the program name, the flags every command accepts under
+/// "global_flags", and under "commands" the name, usage arguments, summary,
+/// operands and flags of each command in the order of the usage summary.
+pub fn help_json(program: &str, commands: &[Command]) -> String {
+    let global: Vec<String> = GLOBAL_FLAGS.iter().map(flag_json).collect();
+    let commands: Vec<String> = commands
+        .iter()
+        .map(|command| {
+            let operands: Vec<String> = command.operands.iter().map(|&value| value_json(value)).collect();
+            let flags: Vec<String> = command.flags.iter().map(flag_json).collect();
+            format!(
+                "{{\"name\":{},\"args\":{},\"summary\":{},\"operands\":[{}],\"flags\":[{}]}}",
+                json_string(command.name),
+                json_string(command.args),
+                json_string(command.summary),
+                operands.join(","),
+                flags.join(",")
+            )
+        })
+        .collect();
+    format!(
+        "{{\"program\":{},\"global_flags\":[{}],\"commands\":[{}]}}",
+        json_string(program),
+        global.join(","),
+        commands.join(",")
+    )
+}
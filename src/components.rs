@@ -0,0 +1,218 @@
+// src/components.rs - Connected components of the undirected graph
+use crate::algo::{self, GraphRef};
+use crate::disjoint_set::DisjointSet;
+use crate::error::GraphError;
+use crate::suggest::{closest_among, DEFAULT_SUGGESTIONS};
+use crate::Graph;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+
+/// The components the graph would have without some vertices, from Graph::what_if_remove
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhatIfReport {
+    /// The removed labels that are vertices of the graph
+    pub removed: Vec<String>,
+    /// Labels that are not vertices, and so change nothing
+    pub unknown: Vec<String>,
+    pub component_count: usize,
+    pub largest_component: usize,
+    /// Vertices with neighbors now that would have none left, alphabetically
+    pub newly_isolated: Vec<String>,
+}
+
+impl fmt::Display for WhatIfReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "without {{{}}}:", self.removed.join(","))?;
+        if !self.unknown.is_empty() {
+            writeln!(f, "  not vertices: {}", self.unknown.join(","))?;
+        }
+        writeln!(f, "  components: {}", self.component_count)?;
+        writeln!(f, "  largest component: {} vertices", self.largest_component)?;
+        writeln!(f, "  newly isolated: {{{}}}", self.newly_isolated.join(","))
+    }
+}
+
+/// Connected components kept current under edge insertions, from Graph::components_index
+///
+/// A DisjointSet of the vertices, so queries and insertions take near-constant time.
+/// It only grows: it cannot split a component, so once an edge or vertex is removed
+/// from the graph the index is stale and must be rebuilt with ComponentsIndex::rebuild.
+/// Edges count in either direction.
+#[derive(Debug, Clone)]
+pub struct ComponentsIndex {
+    labels: Vec<String>,
+    lookup: HashMap<String, usize>,
+    /// Vertex indices in alphabetical order of their labels
+    sorted: Vec<usize>,
+    sets: DisjointSet,
+}
+
+impl ComponentsIndex {
+    /// Builds the index of the current edges of a graph
+    pub fn new(graph: &Graph) -> Self {
+        let n = graph.n_vertices;
+        let labels: Vec<String> = graph.vertices[..n].to_vec();
+        let mut index = ComponentsIndex {
+            lookup: labels.iter().enumerate().map(|(i, label)| (label.clone(), i)).collect(),
+            labels,
+            sorted: graph.cached_sorted().to_vec(),
+            sets: DisjointSet::new(n),
+        };
+        for i in 0..n {
+            for j in i + 1..n {
+                if graph.adj_matrix[i][j] == 1 || graph.adj_matrix[j][i] == 1 {
+                    index.sets.union(i, j);
+                }
+            }
+        }
+        index
+    }
+
+    /// Starts over from the current edges of a graph, as needed after a removal
+    pub fn rebuild(&mut self, graph: &Graph) {
+        *self = ComponentsIndex::new(graph);
+    }
+
+    fn idx(&self, label: &str) -> Result<usize, GraphError> {
+        self.lookup.get(label).copied().ok_or_else(|| GraphError::UnknownVertex {
+            label: label.to_string(),
+            suggestions: closest_among(&self.labels, label, DEFAULT_SUGGESTIONS),
+        })
+    }
+
+    /// Checks whether two vertices are in the same component
+    pub fn same_component(&mut self, a: &str, b: &str) -> Result<bool, GraphError> {
+        let (u, v) = (self.idx(a)?, self.idx(b)?);
+        Ok(self.sets.same_set(u, v))
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.sets.set_count()
+    }
+
+    /// Records that the edge a-b was added to the graph; returns true when it joined two components
+    /// Both endpoints must be vertices the index was built with
+    pub fn apply_edge_insertion(&mut self, a: &str, b: &str) -> Result<bool, GraphError> {
+        let (u, v) = (self.idx(a)?, self.idx(b)?);
+        Ok(self.sets.union(u, v))
+    }
+
+    /// Returns the labels of each component, in the order of Graph::connected_components
+    pub fn components(&mut self) -> Vec<Vec<String>> {
+        let mut slot: HashMap<usize, usize> = HashMap::new();
+        let mut components: Vec<Vec<String>> = Vec::new();
+        for k in 0..self.sorted.len() {
+            let v = self.sorted[k];
+            let root = self.sets.find(v);
+            let next = components.len();
+            let at = *slot.entry(root).or_insert(next);
+            if at == next {
+                components.push(Vec::new());
+            }
+            components[at].push(self.labels[v].clone());
+        }
+        components
+    }
+}
+
+/// Checks whether a vertex is joined, in either direction, to a vertex other than itself
+fn has_other_neighbor<G: GraphRef>(g: &G, v: usize) -> bool {
+    (0..g.vertex_count()).any(|u| u != v && (g.has_edge(v, u) || g.has_edge(u, v)))
+}
+
+impl Graph {
+    /// Builds a ComponentsIndex of the current edges, to be kept current under insertions
+    pub fn components_index(&self) -> ComponentsIndex {
+        ComponentsIndex::new(self)
+    }
+
+    /// Assigns every vertex the number of its connected component
+    /// Components are numbered in order of their alphabetically smallest vertex
+    pub fn component_ids(&self) -> Vec<usize> {
+        self.cached_component_ids().to_vec()
+    }
+
+    /// Floods every component to number the vertices, without the cache
+    /// Dense graphs are flooded through the complement; see density_strategy
+    pub(crate) fn compute_component_ids(&self) -> Vec<usize> {
+        algo::component_ids_with(self, self.density_strategy())
+    }
+
+    /// Returns the labels of each connected component sorted alphabetically,
+    /// with components ordered by their smallest label
+    pub fn connected_components(&self) -> Vec<Vec<String>> {
+        self.components_from_ids(self.cached_component_ids())
+    }
+
+    /// Groups the labels by component number, each group sorted alphabetically
+    pub(crate) fn components_from_ids(&self, ids: &[usize]) -> Vec<Vec<String>> {
+        let n_components = ids.iter().map(|&id| id + 1).max().unwrap_or(0);
+        let mut components = vec![Vec::new(); n_components];
+
+        for &idx in self.cached_sorted() {
+            components[ids[idx]].push(self.vertices[idx].clone());
+        }
+
+        components
+    }
+
+    /// To create/write an output file of the connected components
+    /// One `{A,B,C}` line per component, in the order of connected_components, so
+    /// the vertices a traversal from one start misses are listed on the other lines
+    pub fn produce_output_file_components(&self, base_name: &str) -> Result<(), GraphError> {
+        let output_name = Self::get_output_filename(base_name, "-COMPONENTS");
+        let mut fp = File::create(output_name)?;
+
+        for component in self.connected_components() {
+            writeln!(fp, "{{{}}}", component.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    /// Previews removing some vertices without touching the graph
+    ///
+    /// The components are those of a view with the removed vertices masked out, so the
+    /// counts equal those of a copy with the vertices deleted. Edges count in either
+    /// direction, and a self-loop does not keep a vertex from being isolated.
+    pub fn what_if_remove(&self, labels: &[&str]) -> WhatIfReport {
+        let (mut removed, mut unknown) = (Vec::new(), Vec::new());
+        for &label in labels {
+            match self.find_vertex_idx(label) {
+                Some(_) if !removed.iter().any(|r| r == label) => removed.push(label.to_string()),
+                Some(_) => {}
+                None => unknown.push(label.to_string()),
+            }
+        }
+        let masked: Vec<&str> = removed.iter().map(String::as_str).collect();
+        let view = self
+            .view()
+            .without_vertices(&masked)
+            .expect("only labels of vertices are masked");
+
+        let ids = algo::component_ids_with(&view, self.density_strategy());
+        let component_count = ids.iter().map(|&id| id + 1).max().unwrap_or(0);
+        let mut sizes = vec![0; component_count];
+        for &id in &ids {
+            sizes[id] += 1;
+        }
+        let largest_component = sizes.into_iter().max().unwrap_or(0);
+
+        // Vertices with an edge in the graph but none left in the view, self-loops aside
+        let newly_isolated = algo::sorted_vertices(&view)
+            .into_iter()
+            .filter(|&v| has_other_neighbor(self, view.graph_index(v)) && !has_other_neighbor(&view, v))
+            .map(|v| view.label(v).to_string())
+            .collect();
+
+        WhatIfReport {
+            removed,
+            unknown,
+            component_count,
+            largest_component,
+            newly_isolated,
+        }
+    }
+}
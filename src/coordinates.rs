@@ -0,0 +1,94 @@
+// src/coordinates.rs - Optional vertex positions for geometric graphs
+use crate::dialect::{read_file_lines, Encoding};
+use crate::error::GraphError;
+use crate::Graph;
+
+impl Graph {
+    /// Sets the position of a vertex
+    pub fn set_coordinates(&mut self, label: &str, x: f64, y: f64) -> Result<(), GraphError> {
+        let idx = self.require_vertex_idx(label)?;
+        self.coordinates.insert(idx, (x, y));
+        self.invalidate_cache();
+        Ok(())
+    }
+
+    /// Sets the positions of vertices from a CSV file of `label,x,y` rows
+    ///
+    /// Blank lines and lines starting with `#` are skipped, and so is a first line
+    /// whose x column is not a number, taken as a header. Every row is checked
+    /// before any position changes. Returns the number of rows applied; a label
+    /// given twice takes the later position. Invalid UTF-8 is an error; see
+    /// apply_coordinates_csv_with_encoding
+    pub fn apply_coordinates_csv(&mut self, path: &str) -> Result<usize, GraphError> {
+        self.apply_coordinates_csv_with_encoding(path, Encoding::Strict)
+    }
+
+    /// Sets positions from a CSV file as apply_coordinates_csv does, decoding it under
+    /// the given encoding
+    pub fn apply_coordinates_csv_with_encoding(&mut self, path: &str, encoding: Encoding) -> Result<usize, GraphError> {
+        let (lines, _) = read_file_lines(path, encoding)?;
+        let invalid = |line: usize, reason: String| GraphError::InvalidFormat {
+            format: "coordinates CSV",
+            reason: format!("line {}: {}", line + 1, reason),
+        };
+
+        let mut rows: Vec<(usize, f64, f64)> = Vec::new();
+        let mut first = true;
+        for (line_no, line) in lines.iter().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let header_allowed = std::mem::replace(&mut first, false);
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [label, x, y] = fields.as_slice() else {
+                return Err(invalid(line_no, format!("expected label,x,y, found \"{}\"", line)));
+            };
+            let (x, y): (f64, f64) = match (x.parse(), y.parse()) {
+                (Ok(x), Ok(y)) => (x, y),
+                _ if header_allowed => continue,
+                (Err(_), _) => return Err(invalid(line_no, format!("{} is not a number", x))),
+                (_, Err(_)) => return Err(invalid(line_no, format!("{} is not a number", y))),
+            };
+            if !x.is_finite() || !y.is_finite() {
+                return Err(invalid(line_no, format!("position ({}, {}) is not finite", x, y)));
+            }
+            rows.push((self.require_vertex_idx(label)?, x, y));
+        }
+
+        for &(idx, x, y) in &rows {
+            self.coordinates.insert(idx, (x, y));
+        }
+        self.invalidate_cache();
+        Ok(rows.len())
+    }
+
+    /// Returns the position of a vertex, if one was set
+    pub fn coordinates_of(&self, label: &str) -> Option<(f64, f64)> {
+        let idx = self.find_vertex_idx(label)?;
+        self.coordinates.get(&idx).copied()
+    }
+
+    /// Returns true when every vertex has a position
+    pub fn has_coordinates(&self) -> bool {
+        (0..self.n_vertices).all(|i| self.coordinates.contains_key(&i))
+    }
+
+    /// Builds the straight-line distance heuristic towards a target vertex for astar
+    ///
+    /// The heuristic is admissible as long as no edge weight is smaller than the
+    /// Euclidean distance between its endpoints.
+    pub fn euclidean_heuristic(&self, to: &str) -> Result<impl Fn(&str) -> f64 + '_, GraphError> {
+        let (tx, ty) = self
+            .coordinates_of(to)
+            .ok_or_else(|| GraphError::MissingCoordinates(to.to_string()))?;
+        if let Some(i) = (0..self.n_vertices).find(|i| !self.coordinates.contains_key(i)) {
+            return Err(GraphError::MissingCoordinates(self.vertices[i].clone()));
+        }
+
+        Ok(move |label: &str| match self.coordinates_of(label) {
+            Some((x, y)) => ((x - tx).powi(2) + (y - ty).powi(2)).sqrt(),
+            None => 0.0,
+        })
+    }
+}
@@ -0,0 +1,131 @@
+// src/cycles.rs - Finding a cycle, with the edges taken as undirected or as directed
+//
+// Both searches are depth-first, trying roots and neighbors in alphabetical order,
+// so the same graph always gives the same cycle. A cycle is returned closed: its
+// first label is repeated at the end. A self-loop is the cycle [A, A].
+use crate::direction::Direction;
+use crate::Graph;
+
+/// Where the depth-first search of a directed graph stands with a vertex
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    /// Not reached yet
+    White,
+    /// On the current path
+    Gray,
+    /// Finished, along with everything it reaches
+    Black,
+}
+
+impl Graph {
+    /// Checks whether the graph has a cycle when its edges are taken as undirected
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    /// Finds a cycle of the graph with its edges taken as undirected
+    pub fn find_cycle(&self) -> Option<Vec<String>> {
+        self.find_cycle_with(Direction::Undirected)
+    }
+
+    /// Checks whether the graph has a cycle, reading its edges in the given direction
+    pub fn has_cycle_with(&self, direction: Direction) -> bool {
+        self.find_cycle_with(direction).is_some()
+    }
+
+    /// Finds a cycle, reading the edges in the given direction, as labels closed on the first
+    ///
+    /// Undirected, an edge listed either way joins its endpoints, and only an edge back
+    /// to a vertex other than the DFS parent closes a cycle, so a lone edge is not one.
+    /// Directed, the edges are the adjacency matrix entries: the DFS colors the vertices
+    /// on its current path gray, and an edge to a gray vertex closes a cycle, so a pair
+    /// listed both ways is the cycle [A, B, A].
+    pub fn find_cycle_with(&self, direction: Direction) -> Option<Vec<String>> {
+        let cycle = match direction {
+            Direction::Undirected => self.undirected_cycle(),
+            Direction::Directed => self.directed_cycle(),
+        }?;
+        let mut labels: Vec<String> = cycle.iter().map(|&i| self.vertices[i].clone()).collect();
+        labels.push(labels[0].clone());
+        Some(labels)
+    }
+
+    /// The vertices of a cycle of the undirected graph, in order around it
+    fn undirected_cycle(&self) -> Option<Vec<usize>> {
+        let sorted = self.sort_vertices();
+        if let Some(&v) = sorted.iter().find(|&&v| self.adj_matrix[v][v] == 1) {
+            return Some(vec![v]);
+        }
+
+        let mut parent: Vec<Option<usize>> = vec![None; self.n_vertices];
+        let mut visited = vec![false; self.n_vertices];
+        for &root in &sorted {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            // Each stack entry holds a vertex and the position of its next neighbor to try
+            let mut stack = vec![(root, 0)];
+            while let Some(&mut (u, ref mut next)) = stack.last_mut() {
+                let Some(&v) = sorted[*next..].iter().find(|&&v| v != u && self.linked(u, v)) else {
+                    stack.pop();
+                    continue;
+                };
+                *next = sorted.iter().position(|&w| w == v).unwrap_or(0) + 1;
+                if parent[u] == Some(v) {
+                    continue;
+                }
+                if visited[v] {
+                    // A non-tree edge of an undirected DFS always leads back to an ancestor
+                    let mut cycle = vec![u];
+                    let mut w = u;
+                    while w != v {
+                        w = parent[w]?;
+                        cycle.push(w);
+                    }
+                    cycle.reverse();
+                    return Some(cycle);
+                }
+                visited[v] = true;
+                parent[v] = Some(u);
+                stack.push((v, 0));
+            }
+        }
+        None
+    }
+
+    /// The vertices of a cycle following the edge directions, in order along it
+    fn directed_cycle(&self) -> Option<Vec<usize>> {
+        let sorted = self.sort_vertices();
+        let mut color = vec![Color::White; self.n_vertices];
+        for &root in &sorted {
+            if color[root] != Color::White {
+                continue;
+            }
+            color[root] = Color::Gray;
+            // The stack is the current path; each entry holds the position of its next neighbor to try
+            let mut stack = vec![(root, 0)];
+            while let Some(&mut (u, ref mut next)) = stack.last_mut() {
+                let Some(offset) = sorted[*next..].iter().position(|&v| self.adj_matrix[u][v] == 1) else {
+                    color[u] = Color::Black;
+                    stack.pop();
+                    continue;
+                };
+                let v = sorted[*next + offset];
+                *next += offset + 1;
+                match color[v] {
+                    Color::White => {
+                        color[v] = Color::Gray;
+                        stack.push((v, 0));
+                    }
+                    Color::Gray => {
+                        let start = stack.iter().position(|&(w, _)| w == v)?;
+                        return Some(stack[start..].iter().map(|&(w, _)| w).collect());
+                    }
+                    Color::Black => {}
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,282 @@
+// src/dag.rs - Topological order, layers and critical paths of directed acyclic graphs
+//
+// The edges are the adjacency matrix entries, read in their direction, so a pair
+// listed both ways is a cycle of two. Every function here fails with
+// GraphError::Cycle, naming a cycle found by find_cycle_with, when the graph is
+// not acyclic.
+use crate::direction::Direction;
+use crate::error::GraphError;
+use crate::Graph;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::Write;
+
+impl Graph {
+    /// The error naming a directed cycle of the graph
+    fn cycle_error(&self) -> GraphError {
+        GraphError::Cycle {
+            cycle: self.find_cycle_with(Direction::Directed).unwrap_or_default(),
+        }
+    }
+
+    /// Orders the vertices so every edge points forward, by Kahn's algorithm
+    /// Of the vertices ready at once, the alphabetically first comes first
+    pub(crate) fn topological_order(&self) -> Result<Vec<usize>, GraphError> {
+        let n = self.n_vertices;
+        let mut rank = vec![0; n];
+        for (position, &v) in self.cached_sorted().iter().enumerate() {
+            rank[v] = position;
+        }
+        let mut in_degree: Vec<usize> = (0..n)
+            .map(|v| (0..n).filter(|&u| self.adj_matrix[u][v] == 1).count())
+            .collect();
+        let mut ready: BinaryHeap<Reverse<(usize, usize)>> =
+            (0..n).filter(|&v| in_degree[v] == 0).map(|v| Reverse((rank[v], v))).collect();
+
+        let mut order = Vec::with_capacity(n);
+        while let Some(Reverse((_, u))) = ready.pop() {
+            order.push(u);
+            for v in 0..n {
+                if self.adj_matrix[u][v] == 1 {
+                    in_degree[v] -= 1;
+                    if in_degree[v] == 0 {
+                        ready.push(Reverse((rank[v], v)));
+                    }
+                }
+            }
+        }
+
+        // Vertices on or behind a cycle never become ready
+        if order.len() < n {
+            return Err(self.cycle_error());
+        }
+        Ok(order)
+    }
+
+    /// Orders the vertices so that every edge points from an earlier to a later one
+    ///
+    /// Kahn's algorithm takes the vertices without remaining incoming edges one at a
+    /// time, the alphabetically first of them each time, so the order is unique for a
+    /// given graph. Fails with GraphError::Cycle, naming the vertices of one directed
+    /// cycle, when there is no such order.
+    pub fn topological_sort(&self) -> Result<Vec<String>, GraphError> {
+        Ok(self.labels_of(&self.topological_order()?))
+    }
+
+    /// Groups the vertices by the number of edges on the longest path reaching them
+    /// from a vertex without incoming edges, each layer sorted alphabetically
+    ///
+    /// Layer 0 holds the sources, and every edge points to a later layer. Fails with
+    /// GraphError::Cycle on a graph with a directed cycle.
+    pub fn dag_layers(&self) -> Result<Vec<Vec<String>>, GraphError> {
+        let order = self.topological_order()?;
+        let n = self.n_vertices;
+        let mut layer = vec![0; n];
+        for &u in &order {
+            for v in 0..n {
+                if self.adj_matrix[u][v] == 1 {
+                    layer[v] = layer[v].max(layer[u] + 1);
+                }
+            }
+        }
+
+        let mut layers: Vec<Vec<String>> = Vec::new();
+        for &v in self.cached_sorted() {
+            if layers.len() <= layer[v] {
+                layers.resize(layer[v] + 1, Vec::new());
+            }
+            layers[layer[v]].push(self.vertices[v].clone());
+        }
+        Ok(layers)
+    }
+
+    /// Groups the vertices by the earliest step they can run at when each must wait
+    /// for all of its predecessors, each generation sorted alphabetically
+    ///
+    /// A vertex can run one step after its latest predecessor, so the generations are
+    /// the layers of dag_layers, read as a schedule. The number of generations is the
+    /// fewest steps any schedule needs. Fails with GraphError::Cycle on a graph with a
+    /// directed cycle.
+    pub fn topological_generations(&self) -> Result<Vec<Vec<String>>, GraphError> {
+        self.dag_layers()
+    }
+
+    /// Finds a longest path and its length, the sum of its edge weights
+    fn longest_path(&self) -> Result<(Vec<String>, f64), GraphError> {
+        let order = self.topological_order()?;
+        let n = self.n_vertices;
+        let mut length = vec![0.0; n];
+        let mut pred: Vec<Option<usize>> = vec![None; n];
+        for &u in &order {
+            for v in 0..n {
+                if self.adj_matrix[u][v] != 1 {
+                    continue;
+                }
+                let through_u = length[u] + self.weight_at(u, v);
+                // Until a predecessor improves on it, the best path to v is v alone
+                let better = match pred[v] {
+                    None => through_u > length[v],
+                    Some(p) => {
+                        through_u > length[v] || (through_u == length[v] && self.vertices[u] < self.vertices[p])
+                    }
+                };
+                if better {
+                    length[v] = through_u;
+                    pred[v] = Some(u);
+                }
+            }
+        }
+
+        // The longest path ends at the alphabetically first vertex of greatest length
+        let Some(&end) = self
+            .cached_sorted()
+            .iter()
+            .reduce(|best, v| if length[*v] > length[*best] { v } else { best })
+        else {
+            return Ok((Vec::new(), 0.0));
+        };
+        let mut path = vec![end];
+        let mut current = end;
+        while let Some(p) = pred[current] {
+            path.push(p);
+            current = p;
+        }
+        path.reverse();
+        Ok((path.iter().map(|&v| self.vertices[v].clone()).collect(), length[end]))
+    }
+
+    /// Returns one longest path of the directed acyclic graph, as labels from its start
+    ///
+    /// Paths are measured by the sum of their edge weights, so an unweighted graph
+    /// gives a path with the most edges. Of equally long paths the one ending at the
+    /// alphabetically first vertex is taken, reached through the alphabetically first
+    /// predecessor at every step. Empty when the graph has no vertices or a cycle.
+    pub fn critical_path(&self) -> Vec<String> {
+        self.longest_path().map(|(path, _)| path).unwrap_or_default()
+    }
+
+    /// To create/write an output file of the topological order, space-separated on one
+    /// line like the BFS and DFS files
+    pub fn produce_output_file_topo(&self, base_name: &str) -> Result<(), GraphError> {
+        let order = self.topological_sort()?;
+        let mut fp = File::create(Self::get_output_filename(base_name, "-TOPO"))?;
+        writeln!(fp, "{}", order.join(" "))?;
+        Ok(())
+    }
+
+    /// To create/write an output file of the layers of a directed acyclic graph
+    /// One `L<k>: {a,b,c}` line per layer of dag_layers is followed by the line
+    /// `critical path: A->B->C (length 2)`, the length summing the edge weights
+    pub fn produce_output_layers_dag(&self, base_name: &str) -> Result<(), GraphError> {
+        let layers = self.dag_layers()?;
+        let (path, length) = self.longest_path()?;
+        let output_name = Self::get_output_filename(base_name, "-LAYERS-DAG");
+        let mut fp = File::create(output_name)?;
+
+        for (k, layer) in layers.iter().enumerate() {
+            writeln!(fp, "L{}: {{{}}}", k, layer.join(","))?;
+        }
+        writeln!(fp, "critical path: {} (length {})", path.join("->"), length)?;
+
+        Ok(())
+    }
+
+    /// To create/write an output file of a parallel schedule of a directed acyclic graph
+    /// One `Step k: {a,b,c}` line per generation of topological_generations, counted
+    /// from 1, is followed by the lines `makespan: N`, the number of steps, and
+    /// `width: W`, the most vertices running at one step
+    pub fn produce_output_file_schedule(&self, base_name: &str) -> Result<(), GraphError> {
+        let generations = self.topological_generations()?;
+        let output_name = Self::get_output_filename(base_name, "-SCHEDULE");
+        let mut fp = File::create(output_name)?;
+
+        for (k, generation) in generations.iter().enumerate() {
+            writeln!(fp, "Step {}: {{{}}}", k + 1, generation.join(","))?;
+        }
+        writeln!(fp, "makespan: {}", generations.len())?;
+        writeln!(fp, "width: {}", generations.iter().map(Vec::len).max().unwrap_or(0))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::Dialect;
+    use crate::fixtures::scratch_dir;
+    use std::fs;
+
+    fn directed(text: &str) -> Graph {
+        let mut graph = Graph::new();
+        graph.read_input_text_with(text, Dialect::Directed).unwrap();
+        graph
+    }
+
+    fn strings(groups: &[&[&str]]) -> Vec<Vec<String>> {
+        groups.iter().map(|group| group.iter().map(|s| s.to_string()).collect()).collect()
+    }
+
+    /// A fans out to B and C, which both lead to D
+    fn diamond() -> Graph {
+        directed("4\nA B C -1\nB D -1\nC D -1\nD -1\n")
+    }
+
+    /// S forks into A, B and C, which join at J before the end T
+    fn fork_join() -> Graph {
+        directed("6\nS A B C -1\nA J -1\nB J -1\nC J -1\nJ T -1\nT -1\n")
+    }
+
+    #[test]
+    fn diamond_layers_and_critical_path() {
+        let graph = diamond();
+        assert_eq!(graph.topological_sort().unwrap(), ["A", "B", "C", "D"]);
+        assert_eq!(graph.dag_layers().unwrap(), strings(&[&["A"], &["B", "C"], &["D"]]));
+        assert_eq!(graph.critical_path(), ["A", "B", "D"]);
+    }
+
+    #[test]
+    fn weighted_chain_takes_the_heavier_path() {
+        // A-C directly is one edge, but A-B-C weighs more
+        let graph = directed("3\nA B:2 C:4 -1\nB C:3 -1\nC -1\n");
+        assert_eq!(graph.dag_layers().unwrap(), strings(&[&["A"], &["B"], &["C"]]));
+        assert_eq!(graph.critical_path(), ["A", "B", "C"]);
+        let dir = scratch_dir("dag", "chain");
+        let base = dir.join("g").display().to_string();
+        graph.produce_output_layers_dag(&base).unwrap();
+        assert_eq!(
+            fs::read_to_string(format!("{}-LAYERS-DAG.TXT", base)).unwrap(),
+            "L0: {A}\nL1: {B}\nL2: {C}\ncritical path: A->B->C (length 5)\n"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn fork_join_generations_and_width() {
+        let graph = fork_join();
+        assert_eq!(
+            graph.topological_generations().unwrap(),
+            strings(&[&["S"], &["A", "B", "C"], &["J"], &["T"]])
+        );
+        let dir = scratch_dir("dag", "fork-join");
+        let base = dir.join("g").display().to_string();
+        graph.produce_output_file_schedule(&base).unwrap();
+        assert_eq!(
+            fs::read_to_string(format!("{}-SCHEDULE.TXT", base)).unwrap(),
+            "Step 1: {S}\nStep 2: {A,B,C}\nStep 3: {J}\nStep 4: {T}\nmakespan: 4\nwidth: 3\n"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn cycles_are_named() {
+        let graph = directed("3\nA B -1\nB C -1\nC A -1\n");
+        match graph.dag_layers() {
+            Err(GraphError::Cycle { cycle }) => assert_eq!(cycle.len(), 4, "{:?}", cycle),
+            other => panic!("expected a cycle, got {:?}", other),
+        }
+        assert!(graph.topological_generations().is_err());
+        assert!(graph.critical_path().is_empty());
+    }
+}
@@ -0,0 +1,395 @@
+// src/dedupe.rs - Deduplicating edge lists too large to hold in memory
+//
+// An external merge sort: the edges are read in runs that fit the memory budget,
+// each run is sorted and spilled to a file of a temporary directory, and the runs
+// are merged a bounded number at a time until one is left. Duplicates are dropped
+// whenever a sorted sequence is written, so the output, sorted by endpoint labels,
+// has every edge once. The input is read once and the temporary files are removed
+// when the work ends, successfully or not.
+use crate::direction::Direction;
+use crate::edge_list::parse_edge_line;
+use crate::error::{file_error, GraphError};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Memory budget of the dedupe-edges subcommand when none is given
+pub const DEFAULT_MEM_BUDGET_BYTES: usize = 64 * 1024 * 1024;
+/// Read buffer of each run being merged; the budget decides how many runs merge at once
+const MERGE_BUFFER_BYTES: usize = 8 * 1024;
+/// Most runs merged at once, whatever the budget, to stay well within open file limits
+const MAX_MERGE_FAN_IN: usize = 64;
+
+/// Counts reported by dedupe_edge_list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DedupStats {
+    /// Lines of the input, including blank and comment lines
+    pub lines_read: usize,
+    /// Edge lines of the input
+    pub edges_read: usize,
+    /// Edges dropped for repeating the endpoints of a kept edge
+    pub duplicates_removed: usize,
+    /// Dropped duplicates whose weight differed from the kept edge's
+    pub conflicting_weights: usize,
+    /// Edges in the output
+    pub edges_written: usize,
+    /// Sorted runs spilled to temporary files; 0 when the input fit the budget
+    pub runs: usize,
+    /// Merge passes over the spilled runs
+    pub merge_passes: usize,
+}
+
+impl fmt::Display for DedupStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "edges read: {} ({} lines)", self.edges_read, self.lines_read)?;
+        writeln!(f, "duplicates removed: {}", self.duplicates_removed)?;
+        if self.conflicting_weights > 0 {
+            writeln!(
+                f,
+                "Warning: {} duplicates had another weight; the unweighted or lightest edge was kept",
+                self.conflicting_weights
+            )?;
+        }
+        writeln!(f, "edges written: {}", self.edges_written)?;
+        writeln!(f, "sorted runs: {} in {} merge passes", self.runs, self.merge_passes)
+    }
+}
+
+/// One edge in canonical form
+#[derive(Debug, Clone, PartialEq)]
+struct EdgeRecord {
+    u: String,
+    v: String,
+    weight: Option<f64>,
+}
+
+impl EdgeRecord {
+    /// Canonicalizes an edge; undirected, the endpoints are put in label order
+    /// A label starting with # never goes first, where it would make a comment line
+    fn new(u: String, v: String, weight: Option<f64>, direction: Direction) -> Self {
+        if direction == Direction::Undirected && v < u && !v.starts_with('#') {
+            EdgeRecord { u: v, v: u, weight }
+        } else {
+            EdgeRecord { u, v, weight }
+        }
+    }
+
+    fn same_edge(&self, other: &EdgeRecord) -> bool {
+        self.u == other.u && self.v == other.v
+    }
+
+    /// Memory the record takes in a run, counted against the budget
+    fn size(&self) -> usize {
+        std::mem::size_of::<EdgeRecord>() + self.u.len() + self.v.len()
+    }
+
+    fn write_line(&self, out: &mut dyn Write) -> io::Result<()> {
+        match self.weight {
+            Some(weight) => writeln!(out, "{} {} {}", self.u, self.v, weight),
+            None => writeln!(out, "{} {}", self.u, self.v),
+        }
+    }
+}
+
+impl Eq for EdgeRecord {}
+
+impl Ord for EdgeRecord {
+    /// By endpoints, then unweighted before weighted and lighter before heavier
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.u
+            .cmp(&other.u)
+            .then_with(|| self.v.cmp(&other.v))
+            .then_with(|| match (self.weight, other.weight) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (a, b) => a.is_some().cmp(&b.is_some()),
+            })
+    }
+}
+
+impl PartialOrd for EdgeRecord {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Writes a sorted sequence of records, dropping each one with the endpoints of the
+/// record before it, which the sort order makes the lightest of its edge
+struct UniqueWriter<'a> {
+    out: &'a mut dyn Write,
+    last: Option<EdgeRecord>,
+    written: usize,
+}
+
+impl<'a> UniqueWriter<'a> {
+    fn new(out: &'a mut dyn Write) -> Self {
+        UniqueWriter {
+            out,
+            last: None,
+            written: 0,
+        }
+    }
+
+    fn push(&mut self, record: EdgeRecord, stats: &mut DedupStats) -> io::Result<()> {
+        if let Some(last) = &self.last {
+            if last.same_edge(&record) {
+                stats.duplicates_removed += 1;
+                if last.weight.map(f64::to_bits) != record.weight.map(f64::to_bits) {
+                    stats.conflicting_weights += 1;
+                }
+                return Ok(());
+            }
+        }
+        record.write_line(self.out)?;
+        self.written += 1;
+        self.last = Some(record);
+        Ok(())
+    }
+}
+
+/// Temporary directory of the spilled runs, removed with its files when dropped
+struct SpillDir {
+    path: PathBuf,
+    files: usize,
+}
+
+impl SpillDir {
+    fn new() -> io::Result<Self> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|t| t.as_nanos()).unwrap_or(0);
+        let path = std::env::temp_dir().join(format!("graph-dedupe-{}-{}", std::process::id(), nanos));
+        fs::create_dir(&path)?;
+        Ok(SpillDir { path, files: 0 })
+    }
+
+    /// Creates the next spill file
+    fn create(&mut self) -> io::Result<(PathBuf, BufWriter<File>)> {
+        self.files += 1;
+        let path = self.path.join(format!("run-{}.txt", self.files));
+        let file = File::create(&path)?;
+        Ok((path, BufWriter::new(file)))
+    }
+}
+
+impl Drop for SpillDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Sorts a run and writes it, deduplicated, to a new spill file
+fn spill_run(spill: &mut SpillDir, run: &mut Vec<EdgeRecord>, stats: &mut DedupStats) -> io::Result<PathBuf> {
+    run.sort_unstable();
+    let (path, mut out) = spill.create()?;
+    let mut writer = UniqueWriter::new(&mut out);
+    for record in run.drain(..) {
+        writer.push(record, stats)?;
+    }
+    out.flush()?;
+    stats.runs += 1;
+    Ok(path)
+}
+
+/// Reads the next record of a spilled run
+fn next_record(lines: &mut Lines<BufReader<File>>) -> Result<Option<EdgeRecord>, GraphError> {
+    let Some(line) = lines.next() else {
+        return Ok(None);
+    };
+    // Spilled records are already canonical, so they keep their direction
+    Ok(parse_edge_line(&line?, 0)?.map(|edge| EdgeRecord::new(edge.u, edge.v, edge.weight, Direction::Directed)))
+}
+
+/// Merges sorted runs into one sorted, deduplicated sequence, returning its length
+fn merge_runs(paths: &[PathBuf], out: &mut dyn Write, stats: &mut DedupStats) -> Result<usize, GraphError> {
+    let mut runs = Vec::with_capacity(paths.len());
+    for path in paths {
+        runs.push(BufReader::with_capacity(MERGE_BUFFER_BYTES, File::open(path)?).lines());
+    }
+    let mut heap = BinaryHeap::with_capacity(runs.len());
+    for (i, run) in runs.iter_mut().enumerate() {
+        if let Some(record) = next_record(run)? {
+            heap.push(Reverse((record, i)));
+        }
+    }
+
+    let mut writer = UniqueWriter::new(out);
+    while let Some(Reverse((record, i))) = heap.pop() {
+        if let Some(next) = next_record(&mut runs[i])? {
+            heap.push(Reverse((next, i)));
+        }
+        writer.push(record, stats)?;
+    }
+    let written = writer.written;
+    out.flush()?;
+    Ok(written)
+}
+
+/// Sorts and deduplicates an edge list within a memory budget, reading the edges as directed
+/// See dedupe_edge_list_with
+pub fn dedupe_edge_list(input: &Path, output: &Path, mem_budget_bytes: usize) -> Result<DedupStats, GraphError> {
+    dedupe_edge_list_with(input, output, mem_budget_bytes, Direction::Directed)
+}
+
+/// Writes the edges of an edge list file to output sorted and each once, holding at
+/// most about mem_budget_bytes of edges in memory
+///
+/// Directed, `u v` and `v u` are different edges; undirected, every edge is written
+/// with its endpoints in label order, so `v u` is a duplicate of `u v`. Edges with
+/// the same endpoints but different weights are duplicates too: the unweighted one,
+/// or else the lightest, is kept and the others are counted as conflicting. Output
+/// lines are `u v` or `u v weight`; comments and blank lines are not copied. The
+/// output may be the input file itself, since it is only created once the input has
+/// been read. The budget is a target rather than a hard cap: a run holds at least
+/// one edge and a merge reads from at least two runs.
+pub fn dedupe_edge_list_with(
+    input: &Path,
+    output: &Path,
+    mem_budget_bytes: usize,
+    direction: Direction,
+) -> Result<DedupStats, GraphError> {
+    let file = File::open(input).map_err(file_error(&input.display().to_string()))?;
+    let mut stats = DedupStats::default();
+    let mut spill: Option<SpillDir> = None;
+    let mut spilled: Vec<PathBuf> = Vec::new();
+    let mut run: Vec<EdgeRecord> = Vec::new();
+    let mut run_bytes = 0;
+
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        stats.lines_read += 1;
+        let Some(edge) = parse_edge_line(&line?, i + 1)? else {
+            continue;
+        };
+        stats.edges_read += 1;
+        let record = EdgeRecord::new(edge.u, edge.v, edge.weight, direction);
+        run_bytes += record.size();
+        run.push(record);
+        if run_bytes >= mem_budget_bytes {
+            if spill.is_none() {
+                spill = Some(SpillDir::new()?);
+            }
+            if let Some(spill) = spill.as_mut() {
+                spilled.push(spill_run(spill, &mut run, &mut stats)?);
+            }
+            run_bytes = 0;
+        }
+    }
+
+    let Some(mut spill) = spill else {
+        // Everything fit in the budget, so the one run goes straight to the output
+        run.sort_unstable();
+        let mut out = BufWriter::new(File::create(output)?);
+        let mut writer = UniqueWriter::new(&mut out);
+        for record in run {
+            writer.push(record, &mut stats)?;
+        }
+        stats.edges_written = writer.written;
+        out.flush()?;
+        return Ok(stats);
+    };
+    if !run.is_empty() {
+        spilled.push(spill_run(&mut spill, &mut run, &mut stats)?);
+    }
+
+    let fan_in = (mem_budget_bytes / MERGE_BUFFER_BYTES).clamp(2, MAX_MERGE_FAN_IN);
+    while spilled.len() > fan_in {
+        let mut merged = Vec::new();
+        for group in spilled.chunks(fan_in) {
+            let (path, mut out) = spill.create()?;
+            merge_runs(group, &mut out, &mut stats)?;
+            merged.push(path);
+        }
+        for path in &spilled {
+            fs::remove_file(path)?;
+        }
+        spilled = merged;
+        stats.merge_passes += 1;
+    }
+
+    let mut out = BufWriter::new(File::create(output)?);
+    stats.edges_written = merge_runs(&spilled, &mut out, &mut stats)?;
+    stats.merge_passes += 1;
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::scratch_dir;
+    use std::collections::BTreeSet;
+
+    /// 600 edges over 12 vertices, each pair written both ways round many times
+    fn noisy_edge_list() -> String {
+        let mut text = String::from("# generated\n\n");
+        for k in 0..600 {
+            let (u, v) = ((k * 7) % 12, (k * 5 + 1) % 12);
+            text.push_str(&format!("v{} v{}\n", u, v));
+        }
+        text
+    }
+
+    fn expected_lines(text: &str, direction: Direction) -> Vec<String> {
+        let edges: BTreeSet<(String, String)> = text
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once(' '))
+            .map(|(u, v)| {
+                let (u, v) = (u.to_string(), v.to_string());
+                if direction == Direction::Undirected && v < u {
+                    (v, u)
+                } else {
+                    (u, v)
+                }
+            })
+            .collect();
+        edges.into_iter().map(|(u, v)| format!("{} {}", u, v)).collect()
+    }
+
+    #[test]
+    fn tiny_budget_spills_and_merges_to_the_same_result() {
+        let dir = scratch_dir("dedupe", "budget");
+        let (input, small, large) = (dir.join("in.edges"), dir.join("small.edges"), dir.join("large.edges"));
+        let text = noisy_edge_list();
+        fs::write(&input, &text).unwrap();
+
+        let spilled = dedupe_edge_list_with(&input, &small, 1, Direction::Undirected).unwrap();
+        let in_memory = dedupe_edge_list_with(&input, &large, DEFAULT_MEM_BUDGET_BYTES, Direction::Undirected).unwrap();
+        let written = fs::read_to_string(&small).unwrap();
+        assert_eq!(written, fs::read_to_string(&large).unwrap());
+        assert_eq!(written.lines().collect::<Vec<_>>(), expected_lines(&text, Direction::Undirected));
+
+        assert_eq!((spilled.lines_read, spilled.edges_read), (602, 600));
+        assert_eq!(spilled.edges_written, written.lines().count());
+        assert_eq!(spilled.duplicates_removed, 600 - spilled.edges_written);
+        assert_eq!(spilled.runs, 600);
+        assert!(spilled.merge_passes > 1, "{:?}", spilled);
+        assert_eq!((in_memory.runs, in_memory.edges_written), (0, spilled.edges_written));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn directed_keeps_both_directions() {
+        let dir = scratch_dir("dedupe", "directed");
+        let (input, output) = (dir.join("in.edges"), dir.join("out.edges"));
+        let text = noisy_edge_list();
+        fs::write(&input, &text).unwrap();
+        let stats = dedupe_edge_list(&input, &output, 1).unwrap();
+        let written = fs::read_to_string(&output).unwrap();
+        assert_eq!(written.lines().collect::<Vec<_>>(), expected_lines(&text, Direction::Directed));
+        assert_eq!(stats.edges_written, written.lines().count());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn lightest_duplicate_is_kept() {
+        let dir = scratch_dir("dedupe", "weights");
+        let input = dir.join("in.edges");
+        fs::write(&input, "a b 3\nb a 1.5\na b 2\nc d\nc d 4\n").unwrap();
+        // The output may be the input file itself
+        let stats = dedupe_edge_list_with(&input, &input, 1, Direction::Undirected).unwrap();
+        assert_eq!(fs::read_to_string(&input).unwrap(), "a b 1.5\nc d\n");
+        assert_eq!((stats.duplicates_removed, stats.conflicting_weights), (3, 3));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,114 @@
+// src/density.rs - Choosing between direct and complement-based algorithms
+//
+// On a dense graph most pairs are joined, so an algorithm that walks the edges
+// does nearly n^2 work, while one that walks the missing edges, the complement,
+// does little. The algorithms below have both forms, returning the same result,
+// and by default pick one from the density of the graph. The *_with methods take
+// the strategy explicitly.
+use crate::algo;
+use crate::Graph;
+use std::fmt;
+
+/// Density from which the complement-based algorithms are picked by default
+pub const COMPLEMENT_DENSITY_THRESHOLD: f64 = 0.5;
+
+/// How an algorithm with a direct and a complement-based form goes about its work
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DensityStrategy {
+    /// Follow the edges
+    #[default]
+    Direct,
+    /// Follow the missing edges, skipping over the vertices they leave out
+    Complement,
+}
+
+impl DensityStrategy {
+    /// Picks the strategy for a graph of the given density
+    pub fn for_density(density: f64) -> Self {
+        if density >= COMPLEMENT_DENSITY_THRESHOLD {
+            DensityStrategy::Complement
+        } else {
+            DensityStrategy::Direct
+        }
+    }
+}
+
+impl fmt::Display for DensityStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DensityStrategy::Direct => write!(f, "direct"),
+            DensityStrategy::Complement => write!(f, "complement"),
+        }
+    }
+}
+
+impl Graph {
+    /// Share of the ordered pairs of distinct vertices listed as edges, from 0 to 1
+    ///
+    /// Read off the degree counts in linear time, so an undirected edge counts in
+    /// both directions and a self-loop counts as one listing. Graphs of fewer than
+    /// two vertices have density 0.
+    pub fn density(&self) -> f64 {
+        let n = self.n_vertices;
+        if n < 2 {
+            return 0.0;
+        }
+        let listed: usize = self.cached_degrees().iter().sum();
+        (listed as f64 / (n * (n - 1)) as f64).min(1.0)
+    }
+
+    /// The strategy the algorithms with two forms use on this graph by default
+    pub fn density_strategy(&self) -> DensityStrategy {
+        DensityStrategy::for_density(self.density())
+    }
+
+    /// Returns the vertices in BFS order from a start, as bfs does, with the given strategy
+    pub fn bfs_with(&self, starting_index: usize, strategy: DensityStrategy) -> Vec<String> {
+        self.labels_of(&algo::bfs_with(self, starting_index, strategy))
+    }
+
+    /// Returns the connected components, as connected_components does, with the given
+    /// strategy and without the cache
+    pub fn connected_components_with(&self, strategy: DensityStrategy) -> Vec<Vec<String>> {
+        let ids = algo::component_ids_with(self, strategy);
+        self.components_from_ids(&ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn strategy_follows_the_threshold() {
+        assert_eq!(fixtures::triangle().density(), 1.0);
+        assert_eq!(fixtures::triangle().density_strategy(), DensityStrategy::Complement);
+        assert_eq!(fixtures::disconnected_pair().density(), 4.0 / 12.0);
+        assert_eq!(fixtures::disconnected_pair().density_strategy(), DensityStrategy::Direct);
+        assert_eq!(Graph::new().density(), 0.0);
+    }
+
+    #[test]
+    fn both_strategies_agree() {
+        for graph in [
+            fixtures::triangle(),
+            fixtures::petersen(),
+            fixtures::two_cliques_bridge(),
+            fixtures::disconnected_pair(),
+            fixtures::weighted_square(),
+        ] {
+            for start in 0..graph.n_vertices {
+                assert_eq!(
+                    graph.bfs_with(start, DensityStrategy::Direct),
+                    graph.bfs_with(start, DensityStrategy::Complement)
+                );
+            }
+            assert_eq!(
+                graph.connected_components_with(DensityStrategy::Direct),
+                graph.connected_components_with(DensityStrategy::Complement)
+            );
+            assert_eq!(graph.connected_components_with(DensityStrategy::Direct), graph.connected_components());
+        }
+    }
+}
@@ -0,0 +1,245 @@
+// src/dialect.rs - Input file dialects and the warnings they can produce
+use crate::direction::{Direction, DirectionProfile};
+use crate::error::{file_error, GraphError};
+use std::fmt;
+
+/// Selects how strictly the adjacency input format is interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    /// The first line must be the vertex count; anything else is an error
+    #[default]
+    Strict,
+    /// The leading count line may be absent, in which case the number of
+    /// vertices is inferred from the non-empty adjacency lines
+    InferCount,
+    /// Like InferCount, and the direction is inferred from the share of pairs
+    /// listed both ways: a mostly reciprocal file is made undirected, a mostly
+    /// one-way file is kept directed, and anything in between is an error
+    AutoDirection,
+    /// Like InferCount, and the graph is kept directed: each line lists only the
+    /// successors of its vertex
+    Directed,
+    /// Like InferCount, and the graph is made undirected by adding every missing
+    /// reverse entry
+    Undirected,
+}
+
+/// Selects how bytes that are not valid UTF-8 are read, as in files from Latin-1 systems
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// Invalid UTF-8 is an error naming the line and byte where it starts
+    #[default]
+    Strict,
+    /// Every invalid sequence becomes U+FFFD, and a warning lists the lines and
+    /// labels affected. Labels differing only in the replaced bytes become equal.
+    Lossy,
+}
+
+/// Decodes the lines of a file one at a time, remembering what Lossy replaced
+///
+/// decode_lines runs over a whole file in memory; readers that stream their input,
+/// such as the edge list and graph6 readers, feed it line by line instead.
+pub(crate) struct LineDecoder {
+    encoding: Encoding,
+    replaced_lines: Vec<usize>,
+    labels: Vec<String>,
+}
+
+impl LineDecoder {
+    pub(crate) fn new(encoding: Encoding) -> Self {
+        LineDecoder {
+            encoding,
+            replaced_lines: Vec::new(),
+            labels: Vec::new(),
+        }
+    }
+
+    /// Decodes line number line (counted from 1), without its LF or CR LF ending
+    pub(crate) fn decode(&mut self, raw: &[u8], line: usize) -> Result<String, GraphError> {
+        let raw = raw.strip_suffix(b"\n").unwrap_or(raw);
+        let raw = raw.strip_suffix(b"\r").unwrap_or(raw);
+        match std::str::from_utf8(raw) {
+            Ok(text) => Ok(text.to_string()),
+            Err(error) if self.encoding == Encoding::Strict => Err(GraphError::InvalidEncoding {
+                line,
+                byte_offset: error.valid_up_to(),
+            }),
+            Err(_) => {
+                let text = String::from_utf8_lossy(raw).into_owned();
+                self.replaced_lines.push(line);
+                for token in text.split_whitespace().filter(|token| token.contains('\u{FFFD}')) {
+                    if !self.labels.iter().any(|label| label == token) {
+                        self.labels.push(token.to_string());
+                    }
+                }
+                Ok(text)
+            }
+        }
+    }
+
+    /// Returns the warning listing the replaced lines and labels, if any were
+    pub(crate) fn finish(self) -> Option<ParseWarning> {
+        (!self.replaced_lines.is_empty()).then_some(ParseWarning::EncodingReplaced {
+            lines: self.replaced_lines,
+            labels: self.labels,
+        })
+    }
+}
+
+/// Splits the bytes of a file into lines, as BufRead::lines does, decoding them as UTF-8
+///
+/// Strict, the first invalid byte is a GraphError::InvalidEncoding. Lossy, invalid
+/// sequences are replaced with U+FFFD and the returned warning, if any, lists the
+/// changed lines and the labels on them that hold a replacement character.
+pub fn decode_lines(bytes: &[u8], encoding: Encoding) -> Result<(Vec<String>, Option<ParseWarning>), GraphError> {
+    let mut decoder = LineDecoder::new(encoding);
+    if bytes.is_empty() {
+        return Ok((Vec::new(), None));
+    }
+    let body = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    let lines = body
+        .split(|&b| b == b'\n')
+        .enumerate()
+        .map(|(i, raw)| decoder.decode(raw, i + 1))
+        .collect::<Result<Vec<String>, GraphError>>()?;
+    Ok((lines, decoder.finish()))
+}
+
+/// Reads a text file and splits it into lines with decode_lines
+pub fn read_file_lines(path: &str, encoding: Encoding) -> Result<(Vec<String>, Option<ParseWarning>), GraphError> {
+    decode_lines(&std::fs::read(path).map_err(file_error(path))?, encoding)
+}
+
+/// Non-fatal observations made while reading an input file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// No count line was found; the graph was read with this many vertices
+    CountInferred(usize),
+    /// The first line was taken as an adjacency line even though its first
+    /// token is a number, so that label could have been a missing count
+    NumericFirstLabel(String),
+    /// Input of the form #N named an existing label and was read as that label,
+    /// not as index N
+    AmbiguousVertexRef(String),
+    /// Lossy encoding replaced invalid UTF-8 on these lines, changing these labels
+    EncodingReplaced { lines: Vec<usize>, labels: Vec<String> },
+    /// AutoDirection read the graph with this direction, given these pair counts
+    DirectionChosen {
+        direction: Direction,
+        reciprocal_pairs: usize,
+        one_way_pairs: usize,
+    },
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWarning::CountInferred(n) => {
+                write!(f, "missing vertex count line; inferred {} vertices", n)
+            }
+            ParseWarning::NumericFirstLabel(label) => write!(
+                f,
+                "first line starts with numeric label {}; treated it as a vertex, not a count",
+                label
+            ),
+            ParseWarning::AmbiguousVertexRef(input) => write!(
+                f,
+                "{} is a vertex label; using the label rather than the index",
+                input
+            ),
+            ParseWarning::EncodingReplaced { lines, labels } => {
+                let lines: Vec<String> = lines.iter().map(usize::to_string).collect();
+                write!(f, "invalid UTF-8 replaced with U+FFFD on lines {}", lines.join(","))?;
+                if !labels.is_empty() {
+                    write!(f, "; affected labels: {}", labels.join(","))?;
+                }
+                Ok(())
+            }
+            ParseWarning::DirectionChosen {
+                direction,
+                reciprocal_pairs,
+                one_way_pairs,
+            } => {
+                let profile = DirectionProfile::new(*reciprocal_pairs, *one_way_pairs);
+                match direction {
+                    Direction::Undirected if *one_way_pairs > 0 => write!(
+                        f,
+                        "{}; read as undirected, adding the missing reverse entries",
+                        profile
+                    ),
+                    _ => write!(f, "{}; read as {}", profile, direction),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Graph;
+
+    const LATIN1: &[u8] = b"2\n\xe9t\xe9 B\nB \xe9t\xe9\n";
+
+    fn read(text: &str, dialect: Dialect) -> Result<(Graph, Vec<ParseWarning>), GraphError> {
+        let mut graph = Graph::new();
+        let warnings = graph.read_input_text_with(text, dialect)?;
+        Ok((graph, warnings))
+    }
+
+    #[test]
+    fn strict_encoding_points_at_the_first_bad_byte() {
+        assert_eq!(
+            decode_lines(LATIN1, Encoding::Strict),
+            Err(GraphError::InvalidEncoding { line: 2, byte_offset: 0 })
+        );
+        assert_eq!(
+            decode_lines(b"A -1\r\nB -1", Encoding::Strict),
+            Ok((vec!["A -1".to_string(), "B -1".to_string()], None))
+        );
+    }
+
+    #[test]
+    fn lossy_encoding_lists_lines_and_labels() {
+        let (lines, warning) = decode_lines(LATIN1, Encoding::Lossy).unwrap();
+        assert_eq!(lines[1], "\u{FFFD}t\u{FFFD} B");
+        let warning = warning.unwrap();
+        assert_eq!(
+            warning,
+            ParseWarning::EncodingReplaced {
+                lines: vec![2, 3],
+                labels: vec!["\u{FFFD}t\u{FFFD}".to_string()],
+            }
+        );
+        assert_eq!(
+            warning.to_string(),
+            "invalid UTF-8 replaced with U+FFFD on lines 2,3; affected labels: \u{FFFD}t\u{FFFD}"
+        );
+    }
+
+    #[test]
+    fn strict_needs_the_count_and_infer_count_warns() {
+        assert!(read("A B -1\nB A -1\n", Dialect::Strict).is_err());
+        let (graph, warnings) = read("A B -1\nB A -1\n", Dialect::InferCount).unwrap();
+        assert_eq!(graph.n_vertices, 2);
+        assert_eq!(warnings, vec![ParseWarning::CountInferred(2)]);
+    }
+
+    #[test]
+    fn direction_dialects() {
+        let one_way = "A B C -1\nB C -1\nC -1\n";
+        let (graph, _) = read(one_way, Dialect::Directed).unwrap();
+        assert_eq!((graph.adj_matrix[0][1], graph.adj_matrix[1][0]), (1, 0));
+        let (graph, _) = read(one_way, Dialect::Undirected).unwrap();
+        assert_eq!((graph.adj_matrix[0][1], graph.adj_matrix[1][0]), (1, 1));
+        graph.check_invariants().unwrap();
+
+        let (_, warnings) = read(one_way, Dialect::AutoDirection).unwrap();
+        assert!(warnings.iter().any(|warning| matches!(
+            warning,
+            ParseWarning::DirectionChosen { direction: Direction::Directed, reciprocal_pairs: 0, one_way_pairs: 3 }
+        )));
+        // Half of the pairs each way is too close to call
+        assert!(read("A B C -1\nB A -1\nC -1\n", Dialect::AutoDirection).is_err());
+    }
+}
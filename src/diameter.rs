@@ -0,0 +1,120 @@
+// src/diameter.rs - Diameter bounds from a few BFS sweeps
+//
+// Edges count in either direction, and the diameter is the largest finite
+// distance between two vertices, so a disconnected graph has the diameter of its
+// widest component. Graphs of up to EXACT_DIAMETER_LIMIT vertices get the exact
+// value from a BFS per vertex; larger ones get bounds from double sweeps.
+use crate::Graph;
+use std::collections::VecDeque;
+
+/// Graphs with at most this many vertices have their diameter computed exactly
+pub const EXACT_DIAMETER_LIMIT: usize = 500;
+
+impl Graph {
+    /// Returns the undirected hop distance from start to every vertex
+    pub(crate) fn undirected_hops(&self, start: usize) -> Vec<Option<usize>> {
+        let n = self.n_vertices;
+        let mut hops = vec![None; n];
+        hops[start] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(current) = queue.pop_front() {
+            let next = hops[current].map(|h| h + 1);
+            for (i, hop) in hops.iter_mut().enumerate() {
+                let linked = self.adj_matrix[current][i] == 1 || self.adj_matrix[i][current] == 1;
+                if linked && hop.is_none() {
+                    *hop = next;
+                    queue.push_back(i);
+                }
+            }
+        }
+        hops
+    }
+
+    /// Returns the farthest vertex in hops and its distance, the alphabetically first on ties
+    fn farthest(&self, hops: &[Option<usize>]) -> (usize, usize) {
+        let mut best: Option<(usize, usize)> = None;
+        for &v in self.cached_sorted() {
+            if let Some(d) = hops[v] {
+                if !matches!(best, Some((_, b)) if b >= d) {
+                    best = Some((v, d));
+                }
+            }
+        }
+        best.unwrap_or((0, 0))
+    }
+
+    /// Runs a double sweep in the component of every vertex not yet covered
+    /// Returns per component (u, v, d(u, v), eccentricity of a middle vertex of the u-v path)
+    fn double_sweeps(&self) -> Vec<(usize, usize, usize, usize)> {
+        let mut covered = vec![false; self.n_vertices];
+        let mut sweeps = Vec::new();
+        for &start in self.cached_sorted() {
+            if covered[start] {
+                continue;
+            }
+            let from_start = self.undirected_hops(start);
+            for (v, hop) in from_start.iter().enumerate() {
+                covered[v] |= hop.is_some();
+            }
+            let (u, _) = self.farthest(&from_start);
+            let from_u = self.undirected_hops(u);
+            let (v, d) = self.farthest(&from_u);
+
+            // A vertex halfway along a shortest u-v path is a good center candidate
+            let from_v = self.undirected_hops(v);
+            let middle = (0..self.n_vertices)
+                .find(|&w| from_u[w] == Some(d / 2) && from_v[w] == Some(d - d / 2))
+                .unwrap_or(u);
+            let (_, eccentricity) = self.farthest(&self.undirected_hops(middle));
+            sweeps.push((u, v, d, eccentricity));
+        }
+        sweeps
+    }
+
+    /// Computes the diameter exactly with a BFS from every vertex, with a pair at that distance
+    fn exact_diameter(&self) -> (usize, usize, usize) {
+        let mut best: Option<(usize, usize, usize)> = None;
+        for &u in self.cached_sorted() {
+            let (v, d) = self.farthest(&self.undirected_hops(u));
+            if !matches!(best, Some((_, _, b)) if b >= d) {
+                best = Some((u, v, d));
+            }
+        }
+        best.unwrap_or((0, 0, 0))
+    }
+
+    /// Finds two vertices at distance at least k, or None if none was found
+    ///
+    /// Small graphs are searched exhaustively, so None means the diameter is below k.
+    /// Above EXACT_DIAMETER_LIMIT only the double sweep pairs are tried, and None
+    /// only means no sweep reached that far.
+    pub fn diameter_at_least(&self, k: usize) -> Option<(String, String)> {
+        let (u, v, d) = if self.n_vertices <= EXACT_DIAMETER_LIMIT {
+            self.exact_diameter()
+        } else {
+            self.double_sweeps()
+                .into_iter()
+                .map(|(u, v, d, _)| (u, v, d))
+                .max_by_key(|&(_, _, d)| d)?
+        };
+        (self.n_vertices > 0 && d >= k).then(|| (self.vertices[u].clone(), self.vertices[v].clone()))
+    }
+
+    /// Returns certified lower and upper bounds on the diameter
+    ///
+    /// The lower bound is the distance of the farthest double sweep pair, which is
+    /// a real distance; the upper bound is twice the eccentricity of the middle
+    /// vertex of that pair's path, since every two vertices of a component are
+    /// within that of each other. Small graphs get the exact value for both.
+    pub fn diameter_approx(&self) -> (usize, usize) {
+        if self.n_vertices <= EXACT_DIAMETER_LIMIT {
+            let (_, _, d) = self.exact_diameter();
+            return (d, d);
+        }
+        self.double_sweeps()
+            .into_iter()
+            .fold((0, 0), |(lower, upper), (_, _, d, eccentricity)| {
+                (lower.max(d), upper.max((2 * eccentricity).max(d)))
+            })
+    }
+}
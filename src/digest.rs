@@ -0,0 +1,183 @@
+// src/digest.rs - Checksums that tell whether the graph representations drifted apart
+//
+// Each representation is hashed on its own: the adjacency matrix by its rows, the
+// adjacency lists by the vertex indices their labels resolve to, and adj_count by
+// the degree of each vertex. A vertex hashes to the wrapping sum of the hashes of
+// its (vertex, neighbor) pairs, so neither the order of a list nor repeated
+// entries change it, and the graph hashes to the wrapping sum over its vertices.
+// Neighbors naming no vertex are left out of the list hash, as make_adj_matrix
+// leaves them out of the matrix, but they count in the list length.
+use crate::rng::Rng;
+use crate::Graph;
+use std::collections::HashMap;
+use std::fmt;
+
+/// The checksums of one vertex
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VertexDigest {
+    /// Hash of the neighbors the matrix row holds
+    pub matrix: u64,
+    /// Hash of the neighbors the adjacency list names
+    pub list: u64,
+    pub adj_count: i32,
+    /// Number of entries in the adjacency list
+    pub list_len: usize,
+}
+
+/// Independent checksums of the adjacency matrix, the adjacency lists and the degree counts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepresentationDigest {
+    pub matrix: u64,
+    pub lists: u64,
+    pub counts: u64,
+    /// Hash of the list lengths, which counts must equal
+    list_lengths: u64,
+    /// Label and checksums of every vertex, in index order
+    pub vertices: Vec<(String, VertexDigest)>,
+}
+
+/// The first vertex at which two representations disagree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DigestMismatch {
+    /// The matrix row and the adjacency list hold different neighbors
+    Neighbors { vertex: String, index: usize },
+    /// adj_count differs from the length of the adjacency list
+    Degree {
+        vertex: String,
+        index: usize,
+        adj_count: i32,
+        list_len: usize,
+    },
+}
+
+impl fmt::Display for DigestMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DigestMismatch::Neighbors { vertex, index } => write!(
+                f,
+                "vertex {} (index {}): the matrix row and the adjacency list hold different neighbors",
+                vertex, index
+            ),
+            DigestMismatch::Degree {
+                vertex,
+                index,
+                adj_count,
+                list_len,
+            } => write!(
+                f,
+                "vertex {} (index {}): degree count {} but {} adjacency list entries",
+                vertex, index, adj_count, list_len
+            ),
+        }
+    }
+}
+
+/// Hash of a (vertex, value) pair
+fn pair_hash(vertex: usize, value: u64) -> u64 {
+    Rng::new(Rng::new(vertex as u64).next_u64() ^ value).next_u64()
+}
+
+impl RepresentationDigest {
+    /// Checks whether the three representations describe the same graph
+    pub fn consistent(&self) -> bool {
+        self.matrix == self.lists && self.counts == self.list_lengths
+    }
+
+    /// Returns the lowest-indexed vertex where the representations disagree, or None
+    /// Disagreeing neighbors are reported before disagreeing degrees
+    pub fn first_mismatch(&self) -> Option<DigestMismatch> {
+        self.vertices.iter().enumerate().find_map(|(index, (vertex, digest))| {
+            if digest.matrix != digest.list {
+                Some(DigestMismatch::Neighbors {
+                    vertex: vertex.clone(),
+                    index,
+                })
+            } else if digest.adj_count < 0 || digest.adj_count as usize != digest.list_len {
+                Some(DigestMismatch::Degree {
+                    vertex: vertex.clone(),
+                    index,
+                    adj_count: digest.adj_count,
+                    list_len: digest.list_len,
+                })
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl fmt::Display for RepresentationDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "matrix: {:016x}", self.matrix)?;
+        writeln!(f, "lists:  {:016x}", self.lists)?;
+        writeln!(f, "counts: {:016x}", self.counts)?;
+        match self.first_mismatch() {
+            Some(mismatch) => writeln!(f, "inconsistent: {}", mismatch),
+            None if self.consistent() => writeln!(f, "consistent ({} vertices)", self.vertices.len()),
+            // Only a hash collision gets here, the graph sums disagreeing with every vertex
+            None => writeln!(f, "inconsistent: the totals differ but no vertex does"),
+        }
+    }
+}
+
+impl Graph {
+    /// Hashes the adjacency matrix, the adjacency lists and the degree counts separately
+    ///
+    /// Takes time proportional to the square of the number of vertices, as the
+    /// matrix does. See RepresentationDigest::first_mismatch for where they disagree.
+    pub fn representation_digest(&self) -> RepresentationDigest {
+        let mut index: HashMap<&str, usize> = HashMap::with_capacity(self.vertices.len());
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            index.entry(vertex.as_str()).or_insert(i);
+        }
+
+        let mut digest = RepresentationDigest {
+            matrix: 0,
+            lists: 0,
+            counts: 0,
+            list_lengths: 0,
+            vertices: Vec::with_capacity(self.n_vertices),
+        };
+        for v in 0..self.n_vertices {
+            let matrix = self.adj_matrix[v]
+                .iter()
+                .take(self.n_vertices)
+                .enumerate()
+                .filter(|&(_, &cell)| cell != 0)
+                .fold(0u64, |sum, (u, _)| sum.wrapping_add(pair_hash(v, u as u64)));
+
+            let listed = self.collect_adjacent_vertices(&self.adj_list[v]);
+            let mut neighbors: Vec<usize> = listed.iter().filter_map(|label| index.get(label.as_str()).copied()).collect();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            let list = neighbors
+                .iter()
+                .fold(0u64, |sum, &u| sum.wrapping_add(pair_hash(v, u as u64)));
+
+            let vertex = VertexDigest {
+                matrix,
+                list,
+                adj_count: self.adj_count[v],
+                list_len: listed.len(),
+            };
+            digest.matrix = digest.matrix.wrapping_add(pair_hash(v, matrix));
+            digest.lists = digest.lists.wrapping_add(pair_hash(v, list));
+            digest.counts = digest.counts.wrapping_add(pair_hash(v, vertex.adj_count as u64));
+            digest.list_lengths = digest.list_lengths.wrapping_add(pair_hash(v, vertex.list_len as u64));
+            digest.vertices.push((self.vertices.get(v).cloned().unwrap_or_default(), vertex));
+        }
+        digest
+    }
+
+    /// In debug builds, panics with the first mismatch when the representations disagree
+    ///
+    /// Called after reading, after make_adj_matrix and after every structural edit.
+    /// Release builds skip it; `main doctor` reports the same on any build.
+    pub(crate) fn debug_check_representations(&self) {
+        if cfg!(debug_assertions) {
+            if let Some(mismatch) = self.representation_digest().first_mismatch() {
+                panic!("graph representations drifted apart: {}", mismatch);
+            }
+        }
+    }
+}
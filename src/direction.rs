@@ -0,0 +1,124 @@
+// src/direction.rs - Deciding whether an input file describes a directed graph
+//
+// The input format lists an undirected edge in the lines of both endpoints and a
+// directed edge in the line of its source only. Files that mix the two are read
+// as directed when few pairs are listed both ways, as undirected when nearly all
+// are, and left to the user in between.
+use crate::Graph;
+use std::fmt;
+
+/// Reciprocity at or above which a graph is read as undirected
+pub const UNDIRECTED_RECIPROCITY: f64 = 0.9;
+/// Reciprocity at or below which a graph is read as directed
+pub const DIRECTED_RECIPROCITY: f64 = 0.1;
+
+/// Whether the edges of a graph have a direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Undirected,
+    Directed,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Undirected => write!(f, "undirected"),
+            Direction::Directed => write!(f, "directed"),
+        }
+    }
+}
+
+/// How many joined vertex pairs the adjacency lists give in both directions and in one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectionProfile {
+    /// Pairs each listed in the line of the other
+    pub reciprocal_pairs: usize,
+    /// Pairs where only one lists the other
+    pub one_way_pairs: usize,
+    /// Share of the pairs that are reciprocal; 1 when there are no pairs
+    pub reciprocity: f64,
+}
+
+impl DirectionProfile {
+    /// Builds the profile from the pair counts
+    pub fn new(reciprocal_pairs: usize, one_way_pairs: usize) -> Self {
+        let pairs = reciprocal_pairs + one_way_pairs;
+        DirectionProfile {
+            reciprocal_pairs,
+            one_way_pairs,
+            reciprocity: if pairs == 0 { 1.0 } else { reciprocal_pairs as f64 / pairs as f64 },
+        }
+    }
+
+    /// The direction the reciprocity points to, or None when it is too close to call
+    pub fn suggested(&self) -> Option<Direction> {
+        if self.reciprocity >= UNDIRECTED_RECIPROCITY {
+            Some(Direction::Undirected)
+        } else if self.reciprocity <= DIRECTED_RECIPROCITY {
+            Some(Direction::Directed)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for DirectionProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} pairs listed both ways, {} one way (reciprocity {:.2})",
+            self.reciprocal_pairs, self.one_way_pairs, self.reciprocity
+        )
+    }
+}
+
+impl Graph {
+    /// Counts the reciprocal and one-way vertex pairs in the adjacency lists
+    ///
+    /// Only the lists are read, so the profile describes the file as written even
+    /// after the matrix was built. Self-loops and neighbors without a line of their
+    /// own are not pairs and are skipped.
+    pub fn direction_profile(&self) -> DirectionProfile {
+        let n = self.n_vertices;
+        let mut listed = vec![vec![false; n]; n];
+        for (i, row) in listed.iter_mut().enumerate() {
+            for adj_vertex in self.collect_adjacent_vertices(&self.adj_list[i]) {
+                if let Some(j) = self.find_vertex_idx(&adj_vertex) {
+                    row[j] = true;
+                }
+            }
+        }
+
+        let (mut reciprocal, mut one_way) = (0, 0);
+        for (i, row) in listed.iter().enumerate() {
+            for (j, &forward) in row.iter().enumerate().skip(i + 1) {
+                match (forward, listed[j][i]) {
+                    (true, true) => reciprocal += 1,
+                    (true, false) | (false, true) => one_way += 1,
+                    (false, false) => {}
+                }
+            }
+        }
+        DirectionProfile::new(reciprocal, one_way)
+    }
+
+    /// Makes every edge undirected by adding the missing reverse adjacency list entries
+    /// Returns the number of entries added
+    pub fn symmetrize(&mut self) -> usize {
+        let mut missing = Vec::new();
+        for i in 0..self.n_vertices {
+            for j in 0..self.n_vertices {
+                if i != j && self.adj_matrix[i][j] == 1 && self.adj_matrix[j][i] == 0 {
+                    missing.push((j, self.vertices[i].clone()));
+                }
+            }
+        }
+        for (j, label) in &missing {
+            self.add_to_adj_list(*j, label);
+        }
+        if !missing.is_empty() {
+            self.make_adj_matrix();
+        }
+        missing.len()
+    }
+}
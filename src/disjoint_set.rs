@@ -0,0 +1,75 @@
+// src/disjoint_set.rs - Union-find over the elements 0..n
+
+/// Disjoint sets of the elements 0..n, merged by union and told apart by find
+///
+/// Path compression and union by rank make every operation take near-constant
+/// amortized time. Sets can only be merged, never split again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    sets: usize,
+}
+
+impl DisjointSet {
+    /// Puts each of the elements 0..n in a set of its own
+    pub fn new(n: usize) -> Self {
+        DisjointSet {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            sets: n,
+        }
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of disjoint sets
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+
+    /// Returns the representative of the set of v, the same for every element of the set
+    /// Panics when v is not below len()
+    pub fn find(&mut self, v: usize) -> usize {
+        let mut root = v;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut current = v;
+        while self.parent[current] != root {
+            current = std::mem::replace(&mut self.parent[current], root);
+        }
+        root
+    }
+
+    /// Merges the sets of u and v; returns false when they were already one
+    pub fn union(&mut self, u: usize, v: usize) -> bool {
+        let (root_u, root_v) = (self.find(u), self.find(v));
+        if root_u == root_v {
+            return false;
+        }
+        let (low, high) = if self.rank[root_u] < self.rank[root_v] {
+            (root_u, root_v)
+        } else {
+            (root_v, root_u)
+        };
+        self.parent[low] = high;
+        if self.rank[low] == self.rank[high] {
+            self.rank[high] += 1;
+        }
+        self.sets -= 1;
+        true
+    }
+
+    /// Checks whether u and v are in the same set
+    pub fn same_set(&mut self, u: usize, v: usize) -> bool {
+        self.find(u) == self.find(v)
+    }
+}
@@ -0,0 +1,69 @@
+// src/dot.rs - Export to the Graphviz DOT language
+//
+// The DOT text lists every vertex as a node statement, so isolated vertices are
+// drawn too, then the edges in the canonical order of ordered_edges. A graph
+// without one-way pairs is written as `graph` with `--` edges, each edge once; any
+// other graph as `digraph` with `->` arcs, a pair listed both ways giving two arcs.
+use crate::error::GraphError;
+use crate::Graph;
+use std::fs::File;
+use std::io::Write;
+
+/// Words DOT reserves, case-insensitively, which must be quoted to be used as IDs
+const DOT_KEYWORDS: [&str; 6] = ["node", "edge", "graph", "digraph", "subgraph", "strict"];
+
+/// Writes a label as a DOT ID, bare when DOT would read it unchanged and quoted otherwise
+///
+/// Bare IDs are identifiers of letters, digits and underscores not starting with a
+/// digit, and plain numerals such as 12 or -3.5. Inside quotes, `"` and `\` are
+/// escaped with a backslash.
+pub fn dot_id(label: &str) -> String {
+    let identifier = label.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let digits = label.strip_prefix('-').unwrap_or(label);
+    let numeral = !digits.is_empty()
+        && digits != "."
+        && digits.chars().filter(|&c| c == '.').count() <= 1
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.');
+    let keyword = DOT_KEYWORDS.iter().any(|word| word.eq_ignore_ascii_case(label));
+    if (identifier || numeral) && !keyword {
+        label.to_string()
+    } else {
+        format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+impl Graph {
+    /// Returns the graph in the DOT language, with `name` as the graph ID
+    ///
+    /// Edges with an explicit weight carry it as their label. The text ends with a
+    /// newline and can be piped straight into `dot -Tpng`.
+    pub fn to_dot(&self, name: &str) -> String {
+        let (directed, edges) = self.export_edges();
+        let (keyword, connector) = if directed { ("digraph", "->") } else { ("graph", "--") };
+        let mut text = format!("{} {} {{\n", keyword, dot_id(name));
+
+        for &v in self.cached_sorted() {
+            text.push_str(&format!("  {};\n", dot_id(&self.vertices[v])));
+        }
+        for edge in edges {
+            let label = match edge.weight {
+                Some(weight) => format!(" [label={}]", dot_id(&weight.to_string())),
+                None => String::new(),
+            };
+            let (source, target) = (dot_id(&edge.source), dot_id(&edge.target));
+            text.push_str(&format!("  {} {} {}{};\n", source, connector, target, label));
+        }
+
+        text.push_str("}\n");
+        text
+    }
+
+    /// To create/write the graph in the DOT language, named base-GRAPH.dot
+    /// The graph ID is the graph's name, or the base name when it has none
+    pub fn produce_output_file_dot(&self, base_name: &str) -> Result<(), GraphError> {
+        let mut fp = File::create(format!("{}-GRAPH.dot", base_name))?;
+        write!(fp, "{}", self.to_dot(self.display_name(base_name)))?;
+        Ok(())
+    }
+}
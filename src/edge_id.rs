@@ -0,0 +1,253 @@
+// src/edge_id.rs - Stable handles for the edges of a graph
+//
+// Every linked pair of vertices gets an EdgeId the first time the graph sees it,
+// and keeps it until the pair is unlinked: an undirected edge, a one-way arc and a
+// pair listed both ways are each one edge with one id. Ids are never reused, so an
+// id of a removed edge finds nothing rather than some later edge. Edits through
+// Graph's methods keep the ids up to date, removing a vertex or splitting one
+// included, since the registry is keyed by vertex index and moves with the
+// indices. Code writing adj_matrix directly is seen at the next make_adj_matrix.
+// Weights and attributes are stored under the id, so they follow the edge too and
+// go when it goes; the pair lookups of weights.rs are a convenience over them.
+use crate::Graph;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Handle of one edge, assigned when the edge is first seen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeId(pub u64);
+
+impl fmt::Display for EdgeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "e{}", self.0)
+    }
+}
+
+/// The ids of the edges and the weights and attributes stored under them
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EdgeRegistry {
+    /// Id of every linked pair, keyed by (smaller index, larger index)
+    by_pair: HashMap<(usize, usize), EdgeId>,
+    /// Pair of every live id, in the order the ids were assigned
+    pairs: BTreeMap<EdgeId, (usize, usize)>,
+    /// Weight of each direction of an edge: from the smaller index of its pair to
+    /// the larger one, then back; a self-loop uses the first
+    weights: HashMap<EdgeId, [Option<f64>; 2]>,
+    attrs: HashMap<EdgeId, BTreeMap<String, String>>,
+    next: u64,
+}
+
+/// The registry key of the pair u, v
+fn pair_key(u: usize, v: usize) -> (usize, usize) {
+    (u.min(v), u.max(v))
+}
+
+/// The weight slot of the direction from u to v
+fn direction_slot(u: usize, v: usize) -> usize {
+    usize::from(u > v)
+}
+
+impl EdgeRegistry {
+    /// Returns the id of the pair, assigning the next one when it has none
+    pub(crate) fn register(&mut self, u: usize, v: usize) -> EdgeId {
+        let key = pair_key(u, v);
+        if let Some(&id) = self.by_pair.get(&key) {
+            return id;
+        }
+        let id = EdgeId(self.next);
+        self.next += 1;
+        self.by_pair.insert(key, id);
+        self.pairs.insert(id, key);
+        id
+    }
+
+    /// Drops the id of the pair and its weights and attributes
+    pub(crate) fn unregister(&mut self, u: usize, v: usize) {
+        if let Some(id) = self.by_pair.remove(&pair_key(u, v)) {
+            self.pairs.remove(&id);
+            self.weights.remove(&id);
+            self.attrs.remove(&id);
+        }
+    }
+
+    /// Drops every id, keeping the counter so that no id is handed out twice
+    pub(crate) fn clear(&mut self) {
+        self.by_pair.clear();
+        self.pairs.clear();
+        self.weights.clear();
+        self.attrs.clear();
+    }
+
+    /// Returns the weight stored for the direction from u to v of their edge
+    pub(crate) fn weight(&self, u: usize, v: usize) -> Option<f64> {
+        let id = self.by_pair.get(&pair_key(u, v))?;
+        self.weights.get(id)?[direction_slot(u, v)]
+    }
+
+    /// Stores the weight of the direction from u to v, registering the pair if needed
+    pub(crate) fn set_weight(&mut self, u: usize, v: usize, weight: f64) {
+        let id = self.register(u, v);
+        self.weights.entry(id).or_default()[direction_slot(u, v)] = Some(weight);
+    }
+
+    /// Lists every stored weight as ((from, to), weight), in no particular order
+    pub(crate) fn weights(&self) -> impl Iterator<Item = ((usize, usize), f64)> + '_ {
+        self.weights.iter().flat_map(|(id, slots)| {
+            let (u, v) = self.pairs[id];
+            [(u, v), (v, u)]
+                .into_iter()
+                .zip(slots)
+                .take(if u == v { 1 } else { 2 })
+                .filter_map(|(direction, weight)| weight.map(|weight| (direction, weight)))
+        })
+    }
+
+    /// Checks whether any edge has a stored weight
+    pub(crate) fn has_weights(&self) -> bool {
+        !self.weights.is_empty()
+    }
+
+    /// Number of edges the weight table has room for, for the memory estimate
+    pub(crate) fn weights_capacity(&self) -> usize {
+        self.weights.capacity()
+    }
+
+    /// Moves every id to the indices `moved` gives its endpoints, dropping the ids
+    /// whose endpoints it maps to None
+    ///
+    /// `moved` gets the endpoints smaller index first and returns them in the same
+    /// order, so a weight keeps its direction when the order of the indices flips.
+    pub(crate) fn remap(&mut self, moved: impl Fn(usize, usize) -> Option<(usize, usize)>) {
+        let pairs = std::mem::take(&mut self.pairs);
+        self.by_pair.clear();
+        for (id, (u, v)) in pairs {
+            match moved(u, v) {
+                Some((new_u, new_v)) => {
+                    let key = pair_key(new_u, new_v);
+                    self.by_pair.insert(key, id);
+                    self.pairs.insert(id, key);
+                    if new_u > new_v {
+                        if let Some(slots) = self.weights.get_mut(&id) {
+                            slots.swap(0, 1);
+                        }
+                    }
+                }
+                None => {
+                    self.weights.remove(&id);
+                    self.attrs.remove(&id);
+                }
+            }
+        }
+    }
+}
+
+impl Graph {
+    /// Gives every linked pair an id and drops the ids of pairs no longer linked
+    ///
+    /// Called by make_adj_matrix, so every read and every rebuilding edit ends here.
+    pub(crate) fn sync_edge_ids(&mut self) {
+        let n = self.n_vertices;
+        let stale: Vec<(usize, usize)> = self
+            .edge_registry
+            .pairs
+            .values()
+            .copied()
+            .filter(|&(u, v)| u >= n || v >= n || !self.linked(u, v))
+            .collect();
+        for (u, v) in stale {
+            self.edge_registry.unregister(u, v);
+        }
+        for u in 0..n {
+            for v in u..n {
+                if self.linked(u, v) {
+                    self.edge_registry.register(u, v);
+                }
+            }
+        }
+    }
+
+    /// Iterates over the ids of the edges, in the order they were assigned
+    pub fn edge_ids(&self) -> impl Iterator<Item = EdgeId> + '_ {
+        self.edge_registry.pairs.keys().copied()
+    }
+
+    /// Indices of the endpoints of an edge, in the order edge_endpoints gives them
+    fn edge_indices(&self, id: EdgeId) -> Option<(usize, usize)> {
+        let &(u, v) = self.edge_registry.pairs.get(&id)?;
+        Some(match (self.adj_matrix[u][v] == 1, self.adj_matrix[v][u] == 1) {
+            (true, false) => (u, v),
+            (false, true) => (v, u),
+            _ if self.vertices[v] < self.vertices[u] => (v, u),
+            _ => (u, v),
+        })
+    }
+
+    /// Returns the labels of the endpoints of an edge, or None for an unknown or removed id
+    ///
+    /// The endpoints come in the order ordered_edges lists them: a one-way arc from
+    /// its source to its target, any other edge alphabetically.
+    pub fn edge_endpoints(&self, id: EdgeId) -> Option<(&str, &str)> {
+        let (u, v) = self.edge_indices(id)?;
+        Some((self.vertices[u].as_str(), self.vertices[v].as_str()))
+    }
+
+    /// Returns the id of the edge between two labels, in either direction
+    ///
+    /// None when either label is not a vertex or the two are not linked.
+    pub fn find_edge(&self, a: &str, b: &str) -> Option<EdgeId> {
+        self.find_edge_idx(self.find_vertex_idx(a)?, self.find_vertex_idx(b)?)
+    }
+
+    /// Returns the id of the edge between two vertex indices, in either direction
+    pub(crate) fn find_edge_idx(&self, u: usize, v: usize) -> Option<EdgeId> {
+        self.edge_registry.by_pair.get(&pair_key(u, v)).copied()
+    }
+
+    /// Returns the weight stored for an edge, None when it has none or the id is unknown
+    ///
+    /// A one-way arc gives the weight of its direction; a pair listed both ways the
+    /// weight from its alphabetically first endpoint, as ordered_edges reports it.
+    pub fn edge_weight_by_id(&self, id: EdgeId) -> Option<f64> {
+        let (u, v) = self.edge_indices(id)?;
+        self.edge_registry.weight(u, v)
+    }
+
+    /// Sets the weight of an edge in every direction it is listed in
+    /// Returns false, changing nothing, for an unknown or removed id
+    pub fn set_edge_weight_by_id(&mut self, id: EdgeId, weight: f64) -> bool {
+        let Some(&(u, v)) = self.edge_registry.pairs.get(&id) else {
+            return false;
+        };
+        for (from, to) in [(u, v), (v, u)] {
+            if self.adj_matrix[from][to] == 1 {
+                self.edge_registry.set_weight(from, to, weight);
+            }
+        }
+        self.invalidate_cache();
+        true
+    }
+
+    /// Returns the value of an edge attribute, None when it is unset or the id is unknown
+    pub fn edge_attr(&self, id: EdgeId, key: &str) -> Option<&str> {
+        self.edge_registry.attrs.get(&id)?.get(key).map(String::as_str)
+    }
+
+    /// Returns every attribute of an edge, sorted by key
+    pub fn edge_attrs(&self, id: EdgeId) -> BTreeMap<String, String> {
+        self.edge_registry.attrs.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Sets an edge attribute, replacing any earlier value under the key
+    /// Returns false, storing nothing, for an unknown or removed id
+    pub fn set_edge_attr(&mut self, id: EdgeId, key: &str, value: &str) -> bool {
+        if !self.edge_registry.pairs.contains_key(&id) {
+            return false;
+        }
+        self.edge_registry
+            .attrs
+            .entry(id)
+            .or_default()
+            .insert(key.to_string(), value.to_string());
+        true
+    }
+}
@@ -0,0 +1,312 @@
+// src/edge_list.rs - Streaming reads of edge list files
+//
+// An edge list has one edge per line, `u v` or `u v weight`, with the two
+// endpoints separated by whitespace. Blank lines and lines starting with `#`
+// are skipped. Edges are undirected. Files named *.edges are read as edge lists
+// wherever an input file is expected.
+use crate::dialect::{Encoding, LineDecoder, ParseWarning};
+use crate::error::{file_error, GraphError};
+use crate::suggest::{closest_among, DEFAULT_SUGGESTIONS};
+use crate::{Graph, MATRIX_VERTEX_LIMIT};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Checks whether a path names an edge list by its extension, .edges in any case
+pub fn is_edge_list_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("edges"))
+}
+
+/// Counts reported by read_edge_list_filtered_with_stats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EdgeListScan {
+    /// Passes made over the file
+    pub passes: usize,
+    /// Lines read over all passes, including blank and comment lines
+    pub lines_scanned: usize,
+    /// Edge lines kept in the resulting graph
+    pub edges_kept: usize,
+}
+
+/// One edge read from an edge list line
+pub(crate) struct EdgeLine {
+    pub(crate) u: String,
+    pub(crate) v: String,
+    pub(crate) weight: Option<f64>,
+}
+
+/// Parses one line of an edge list; Ok(None) for blank and comment lines
+pub(crate) fn parse_edge_line(line: &str, line_number: usize) -> Result<Option<EdgeLine>, GraphError> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let invalid = |reason: String| GraphError::InvalidFormat {
+        format: "edge list",
+        reason: format!("line {}: {}", line_number, reason),
+    };
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let weight = match tokens.as_slice() {
+        [_, _] => None,
+        [_, _, weight] => Some(
+            weight
+                .parse::<f64>()
+                .map_err(|_| invalid(format!("weight {} is not a number", weight)))?,
+        ),
+        _ => return Err(invalid(format!("expected \"u v\" or \"u v weight\", found \"{}\"", line))),
+    };
+    Ok(Some(EdgeLine {
+        u: tokens[0].to_string(),
+        v: tokens[1].to_string(),
+        weight,
+    }))
+}
+
+/// Streams the edges of an edge list file to visit, returning the number of lines read
+/// Each line goes through the decoder, which keeps what Lossy replaced for the caller
+fn scan_edges(path: &str, decoder: &mut LineDecoder, visit: &mut dyn FnMut(EdgeLine)) -> Result<usize, GraphError> {
+    let mut reader = BufReader::new(File::open(path).map_err(file_error(path))?);
+    let mut raw = Vec::new();
+    let mut lines = 0;
+    while reader.read_until(b'\n', &mut raw)? > 0 {
+        lines += 1;
+        if let Some(edge) = parse_edge_line(&decoder.decode(&raw, lines)?, lines)? {
+            visit(edge);
+        }
+        raw.clear();
+    }
+    Ok(lines)
+}
+
+impl Graph {
+    /// Replaces the graph with the one an edge list file describes
+    ///
+    /// Vertices are numbered in order of first appearance, and each adjacency line
+    /// lists its neighbors in the same order. Every edge is entered in the lines of
+    /// both endpoints, so the matrix is symmetric; a self-loop is listed once. A
+    /// pair given again, either way round, is skipped, except that a weight on the
+    /// later line replaces the earlier one. The graph is named after the file stem.
+    /// Invalid UTF-8 is an error; see read_edge_list_with_encoding
+    pub fn read_edge_list(&mut self, path: &str) -> Result<(), GraphError> {
+        self.read_edge_list_with_encoding(path, Encoding::Strict).map(|_| ())
+    }
+
+    /// Reads an edge list as read_edge_list does, decoding it under the given encoding
+    /// With Encoding::Lossy, a warning lists the lines and labels where invalid UTF-8 was replaced
+    pub fn read_edge_list_with_encoding(
+        &mut self,
+        path: &str,
+        encoding: Encoding,
+    ) -> Result<Vec<ParseWarning>, GraphError> {
+        let mut decoder = LineDecoder::new(encoding);
+        let mut position: HashMap<String, usize> = HashMap::new();
+        let mut rows: Vec<(String, Vec<String>)> = Vec::new();
+        let mut seen: HashSet<(usize, usize)> = HashSet::new();
+        let mut weights = HashMap::new();
+        scan_edges(path, &mut decoder, &mut |edge| {
+            let mut index_of = |label: &String| {
+                *position.entry(label.clone()).or_insert_with(|| {
+                    rows.push((label.clone(), Vec::new()));
+                    rows.len() - 1
+                })
+            };
+            let (i, j) = (index_of(&edge.u), index_of(&edge.v));
+            if seen.insert((i.min(j), i.max(j))) {
+                rows[i].1.push(edge.v.clone());
+                if i != j {
+                    rows[j].1.push(edge.u.clone());
+                }
+            }
+            if let Some(weight) = edge.weight {
+                weights.insert((i, j), weight);
+                weights.insert((j, i), weight);
+            }
+        })?;
+        Graph::check_vertex_limit(rows.len())?;
+
+        // A new graph, so no EdgeId of the old one carries over
+        self.edge_registry.clear();
+        self.rebuild_from_rows(&rows);
+        for ((i, j), weight) in weights {
+            self.store_weight(i, j, weight);
+        }
+        self.name = Some(Graph::get_base_filename(path));
+        Ok(decoder.finish().into_iter().collect())
+    }
+
+    /// Reads the part of an edge list within radius hops of the seed vertices
+    /// See read_edge_list_filtered_with_stats
+    pub fn read_edge_list_filtered(path: &str, seeds: &[&str], radius: usize) -> Result<Graph, GraphError> {
+        Self::read_edge_list_filtered_with_stats(path, seeds, radius).map(|(graph, _)| graph)
+    }
+
+    /// Reads the subgraph induced by the vertices within radius hops of the seeds
+    ///
+    /// The file is streamed radius + 1 times and never held in memory: each of the
+    /// first radius passes grows the neighborhood by one hop, and the last pass keeps
+    /// the edges between its vertices. Seeds come first in the graph, in the given
+    /// order, and the other vertices follow in order of first appearance.
+    pub fn read_edge_list_filtered_with_stats(
+        path: &str,
+        seeds: &[&str],
+        radius: usize,
+    ) -> Result<(Graph, EdgeListScan), GraphError> {
+        let mut scan = EdgeListScan::default();
+        let mut order: Vec<String> = Vec::new();
+        let mut reached: HashSet<String> = HashSet::new();
+        for seed in seeds {
+            if reached.insert(seed.to_string()) {
+                order.push(seed.to_string());
+            }
+        }
+
+        let mut in_file: HashSet<String> = HashSet::new();
+        for _ in 0..radius {
+            let mut grown = Vec::new();
+            scan.lines_scanned += scan_edges(path, &mut LineDecoder::new(Encoding::Strict), &mut |edge| {
+                for (a, b) in [(&edge.u, &edge.v), (&edge.v, &edge.u)] {
+                    if reached.contains(a) {
+                        in_file.insert(a.clone());
+                        if !reached.contains(b) && !grown.contains(b) {
+                            grown.push(b.clone());
+                        }
+                    }
+                }
+            })?;
+            scan.passes += 1;
+            if grown.is_empty() {
+                break;
+            }
+            for vertex in grown {
+                reached.insert(vertex.clone());
+                order.push(vertex);
+            }
+            if order.len() > MATRIX_VERTEX_LIMIT {
+                break;
+            }
+        }
+        Graph::check_vertex_limit(order.len())?;
+
+        // Collect the edges between reached vertices, each neighbor listed once
+        let position: HashMap<String, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.clone(), i))
+            .collect();
+        let mut rows: Vec<(String, Vec<String>)> =
+            order.iter().map(|label| (label.clone(), Vec::new())).collect();
+        let mut weights = HashMap::new();
+        scan.lines_scanned += scan_edges(path, &mut LineDecoder::new(Encoding::Strict), &mut |edge| {
+            for label in [&edge.u, &edge.v] {
+                if position.contains_key(label) {
+                    in_file.insert(label.clone());
+                }
+            }
+            let (Some(&i), Some(&j)) = (position.get(&edge.u), position.get(&edge.v)) else {
+                return;
+            };
+            if !rows[i].1.contains(&edge.v) {
+                rows[i].1.push(edge.v.clone());
+                if i != j {
+                    rows[j].1.push(edge.u.clone());
+                }
+                scan.edges_kept += 1;
+            }
+            if let Some(weight) = edge.weight {
+                weights.insert((i, j), weight);
+                weights.insert((j, i), weight);
+            }
+        })?;
+        scan.passes += 1;
+
+        if let Some(missing) = seeds.iter().find(|seed| !in_file.contains(**seed)) {
+            // One more pass collects the labels to suggest, only once the read has failed
+            let mut labels: HashSet<String> = HashSet::new();
+            scan_edges(path, &mut LineDecoder::new(Encoding::Strict), &mut |edge| {
+                labels.insert(edge.u);
+                labels.insert(edge.v);
+            })?;
+            return Err(GraphError::UnknownVertex {
+                label: missing.to_string(),
+                suggestions: closest_among(&labels, missing, DEFAULT_SUGGESTIONS),
+            });
+        }
+
+        let mut graph = Graph::new();
+        graph.rebuild_from_rows(&rows);
+        for ((i, j), weight) in weights {
+            graph.store_weight(i, j, weight);
+        }
+        Ok((graph, scan))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::scratch_dir;
+    use std::fs;
+    use std::path::PathBuf;
+
+    const CHAIN: &str = "a b\nb c\n\n# the far end\nc d\nd e 2.5\n";
+
+    /// Writes CHAIN to a directory of its own and returns the directory and the file path
+    fn chain_file(test: &str) -> (PathBuf, String) {
+        let dir = scratch_dir("edges", test);
+        let path = dir.join("chain.edges");
+        fs::write(&path, CHAIN).unwrap();
+        (dir, path.display().to_string())
+    }
+
+    #[test]
+    fn edge_lines() {
+        assert!(parse_edge_line("  # note", 1).unwrap().is_none());
+        let edge = parse_edge_line("a b 1.5", 1).unwrap().unwrap();
+        assert_eq!((edge.u.as_str(), edge.v.as_str(), edge.weight), ("a", "b", Some(1.5)));
+        let Err(error) = parse_edge_line("a b c d", 4) else {
+            panic!("four tokens are not an edge");
+        };
+        assert!(error.to_string().contains("line 4"), "{}", error);
+        assert!(parse_edge_line("a b heavy", 1).is_err());
+        assert!(is_edge_list_path("dir/g.EDGES"));
+        assert!(!is_edge_list_path("g.txt"));
+    }
+
+    #[test]
+    fn whole_file_is_named_after_its_path() {
+        let (dir, path) = chain_file("whole");
+        let mut graph = Graph::new();
+        graph.read_edge_list(&path).unwrap();
+        assert_eq!(graph.vertices[..5], ["a", "b", "c", "d", "e"].map(String::from));
+        assert_eq!(graph.weight("e", "d"), Some(2.5));
+        assert_eq!(graph.display_name("x"), Graph::get_base_filename(&path));
+        graph.check_invariants().unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn filtered_read_counts_every_pass() {
+        let (dir, path) = chain_file("filtered");
+        let (graph, scan) = Graph::read_edge_list_filtered_with_stats(&path, &["b"], 1).unwrap();
+        assert_eq!(graph.vertices[..3], ["b", "a", "c"].map(String::from));
+        assert_eq!(scan, EdgeListScan { passes: 2, lines_scanned: 12, edges_kept: 2 });
+
+        let (graph, scan) = Graph::read_edge_list_filtered_with_stats(&path, &["a"], 0).unwrap();
+        assert_eq!((graph.n_vertices, scan.passes, scan.lines_scanned), (1, 1, 6));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn unknown_seed_gets_suggestions() {
+        let (dir, path) = chain_file("unknown");
+        assert_eq!(
+            Graph::read_edge_list_filtered(&path, &["dd"], 1).unwrap_err(),
+            GraphError::UnknownVertex { label: "dd".to_string(), suggestions: vec!["d".to_string()] }
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
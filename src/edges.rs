@@ -0,0 +1,191 @@
+// src/edges.rs - The canonical order in which every writer lists edges
+use crate::Graph;
+use std::collections::BTreeMap;
+
+/// One edge of the graph as reported by Graph::ordered_edges
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeView {
+    pub source: String,
+    pub target: String,
+    pub source_idx: usize,
+    pub target_idx: usize,
+    /// True for an arc present in only one direction of the adjacency matrix
+    pub directed: bool,
+    /// The explicit weight of the edge, if one was set
+    pub weight: Option<f64>,
+    /// Attributes set on the EdgeId of the edge, sorted by key
+    pub attrs: BTreeMap<String, String>,
+}
+
+impl EdgeView {
+    /// Checks whether the edge joins a vertex to itself
+    pub fn is_loop(&self) -> bool {
+        self.source_idx == self.target_idx
+    }
+}
+
+impl Graph {
+    /// Lists every edge of the graph in the canonical order used by all output formats
+    ///
+    /// This order is a stable guarantee: edges are sorted alphabetically by source
+    /// label, then by target label. An undirected edge (present in both directions
+    /// of the adjacency matrix) is reported once, with its endpoints in alphabetical
+    /// order. An arc present in only one direction keeps its orientation and is
+    /// marked as directed. Self-loops are reported once with equal endpoints.
+    pub fn ordered_edges(&self) -> Vec<EdgeView> {
+        let ranks = self.alphabetical_ranks();
+        let sorted = self.sort_vertices();
+        let mut edges = Vec::new();
+
+        for &i in &sorted {
+            for &j in &sorted {
+                if self.adj_matrix[i][j] != 1 {
+                    continue;
+                }
+                let reverse = self.adj_matrix[j][i] == 1;
+                // The reverse direction of an undirected edge is reported from its smaller endpoint
+                if reverse && ranks[j] < ranks[i] {
+                    continue;
+                }
+                edges.push(EdgeView {
+                    source: self.vertices[i].clone(),
+                    target: self.vertices[j].clone(),
+                    source_idx: i,
+                    target_idx: j,
+                    directed: !reverse,
+                    weight: self.stored_weight(i, j),
+                    attrs: self.find_edge_idx(i, j).map(|id| self.edge_attrs(id)).unwrap_or_default(),
+                });
+            }
+        }
+
+        edges
+    }
+
+    /// Lists the edges as written by the exporters, with whether the graph is directed
+    ///
+    /// A graph without one-way pairs is undirected and gets ordered_edges as they are.
+    /// Any other graph is directed, and a pair listed both ways gives both arcs, the
+    /// reverse one right after the other, each with its own weight.
+    pub(crate) fn export_edges(&self) -> (bool, Vec<EdgeView>) {
+        let directed = self.direction_profile().one_way_pairs > 0;
+        let mut edges = Vec::new();
+        for edge in self.ordered_edges() {
+            let reverse = (directed && !edge.directed && !edge.is_loop()).then(|| EdgeView {
+                source: edge.target.clone(),
+                target: edge.source.clone(),
+                source_idx: edge.target_idx,
+                target_idx: edge.source_idx,
+                directed: false,
+                weight: self.stored_weight(edge.target_idx, edge.source_idx),
+                attrs: edge.attrs.clone(),
+            });
+            edges.push(edge);
+            edges.extend(reverse);
+        }
+        (directed, edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dialect::Dialect;
+    use crate::fixtures;
+    use crate::Graph;
+
+    /// A path B-C-A read in reverse alphabetical order, with a weight on C-A
+    fn shuffled_path() -> Graph {
+        let mut graph = Graph::new();
+        graph
+            .read_input_text_with("3\nC B A -1\nB C -1\nA C -1\n", Dialect::Strict)
+            .unwrap();
+        graph.set_edge_weight("C", "A", 2.5).unwrap();
+        graph
+    }
+
+    fn graphs() -> Vec<Graph> {
+        vec![
+            fixtures::triangle(),
+            fixtures::petersen(),
+            fixtures::two_cliques_bridge(),
+            fixtures::disconnected_pair(),
+            fixtures::weighted_square(),
+            shuffled_path(),
+        ]
+    }
+
+    fn pairs(graph: &Graph) -> Vec<(String, String)> {
+        graph.ordered_edges().into_iter().map(|edge| (edge.source, edge.target)).collect()
+    }
+
+    /// The edges of the E(...) line of a SET file
+    fn set_edges(graph: &Graph) -> Vec<(String, String)> {
+        let mut out = Vec::new();
+        graph.write_set(&mut out, "g").unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let line = text.lines().find(|line| line.starts_with("E(")).unwrap();
+        let list = &line[line.find("={").unwrap() + 2..line.len() - 1];
+        list.trim_start_matches('(')
+            .trim_end_matches(')')
+            .split("),(")
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (u, v) = pair.split_once(',').unwrap();
+                (u.to_string(), v.to_string())
+            })
+            .collect()
+    }
+
+    /// The edge statements of a DOT document
+    fn dot_edges(graph: &Graph) -> Vec<(String, String)> {
+        graph
+            .to_dot("g")
+            .lines()
+            .filter_map(|line| line.trim().strip_suffix(';')?.split_once(" -- "))
+            .map(|(u, v)| (u.to_string(), v.split(" [").next().unwrap().to_string()))
+            .collect()
+    }
+
+    /// The "edges" array of a node-link JSON document
+    fn json_edges(graph: &Graph) -> Vec<(String, String)> {
+        let json = graph.to_json();
+        let list = &json[json.find("\"edges\":[").unwrap() + 9..json.rfind("]}").unwrap()];
+        list.trim_start_matches('[')
+            .trim_end_matches(']')
+            .split("],[")
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (u, v) = pair.split_once(',').unwrap();
+                (u.trim_matches('"').to_string(), v.trim_matches('"').to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ordered_edges_sort_by_source_then_target() {
+        assert_eq!(
+            pairs(&shuffled_path()),
+            [("A".to_string(), "C".to_string()), ("B".to_string(), "C".to_string())]
+        );
+        let edges = shuffled_path().ordered_edges();
+        assert_eq!(edges[0].weight, Some(2.5));
+        assert!(edges.iter().all(|edge| !edge.directed && !edge.is_loop()));
+        for graph in graphs() {
+            let listed = pairs(&graph);
+            let mut sorted = listed.clone();
+            sorted.sort();
+            assert_eq!(listed, sorted);
+            assert!(listed.iter().all(|(u, v)| u < v));
+        }
+    }
+
+    #[test]
+    fn writers_list_edges_in_the_same_order() {
+        for graph in graphs() {
+            let expected = pairs(&graph);
+            assert_eq!(set_edges(&graph), expected);
+            assert_eq!(dot_edges(&graph), expected);
+            assert_eq!(json_edges(&graph), expected);
+        }
+    }
+}
@@ -0,0 +1,585 @@
+// src/edit.rs - Structural edits of a loaded graph
+//
+// Edits rewrite the adjacency rows and rebuild the list, counts and matrix from
+// them, so every representation stays consistent. Vertices keep their indices
+// unless an edit says otherwise, and weights, coordinates and EdgeIds follow their
+// vertices.
+// Debug builds check the representations against each other after every edit,
+// in make_adj_matrix for the edits that rebuild.
+use crate::error::GraphError;
+use crate::Graph;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// What extend_edges does with an endpoint that is not a vertex
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NewVertexPolicy {
+    /// Fail without adding anything
+    #[default]
+    Error,
+    /// Add the vertex after the existing ones, as add_vertex would
+    Create,
+}
+
+/// What Graph::extend_edges changed
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExtendReport {
+    /// Edges that were not in the graph or earlier in the batch
+    pub added: usize,
+    /// Edges already in the graph or repeated in the batch, in either direction
+    pub duplicates: usize,
+    /// Vertices the Create policy added, in the order they were first named
+    pub created: Vec<String>,
+}
+
+impl fmt::Display for ExtendReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "added edges: {}", self.added)?;
+        writeln!(f, "duplicate edges skipped: {}", self.duplicates)?;
+        if !self.created.is_empty() {
+            writeln!(f, "created vertices: {{{}}}", self.created.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+/// Refuses a new label that is empty or contains whitespace, which the input format cannot hold
+fn check_label(name: &'static str, label: &str) -> Result<(), GraphError> {
+    if label.is_empty() || label.contains(char::is_whitespace) {
+        return Err(GraphError::InvalidParameter {
+            name,
+            reason: format!("\"{}\" is empty or contains whitespace", label),
+        });
+    }
+    Ok(())
+}
+
+impl Graph {
+    /// Adds a vertex without edges after the existing ones and returns its index
+    ///
+    /// The label must be new, non-empty and free of whitespace, so that the graph can
+    /// still be written in the input format. Adjacency lines that already named the
+    /// label as a dangling neighbor become edges to the new vertex.
+    pub fn add_vertex(&mut self, label: &str) -> Result<usize, GraphError> {
+        check_label("label", label)?;
+        if self.find_vertex_idx(label).is_some() {
+            return Err(GraphError::InvalidParameter {
+                name: "label",
+                reason: format!("{} is already a vertex", label),
+            });
+        }
+        Graph::check_vertex_limit(self.n_vertices + 1)?;
+
+        let idx = self.n_vertices;
+        self.vertices.truncate(idx);
+        self.vertices.push(label.to_string());
+        self.n_vertices += 1;
+        self.reserve_vertices(self.n_vertices);
+        self.adj_list[idx] = None;
+        self.adj_count[idx] = 0;
+
+        // Only the new row and column change, so the matrix is patched, not rebuilt
+        for i in 0..=idx {
+            self.adj_matrix[idx][i] = 0;
+            self.adj_matrix[i][idx] = 0;
+        }
+        for i in 0..idx {
+            if self.collect_adjacent_vertices(&self.adj_list[i]).iter().any(|adj| adj == label) {
+                self.adj_matrix[i][idx] = 1;
+                self.edge_registry.register(i, idx);
+            }
+        }
+        self.invalidate_cache();
+        self.debug_check_representations();
+        Ok(idx)
+    }
+
+    /// Adds the undirected edge u-v, listing each endpoint in the line of the other
+    ///
+    /// Both labels must be vertices. An endpoint already listing the other is left
+    /// as it is, so adding an existing edge changes nothing, and a self-loop is
+    /// listed once.
+    pub fn add_edge(&mut self, u: &str, v: &str) -> Result<(), GraphError> {
+        let u_idx = self.require_vertex_idx(u)?;
+        let v_idx = self.require_vertex_idx(v)?;
+        for (from, to) in [(u_idx, v), (v_idx, u)] {
+            if !self.collect_adjacent_vertices(&self.adj_list[from]).iter().any(|adj| adj == to) {
+                self.add_to_adj_list(from, to);
+            }
+        }
+        self.adj_matrix[u_idx][v_idx] = 1;
+        self.adj_matrix[v_idx][u_idx] = 1;
+        self.edge_registry.register(u_idx, v_idx);
+        self.invalidate_cache();
+        self.debug_check_representations();
+        Ok(())
+    }
+
+    /// Adds many undirected edges at once, as add_edge would one at a time
+    ///
+    /// Every edge is staged and checked before the graph changes, so an unknown
+    /// endpoint under NewVertexPolicy::Error, an invalid new label or too many new
+    /// vertices fails with the graph untouched. The labels are then looked up once,
+    /// every adjacency list is walked to its end once, and the matrix is patched at
+    /// the end (rebuilt, when vertices were created, so that dangling neighbors
+    /// naming them become edges as with add_vertex). Adding many edges this way
+    /// avoids the per-call list scans and label lookups of add_edge.
+    pub fn extend_edges<I: IntoIterator<Item = (String, String)>>(
+        &mut self,
+        edges: I,
+        policy: NewVertexPolicy,
+    ) -> Result<ExtendReport, GraphError> {
+        let n = self.n_vertices;
+        let mut index: HashMap<String, usize> = HashMap::with_capacity(n);
+        for (i, vertex) in self.vertices.iter().take(n).enumerate() {
+            index.entry(vertex.clone()).or_insert(i);
+        }
+
+        // Resolve and check every endpoint before anything changes
+        let mut created: Vec<String> = Vec::new();
+        let mut staged = Vec::new();
+        for (u, v) in edges {
+            let mut resolve = |label: String| -> Result<usize, GraphError> {
+                if let Some(&idx) = index.get(&label) {
+                    return Ok(idx);
+                }
+                if policy == NewVertexPolicy::Error {
+                    return Err(self.unknown_vertex(&label));
+                }
+                check_label("label", &label)?;
+                let idx = n + created.len();
+                index.insert(label.clone(), idx);
+                created.push(label);
+                Ok(idx)
+            };
+            let u_idx = resolve(u)?;
+            let v_idx = resolve(v)?;
+            staged.push((u_idx, v_idx));
+        }
+        Graph::check_vertex_limit(n + created.len())?;
+
+        // The neighbors each touched vertex lists, and the ones it is to list
+        let label_of = |idx: usize| if idx < n { self.vertices[idx].clone() } else { created[idx - n].clone() };
+        let mut listed: HashMap<usize, HashSet<String>> = HashMap::new();
+        let mut appended: HashMap<usize, Vec<String>> = HashMap::new();
+        let mut report = ExtendReport::default();
+        for &(u_idx, v_idx) in &staged {
+            let mut changed = false;
+            for (from, to) in [(u_idx, v_idx), (v_idx, u_idx)] {
+                let to_label = label_of(to);
+                let seen = listed.entry(from).or_insert_with(|| match from < n {
+                    true => self.collect_adjacent_vertices(&self.adj_list[from]).into_iter().collect(),
+                    false => HashSet::new(),
+                });
+                if seen.insert(to_label.clone()) {
+                    appended.entry(from).or_default().push(to_label);
+                    changed = true;
+                }
+            }
+            if changed {
+                report.added += 1;
+            } else {
+                report.duplicates += 1;
+            }
+        }
+
+        // Apply the staged changes
+        if !created.is_empty() {
+            self.vertices.truncate(n);
+            self.vertices.extend(created.iter().cloned());
+            self.n_vertices = n + created.len();
+            self.reserve_vertices(self.n_vertices);
+            for idx in n..self.n_vertices {
+                self.adj_list[idx] = None;
+                self.adj_count[idx] = 0;
+            }
+        }
+        for (idx, labels) in appended {
+            self.append_to_adj_list(idx, &labels);
+        }
+        if created.is_empty() {
+            for &(u_idx, v_idx) in &staged {
+                self.adj_matrix[u_idx][v_idx] = 1;
+                self.adj_matrix[v_idx][u_idx] = 1;
+                self.edge_registry.register(u_idx, v_idx);
+            }
+            self.invalidate_cache();
+            self.debug_check_representations();
+        } else {
+            self.make_adj_matrix();
+        }
+
+        report.created = created;
+        Ok(report)
+    }
+
+    /// Appends neighbors to the end of a vertex's adjacency list, walking it once
+    fn append_to_adj_list(&mut self, vertex_idx: usize, adj_vertices: &[String]) {
+        let shared: Vec<_> = adj_vertices.iter().map(|label| self.intern_label(label)).collect();
+        let mut tail = &mut self.adj_list[vertex_idx];
+        while let Some(node) = tail {
+            tail = &mut node.next;
+        }
+        for label in shared {
+            let node = tail.insert(Self::create_node(label));
+            tail = &mut node.next;
+        }
+        self.adj_count[vertex_idx] += adj_vertices.len() as i32;
+    }
+
+    /// Unlinks every node naming adj_vertex from a vertex's adjacency list and returns how many there were
+    fn unlink_from_adj_list(&mut self, vertex_idx: usize, adj_vertex: &str) -> usize {
+        let mut unlinked = 0;
+        let mut current = &mut self.adj_list[vertex_idx];
+        loop {
+            match current {
+                Some(node) if &*node.vertex == adj_vertex => {
+                    *current = node.next.take();
+                    unlinked += 1;
+                }
+                Some(node) => current = &mut node.next,
+                None => break,
+            }
+        }
+        self.adj_count[vertex_idx] -= unlinked as i32;
+        unlinked
+    }
+
+    /// Removes the edge u-v in both directions and returns whether there was one
+    ///
+    /// Both adjacency lists lose their entry for the other endpoint, both matrix
+    /// cells are cleared and the weights of the edge are dropped. Removing an edge
+    /// that does not exist returns Ok(false); unknown labels are an error.
+    pub fn remove_edge(&mut self, u: &str, v: &str) -> Result<bool, GraphError> {
+        let u_idx = self.require_vertex_idx(u)?;
+        let v_idx = self.require_vertex_idx(v)?;
+        let mut unlinked = self.unlink_from_adj_list(u_idx, v);
+        if u_idx != v_idx {
+            unlinked += self.unlink_from_adj_list(v_idx, u);
+        }
+        let existed = unlinked > 0 || self.adj_matrix[u_idx][v_idx] == 1 || self.adj_matrix[v_idx][u_idx] == 1;
+        self.adj_matrix[u_idx][v_idx] = 0;
+        self.adj_matrix[v_idx][u_idx] = 0;
+        self.edge_registry.unregister(u_idx, v_idx);
+        self.invalidate_cache();
+        self.debug_check_representations();
+        Ok(existed)
+    }
+
+    /// Removes a vertex and every edge touching it
+    ///
+    /// The later vertices move up one index, and their weights and coordinates move
+    /// with them, so traversals and output files look as if the vertex had never
+    /// been there. Removing the last vertex leaves an empty graph.
+    pub fn remove_vertex(&mut self, label: &str) -> Result<(), GraphError> {
+        let removed = self.require_vertex_idx(label)?;
+        let mut rows = self.adjacency_rows();
+        rows.remove(removed);
+        for (_, adj_vertices) in rows.iter_mut() {
+            adj_vertices.retain(|adj_vertex| adj_vertex != label);
+        }
+
+        let shifted = |v: usize| if v > removed { v - 1 } else { v };
+        let coordinates = std::mem::take(&mut self.coordinates)
+            .into_iter()
+            .filter(|&(v, _)| v != removed)
+            .map(|(v, position)| (shifted(v), position))
+            .collect();
+        self.edge_registry
+            .remap(|u, v| (u != removed && v != removed).then(|| (shifted(u), shifted(v))));
+
+        self.rebuild_from_rows(&rows);
+        self.coordinates = coordinates;
+        Ok(())
+    }
+
+    /// Splits a vertex in two, moving its edges to the given neighbors onto a new vertex
+    ///
+    /// The new vertex is added last. Each moved edge keeps its direction and weight,
+    /// and with connect_halves the two halves are joined by an undirected edge. The
+    /// new vertex starts at the coordinates of the original, if it has any. Every
+    /// listed neighbor must be adjacent to the original, and new_label must be unused,
+    /// non-empty and free of whitespace, as add_vertex requires.
+    pub fn split_vertex(
+        &mut self,
+        label: &str,
+        new_label: &str,
+        neighbors_for_new: &[&str],
+        connect_halves: bool,
+    ) -> Result<(), GraphError> {
+        let original = self.require_vertex_idx(label)?;
+        check_label("new_label", new_label)?;
+        if self.find_vertex_idx(new_label).is_some() {
+            return Err(GraphError::InvalidParameter {
+                name: "new_label",
+                reason: format!("{} is already a vertex", new_label),
+            });
+        }
+        Graph::check_vertex_limit(self.n_vertices + 1)?;
+        let mut moved = Vec::with_capacity(neighbors_for_new.len());
+        for &neighbor in neighbors_for_new {
+            let idx = self.require_vertex_idx(neighbor)?;
+            let reason = if idx == original {
+                Some(format!("the self-loop of {} cannot be moved", label))
+            } else if self.adj_matrix[original][idx] == 0 && self.adj_matrix[idx][original] == 0 {
+                Some(format!("{} is not adjacent to {}", neighbor, label))
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                return Err(GraphError::InvalidParameter {
+                    name: "neighbors_for_new",
+                    reason,
+                });
+            }
+            moved.push(idx);
+        }
+
+        let new_idx = self.n_vertices;
+        let is_moved = |adj_vertex: &str| neighbors_for_new.contains(&adj_vertex);
+        let mut rows = self.adjacency_rows();
+        let mut new_row = (new_label.to_string(), Vec::new());
+        let (kept, taken): (Vec<String>, Vec<String>) =
+            rows[original].1.drain(..).partition(|adj_vertex| !is_moved(adj_vertex));
+        rows[original].1 = kept;
+        new_row.1 = taken;
+        for &idx in &moved {
+            for adj_vertex in rows[idx].1.iter_mut() {
+                if adj_vertex == label {
+                    *adj_vertex = new_label.to_string();
+                }
+            }
+        }
+        if connect_halves {
+            rows[original].1.push(new_label.to_string());
+            new_row.1.push(label.to_string());
+        }
+        rows.push(new_row);
+
+        let mut coordinates = std::mem::take(&mut self.coordinates);
+        if let Some(&position) = coordinates.get(&original) {
+            coordinates.insert(new_idx, position);
+        }
+        self.edge_registry.remap(|u, v| match (u, v) {
+            (u, v) if u == original && moved.contains(&v) => Some((new_idx, v)),
+            (u, v) if v == original && moved.contains(&u) => Some((u, new_idx)),
+            edge => Some(edge),
+        });
+
+        self.rebuild_from_rows(&rows);
+        self.coordinates = coordinates;
+        Ok(())
+    }
+
+    /// Undoes split_vertex, moving every edge of new_label back onto label and removing new_label
+    ///
+    /// Each moved edge keeps its direction and weight and an edge joining the two
+    /// halves is dropped, so undoing a split gives back the graph it was made from,
+    /// with the moved neighbors listed last in the line of label. The vertices after
+    /// new_label move up one index, as remove_vertex does. Fails with InvalidParameter
+    /// when the two halves share a neighbor, which no split leaves behind.
+    pub fn undo_split_vertex(&mut self, label: &str, new_label: &str) -> Result<(), GraphError> {
+        let original = self.require_vertex_idx(label)?;
+        let half = self.require_vertex_idx(new_label)?;
+        if original == half {
+            return Err(GraphError::SameVertex(label.to_string()));
+        }
+        let shared = (0..self.n_vertices)
+            .filter(|&v| v != original && v != half)
+            .find(|&v| self.linked(original, v) && self.linked(half, v))
+            .map(|v| self.vertices[v].clone())
+            .or_else(|| (self.linked(original, original) && self.linked(half, half)).then(|| "itself".to_string()));
+        if let Some(shared) = shared {
+            return Err(GraphError::InvalidParameter {
+                name: "new_label",
+                reason: format!("{} and {} are both adjacent to {}", label, new_label, shared),
+            });
+        }
+
+        let mut rows = self.adjacency_rows();
+        let (_, taken) = std::mem::take(&mut rows[half]);
+        rows[original].1.retain(|adj_vertex| adj_vertex != new_label);
+        rows[original].1.extend(taken.into_iter().filter(|adj_vertex| adj_vertex != label));
+        rows.remove(half);
+        for (_, adj_vertices) in rows.iter_mut() {
+            for adj_vertex in adj_vertices.iter_mut() {
+                if adj_vertex == new_label {
+                    *adj_vertex = label.to_string();
+                }
+            }
+        }
+
+        let merged = |v: usize| if v == half { original } else { v };
+        let shifted = |v: usize| if v > half { v - 1 } else { v };
+        let coordinates = std::mem::take(&mut self.coordinates)
+            .into_iter()
+            .filter(|&(v, _)| v != half)
+            .map(|(v, position)| (shifted(v), position))
+            .collect();
+        self.edge_registry.remap(|u, v| {
+            let joins_halves = (u, v) == (original, half) || (u, v) == (half, original);
+            (!joins_halves).then(|| (shifted(merged(u)), shifted(merged(v))))
+        });
+
+        self.rebuild_from_rows(&rows);
+        self.coordinates = coordinates;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::Dialect;
+    use std::time::Instant;
+
+    fn graph_from(text: &str) -> Graph {
+        let mut graph = Graph::new();
+        graph.read_input_text_with(text, Dialect::Strict).unwrap();
+        graph
+    }
+
+    fn path() -> Graph {
+        graph_from("3\nA B -1\nB A C -1\nC B -1\n")
+    }
+
+    #[test]
+    fn extend_edges_counts_added_and_duplicates() {
+        let mut graph = path();
+        let edges = [("A", "C"), ("B", "A"), ("C", "A"), ("A", "C")]
+            .map(|(u, v)| (u.to_string(), v.to_string()));
+        let report = graph.extend_edges(edges, NewVertexPolicy::Error).unwrap();
+        assert_eq!(report, ExtendReport { added: 1, duplicates: 3, created: vec![] });
+        assert_eq!(graph.stats().n_edges, 3);
+        graph.check_invariants().unwrap();
+    }
+
+    #[test]
+    fn extend_edges_is_all_or_nothing() {
+        let mut graph = path();
+        let before = graph.adjacency_rows();
+        let edges = [("A", "C"), ("A", "Z")].map(|(u, v)| (u.to_string(), v.to_string()));
+        let error = graph.extend_edges(edges, NewVertexPolicy::Error).unwrap_err();
+        assert_eq!(error.code(), "E_UNKNOWN_VERTEX");
+        assert_eq!(graph.adjacency_rows(), before);
+
+        let edges = [("A", "C"), ("A", "bad label")].map(|(u, v)| (u.to_string(), v.to_string()));
+        assert!(graph.extend_edges(edges, NewVertexPolicy::Create).is_err());
+        assert_eq!(graph.adjacency_rows(), before);
+        graph.check_invariants().unwrap();
+    }
+
+    #[test]
+    fn extend_edges_creates_vertices_in_order() {
+        let mut graph = path();
+        let edges = [("C", "E"), ("D", "A")].map(|(u, v)| (u.to_string(), v.to_string()));
+        let report = graph.extend_edges(edges, NewVertexPolicy::Create).unwrap();
+        assert_eq!(report.created, vec!["E".to_string(), "D".to_string()]);
+        assert_eq!(graph.vertices[3..5], ["E".to_string(), "D".to_string()]);
+        graph.check_invariants().unwrap();
+    }
+
+    /// Labels v0..v19 and n insertions cycling through every pair of them, most
+    /// of them repeats
+    fn insertions(n: usize) -> (Graph, Vec<(String, String)>) {
+        let labels: Vec<String> = (0..20).map(|i| format!("v{}", i)).collect();
+        let k = labels.len();
+        let pairs = (0..n)
+            .map(|i| (labels[i % k].clone(), labels[(i % k + 1 + i / k % (k - 1)) % k].clone()))
+            .collect();
+        let mut empty = Graph::new();
+        for label in &labels {
+            empty.add_vertex(label).unwrap();
+        }
+        (empty, pairs)
+    }
+
+    #[test]
+    fn extend_edges_matches_add_edge() {
+        let (empty, pairs) = insertions(1_000);
+        let mut bulk = empty.clone();
+        let report = bulk.extend_edges(pairs.clone(), NewVertexPolicy::Error).unwrap();
+        assert_eq!(report.added + report.duplicates, 1_000);
+        assert_eq!(report.added, 20 * 19 / 2);
+        bulk.check_invariants().unwrap();
+
+        let mut single = empty;
+        for (u, v) in &pairs {
+            single.add_edge(u, v).unwrap();
+        }
+        assert_eq!(bulk.adjacency_rows(), single.adjacency_rows());
+    }
+
+    /// The same 100k insertions through one extend_edges call and through add_edge;
+    /// a timing, so run it on its own in a release build:
+    /// cargo test --release extend_edges_beats_add_edge -- --ignored
+    #[test]
+    #[ignore]
+    fn extend_edges_beats_add_edge_on_100k_insertions() {
+        let (empty, pairs) = insertions(100_000);
+        let mut bulk = empty.clone();
+        let started = Instant::now();
+        bulk.extend_edges(pairs.clone(), NewVertexPolicy::Error).unwrap();
+        let bulk_time = started.elapsed();
+
+        let mut single = empty;
+        let started = Instant::now();
+        for (u, v) in &pairs {
+            single.add_edge(u, v).unwrap();
+        }
+        let single_time = started.elapsed();
+        assert!(bulk_time < single_time, "extend_edges took {:?}, add_edge {:?}", bulk_time, single_time);
+    }
+
+    #[test]
+    fn add_vertex_turns_dangling_neighbors_into_edges() {
+        let mut graph = Graph::new();
+        graph.read_input_text_with("2\nA B C -1\nB A -1\n", Dialect::AutoDirection).unwrap();
+        assert_eq!(graph.find_vertex_idx("C"), None);
+        let idx = graph.add_vertex("C").unwrap();
+        assert_eq!((graph.adj_matrix[0][idx], graph.adj_matrix[idx][0]), (1, 0));
+    }
+
+    #[test]
+    fn new_labels_must_be_writable() {
+        let mut graph = path();
+        for label in ["", "two words", "tab\there"] {
+            assert!(graph.add_vertex(label).is_err(), "{:?}", label);
+            assert!(graph.split_vertex("B", label, &["C"], false).is_err(), "{:?}", label);
+        }
+        assert!(graph.add_vertex("A").is_err());
+    }
+
+    #[test]
+    fn split_vertex_moves_the_listed_edges() {
+        let mut graph = path();
+        graph.split_vertex("B", "B2", &["C"], true).unwrap();
+        assert_eq!(graph.adj_matrix[1][2], 0);
+        let b2 = graph.find_vertex_idx("B2").unwrap();
+        assert_eq!((graph.adj_matrix[b2][2], graph.adj_matrix[b2][1]), (1, 1));
+        graph.check_invariants().unwrap();
+    }
+
+    #[test]
+    fn split_hub_of_a_star_and_undo() {
+        let mut graph = graph_from("5\nH A B C D -1\nA H -1\nB H -1\nC H -1\nD H -1\n");
+        graph.set_edge_weight("H", "C", 2.5).unwrap();
+        let before = graph.adjacency_rows();
+        graph.split_vertex("H", "H2", &["C", "D"], true).unwrap();
+        let degree = |graph: &Graph, label: &str| graph.adj_count[graph.find_vertex_idx(label).unwrap()];
+        assert_eq!((degree(&graph, "H"), degree(&graph, "H2"), degree(&graph, "C")), (3, 3, 1));
+        assert_eq!(graph.weight("C", "H2"), Some(2.5));
+        graph.check_invariants().unwrap();
+
+        graph.undo_split_vertex("H", "H2").unwrap();
+        assert_eq!(graph.adjacency_rows(), before);
+        assert_eq!(graph.weight("C", "H"), Some(2.5));
+        graph.check_invariants().unwrap();
+
+        // A neighbor of both halves could not have come from a split
+        graph.split_vertex("H", "H2", &["D"], false).unwrap();
+        graph.add_edge("H2", "A").unwrap();
+        assert!(graph.undo_split_vertex("H", "H2").is_err());
+        assert!(graph.undo_split_vertex("H", "H").is_err());
+    }
+}
@@ -0,0 +1,399 @@
+// src/error.rs - Errors returned by the graph algorithms
+use crate::validation::InvariantViolation;
+use std::fmt;
+use std::time::Duration;
+
+/// Errors raised by the graph algorithms
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphError {
+    /// A vertex label that does not exist in the graph, with similar labels that do
+    UnknownVertex { label: String, suggestions: Vec<String> },
+    /// Both endpoints of a query that needs two distinct vertices are the same
+    SameVertex(String),
+    /// An edge weight the chosen algorithm cannot handle
+    InvalidWeight {
+        from: String,
+        to: String,
+        weight: f64,
+        reason: &'static str,
+    },
+    /// A vertex index past the end of the graph
+    IndexOutOfRange { index: usize, n_vertices: usize },
+    /// A vertex without coordinates where a geometric algorithm needs them
+    MissingCoordinates(String),
+    /// A negative-weight cycle reachable from the source, listed in traversal order
+    NegativeCycle { cycle: Vec<String> },
+    /// A directed cycle where an acyclic graph is needed, closed on its first label
+    Cycle { cycle: Vec<String> },
+    /// A malformed line of an input file, numbered from 1
+    Parse { line: usize, reason: String },
+    /// A file whose adjacency list, degree counts and matrix disagree
+    Inconsistent(InvariantViolation),
+    /// Input that does not follow the named file format
+    InvalidFormat { format: &'static str, reason: String },
+    /// Bytes that are not valid UTF-8, starting at byte_offset (from 0) of the line
+    InvalidEncoding { line: usize, byte_offset: usize },
+    /// A graph larger than the representations can hold
+    CapacityExceeded { requested: usize, limit: usize },
+    /// A degree sequence no graph of the requested kind can have
+    InvalidDegreeSequence(String),
+    /// A generator parameter outside its allowed range
+    InvalidParameter { name: &'static str, reason: String },
+    /// A partition of the vertices that misses, repeats or does not know a vertex
+    InvalidPartition(String),
+    /// A search ran out of nodes or time, or was cancelled, before it finished
+    BudgetExceeded { explored: u64, elapsed: Duration },
+    /// An input file that does not exist
+    FileNotFound(String),
+    /// A file could not be read or written
+    Io(String),
+}
+
+/// Escapes a string as a JSON string literal, quotes included
+pub fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl GraphError {
+    /// Returns the stable code of the error kind, for scripts that branch on errors
+    /// Codes never change once released; every variant has its own code
+    pub fn code(&self) -> &'static str {
+        match self {
+            GraphError::UnknownVertex { .. } => "E_UNKNOWN_VERTEX",
+            GraphError::SameVertex(_) => "E_SAME_VERTEX",
+            GraphError::InvalidWeight { .. } => "E_INVALID_WEIGHT",
+            GraphError::IndexOutOfRange { .. } => "E_INDEX_OUT_OF_RANGE",
+            GraphError::MissingCoordinates(_) => "E_MISSING_COORDINATES",
+            GraphError::NegativeCycle { .. } => "E_NEGATIVE_CYCLE",
+            GraphError::Cycle { .. } => "E_CYCLE",
+            GraphError::Parse { .. } => "E_PARSE",
+            GraphError::Inconsistent(_) => "E_INCONSISTENT",
+            GraphError::InvalidFormat { .. } => "E_INVALID_FORMAT",
+            GraphError::InvalidEncoding { .. } => "E_INVALID_ENCODING",
+            GraphError::CapacityExceeded { .. } => "E_CAPACITY_EXCEEDED",
+            GraphError::InvalidDegreeSequence(_) => "E_INVALID_DEGREE_SEQUENCE",
+            GraphError::InvalidParameter { .. } => "E_INVALID_PARAMETER",
+            GraphError::InvalidPartition(_) => "E_INVALID_PARTITION",
+            GraphError::BudgetExceeded { .. } => "E_BUDGET_EXCEEDED",
+            GraphError::FileNotFound(_) => "E_FILE_NOT_FOUND",
+            GraphError::Io(_) => "E_IO",
+        }
+    }
+
+    /// Returns the fields describing the error, with their values already encoded as JSON
+    fn json_context(&self) -> Vec<(&'static str, String)> {
+        match self {
+            GraphError::UnknownVertex { label, suggestions } => {
+                let suggestions: Vec<String> = suggestions.iter().map(|s| json_string(s)).collect();
+                vec![
+                    ("label", json_string(label)),
+                    ("suggestions", format!("[{}]", suggestions.join(","))),
+                ]
+            }
+            GraphError::SameVertex(label) | GraphError::MissingCoordinates(label) => {
+                vec![("label", json_string(label))]
+            }
+            GraphError::InvalidWeight {
+                from,
+                to,
+                weight,
+                reason,
+            } => vec![
+                ("from", json_string(from)),
+                ("to", json_string(to)),
+                // NaN and infinite weights have no JSON number form
+                (
+                    "weight",
+                    if weight.is_finite() {
+                        weight.to_string()
+                    } else {
+                        json_string(&weight.to_string())
+                    },
+                ),
+                ("reason", json_string(reason)),
+            ],
+            GraphError::IndexOutOfRange { index, n_vertices } => vec![
+                ("index", index.to_string()),
+                ("n_vertices", n_vertices.to_string()),
+            ],
+            GraphError::NegativeCycle { cycle } | GraphError::Cycle { cycle } => {
+                let labels: Vec<String> = cycle.iter().map(|label| json_string(label)).collect();
+                vec![("cycle", format!("[{}]", labels.join(",")))]
+            }
+            GraphError::Parse { line, reason } => vec![
+                ("line", line.to_string()),
+                ("reason", json_string(reason)),
+            ],
+            GraphError::Inconsistent(violation) => vec![("reason", json_string(&violation.to_string()))],
+            GraphError::InvalidFormat { format, reason } => vec![
+                ("format", json_string(format)),
+                ("reason", json_string(reason)),
+            ],
+            GraphError::InvalidEncoding { line, byte_offset } => vec![
+                ("line", line.to_string()),
+                ("byte_offset", byte_offset.to_string()),
+            ],
+            GraphError::CapacityExceeded { requested, limit } => vec![
+                ("requested", requested.to_string()),
+                ("limit", limit.to_string()),
+            ],
+            GraphError::InvalidDegreeSequence(reason) | GraphError::InvalidPartition(reason) => {
+                vec![("reason", json_string(reason))]
+            }
+            GraphError::InvalidParameter { name, reason } => {
+                vec![("name", json_string(name)), ("reason", json_string(reason))]
+            }
+            GraphError::BudgetExceeded { explored, elapsed } => vec![
+                ("explored", explored.to_string()),
+                ("elapsed_ms", elapsed.as_millis().to_string()),
+            ],
+            GraphError::FileNotFound(path) => vec![("path", json_string(path))],
+            GraphError::Io(_) => Vec::new(),
+        }
+    }
+
+    /// Formats the error as a single-line JSON object with its code, message and context fields
+    pub fn to_json(&self) -> String {
+        let mut fields = vec![
+            ("code", json_string(self.code())),
+            ("message", json_string(&self.to_string())),
+        ];
+        fields.extend(self.json_context());
+        let body: Vec<String> = fields
+            .iter()
+            .map(|(key, value)| format!("{}:{}", json_string(key), value))
+            .collect();
+        format!("{{{}}}", body.join(","))
+    }
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::UnknownVertex { label, suggestions } if suggestions.is_empty() => {
+                write!(f, "vertex {} not found", label)
+            }
+            GraphError::UnknownVertex { label, suggestions } => write!(
+                f,
+                "vertex {} not found; did you mean {}?",
+                label,
+                suggestions.join(", ")
+            ),
+            GraphError::SameVertex(label) => {
+                write!(f, "both endpoints are {}; two distinct vertices are needed", label)
+            }
+            GraphError::InvalidWeight {
+                from,
+                to,
+                weight,
+                reason,
+            } => write!(f, "edge ({},{}) has weight {}: {}", from, to, weight, reason),
+            GraphError::IndexOutOfRange { index, n_vertices } => write!(
+                f,
+                "vertex index {} is out of range; the graph has {} vertices",
+                index, n_vertices
+            ),
+            GraphError::MissingCoordinates(label) => write!(
+                f,
+                "vertex {} has no coordinates; A* needs a position for every vertex",
+                label
+            ),
+            GraphError::NegativeCycle { cycle } => {
+                write!(f, "negative cycle {}->{}", cycle.join("->"), cycle[0])
+            }
+            GraphError::Cycle { cycle } => {
+                write!(f, "cycle {}; the graph must be acyclic", cycle.join("->"))
+            }
+            GraphError::Parse { line, reason } => write!(f, "line {}: {}", line, reason),
+            GraphError::Inconsistent(violation) => write!(f, "{}", violation),
+            GraphError::InvalidFormat { format, reason } => {
+                write!(f, "invalid {} input: {}", format, reason)
+            }
+            GraphError::InvalidEncoding { line, byte_offset } => write!(
+                f,
+                "line {}: invalid UTF-8 at byte {}; the file may be Latin-1, which --lossy-encoding reads",
+                line, byte_offset
+            ),
+            GraphError::CapacityExceeded { requested, limit } => write!(
+                f,
+                "{} vertices requested but at most {} are supported",
+                requested, limit
+            ),
+            GraphError::InvalidDegreeSequence(reason) => {
+                write!(f, "invalid degree sequence: {}", reason)
+            }
+            GraphError::InvalidParameter { name, reason } => {
+                write!(f, "invalid parameter {}: {}", name, reason)
+            }
+            GraphError::InvalidPartition(reason) => write!(f, "invalid partition: {}", reason),
+            GraphError::BudgetExceeded { explored, elapsed } => write!(
+                f,
+                "search stopped after exploring {} nodes in {:.3}s",
+                explored,
+                elapsed.as_secs_f64()
+            ),
+            GraphError::FileNotFound(path) => write!(f, "file {} not found", path),
+            GraphError::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+/// Converts an error opening or reading the file at path, so a missing file gives
+/// FileNotFound rather than Io
+pub fn file_error(path: &str) -> impl FnOnce(std::io::Error) -> GraphError + '_ {
+    move |error| match error.kind() {
+        std::io::ErrorKind::NotFound => GraphError::FileNotFound(path.to_string()),
+        _ => GraphError::from(error),
+    }
+}
+
+impl From<std::io::Error> for GraphError {
+    fn from(error: std::io::Error) -> Self {
+        GraphError::Io(error.to_string())
+    }
+}
+
+impl From<InvariantViolation> for GraphError {
+    fn from(violation: InvariantViolation) -> Self {
+        GraphError::Inconsistent(violation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::InvariantViolation;
+    use std::collections::HashSet;
+
+    /// One error of every variant, in the order of position
+    fn one_of_each() -> Vec<GraphError> {
+        vec![
+            GraphError::UnknownVertex {
+                label: "X".to_string(),
+                suggestions: vec!["Y".to_string()],
+            },
+            GraphError::SameVertex("A".to_string()),
+            GraphError::InvalidWeight {
+                from: "A".to_string(),
+                to: "B".to_string(),
+                weight: f64::NAN,
+                reason: "not a number",
+            },
+            GraphError::IndexOutOfRange { index: 4, n_vertices: 3 },
+            GraphError::MissingCoordinates("A".to_string()),
+            GraphError::NegativeCycle {
+                cycle: vec!["A".to_string(), "B".to_string()],
+            },
+            GraphError::Cycle {
+                cycle: vec!["A".to_string(), "B".to_string(), "A".to_string()],
+            },
+            GraphError::Parse {
+                line: 2,
+                reason: "bad".to_string(),
+            },
+            GraphError::Inconsistent(InvariantViolation::StaleCache { entry: "components" }),
+            GraphError::InvalidFormat {
+                format: "dimacs",
+                reason: "bad".to_string(),
+            },
+            GraphError::InvalidEncoding { line: 1, byte_offset: 3 },
+            GraphError::CapacityExceeded { requested: 9, limit: 8 },
+            GraphError::InvalidDegreeSequence("odd".to_string()),
+            GraphError::InvalidParameter {
+                name: "k",
+                reason: "odd".to_string(),
+            },
+            GraphError::InvalidPartition("missing A".to_string()),
+            GraphError::BudgetExceeded {
+                explored: 10,
+                elapsed: Duration::from_millis(1500),
+            },
+            GraphError::FileNotFound("g.txt".to_string()),
+            GraphError::Io("disk full".to_string()),
+        ]
+    }
+
+    /// The place of the variant in one_of_each. The match has no wildcard arm, so a
+    /// new variant stops the tests from compiling until it is given a place here and
+    /// an entry in one_of_each, which every_variant_has_its_own_code then checks
+    fn position(error: &GraphError) -> usize {
+        match error {
+            GraphError::UnknownVertex { .. } => 0,
+            GraphError::SameVertex(_) => 1,
+            GraphError::InvalidWeight { .. } => 2,
+            GraphError::IndexOutOfRange { .. } => 3,
+            GraphError::MissingCoordinates(_) => 4,
+            GraphError::NegativeCycle { .. } => 5,
+            GraphError::Cycle { .. } => 6,
+            GraphError::Parse { .. } => 7,
+            GraphError::Inconsistent(_) => 8,
+            GraphError::InvalidFormat { .. } => 9,
+            GraphError::InvalidEncoding { .. } => 10,
+            GraphError::CapacityExceeded { .. } => 11,
+            GraphError::InvalidDegreeSequence(_) => 12,
+            GraphError::InvalidParameter { .. } => 13,
+            GraphError::InvalidPartition(_) => 14,
+            GraphError::BudgetExceeded { .. } => 15,
+            GraphError::FileNotFound(_) => 16,
+            GraphError::Io(_) => 17,
+        }
+    }
+
+    #[test]
+    fn every_variant_has_its_own_code() {
+        let errors = one_of_each();
+        let positions: Vec<usize> = errors.iter().map(position).collect();
+        assert_eq!(positions, (0..errors.len()).collect::<Vec<_>>());
+        let codes: HashSet<&str> = errors.iter().map(GraphError::code).collect();
+        assert_eq!(codes.len(), errors.len());
+        assert!(codes.iter().all(|code| code.starts_with("E_")));
+    }
+
+    #[test]
+    fn to_json_holds_code_message_and_context() {
+        let error = GraphError::UnknownVertex {
+            label: "X\"1".to_string(),
+            suggestions: vec!["X1".to_string()],
+        };
+        assert_eq!(
+            error.to_json(),
+            r#"{"code":"E_UNKNOWN_VERTEX","message":"vertex X\"1 not found; did you mean X1?","label":"X\"1","suggestions":["X1"]}"#
+        );
+        assert_eq!(
+            GraphError::FileNotFound("g.txt".to_string()).to_json(),
+            r#"{"code":"E_FILE_NOT_FOUND","message":"file g.txt not found","path":"g.txt"}"#
+        );
+        // A weight with no JSON number form is written as a string
+        let nan = &one_of_each()[2];
+        assert!(nan.to_json().contains(r#""weight":"NaN""#));
+    }
+
+    #[test]
+    fn json_string_escapes_quotes_and_controls() {
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), r#""a\"b\\c\nd\u0001""#);
+    }
+
+    #[test]
+    fn file_error_maps_not_found_only() {
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        assert_eq!(file_error("g.txt")(missing), GraphError::FileNotFound("g.txt".to_string()));
+        let denied = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert_eq!(file_error("g.txt")(denied), GraphError::Io("denied".to_string()));
+    }
+}
@@ -0,0 +1,183 @@
+// src/examples.rs - A reproducible corpus of example input files
+//
+// Each file shows one feature of the input format. The adjacency format has no
+// comment syntax, so the files are described in README.TXT next to them; only
+// the weights CSV, which skips # lines, carries its description inline. The
+// corpus is built from fixed generators and hand-made rows, so every run writes
+// byte-identical files.
+use crate::error::GraphError;
+use crate::generators;
+use crate::Graph;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the file describing the corpus
+pub const CORPUS_INDEX: &str = "README.TXT";
+
+/// One file of the corpus and what it exercises
+struct Example {
+    file: &'static str,
+    describes: &'static str,
+    text: String,
+}
+
+/// Rows of a graph in input order, keeping only the neighbors keep accepts
+fn filtered_rows(graph: &Graph, keep: impl Fn(usize, &str) -> bool) -> Vec<(String, Vec<String>)> {
+    graph
+        .adjacency_rows()
+        .into_iter()
+        .enumerate()
+        .map(|(i, (label, neighbors))| {
+            let neighbors = neighbors.into_iter().filter(|adj| keep(i, adj)).collect();
+            (label, neighbors)
+        })
+        .collect()
+}
+
+/// Builds a graph from labels and undirected edges between them
+fn labeled(labels: &[&str], edges: &[(&str, &str)]) -> Result<Graph, GraphError> {
+    let mut graph = Graph::new();
+    for label in labels {
+        graph.add_vertex(label)?;
+    }
+    for (u, v) in edges {
+        graph.add_edge(u, v)?;
+    }
+    Ok(graph)
+}
+
+/// Builds every example, in the order they are written
+fn examples() -> Result<Vec<Example>, GraphError> {
+    let mut disconnected = generators::wheel(3)?;
+    for label in ["x", "y", "z"] {
+        disconnected.add_vertex(label)?;
+    }
+    disconnected.add_edge("x", "y")?;
+
+    let stations = [
+        "Amsterdam_Centraal",
+        "Utrecht_Centraal",
+        "Rotterdam_Centraal",
+        "Den_Haag_Hollands_Spoor",
+    ];
+    let long_labels = labeled(
+        &stations,
+        &[
+            (stations[0], stations[1]),
+            (stations[1], stations[2]),
+            (stations[2], stations[3]),
+            (stations[3], stations[0]),
+        ],
+    )?;
+
+    // v1 no longer lists v0, so the pair v0-v1 is joined one way only
+    let petersen = generators::petersen()?;
+    let asymmetric = Graph::from_rows(&filtered_rows(&petersen, |i, adj| !(i == 1 && adj == "v0")))?;
+
+    // Every tree edge points from parent to child, at the higher heap index
+    let tree = generators::binary_tree(2)?;
+    let directed = Graph::from_rows(&filtered_rows(&tree, |i, adj| {
+        tree.find_vertex_idx(adj).is_some_and(|j| j > i)
+    }))?;
+
+    let square = generators::complete_bipartite(2, 2)?;
+    let square_weights = "\
+# Weights for weighted.txt, one a,b,weight row per edge; load them with --weights
+a,b,weight
+a0,b0,1
+a0,b1,4
+a1,b0,2
+a1,b1,3
+";
+
+    let wheel = generators::wheel(5)?;
+    let triangle = labeled(&["v0", "v1", "v2"], &[("v0", "v1"), ("v1", "v2"), ("v2", "v0")])?;
+    let chord = labeled(&["v1", "v3"], &[("v1", "v3")])?;
+
+    Ok(vec![
+        Example {
+            file: "connected.txt",
+            describes: "the Petersen graph: one component, 10 vertices, every vertex of degree 3",
+            text: petersen.to_input_text(),
+        },
+        Example {
+            file: "disconnected.txt",
+            describes: "three components: the wheel v0..v3, the edge x-y and the isolated vertex z",
+            text: disconnected.to_input_text(),
+        },
+        Example {
+            file: "single-vertex.txt",
+            describes: "one vertex without neighbors; its line is just the label and -1",
+            text: labeled(&["A"], &[])?.to_input_text(),
+        },
+        Example {
+            file: "long-labels.txt",
+            describes: "a 4-cycle of labels longer than the 10-character output columns",
+            text: long_labels.to_input_text(),
+        },
+        Example {
+            file: "numeric-labels.txt",
+            describes: "the cube Q3 with binary labels 000..111; labels may be numbers",
+            text: generators::hypercube(3)?.to_input_text(),
+        },
+        Example {
+            file: "asymmetric.txt",
+            describes: "the Petersen graph with v0 missing from the line of v1, so v0-v1 is one-way; \
+                        strict reading rejects it, Dialect::AutoDirection adds the reverse entry",
+            text: asymmetric.to_input_text(),
+        },
+        Example {
+            file: "weighted.txt",
+            describes: "the 4-cycle a0-b0-a1-b1, with its edge weights in weighted.csv",
+            text: square.to_input_text(),
+        },
+        Example {
+            file: "weighted.csv",
+            describes: "weights for weighted.txt as a,b,weight rows",
+            text: square_weights.to_string(),
+        },
+        Example {
+            file: "directed.txt",
+            describes: "a binary tree of depth 2 whose lines list only the children; \
+                        strict reading rejects it, Dialect::AutoDirection keeps it directed",
+            text: directed.to_input_text(),
+        },
+        Example {
+            file: "subgraph-g.txt",
+            describes: "the wheel with hub v0 and rim v1..v5, the larger graph of the subgraph pairs",
+            text: wheel.to_input_text(),
+        },
+        Example {
+            file: "subgraph-h-pass.txt",
+            describes: "the triangle v0-v1-v2, a subgraph of subgraph-g.txt",
+            text: triangle.to_input_text(),
+        },
+        Example {
+            file: "subgraph-h-fail.txt",
+            describes: "the edge v1-v3, which subgraph-g.txt lacks, so it is not a subgraph",
+            text: chord.to_input_text(),
+        },
+    ])
+}
+
+/// Writes the example corpus into dir, creating it if needed, and returns the paths written
+///
+/// Every .txt file reads in strict mode except the one-way asymmetric.txt and
+/// directed.txt, which need Dialect::AutoDirection. README.TXT, written last,
+/// describes each file on one line. Existing files of the same names are overwritten.
+pub fn generate_corpus(dir: &Path) -> Result<Vec<PathBuf>, GraphError> {
+    fs::create_dir_all(dir)?;
+    let examples = examples()?;
+    let mut paths = Vec::with_capacity(examples.len() + 1);
+    let mut index = String::from("Example input files; each line names a file and what it exercises\n\n");
+    for example in &examples {
+        let path = dir.join(example.file);
+        fs::write(&path, &example.text)?;
+        index.push_str(&format!("{}: {}\n", example.file, example.describes));
+        paths.push(path);
+    }
+    let path = dir.join(CORPUS_INDEX);
+    fs::write(&path, index)?;
+    paths.push(path);
+    Ok(paths)
+}
@@ -0,0 +1,216 @@
+// src/explain.rs - Explaining why an edge does or does not show up in the outputs
+use crate::suggest::DEFAULT_SUGGESTIONS;
+use crate::Graph;
+use std::fmt;
+
+/// What is known about one endpoint of the explained edge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointCheck {
+    pub label: String,
+    /// Index of the vertex, or None when no vertex has this label
+    pub index: Option<usize>,
+    /// Labels close to a missing label, nearest first
+    pub suggestions: Vec<String>,
+}
+
+/// Whether one output file shows the edge, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputVerdict {
+    pub output: &'static str,
+    pub included: bool,
+    pub reason: String,
+}
+
+/// The evidence on an edge gathered by Graph::explain_edge, from the labels to the outputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeExplanation {
+    pub a: EndpointCheck,
+    pub b: EndpointCheck,
+    /// The adjacency list line of a names b
+    pub a_lists_b: bool,
+    /// The adjacency list line of b names a
+    pub b_lists_a: bool,
+    /// The matrix cells [a][b] and [b][a], when both vertices exist
+    pub matrix: Option<(i32, i32)>,
+    pub outputs: Vec<OutputVerdict>,
+}
+
+impl Graph {
+    /// Looks up one endpoint, with suggestions when it does not exist
+    fn check_endpoint(&self, label: &str) -> EndpointCheck {
+        let index = self.find_vertex_idx(label);
+        EndpointCheck {
+            label: label.to_string(),
+            index,
+            suggestions: if index.is_none() {
+                self.closest_labels(label, DEFAULT_SUGGESTIONS)
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    /// Checks whether the adjacency list line of a vertex names a neighbor
+    fn lists(&self, idx: Option<usize>, neighbor: &str) -> bool {
+        idx.is_some_and(|i| {
+            self.collect_adjacent_vertices(&self.adj_list[i])
+                .iter()
+                .any(|adj| adj == neighbor)
+        })
+    }
+
+    /// Explains how the pair (a, b) is represented and which outputs show it
+    ///
+    /// The checks go in the order a confused user needs them: do both labels exist,
+    /// does the adjacency list name the neighbor in either direction, what does the
+    /// matrix hold, and so which of the -SET, -LIST, -MATRIX and traversal outputs
+    /// include the edge.
+    pub fn explain_edge(&self, a: &str, b: &str) -> EdgeExplanation {
+        let end_a = self.check_endpoint(a);
+        let end_b = self.check_endpoint(b);
+        let a_lists_b = self.lists(end_a.index, b);
+        let b_lists_a = self.lists(end_b.index, a);
+        let matrix = match (end_a.index, end_b.index) {
+            (Some(i), Some(j)) => Some((self.adj_matrix[i][j], self.adj_matrix[j][i])),
+            _ => None,
+        };
+
+        let missing: Vec<&str> = [&end_a, &end_b]
+            .iter()
+            .filter(|end| end.index.is_none())
+            .map(|end| end.label.as_str())
+            .collect();
+        let not_vertex = || format!("{} is not a vertex", missing.join(" and "));
+
+        let mut outputs = Vec::new();
+
+        let set = match matrix {
+            None => (false, not_vertex()),
+            Some(_) if a == b => (false, "self-loops are left out of -SET".to_string()),
+            Some((0, 0)) => (false, "neither matrix cell is set".to_string()),
+            Some((ab, ba)) => {
+                let (source, target) = if ab == 1 && ba == 1 {
+                    if a < b { (a, b) } else { (b, a) }
+                } else if ab == 1 {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+                let how = if ab == 1 && ba == 1 {
+                    "once, with the endpoints in alphabetical order"
+                } else {
+                    "in its one direction"
+                };
+                (true, format!("listed as ({},{}) {}", source, target, how))
+            }
+        };
+        outputs.push(OutputVerdict {
+            output: "-SET",
+            included: set.0,
+            reason: set.1,
+        });
+
+        let mut lines = Vec::new();
+        if a_lists_b {
+            lines.push(format!("the line of {} names {}", a, b));
+        }
+        if b_lists_a && a != b {
+            lines.push(format!("the line of {} names {}", b, a));
+        }
+        outputs.push(OutputVerdict {
+            output: "-LIST",
+            included: !lines.is_empty(),
+            reason: if lines.is_empty() {
+                "neither adjacency list line names the other vertex".to_string()
+            } else {
+                lines.join(" and ")
+            },
+        });
+
+        let cells = match matrix {
+            None => (false, not_vertex()),
+            Some((ab, ba)) => (
+                ab == 1 || ba == 1,
+                format!("[{}][{}] = {}, [{}][{}] = {}", a, b, ab, b, a, ba),
+            ),
+        };
+        outputs.push(OutputVerdict {
+            output: "-MATRIX",
+            included: cells.0,
+            reason: cells.1,
+        });
+
+        let steps = match matrix {
+            None => (false, not_vertex()),
+            Some((0, 0)) => (false, "neither matrix cell is set, so no traversal uses the edge".to_string()),
+            Some((1, 1)) => (true, "traversals can step either way".to_string()),
+            Some((1, _)) => (true, format!("traversals can step from {} to {} only", a, b)),
+            Some(_) => (true, format!("traversals can step from {} to {} only", b, a)),
+        };
+        outputs.push(OutputVerdict {
+            output: "-BFS/-DFS",
+            included: steps.0,
+            reason: steps.1,
+        });
+
+        EdgeExplanation {
+            a: end_a,
+            b: end_b,
+            a_lists_b,
+            b_lists_a,
+            matrix,
+            outputs,
+        }
+    }
+}
+
+impl fmt::Display for EndpointCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "vertex {}: found at index {}", self.label, index),
+            None if self.suggestions.is_empty() => write!(f, "vertex {}: not found", self.label),
+            None => write!(
+                f,
+                "vertex {}: not found; did you mean {}?",
+                self.label,
+                self.suggestions.join(", ")
+            ),
+        }
+    }
+}
+
+impl fmt::Display for EdgeExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (a, b) = (&self.a.label, &self.b.label);
+        writeln!(f, "{}", self.a)?;
+        writeln!(f, "{}", self.b)?;
+
+        let listing = |from: &EndpointCheck, to: &str, listed: bool| match (from.index, listed) {
+            (None, _) => format!("{} has no line of its own", from.label),
+            (Some(_), true) => format!("{} lists {}", from.label, to),
+            (Some(_), false) => format!("{} does not list {}", from.label, to),
+        };
+        writeln!(
+            f,
+            "adjacency list: {}; {}",
+            listing(&self.a, b, self.a_lists_b),
+            listing(&self.b, a, self.b_lists_a)
+        )?;
+        if (self.a_lists_b && self.b.index.is_none()) || (self.b_lists_a && self.a.index.is_none()) {
+            writeln!(f, "  a neighbor without a line of its own is dangling and never reaches the matrix")?;
+        } else if self.a_lists_b != self.b_lists_a && a != b {
+            writeln!(f, "  the edge is listed in one direction only")?;
+        }
+
+        match self.matrix {
+            Some((ab, ba)) => writeln!(f, "matrix: [{}][{}] = {}, [{}][{}] = {}", a, b, ab, b, a, ba)?,
+            None => writeln!(f, "matrix: no cells, since not both labels are vertices")?,
+        }
+
+        for verdict in &self.outputs {
+            let status = if verdict.included { "included" } else { "excluded" };
+            writeln!(f, "{}: {} ({})", verdict.output, status, verdict.reason)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+// src/fixtures.rs - Small reference graphs for tests (enabled by the test-support feature)
+//
+// The graphs and their labels are a stable contract: every fixture passes
+// check_invariants, and changing one changes the expected output of every test
+// built on it.
+use crate::dialect::Dialect;
+use crate::Graph;
+use std::fs;
+use std::path::PathBuf;
+
+/// The fixtures in the input file format
+pub mod text {
+    /// Triangle on A, B, C
+    pub const TRIANGLE: &str = "\
+3
+A B C -1
+B A C -1
+C A B -1
+";
+
+    /// Petersen graph: outer cycle A-B-C-D-E, inner pentagram F-H-J-G-I,
+    /// and spokes A-F, B-G, C-H, D-I, E-J
+    pub const PETERSEN: &str = "\
+10
+A B E F -1
+B A C G -1
+C B D H -1
+D C E I -1
+E A D J -1
+F A H I -1
+G B I J -1
+H C F J -1
+I D F G -1
+J E G H -1
+";
+
+    /// Two complete graphs on A, B, C, D and on E, F, G, H joined by the bridge D-E
+    pub const TWO_CLIQUES_BRIDGE: &str = "\
+8
+A B C D -1
+B A C D -1
+C A B D -1
+D A B C E -1
+E D F G H -1
+F E G H -1
+G E F H -1
+H E F G -1
+";
+
+    /// The two separate edges A-B and C-D
+    pub const DISCONNECTED_PAIR: &str = "\
+4
+A B -1
+B A -1
+C D -1
+D C -1
+";
+
+    /// The 4-cycle A-B-C-D-A; weighted_square gives its edges the weights
+    /// A-B 1, B-C 2, C-D 3 and D-A 4
+    pub const WEIGHTED_SQUARE: &str = "\
+4
+A B D -1
+B A C -1
+C B D -1
+D A C -1
+";
+}
+
+/// Parses one of the fixture texts in strict mode
+fn parse(source: &str) -> Graph {
+    let mut graph = Graph::new();
+    graph
+        .read_input_text_with(source, Dialect::Strict)
+        .expect("fixture text is a valid graph");
+    graph
+}
+
+/// Triangle on A, B, C
+pub fn triangle() -> Graph {
+    parse(text::TRIANGLE)
+}
+
+/// Petersen graph on A..J, see text::PETERSEN for the edges
+pub fn petersen() -> Graph {
+    parse(text::PETERSEN)
+}
+
+/// Cliques on A..D and E..H joined by the bridge D-E
+pub fn two_cliques_bridge() -> Graph {
+    parse(text::TWO_CLIQUES_BRIDGE)
+}
+
+/// The two separate edges A-B and C-D
+pub fn disconnected_pair() -> Graph {
+    parse(text::DISCONNECTED_PAIR)
+}
+
+/// The 4-cycle A-B-C-D-A with weights A-B 1, B-C 2, C-D 3 and D-A 4
+pub fn weighted_square() -> Graph {
+    let mut graph = parse(text::WEIGHTED_SQUARE);
+    for (u, v, weight) in [("A", "B", 1.0), ("B", "C", 2.0), ("C", "D", 3.0), ("D", "A", 4.0)] {
+        graph
+            .set_edge_weight(u, v, weight)
+            .expect("fixture vertices exist");
+    }
+    graph
+}
+
+/// A fresh, empty directory for the files of one test, under the system temp
+/// directory and named after the process, so parallel test runs stay apart
+pub fn scratch_dir(area: &str, test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("graph_analysis-{}-{}-{}", area, std::process::id(), test));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("the temp directory is writable");
+    dir
+}
@@ -0,0 +1,375 @@
+// src/flow.rs - Maximum flow and disjoint path counting
+use crate::error::GraphError;
+use crate::Graph;
+use std::collections::VecDeque;
+
+/// A flow network over nodes 0..n with capacities in an adjacency matrix,
+/// solved with the Edmonds-Karp algorithm
+pub(crate) struct FlowNetwork {
+    n: usize,
+    capacity: Vec<Vec<f64>>,
+    flow: Vec<Vec<f64>>,
+}
+
+impl FlowNetwork {
+    /// Creates a network with n nodes and no arcs
+    pub(crate) fn new(n: usize) -> Self {
+        FlowNetwork {
+            n,
+            capacity: vec![vec![0.0; n]; n],
+            flow: vec![vec![0.0; n]; n],
+        }
+    }
+
+    /// Adds capacity to the arc from u to v
+    pub(crate) fn add_capacity(&mut self, u: usize, v: usize, capacity: f64) {
+        self.capacity[u][v] += capacity;
+    }
+
+    /// Returns the unused capacity of the arc from u to v, counting flow that can be pushed back
+    fn residual(&self, u: usize, v: usize) -> f64 {
+        self.capacity[u][v] - self.flow[u][v] + self.flow[v][u]
+    }
+
+    /// Finds a shortest augmenting path with BFS, visiting nodes in index order
+    fn augmenting_path(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut pred = vec![None; self.n];
+        let mut seen = vec![false; self.n];
+        let mut queue = VecDeque::from([source]);
+        seen[source] = true;
+
+        while let Some(u) = queue.pop_front() {
+            if u == sink {
+                let mut path = vec![sink];
+                let mut current = sink;
+                while let Some(previous) = pred[current] {
+                    path.push(previous);
+                    current = previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for v in 0..self.n {
+                if !seen[v] && self.residual(u, v) > 1e-9 {
+                    seen[v] = true;
+                    pred[v] = Some(u);
+                    queue.push_back(v);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Pushes as much flow as possible from source to sink and returns its value
+    pub(crate) fn max_flow(&mut self, source: usize, sink: usize) -> f64 {
+        let mut total = 0.0;
+
+        while let Some(path) = self.augmenting_path(source, sink) {
+            let bottleneck = path
+                .windows(2)
+                .map(|arc| self.residual(arc[0], arc[1]))
+                .fold(f64::INFINITY, f64::min);
+
+            for arc in path.windows(2) {
+                let (u, v) = (arc[0], arc[1]);
+                // Cancel opposite flow first, then use forward capacity
+                let cancelled = bottleneck.min(self.flow[v][u]);
+                self.flow[v][u] -= cancelled;
+                self.flow[u][v] += bottleneck - cancelled;
+            }
+            total += bottleneck;
+        }
+
+        total
+    }
+
+    /// Marks the nodes still reachable from source in the residual network; after
+    /// max_flow they form the source side of a minimum cut
+    pub(crate) fn source_side(&self, source: usize) -> Vec<bool> {
+        let mut seen = vec![false; self.n];
+        let mut queue = VecDeque::from([source]);
+        seen[source] = true;
+        while let Some(u) = queue.pop_front() {
+            for (v, seen_v) in seen.iter_mut().enumerate() {
+                if !*seen_v && self.residual(u, v) > 1e-9 {
+                    *seen_v = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+        seen
+    }
+
+    /// Splits an integral flow into source-to-sink paths, taking the lowest
+    /// numbered arc first at every step
+    pub(crate) fn decompose_paths(&mut self, source: usize, sink: usize) -> Vec<Vec<usize>> {
+        let mut paths = Vec::new();
+
+        loop {
+            let mut path = vec![source];
+            let mut current = source;
+            while current != sink {
+                let Some(next) = (0..self.n).find(|&v| self.flow[current][v] > 0.5) else {
+                    return paths;
+                };
+                self.flow[current][next] -= 1.0;
+                // Drop any cycle the walk closes so each path is simple
+                if let Some(pos) = path.iter().position(|&node| node == next) {
+                    path.truncate(pos + 1);
+                } else {
+                    path.push(next);
+                }
+                current = next;
+            }
+            paths.push(path);
+        }
+    }
+}
+
+impl Graph {
+    /// Looks up both endpoints of a disjoint path query, which must differ
+    fn disjoint_endpoints(&self, a: &str, b: &str) -> Result<(usize, usize), GraphError> {
+        let a_idx = self.require_vertex_idx(a)?;
+        let b_idx = self.require_vertex_idx(b)?;
+        if a_idx == b_idx {
+            return Err(GraphError::SameVertex(a.to_string()));
+        }
+        Ok((a_idx, b_idx))
+    }
+
+    /// Counts the maximum number of paths from a to b that share no edge
+    pub fn edge_disjoint_paths(&self, a: &str, b: &str) -> Result<usize, GraphError> {
+        Ok(self.edge_disjoint_paths_with_paths(a, b)?.len())
+    }
+
+    /// Finds a maximum set of paths from a to b that share no edge
+    /// Each path lists the labels from a to b
+    pub fn edge_disjoint_paths_with_paths(
+        &self,
+        a: &str,
+        b: &str,
+    ) -> Result<Vec<Vec<String>>, GraphError> {
+        let (a_idx, b_idx) = self.disjoint_endpoints(a, b)?;
+
+        // Network nodes follow alphabetical order so the chosen paths are deterministic
+        let order = self.sort_vertices();
+        let ranks = self.alphabetical_ranks();
+        let mut network = FlowNetwork::new(self.n_vertices);
+        for i in 0..self.n_vertices {
+            for j in 0..self.n_vertices {
+                if i != j && self.adj_matrix[i][j] == 1 {
+                    network.add_capacity(ranks[i], ranks[j], 1.0);
+                }
+            }
+        }
+
+        network.max_flow(ranks[a_idx], ranks[b_idx]);
+        let paths = network.decompose_paths(ranks[a_idx], ranks[b_idx]);
+        Ok(paths
+            .into_iter()
+            .map(|path| path.into_iter().map(|node| self.vertices[order[node]].clone()).collect())
+            .collect())
+    }
+
+    /// Counts the maximum number of paths from a to b that share no vertex other than a and b
+    pub fn vertex_disjoint_paths(&self, a: &str, b: &str) -> Result<usize, GraphError> {
+        Ok(self.vertex_disjoint_paths_with_paths(a, b)?.len())
+    }
+
+    /// Finds a maximum set of paths from a to b that share no vertex other than a and b
+    ///
+    /// Every vertex is split into an entry node and an exit node joined by an arc
+    /// of capacity 1, so at most one path can pass through it. A direct edge
+    /// between a and b counts as one path.
+    pub fn vertex_disjoint_paths_with_paths(
+        &self,
+        a: &str,
+        b: &str,
+    ) -> Result<Vec<Vec<String>>, GraphError> {
+        let (a_idx, b_idx) = self.disjoint_endpoints(a, b)?;
+
+        let order = self.sort_vertices();
+        let ranks = self.alphabetical_ranks();
+        let entry = |idx: usize| 2 * ranks[idx];
+        let exit = |idx: usize| 2 * ranks[idx] + 1;
+
+        let mut network = FlowNetwork::new(2 * self.n_vertices);
+        for i in 0..self.n_vertices {
+            let through = if i == a_idx || i == b_idx {
+                self.n_vertices as f64
+            } else {
+                1.0
+            };
+            network.add_capacity(entry(i), exit(i), through);
+            for j in 0..self.n_vertices {
+                if i != j && self.adj_matrix[i][j] == 1 {
+                    network.add_capacity(exit(i), entry(j), 1.0);
+                }
+            }
+        }
+
+        network.max_flow(exit(a_idx), entry(b_idx));
+        let paths = network.decompose_paths(exit(a_idx), entry(b_idx));
+
+        // Every original vertex appears as an entry/exit pair, so keep only the entries
+        // (plus the starting exit node) to recover the labels
+        Ok(paths
+            .into_iter()
+            .map(|path| {
+                path.into_iter()
+                    .enumerate()
+                    .filter(|&(pos, node)| pos == 0 || node % 2 == 0)
+                    .map(|(_, node)| self.vertices[order[node / 2]].clone())
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Returns the minimum number of edges whose removal disconnects the graph
+    ///
+    /// Disconnected graphs and graphs with fewer than two vertices have edge
+    /// connectivity 0. Every minimum cut separates the first vertex from some
+    /// other vertex, so only those n-1 pairs are checked.
+    pub fn edge_connectivity(&self) -> usize {
+        if self.n_vertices < 2 || self.connected_components().len() > 1 {
+            return 0;
+        }
+
+        let first = &self.vertices[0];
+        let mut best = self.stats_degrees().min().unwrap_or(0);
+        for other in &self.vertices[1..self.n_vertices] {
+            if best == 0 {
+                break;
+            }
+            best = best.min(self.edge_disjoint_paths(first, other).unwrap_or(0));
+        }
+        best
+    }
+
+    /// Returns the minimum number of vertices whose removal disconnects the graph
+    ///
+    /// Disconnected graphs have vertex connectivity 0, and by convention the
+    /// complete graph on n vertices has vertex connectivity n-1.
+    pub fn vertex_connectivity(&self) -> usize {
+        if self.n_vertices < 2 || self.connected_components().len() > 1 {
+            return 0;
+        }
+
+        // Vertex connectivity never exceeds the minimum degree, and a complete graph
+        // has no non-adjacent pair to separate
+        let mut best = self.stats_degrees().min().unwrap_or(0).min(self.n_vertices - 1);
+
+        // A minimum separator misses at least one of the first best+1 vertices,
+        // so pairs starting from those vertices are enough
+        let mut i = 0;
+        while i <= best && i < self.n_vertices {
+            for j in 0..self.n_vertices {
+                if i != j && self.adj_matrix[i][j] == 0 && self.adj_matrix[j][i] == 0 {
+                    let paths = self
+                        .vertex_disjoint_paths(&self.vertices[i], &self.vertices[j])
+                        .unwrap_or(0);
+                    best = best.min(paths);
+                }
+            }
+            i += 1;
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::Dialect;
+    use crate::fixtures;
+
+    fn graph_from(text: &str) -> Graph {
+        let mut graph = Graph::new();
+        graph.read_input_text_with(text, Dialect::Strict).unwrap();
+        graph
+    }
+
+    /// The 3x3 grid, rows A-C, D-F and G-I
+    const GRID: &str = "9\nA B D -1\nB A C E -1\nC B F -1\nD A E G -1\nE B D F H -1\nF C E I -1\n\
+        G D H -1\nH E G I -1\nI F H -1\n";
+
+    fn edges(graph: &Graph) -> Vec<(usize, usize)> {
+        let n = graph.n_vertices;
+        (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j))).filter(|&(i, j)| graph.adj_matrix[i][j] == 1).collect()
+    }
+
+    /// Whether b can still be reached from a once the masked edges and vertices are gone
+    fn reaches(graph: &Graph, a: usize, b: usize, cut_edges: u64, cut_vertices: u64) -> bool {
+        let edges = edges(graph);
+        let mut seen = vec![false; graph.n_vertices];
+        let mut stack = vec![a];
+        seen[a] = true;
+        while let Some(u) = stack.pop() {
+            for (k, &(i, j)) in edges.iter().enumerate() {
+                let v = if i == u { j } else if j == u { i } else { continue };
+                if cut_edges >> k & 1 == 0 && cut_vertices >> v & 1 == 0 && !seen[v] {
+                    seen[v] = true;
+                    stack.push(v);
+                }
+            }
+        }
+        seen[b]
+    }
+
+    /// The fewest edges whose removal separates a from b, trying every subset
+    fn brute_force_edge_cut(graph: &Graph, a: usize, b: usize) -> usize {
+        let m = edges(graph).len();
+        (0..1u64 << m).filter(|&cut| !reaches(graph, a, b, cut, 0)).map(u64::count_ones).min().unwrap() as usize
+    }
+
+    /// The fewest vertices other than a and b whose removal separates them, trying every subset
+    fn brute_force_vertex_cut(graph: &Graph, a: usize, b: usize) -> usize {
+        let ends = 1 << a | 1 << b;
+        (0..1u64 << graph.n_vertices)
+            .filter(|&cut| cut & ends == 0 && !reaches(graph, a, b, 0, cut))
+            .map(u64::count_ones)
+            .min()
+            .unwrap() as usize
+    }
+
+    #[test]
+    fn disjoint_paths_match_the_brute_force_minimum_cut() {
+        for graph in [fixtures::petersen(), fixtures::two_cliques_bridge(), graph_from(GRID)] {
+            let label = |i: usize| graph.vertices[i].clone();
+            for b in 1..graph.n_vertices {
+                // Menger: as many disjoint paths as the smallest cut
+                let edge_paths = graph.edge_disjoint_paths(&label(0), &label(b)).unwrap();
+                assert_eq!(edge_paths, brute_force_edge_cut(&graph, 0, b), "A-{}", label(b));
+                if graph.adj_matrix[0][b] == 0 {
+                    let vertex_paths = graph.vertex_disjoint_paths(&label(0), &label(b)).unwrap();
+                    assert_eq!(vertex_paths, brute_force_vertex_cut(&graph, 0, b), "A-{}", label(b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_listed_paths_are_disjoint() {
+        let graph = graph_from(GRID);
+        let paths = graph.vertex_disjoint_paths_with_paths("A", "I").unwrap();
+        assert_eq!(paths.len(), 2);
+        let inner: Vec<&String> = paths.iter().flat_map(|path| &path[1..path.len() - 1]).collect();
+        let mut unique = inner.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), inner.len(), "{:?}", paths);
+        assert!(graph.edge_disjoint_paths("A", "A").is_err());
+    }
+
+    #[test]
+    fn complete_graph_k4_has_connectivity_three() {
+        let k4 = graph_from("4\nA B C D -1\nB A C D -1\nC A B D -1\nD A B C -1\n");
+        assert_eq!((k4.edge_connectivity(), k4.vertex_connectivity()), (3, 3));
+        assert_eq!(fixtures::petersen().vertex_connectivity(), 3);
+        let bridged = fixtures::two_cliques_bridge();
+        assert_eq!((bridged.edge_connectivity(), bridged.vertex_connectivity()), (1, 1));
+        let apart = fixtures::disconnected_pair();
+        assert_eq!((apart.edge_connectivity(), apart.vertex_connectivity()), (0, 0));
+    }
+}
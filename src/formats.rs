@@ -0,0 +1,479 @@
+// src/formats.rs - Conversion between the adjacency input format and solver formats
+//
+// DIMACS and METIS number vertices from 1 and carry no labels. Writers number the
+// vertices in input order and return the labels in that order, to be stored in a
+// sidecar `.labels` file (one label per line); readers take the labels back, or
+// name the vertices "1", "2", ... without them.
+//
+// Self-loops: DIMACS lists a loop as `e v v` and counts it in m. METIS has no
+// loops, so to_metis leaves them out of both the lines and m, and from_metis
+// rejects them. Arcs present in one direction only are written as undirected edges.
+//
+// The matrix format is the layout of the -MATRIX output: a header row of labels,
+// then one row per vertex of its label and a 0 or 1 per column. It keeps the
+// labels and the direction of every arc, so it needs no sidecar.
+use crate::direction::Direction;
+use crate::dialect::{read_file_lines, Encoding, ParseWarning};
+use crate::error::GraphError;
+use crate::Graph;
+use std::fmt;
+use std::path::Path;
+
+/// The file formats the convert subcommand reads and writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// The adjacency format of the input files
+    Adjacency,
+    /// DIMACS edge format: `p edge n m`, then `e u v` lines
+    Dimacs,
+    /// METIS graph format: `n m`, then one line of neighbors per vertex
+    Metis,
+    /// Adjacency matrix: a header row of labels, then a labeled row of 0/1 cells per vertex
+    Matrix,
+}
+
+impl GraphFormat {
+    pub const ALL: [GraphFormat; 4] = [
+        GraphFormat::Adjacency,
+        GraphFormat::Dimacs,
+        GraphFormat::Metis,
+        GraphFormat::Matrix,
+    ];
+
+    /// Returns the name used on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            GraphFormat::Adjacency => "adjacency",
+            GraphFormat::Dimacs => "dimacs",
+            GraphFormat::Metis => "metis",
+            GraphFormat::Matrix => "matrix",
+        }
+    }
+
+    /// Looks a format up by its command-line name
+    pub fn from_name(name: &str) -> Option<GraphFormat> {
+        GraphFormat::ALL.into_iter().find(|format| format.name() == name)
+    }
+
+    /// Guesses the format from a file extension: .txt, .dimacs/.col, .metis/.graph or .matrix
+    pub fn from_path(path: &str) -> Option<GraphFormat> {
+        let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "txt" => Some(GraphFormat::Adjacency),
+            "dimacs" | "col" => Some(GraphFormat::Dimacs),
+            "metis" | "graph" => Some(GraphFormat::Metis),
+            "matrix" => Some(GraphFormat::Matrix),
+            _ => None,
+        }
+    }
+
+    /// Checks whether the format needs a sidecar .labels file to keep the labels
+    pub fn uses_label_sidecar(self) -> bool {
+        matches!(self, GraphFormat::Dimacs | GraphFormat::Metis)
+    }
+}
+
+impl fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Returns the path of the sidecar labels file of a DIMACS or METIS file
+pub fn labels_path(path: &str) -> String {
+    format!("{}.labels", path)
+}
+
+/// Reads a sidecar labels file, or None when there is none
+pub fn read_labels(path: &str) -> Result<Option<Vec<String>>, GraphError> {
+    read_labels_with_encoding(path, Encoding::Strict).map(|(labels, _)| labels)
+}
+
+/// Reads a sidecar labels file as read_labels does, decoding it under the given encoding
+fn read_labels_with_encoding(
+    path: &str,
+    encoding: Encoding,
+) -> Result<(Option<Vec<String>>, Option<ParseWarning>), GraphError> {
+    let path = labels_path(path);
+    let (lines, warning) = match read_file_lines(&path, encoding) {
+        Err(GraphError::FileNotFound(_)) => return Ok((None, None)),
+        lines => lines?,
+    };
+    Ok((Some(lines.iter().map(|line| line.trim().to_string()).collect()), warning))
+}
+
+/// Names n vertices by the given labels, or "1".."n" without them
+fn vertex_names(n: usize, labels: Option<&[String]>, format: &'static str) -> Result<Vec<String>, GraphError> {
+    match labels {
+        Some(labels) if labels.len() != n => Err(GraphError::InvalidFormat {
+            format,
+            reason: format!("{} labels given for {} vertices", labels.len(), n),
+        }),
+        Some(labels) => Ok(labels.to_vec()),
+        None => Ok((1..=n).map(|i| i.to_string()).collect()),
+    }
+}
+
+impl Graph {
+    /// Checks whether u and v are joined in either direction
+    fn joined(&self, u: usize, v: usize) -> bool {
+        self.adj_matrix[u][v] == 1 || self.adj_matrix[v][u] == 1
+    }
+
+    /// Returns the labels in input order, the order behind the 1-based solver indices
+    pub fn labels_text(&self) -> String {
+        let mut text = String::new();
+        for label in &self.vertices[..self.n_vertices] {
+            text.push_str(label);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Formats the graph in DIMACS edge format, edges in the canonical edge order
+    pub fn to_dimacs(&self) -> String {
+        let edges = self.ordered_edges();
+        let mut text = format!("p edge {} {}\n", self.n_vertices, edges.len());
+        for edge in &edges {
+            text.push_str(&format!("e {} {}\n", edge.source_idx + 1, edge.target_idx + 1));
+        }
+        text
+    }
+
+    /// Formats the graph in METIS graph format, listing every edge in the lines of both endpoints
+    pub fn to_metis(&self) -> String {
+        let n = self.n_vertices;
+        let m = self.ordered_edges().iter().filter(|edge| !edge.is_loop()).count();
+        let mut text = format!("{} {}\n", n, m);
+        for u in 0..n {
+            let neighbors: Vec<String> = (0..n)
+                .filter(|&v| v != u && self.joined(u, v))
+                .map(|v| (v + 1).to_string())
+                .collect();
+            text.push_str(&neighbors.join(" "));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Reads a graph in DIMACS edge format; comment lines start with `c`
+    pub fn from_dimacs(text: &str, labels: Option<&[String]>) -> Result<Graph, GraphError> {
+        let invalid = |line: usize, reason: String| GraphError::InvalidFormat {
+            format: "DIMACS",
+            reason: format!("line {}: {}", line, reason),
+        };
+        let mut header: Option<(usize, usize)> = None;
+        let mut rows: Vec<(String, Vec<String>)> = Vec::new();
+        let mut edges = 0;
+
+        for (i, line) in text.lines().enumerate() {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens.as_slice() {
+                [] | ["c", ..] => {}
+                ["p", _, n, m] if header.is_none() => {
+                    let n: usize = n.parse().map_err(|_| invalid(i + 1, format!("bad vertex count {}", n)))?;
+                    let m: usize = m.parse().map_err(|_| invalid(i + 1, format!("bad edge count {}", m)))?;
+                    let names = vertex_names(n, labels, "DIMACS")?;
+                    rows = names.into_iter().map(|name| (name, Vec::new())).collect();
+                    header = Some((n, m));
+                }
+                ["e", u, v] => {
+                    let (n, _) = header.ok_or_else(|| invalid(i + 1, "edge before the p line".to_string()))?;
+                    let index = |token: &str| match token.parse::<usize>() {
+                        Ok(k) if (1..=n).contains(&k) => Ok(k - 1),
+                        _ => Err(invalid(i + 1, format!("vertex {} is not in 1..{}", token, n))),
+                    };
+                    let (u, v) = (index(u)?, index(v)?);
+                    let (label_u, label_v) = (rows[u].0.clone(), rows[v].0.clone());
+                    if !rows[u].1.contains(&label_v) {
+                        rows[u].1.push(label_v);
+                        if u != v {
+                            rows[v].1.push(label_u);
+                        }
+                    }
+                    edges += 1;
+                }
+                _ => return Err(invalid(i + 1, format!("unexpected line \"{}\"", line.trim()))),
+            }
+        }
+
+        let (_, m) = header.ok_or_else(|| GraphError::InvalidFormat {
+            format: "DIMACS",
+            reason: "missing p edge line".to_string(),
+        })?;
+        if edges != m {
+            return Err(GraphError::InvalidFormat {
+                format: "DIMACS",
+                reason: format!("header announces {} edges but {} e lines follow", m, edges),
+            });
+        }
+        Graph::from_rows(&rows)
+    }
+
+    /// Reads a graph in METIS graph format; comment lines start with `%`
+    /// Every edge must appear in the lines of both endpoints, and self-loops are rejected
+    pub fn from_metis(text: &str, labels: Option<&[String]>) -> Result<Graph, GraphError> {
+        let invalid = |reason: String| GraphError::InvalidFormat {
+            format: "METIS",
+            reason,
+        };
+        // Blank lines after the header are isolated vertices, so only comments are skipped
+        let mut lines = text.lines().enumerate().filter(|(_, line)| !line.starts_with('%'));
+        let (header_line, header) = lines.next().ok_or_else(|| invalid("missing header".to_string()))?;
+        let header: Vec<usize> = header
+            .split_whitespace()
+            .map(|token| token.parse::<usize>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| invalid(format!("line {}: bad header \"{}\"", header_line + 1, header.trim())))?;
+        let (n, m) = match header.as_slice() {
+            [n, m] | [n, m, 0] => (*n, *m),
+            [_, _, fmt, ..] => return Err(invalid(format!("weights (format {}) are not supported", fmt))),
+            _ => return Err(invalid(format!("line {}: expected \"n m\"", header_line + 1))),
+        };
+
+        let names = vertex_names(n, labels, "METIS")?;
+        let mut neighbors: Vec<Vec<usize>> = Vec::with_capacity(n);
+        for u in 0..n {
+            let (i, line) = lines.next().unwrap_or((usize::MAX, ""));
+            let mut row = Vec::new();
+            for token in line.split_whitespace() {
+                match token.parse::<usize>() {
+                    Ok(k) if k == u + 1 => {
+                        return Err(invalid(format!("line {}: self-loop on vertex {}", i + 1, k)))
+                    }
+                    Ok(k) if (1..=n).contains(&k) => row.push(k - 1),
+                    _ => return Err(invalid(format!("line {}: vertex {} is not in 1..{}", i + 1, token, n))),
+                }
+            }
+            neighbors.push(row);
+        }
+        if let Some((i, line)) = lines.find(|(_, line)| !line.trim().is_empty()) {
+            return Err(invalid(format!("line {}: more than {} vertex lines, found \"{}\"", i + 1, n, line.trim())));
+        }
+
+        for (u, row) in neighbors.iter().enumerate() {
+            if let Some(&v) = row.iter().find(|&&v| !neighbors[v].contains(&u)) {
+                return Err(invalid(format!(
+                    "edge {}-{} is listed for vertex {} but not for vertex {}",
+                    u + 1,
+                    v + 1,
+                    u + 1,
+                    v + 1
+                )));
+            }
+        }
+        let entries: usize = neighbors.iter().map(Vec::len).sum();
+        if entries != 2 * m {
+            return Err(invalid(format!(
+                "header announces {} edges but the lines list {} neighbor entries, not {}",
+                m,
+                entries,
+                2 * m
+            )));
+        }
+
+        let rows: Vec<(String, Vec<String>)> = names
+            .iter()
+            .zip(&neighbors)
+            .map(|(name, row)| (name.clone(), row.iter().map(|&v| names[v].clone()).collect()))
+            .collect();
+        Graph::from_rows(&rows)
+    }
+
+    /// Formats the graph as an adjacency matrix in the layout of the -MATRIX output
+    ///
+    /// Columns are 10 characters wide, as in the output file, or one more than the
+    /// longest label when that is longer, so every label stays apart from the next.
+    pub fn to_adjacency_matrix(&self) -> String {
+        let labels = &self.vertices[..self.n_vertices];
+        let width = labels.iter().map(|label| label.chars().count() + 1).max().unwrap_or(0).max(10);
+        let mut text = format!("{:<width$}", "", width = width);
+        for label in labels {
+            text.push_str(&format!("{:<width$}", label, width = width));
+        }
+        text.push('\n');
+        for (i, label) in labels.iter().enumerate() {
+            text.push_str(&format!("{:<width$}", label, width = width));
+            for cell in &self.adj_matrix[i][..self.n_vertices] {
+                text.push_str(&format!("{:<width$}", cell, width = width));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Reads a graph from an adjacency matrix in the layout of the -MATRIX output
+    ///
+    /// Cells may be separated by spaces, tabs, commas or semicolons, so a matrix saved
+    /// from a spreadsheet reads too. The first row lists the labels, after a corner
+    /// word such as the MATRIX of the course-ps2 profile if there is one; every other row
+    /// starts with the label of its column and holds one 0 or 1 per column, so the
+    /// matrix must be square. Blank lines are skipped. Undirected matrices must be
+    /// symmetric, and the error names the first cell whose mirror differs.
+    pub fn from_adjacency_matrix(text: &str, direction: Direction) -> Result<Graph, GraphError> {
+        let invalid = |reason: String| GraphError::InvalidFormat {
+            format: "adjacency matrix",
+            reason,
+        };
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let cells: Vec<&str> = line
+                    .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+                    .filter(|cell| !cell.is_empty())
+                    .collect();
+                (i + 1, cells)
+            })
+            .filter(|(_, cells)| !cells.is_empty())
+            .peekable();
+
+        let (_, mut labels) = lines.next().ok_or_else(|| invalid("missing header row of labels".to_string()))?;
+        // A header as long as a labeled row starts with a corner word, such as MATRIX
+        if lines.peek().is_some_and(|(_, row)| row.len() == labels.len()) {
+            labels.remove(0);
+        }
+        let n = labels.len();
+        for (k, label) in labels.iter().enumerate() {
+            if labels[..k].contains(label) {
+                return Err(invalid(format!("label {} heads two columns", label)));
+            }
+        }
+
+        let mut cells: Vec<Vec<bool>> = Vec::with_capacity(n);
+        let mut row_lines = Vec::with_capacity(n);
+        for (line, row) in lines {
+            let k = cells.len();
+            if k == n {
+                return Err(invalid(format!(
+                    "line {}: the header has {} labels but there are more rows than that",
+                    line, n
+                )));
+            }
+            if row[0] != labels[k] {
+                return Err(invalid(format!(
+                    "line {}: row {} is labeled {} but column {} is {}",
+                    line,
+                    k + 1,
+                    row[0],
+                    k + 1,
+                    labels[k]
+                )));
+            }
+            if row.len() != n + 1 {
+                return Err(invalid(format!(
+                    "line {}: row {} has {} cells, not one per column ({})",
+                    line,
+                    row[0],
+                    row.len() - 1,
+                    n
+                )));
+            }
+            let values = row[1..]
+                .iter()
+                .enumerate()
+                .map(|(j, &cell)| match cell {
+                    "0" => Ok(false),
+                    "1" => Ok(true),
+                    _ => Err(invalid(format!(
+                        "line {}: cell ({},{}) is {}, not 0 or 1",
+                        line, row[0], labels[j], cell
+                    ))),
+                })
+                .collect::<Result<Vec<bool>, GraphError>>()?;
+            cells.push(values);
+            row_lines.push(line);
+        }
+        if cells.len() != n {
+            return Err(invalid(format!(
+                "the header has {} labels but {} rows follow; the matrix must be square",
+                n,
+                cells.len()
+            )));
+        }
+
+        if direction == Direction::Undirected {
+            for i in 0..n {
+                if let Some(j) = (i + 1..n).find(|&j| cells[i][j] != cells[j][i]) {
+                    return Err(invalid(format!(
+                        "cell ({},{}) on line {} is {} but cell ({},{}) on line {} is {}; \
+                         an undirected matrix must be symmetric",
+                        labels[i],
+                        labels[j],
+                        row_lines[i],
+                        cells[i][j] as u8,
+                        labels[j],
+                        labels[i],
+                        row_lines[j],
+                        cells[j][i] as u8
+                    )));
+                }
+            }
+        }
+
+        let rows: Vec<(String, Vec<String>)> = (0..n)
+            .map(|i| {
+                let neighbors = (0..n).filter(|&j| cells[i][j]).map(|j| labels[j].to_string()).collect();
+                (labels[i].to_string(), neighbors)
+            })
+            .collect();
+        Graph::from_rows(&rows)
+    }
+
+    /// Replaces the graph with the undirected one in an adjacency matrix file
+    /// See read_adjacency_matrix_with
+    pub fn read_adjacency_matrix(&mut self, path: &str) -> Result<(), GraphError> {
+        self.read_adjacency_matrix_with(path, Direction::Undirected)
+    }
+
+    /// Replaces the graph with the one in an adjacency matrix file, such as a -MATRIX
+    /// output, and names it after the path without its extension; see from_adjacency_matrix
+    pub fn read_adjacency_matrix_with(&mut self, path: &str, direction: Direction) -> Result<(), GraphError> {
+        let (lines, _) = read_file_lines(path, Encoding::Strict)?;
+        *self = Graph::from_adjacency_matrix(&lines.join("\n"), direction)?;
+        self.name = Some(Graph::get_base_filename(path));
+        Ok(())
+    }
+
+    /// Reads a graph file in the given format, with its sidecar labels if there are any,
+    /// decoding both under the given encoding; see read_as and decode_lines
+    pub fn read_file_as(
+        format: GraphFormat,
+        path: &str,
+        encoding: Encoding,
+    ) -> Result<(Graph, Vec<ParseWarning>), GraphError> {
+        let (lines, warning) = read_file_lines(path, encoding)?;
+        let (labels, labels_warning) = read_labels_with_encoding(path, encoding)?;
+        let graph = Graph::read_as(format, &lines.join("\n"), labels.as_deref())?;
+        Ok((graph, warning.into_iter().chain(labels_warning).collect()))
+    }
+
+    /// Formats the graph in the given format
+    pub fn write_as(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Adjacency => self.to_input_text(),
+            GraphFormat::Dimacs => self.to_dimacs(),
+            GraphFormat::Metis => self.to_metis(),
+            GraphFormat::Matrix => self.to_adjacency_matrix(),
+        }
+    }
+
+    /// Reads a graph in the given format, with the labels of a sidecar file if there is one
+    pub fn read_as(format: GraphFormat, text: &str, labels: Option<&[String]>) -> Result<Graph, GraphError> {
+        match format {
+            GraphFormat::Adjacency => {
+                let mut graph = Graph::new();
+                graph
+                    .read_input_text_with(text, crate::dialect::Dialect::Strict)
+                    .map_err(|error| GraphError::InvalidFormat {
+                        format: "adjacency",
+                        reason: error.to_string(),
+                    })?;
+                Ok(graph)
+            }
+            GraphFormat::Dimacs => Graph::from_dimacs(text, labels),
+            GraphFormat::Metis => Graph::from_metis(text, labels),
+            GraphFormat::Matrix => Graph::from_adjacency_matrix(text, Direction::Undirected),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// src/fuzz.rs - Fuzz harnesses for the parsers (enabled by the fuzz feature)
+//
+// Each harness takes arbitrary bytes, feeds them to one reader and, when the
+// reader accepts them, checks that the graph it built is consistent. Rejecting
+// the input with an error is always fine; a panic, a hang or an inconsistent
+// graph is a bug. The harnesses are deterministic, so a cargo-fuzz target is one
+// line calling them, and run_corpus replays a directory of saved inputs such as
+// tests/corpus without a fuzzer.
+use crate::dialect::{decode_lines, Dialect, Encoding};
+use crate::Graph;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Panics with the first disagreement between the representations of a parsed graph
+fn assert_consistent(graph: &Graph, reader: &str) {
+    if let Some(mismatch) = graph.representation_digest().first_mismatch() {
+        panic!("{} accepted an inconsistent graph: {}", reader, mismatch);
+    }
+    assert!(
+        graph.vertices.len() >= graph.n_vertices,
+        "{} accepted {} vertices but labelled only {}",
+        reader,
+        graph.n_vertices,
+        graph.vertices.len()
+    );
+}
+
+/// Reads the bytes as an adjacency file under every dialect, decoding them lossily
+///
+/// Strict reads must also pass check_invariants, which they promise.
+pub fn fuzz_adjacency_parser(data: &[u8]) {
+    let Ok((lines, _)) = decode_lines(data, Encoding::Lossy) else {
+        return;
+    };
+    for dialect in [
+        Dialect::Strict,
+        Dialect::InferCount,
+        Dialect::AutoDirection,
+        Dialect::Directed,
+        Dialect::Undirected,
+    ] {
+        let mut graph = Graph::new();
+        if graph.read_input_lines(&lines, dialect).is_ok() {
+            assert_consistent(&graph, "the adjacency reader");
+            if dialect == Dialect::Strict {
+                if let Err(violation) = graph.check_invariants() {
+                    panic!("a strict read broke an invariant: {}", violation);
+                }
+            }
+        }
+    }
+}
+
+/// Reads every line of the bytes as a graph6 string
+pub fn fuzz_graph6_parser(data: &[u8]) {
+    for line in String::from_utf8_lossy(data).lines() {
+        if let Ok(graph) = Graph::from_graph6(line) {
+            assert_consistent(&graph, "the graph6 reader");
+            if let Err(violation) = graph.check_invariants() {
+                panic!("a graph6 graph broke an invariant: {}", violation);
+            }
+        }
+    }
+}
+
+/// Runs a harness on every file of a directory, in name order, and returns how many there were
+pub fn run_corpus(dir: impl AsRef<Path>, harness: fn(&[u8])) -> io::Result<usize> {
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|path| path.is_file());
+    paths.sort();
+    for path in &paths {
+        harness(&fs::read(path)?);
+    }
+    Ok(paths.len())
+}
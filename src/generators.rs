@@ -0,0 +1,452 @@
+// src/generators.rs - Random graph generators driven by a seeded Rng
+//
+// Generated vertices are labeled "v0", "v1", ... in input order, except those of
+// configuration_model, labeled "0", "1", ... like graphs read from graph6. Seeds
+// are taken as a Seed or a plain u64; resolve a SeedPolicy first to draw a fresh
+// one, and report it so the graph can be built again.
+//
+// The deterministic families document their own labels. All of them can also be
+// built by name from a list of integer parameters through by_name, and register
+// adds families of other crates to the same registry.
+use crate::error::GraphError;
+use crate::rng::{Rng, Seed};
+use crate::Graph;
+use std::sync::{OnceLock, RwLock};
+
+/// Number of random pairings configuration_model tries before giving up on a simple graph
+pub const CONFIGURATION_ATTEMPTS: usize = 1_000;
+
+/// Checks the Erdős–Gallai conditions: whether some simple graph has these degrees
+pub fn is_graphical(degrees: &[usize]) -> bool {
+    let mut sorted = degrees.to_vec();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+    let n = sorted.len();
+    if sorted.iter().sum::<usize>() % 2 == 1 {
+        return false;
+    }
+    let mut prefix = 0;
+    for k in 1..=n {
+        prefix += sorted[k - 1];
+        let rest: usize = sorted[k..].iter().map(|&d| d.min(k)).sum();
+        if prefix > k * (k - 1) + rest {
+            return false;
+        }
+    }
+    true
+}
+
+/// Checks that n generated vertices fit in a Graph
+fn check_capacity(n: usize) -> Result<(), GraphError> {
+    Graph::check_vertex_limit(n)?;
+    Ok(())
+}
+
+fn invalid(name: &'static str, reason: String) -> GraphError {
+    GraphError::InvalidParameter { name, reason }
+}
+
+/// Builds a graph on n vertices labeled "v0".."v{n-1}" from undirected edges
+fn graph_from_edges(n: usize, edges: &[(usize, usize)]) -> Result<Graph, GraphError> {
+    let labels: Vec<String> = (0..n).map(|i| format!("v{}", i)).collect();
+    graph_from_labeled_edges(&labels, edges)
+}
+
+/// Builds a graph on the given labels, in that order, from undirected edges between their indices
+fn graph_from_labeled_edges(labels: &[String], edges: &[(usize, usize)]) -> Result<Graph, GraphError> {
+    let mut rows: Vec<(String, Vec<String>)> = labels.iter().map(|label| (label.clone(), Vec::new())).collect();
+    for &(u, v) in edges {
+        let (label_u, label_v) = (labels[u].clone(), labels[v].clone());
+        if !rows[u].1.contains(&label_v) {
+            rows[u].1.push(label_v);
+            if u != v {
+                rows[v].1.push(label_u);
+            }
+        }
+    }
+    Graph::from_rows(&rows)
+}
+
+/// Builds a random graph with the given degree sequence by pairing degree stubs at random
+///
+/// With simple set, pairings that create a self-loop or a repeated edge are thrown
+/// away and drawn again, up to CONFIGURATION_ATTEMPTS times, so every vertex gets
+/// exactly its requested degree. Without it, the first pairing is kept: a repeated
+/// edge is stored once and a self-loop once, so those vertices can end up with a
+/// lower degree than requested. Vertices are labeled "0".."n-1".
+pub fn configuration_model(degrees: &[usize], seed: impl Into<Seed>, simple: bool) -> Result<Graph, GraphError> {
+    let n = degrees.len();
+    check_capacity(n)?;
+    if degrees.iter().sum::<usize>() % 2 == 1 {
+        return Err(GraphError::InvalidDegreeSequence(
+            "the degrees add up to an odd number".to_string(),
+        ));
+    }
+    if simple && !is_graphical(degrees) {
+        return Err(GraphError::InvalidDegreeSequence(
+            "no simple graph has these degrees".to_string(),
+        ));
+    }
+
+    let mut stubs: Vec<usize> = degrees
+        .iter()
+        .enumerate()
+        .flat_map(|(v, &d)| (0..d).map(move |_| v))
+        .collect();
+    let mut rng = Rng::from_seed(seed.into());
+    let labels: Vec<String> = (0..n).map(|i| i.to_string()).collect();
+
+    for _ in 0..CONFIGURATION_ATTEMPTS {
+        rng.shuffle(&mut stubs);
+        let edges: Vec<(usize, usize)> = stubs.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+        if simple {
+            let mut seen = vec![vec![false; n]; n];
+            let clash = edges.iter().any(|&(u, v)| {
+                let repeated = u == v || seen[u][v];
+                seen[u][v] = true;
+                seen[v][u] = true;
+                repeated
+            });
+            if clash {
+                continue;
+            }
+        }
+        return graph_from_labeled_edges(&labels, &edges);
+    }
+
+    Err(GraphError::InvalidDegreeSequence(format!(
+        "no simple pairing found in {} attempts",
+        CONFIGURATION_ATTEMPTS
+    )))
+}
+
+/// Builds a Watts–Strogatz small world: a ring lattice rewired at random
+///
+/// Each of the n vertices starts joined to its k nearest ring neighbors, k/2 on
+/// each side. Then every lattice edge (u, u+j) is rewired with probability p to
+/// (u, w), w drawn uniformly among the vertices not yet joined to u. The edge
+/// count stays n*k/2, and no self-loops or repeated edges appear.
+pub fn watts_strogatz(n: usize, k: usize, p: f64, seed: impl Into<Seed>) -> Result<Graph, GraphError> {
+    check_capacity(n)?;
+    if k % 2 == 1 {
+        return Err(invalid("k", format!("must be even, got {}", k)));
+    }
+    if k >= n {
+        return Err(invalid("k", format!("must be less than n = {}, got {}", n, k)));
+    }
+    if !(0.0..=1.0).contains(&p) {
+        return Err(invalid("p", format!("must be between 0 and 1, got {}", p)));
+    }
+
+    let mut edges: Vec<(usize, usize)> = (0..n).flat_map(|u| (1..=k / 2).map(move |j| (u, (u + j) % n))).collect();
+    let mut joined = vec![vec![false; n]; n];
+    for &(u, v) in &edges {
+        joined[u][v] = true;
+        joined[v][u] = true;
+    }
+
+    let mut rng = Rng::from_seed(seed.into());
+    for edge in edges.iter_mut() {
+        let (u, v) = *edge;
+        if rng.next_f64() >= p {
+            continue;
+        }
+        let free: Vec<usize> = (0..n).filter(|&w| w != u && !joined[u][w]).collect();
+        if free.is_empty() {
+            continue;
+        }
+        let w = free[rng.below(free.len())];
+        joined[u][v] = false;
+        joined[v][u] = false;
+        joined[u][w] = true;
+        joined[w][u] = true;
+        *edge = (u, w);
+    }
+    graph_from_edges(n, &edges)
+}
+
+/// Builds a Barabási–Albert graph by preferential attachment
+///
+/// The graph is seeded with a complete graph on m vertices; each later vertex is
+/// joined to m distinct earlier vertices, drawn with probability proportional to
+/// their degree. The result has C(m,2) + m*(n-m) edges.
+pub fn barabasi_albert(n: usize, m: usize, seed: impl Into<Seed>) -> Result<Graph, GraphError> {
+    check_capacity(n)?;
+    if m == 0 {
+        return Err(invalid("m", "must be at least 1".to_string()));
+    }
+    if m >= n {
+        return Err(invalid("m", format!("must be less than n = {}, got {}", n, m)));
+    }
+
+    let mut edges = Vec::new();
+    // Every vertex appears here once per incident edge, so a uniform draw is degree-weighted
+    let mut ends = Vec::new();
+    for u in 0..m {
+        for v in u + 1..m {
+            edges.push((u, v));
+            ends.push(u);
+            ends.push(v);
+        }
+    }
+
+    let mut rng = Rng::from_seed(seed.into());
+    for u in m..n {
+        let mut targets: Vec<usize> = Vec::with_capacity(m);
+        while targets.len() < m {
+            // Only the lone seed vertex of m = 1 has no edges to be drawn by
+            let v = if ends.is_empty() { rng.below(u) } else { ends[rng.below(ends.len())] };
+            if !targets.contains(&v) {
+                targets.push(v);
+            }
+        }
+        for v in targets {
+            edges.push((v, u));
+            ends.push(v);
+            ends.push(u);
+        }
+    }
+    graph_from_edges(n, &edges)
+}
+
+/// Builds the d-dimensional hypercube Q_d, for d of at least 1
+///
+/// The 2^d vertices are labeled by their d-bit binary strings, "000" to "111" for
+/// d = 3, and two vertices are joined when their labels differ in exactly one bit.
+pub fn hypercube(d: usize) -> Result<Graph, GraphError> {
+    if d == 0 {
+        return Err(invalid("d", "must be at least 1".to_string()));
+    }
+    if d >= usize::BITS as usize {
+        return Err(invalid("d", format!("2^{} vertices cannot be counted", d)));
+    }
+    let n = 1usize << d;
+    check_capacity(n)?;
+    let labels: Vec<String> = (0..n).map(|i| format!("{:0width$b}", i, width = d)).collect();
+    let edges: Vec<(usize, usize)> = (0..n)
+        .flat_map(|u| (0..d).map(move |bit| (u, u ^ (1 << bit))))
+        .filter(|&(u, v)| u < v)
+        .collect();
+    graph_from_labeled_edges(&labels, &edges)
+}
+
+/// Builds the wheel with n rim vertices, for n of at least 3
+///
+/// The hub is "v0" and the rim is the cycle "v1" - "v2" - ... - "v{n}" - "v1";
+/// the hub is joined to every rim vertex, so it has degree n and the rim vertices 3.
+pub fn wheel(n: usize) -> Result<Graph, GraphError> {
+    if n < 3 {
+        return Err(invalid("n", format!("must be at least 3, got {}", n)));
+    }
+    check_capacity(n + 1)?;
+    let spokes = (1..=n).map(|v| (0, v));
+    let rim = (1..=n).map(|v| (v, v % n + 1));
+    let edges: Vec<(usize, usize)> = spokes.chain(rim).collect();
+    graph_from_edges(n + 1, &edges)
+}
+
+/// Builds the Petersen graph on "v0".."v9"
+///
+/// "v0".."v4" form the outer 5-cycle, "v5".."v9" the inner pentagram v5-v7-v9-v6-v8-v5,
+/// and each "vi" of the outer cycle is joined to "v{i+5}". It is 3-regular with girth 5.
+pub fn petersen() -> Result<Graph, GraphError> {
+    let outer = (0..5).map(|i| (i, (i + 1) % 5));
+    let inner = (0..5).map(|i| (5 + i, 5 + (i + 2) % 5));
+    let spokes = (0..5).map(|i| (i, i + 5));
+    let edges: Vec<(usize, usize)> = outer.chain(inner).chain(spokes).collect();
+    graph_from_edges(10, &edges)
+}
+
+/// Builds the complete bipartite graph K_{a,b}, for a and b of at least 1
+///
+/// One side is labeled "a0".."a{a-1}", the other "b0".."b{b-1}", and every vertex of
+/// one side is joined to every vertex of the other.
+pub fn complete_bipartite(a: usize, b: usize) -> Result<Graph, GraphError> {
+    if a == 0 || b == 0 {
+        return Err(invalid("a, b", format!("both sides need a vertex, got {} and {}", a, b)));
+    }
+    check_capacity(a + b)?;
+    let labels: Vec<String> = (0..a)
+        .map(|i| format!("a{}", i))
+        .chain((0..b).map(|j| format!("b{}", j)))
+        .collect();
+    let edges: Vec<(usize, usize)> = (0..a).flat_map(|u| (0..b).map(move |v| (u, a + v))).collect();
+    graph_from_labeled_edges(&labels, &edges)
+}
+
+/// Builds the complete binary tree of the given depth; depth 0 is a single vertex
+///
+/// Vertices are labeled in heap order: the root is "v0" and the children of "vi"
+/// are "v{2i+1}" and "v{2i+2}", giving 2^(depth+1) - 1 vertices.
+pub fn binary_tree(depth: usize) -> Result<Graph, GraphError> {
+    if depth >= usize::BITS as usize - 1 {
+        return Err(invalid("depth", format!("2^({} + 1) vertices cannot be counted", depth)));
+    }
+    let n = (1usize << (depth + 1)) - 1;
+    check_capacity(n)?;
+    let edges: Vec<(usize, usize)> = (1..n).map(|v| ((v - 1) / 2, v)).collect();
+    graph_from_edges(n, &edges)
+}
+
+/// Builds a graph of a registered family from its integer parameters
+pub type GeneratorFn = fn(&[usize]) -> Result<Graph, GraphError>;
+
+/// A generator family known to by_name
+#[derive(Debug, Clone, Copy)]
+pub struct NamedGenerator {
+    pub name: &'static str,
+    /// Names of the parameters, in the order by_name takes them
+    pub params: &'static [&'static str],
+    pub build: GeneratorFn,
+}
+
+/// The families registered before any call to register
+const BUILTIN_GENERATORS: [NamedGenerator; 6] = [
+    NamedGenerator { name: "hypercube", params: &["d"], build: |p| hypercube(p[0]) },
+    NamedGenerator { name: "wheel", params: &["n"], build: |p| wheel(p[0]) },
+    NamedGenerator { name: "petersen", params: &[], build: |_| petersen() },
+    NamedGenerator { name: "complete-bipartite", params: &["a", "b"], build: |p| complete_bipartite(p[0], p[1]) },
+    NamedGenerator { name: "binary-tree", params: &["depth"], build: |p| binary_tree(p[0]) },
+    NamedGenerator { name: "barabasi-albert", params: &["n", "m", "seed"], build: |p| barabasi_albert(p[0], p[1], p[2] as u64) },
+];
+
+fn registry() -> &'static RwLock<Vec<NamedGenerator>> {
+    static REGISTRY: OnceLock<RwLock<Vec<NamedGenerator>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(BUILTIN_GENERATORS.to_vec()))
+}
+
+/// Adds a family to the registry, replacing any family of the same name
+///
+/// by_name checks the number of parameters against params before calling build,
+/// so build may index its slice directly.
+pub fn register(name: &'static str, params: &'static [&'static str], build: GeneratorFn) {
+    let mut generators = registry().write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    generators.retain(|generator| generator.name != name);
+    generators.push(NamedGenerator { name, params, build });
+}
+
+/// Returns every registered family, sorted by name
+pub fn registered() -> Vec<NamedGenerator> {
+    let mut generators = registry().read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    generators.sort_by_key(|generator| generator.name);
+    generators
+}
+
+/// Builds a graph of the family registered under name from its integer parameters
+pub fn by_name(name: &str, params: &[usize]) -> Result<Graph, GraphError> {
+    let generators = registered();
+    let Some(generator) = generators.iter().find(|generator| generator.name == name) else {
+        let known: Vec<&str> = generators.iter().map(|generator| generator.name).collect();
+        return Err(invalid("name", format!("unknown generator {}; known: {}", name, known.join(", "))));
+    };
+    if params.len() != generator.params.len() {
+        return Err(invalid(
+            "params",
+            format!(
+                "{} expects {} parameter(s) ({}), got {}",
+                name,
+                generator.params.len(),
+                generator.params.join(" "),
+                params.len()
+            ),
+        ));
+    }
+    (generator.build)(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn degrees(graph: &Graph) -> Vec<i32> {
+        graph.adj_count[..graph.n_vertices].to_vec()
+    }
+
+    fn edge_list(graph: &Graph) -> Vec<(String, String)> {
+        graph
+            .ordered_edges()
+            .iter()
+            .map(|edge| (edge.source.to_string(), edge.target.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn erdos_gallai() {
+        assert!(is_graphical(&[3, 3, 3, 3]));
+        assert!(is_graphical(&[]));
+        assert!(!is_graphical(&[3, 1]));
+        assert!(!is_graphical(&[1, 1, 1]));
+        assert!(!is_graphical(&[3, 3, 1, 1]));
+    }
+
+    #[test]
+    fn configuration_model_labels_from_zero() {
+        let graph = configuration_model(&[2, 2, 2, 2], 5, true).unwrap();
+        assert_eq!(graph.vertices[..4], ["0", "1", "2", "3"].map(String::from));
+        assert_eq!(degrees(&graph), vec![2, 2, 2, 2]);
+        graph.check_invariants().unwrap();
+        assert!(configuration_model(&[3, 1], 5, true).is_err());
+    }
+
+    #[test]
+    fn seeded_generators_repeat() {
+        let a = watts_strogatz(12, 4, 0.3, 9).unwrap();
+        let b = watts_strogatz(12, 4, 0.3, 9).unwrap();
+        assert_eq!(edge_list(&a), edge_list(&b));
+        assert_eq!(a.stats().n_edges, 24);
+        let a = barabasi_albert(15, 3, 4).unwrap();
+        assert_eq!(edge_list(&a), edge_list(&barabasi_albert(15, 3, 4).unwrap()));
+        assert_eq!(a.stats().n_edges, 3 + 3 * 12);
+        a.check_invariants().unwrap();
+    }
+
+    #[test]
+    fn watts_strogatz_without_rewiring_is_the_ring_lattice() {
+        let graph = watts_strogatz(8, 2, 0.0, 1).unwrap();
+        assert_eq!(degrees(&graph), vec![2; 8]);
+        assert_eq!(graph.girth(), Some(8));
+    }
+
+    #[test]
+    fn hypercube_is_bipartite_with_binary_labels() {
+        let cube = hypercube(3).unwrap();
+        assert_eq!(cube.stats().n_edges, 12);
+        assert_eq!(cube.vertices[0], "000");
+        assert_eq!(cube.vertices[7], "111");
+        assert!(cube.is_bipartite().is_some());
+        assert!(hypercube(0).is_err());
+    }
+
+    #[test]
+    fn wheel_has_its_hub() {
+        let graph = wheel(5).unwrap();
+        assert_eq!(degrees(&graph), vec![5, 3, 3, 3, 3, 3]);
+        assert!(wheel(2).is_err());
+    }
+
+    #[test]
+    fn petersen_is_cubic_with_girth_five() {
+        let graph = petersen().unwrap();
+        assert_eq!(degrees(&graph), vec![3; 10]);
+        assert_eq!(graph.girth(), Some(5));
+    }
+
+    #[test]
+    fn complete_bipartite_and_binary_tree() {
+        let graph = complete_bipartite(2, 3).unwrap();
+        assert_eq!(graph.stats().n_edges, 6);
+        assert_eq!(graph.vertices[..5], ["a0", "a1", "b0", "b1", "b2"].map(String::from));
+        let tree = binary_tree(2).unwrap();
+        assert_eq!((tree.n_vertices, tree.stats().n_edges), (7, 6));
+        assert_eq!(tree.girth(), None);
+        assert!(binary_tree(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn by_name_checks_name_and_parameters() {
+        assert_eq!(by_name("wheel", &[4]).unwrap().n_vertices, 5);
+        assert_eq!(by_name("petersen", &[]).unwrap().n_vertices, 10);
+        let error = by_name("lattice", &[3]).unwrap_err();
+        assert!(error.to_string().contains("known: barabasi-albert"), "{}", error);
+        assert!(by_name("wheel", &[4, 1]).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+// src/gomory_hu.rs - Gomory-Hu cut trees: every pairwise minimum cut from n-1 max flows
+//
+// Edges are treated as undirected, with the weight of an edge as its capacity
+// (1 when no weight was set). The tree is built with Gusfield's algorithm on the
+// Edmonds-Karp machinery of flow.rs and cached until the graph changes.
+use crate::error::GraphError;
+use crate::flow::FlowNetwork;
+use crate::Graph;
+use std::fs::File;
+use std::io::Write;
+
+/// A cut tree in parent form: vertex i hangs below parent[i] with the cut value cut[i];
+/// vertex 0 is the root and its own parent
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CutTree {
+    pub(crate) parent: Vec<usize>,
+    pub(crate) cut: Vec<f64>,
+}
+
+impl Graph {
+    /// Builds the flow network of the undirected graph, one arc each way per edge
+    fn cut_network(&self) -> FlowNetwork {
+        let n = self.n_vertices;
+        let mut network = FlowNetwork::new(n);
+        for i in 0..n {
+            for j in i + 1..n {
+                let capacity = if self.adj_matrix[i][j] == 1 {
+                    self.weight_at(i, j)
+                } else if self.adj_matrix[j][i] == 1 {
+                    self.weight_at(j, i)
+                } else {
+                    continue;
+                };
+                network.add_capacity(i, j, capacity);
+                network.add_capacity(j, i, capacity);
+            }
+        }
+        network
+    }
+
+    /// Runs Gusfield's algorithm, without the cache
+    pub(crate) fn compute_cut_tree(&self) -> Result<CutTree, GraphError> {
+        self.validate_weights(|w| w.is_finite() && w >= 0.0, "cut capacities must be finite and nonnegative")?;
+        let n = self.n_vertices;
+        let mut parent = vec![0; n];
+        let mut cut = vec![0.0; n];
+
+        for s in 1..n {
+            let t = parent[s];
+            let mut network = self.cut_network();
+            let value = network.max_flow(s, t);
+            let side = network.source_side(s);
+            cut[s] = value;
+            for i in 0..n {
+                if i != s && side[i] && parent[i] == t {
+                    parent[i] = s;
+                }
+            }
+            // Keep the tree a true cut tree when s ends up on the side of t's parent
+            if side[parent[t]] {
+                parent[s] = parent[t];
+                parent[t] = s;
+                cut[s] = cut[t];
+                cut[t] = value;
+            }
+        }
+        Ok(CutTree { parent, cut })
+    }
+
+    /// Builds the Gomory-Hu tree of the graph
+    ///
+    /// The tree has the vertices of the graph and n-1 edges weighted by cut values:
+    /// the minimum cut between any two vertices equals the smallest weight on the
+    /// tree path between them, and removing that edge splits the vertices into the
+    /// two sides of such a cut. Vertices of different components are joined by
+    /// edges of weight 0. Fails on negative or non-finite weights.
+    pub fn gomory_hu(&self) -> Result<Graph, GraphError> {
+        let tree = self.cached_cut_tree()?;
+        let n = self.n_vertices;
+        let mut rows: Vec<(String, Vec<String>)> =
+            self.vertices[..n].iter().map(|label| (label.clone(), Vec::new())).collect();
+        for v in 1..n {
+            let u = tree.parent[v];
+            let (label_u, label_v) = (self.vertices[u].clone(), self.vertices[v].clone());
+            rows[u].1.push(label_v);
+            rows[v].1.push(label_u);
+        }
+        let mut result = Graph::from_rows(&rows)?;
+        for v in 1..n {
+            let u = tree.parent[v];
+            result.store_weight(u, v, tree.cut[v]);
+            result.store_weight(v, u, tree.cut[v]);
+        }
+        result.name = self.name.clone();
+        Ok(result)
+    }
+
+    /// Returns the value of a minimum cut between two distinct vertices, read from the cached Gomory-Hu tree
+    pub fn min_cut_between(&self, a: &str, b: &str) -> Result<f64, GraphError> {
+        let a_idx = self.require_vertex_idx(a)?;
+        let b_idx = self.require_vertex_idx(b)?;
+        if a_idx == b_idx {
+            return Err(GraphError::SameVertex(a.to_string()));
+        }
+        let tree = self.cached_cut_tree()?;
+
+        // The smallest weight on the tree path, climbing from both ends to their meeting point
+        let depth = |mut v: usize| {
+            let mut d = 0;
+            while v != 0 {
+                v = tree.parent[v];
+                d += 1;
+            }
+            d
+        };
+        let (mut u, mut v) = (a_idx, b_idx);
+        let (mut depth_u, mut depth_v) = (depth(u), depth(v));
+        let mut best = f64::INFINITY;
+        while u != v {
+            if depth_u >= depth_v {
+                best = best.min(tree.cut[u]);
+                u = tree.parent[u];
+                depth_u -= 1;
+            } else {
+                best = best.min(tree.cut[v]);
+                v = tree.parent[v];
+                depth_v -= 1;
+            }
+        }
+        Ok(best)
+    }
+
+    /// To create/write an output file that lists the edges of the Gomory-Hu tree
+    /// Lines read `a b cut`, one per tree edge in the canonical edge order
+    pub fn produce_output_file_gomory_hu(&self, base_name: &str) -> Result<(), GraphError> {
+        let tree = self.gomory_hu()?;
+        let output_name = Self::get_output_filename(base_name, "-GOMORYHU");
+        let mut fp = File::create(output_name)?;
+
+        for edge in tree.ordered_edges() {
+            writeln!(fp, "{} {} {}", edge.source, edge.target, edge.weight.unwrap_or(0.0))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,343 @@
+// src/graph6.rs - Reading graphs in nauty's graph6 format and streaming batch analysis
+//
+// Batches of small graphs often repeat the same graph many times. The streaming
+// analysis remembers the metric fields of recent graphs by their graph6 string,
+// which is the same for every copy of a graph, and only writes the row again.
+use crate::dialect::{Encoding, LineDecoder};
+use crate::error::GraphError;
+use crate::Graph;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Write};
+
+/// Optional header line some graph6 files start with
+const GRAPH6_HEADER: &str = ">>graph6<<";
+
+/// Graphs whose metric fields the streaming analysis remembers by default
+pub const DEFAULT_STREAM_CACHE_ENTRIES: usize = 4096;
+
+/// A per-graph number computed by the streaming analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamMetric {
+    Order,
+    Size,
+    Connected,
+    Girth,
+    MinDegree,
+    MaxDegree,
+    MeanDegree,
+}
+
+impl StreamMetric {
+    /// Every metric, in the column order used by default
+    pub const ALL: [StreamMetric; 7] = [
+        StreamMetric::Order,
+        StreamMetric::Size,
+        StreamMetric::Connected,
+        StreamMetric::Girth,
+        StreamMetric::MinDegree,
+        StreamMetric::MaxDegree,
+        StreamMetric::MeanDegree,
+    ];
+
+    /// Returns the CSV column name, which is also the name accepted by from_name
+    pub fn name(&self) -> &'static str {
+        match self {
+            StreamMetric::Order => "order",
+            StreamMetric::Size => "size",
+            StreamMetric::Connected => "connected",
+            StreamMetric::Girth => "girth",
+            StreamMetric::MinDegree => "min-degree",
+            StreamMetric::MaxDegree => "max-degree",
+            StreamMetric::MeanDegree => "mean-degree",
+        }
+    }
+
+    /// Parses a metric from its column name
+    pub fn from_name(name: &str) -> Option<StreamMetric> {
+        StreamMetric::ALL.into_iter().find(|metric| metric.name() == name)
+    }
+
+    /// Computes the metric for one graph and formats it as a CSV field
+    /// An acyclic graph has an empty girth field
+    fn evaluate(&self, graph: &Graph) -> String {
+        let degrees: Vec<usize> = graph.stats_degrees().collect();
+        match self {
+            StreamMetric::Order => graph.n_vertices.to_string(),
+            StreamMetric::Size => graph.edge_count().to_string(),
+            StreamMetric::Connected => (graph.connected_components().len() <= 1).to_string(),
+            StreamMetric::Girth => graph.girth().map(|g| g.to_string()).unwrap_or_default(),
+            StreamMetric::MinDegree => degrees.iter().min().copied().unwrap_or(0).to_string(),
+            StreamMetric::MaxDegree => degrees.iter().max().copied().unwrap_or(0).to_string(),
+            StreamMetric::MeanDegree => {
+                let mean = if degrees.is_empty() {
+                    0.0
+                } else {
+                    degrees.iter().sum::<usize>() as f64 / degrees.len() as f64
+                };
+                format!("{:.3}", mean)
+            }
+        }
+    }
+}
+
+/// What the streaming analysis computes and where it writes the rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamConfig {
+    /// Columns written after the line number and graph6 string, in order
+    pub metrics: Vec<StreamMetric>,
+    /// Path of the CSV file to create
+    pub output: String,
+    /// Most graphs whose fields are remembered, the least recently used going
+    /// first; 0 computes every graph afresh
+    pub cache_entries: usize,
+    /// How invalid UTF-8 is read; under Lossy such a line is a malformed graph
+    pub encoding: Encoding,
+}
+
+impl StreamConfig {
+    /// Computes every metric and writes the rows to output
+    pub fn new(output: &str) -> Self {
+        StreamConfig {
+            metrics: StreamMetric::ALL.to_vec(),
+            output: output.to_string(),
+            cache_entries: DEFAULT_STREAM_CACHE_ENTRIES,
+            encoding: Encoding::Strict,
+        }
+    }
+}
+
+/// Outcome of a streaming analysis
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct StreamSummary {
+    /// Number of graphs analyzed, i.e. CSV rows written
+    pub graphs: usize,
+    /// Line number and reason of every line that was skipped as malformed
+    pub malformed: Vec<(usize, String)>,
+    /// Graphs whose fields were taken from the cache
+    pub cache_hits: usize,
+    /// Graphs decoded and computed, whether or not they then entered the cache
+    pub cache_misses: usize,
+}
+
+/// The metric fields of recently seen graphs, by graph6 string, dropping the least
+/// recently used beyond capacity
+struct FieldCache {
+    capacity: usize,
+    /// Fields and the tick of their last use
+    entries: HashMap<String, (u64, Vec<String>)>,
+    /// Key of every entry by the tick of its last use, the oldest first
+    by_use: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl FieldCache {
+    fn new(capacity: usize) -> Self {
+        FieldCache {
+            capacity,
+            entries: HashMap::new(),
+            by_use: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Returns the fields of a graph and marks them as just used
+    fn get(&mut self, code: &str) -> Option<&[String]> {
+        self.tick += 1;
+        let (used, fields) = self.entries.get_mut(code)?;
+        let key = self.by_use.remove(used).unwrap_or_default();
+        *used = self.tick;
+        self.by_use.insert(self.tick, key);
+        Some(fields)
+    }
+
+    /// Stores the fields of a graph, first dropping the least recently used when full
+    fn insert(&mut self, code: &str, fields: Vec<String>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = self.by_use.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.tick += 1;
+        self.by_use.insert(self.tick, code.to_string());
+        self.entries.insert(code.to_string(), (self.tick, fields));
+    }
+}
+
+/// Decodes the vertex count at the start of a graph6 string and returns it with the rest
+fn decode_order(bytes: &[u8]) -> Result<(usize, &[u8]), String> {
+    match bytes {
+        [] => Err("empty line".to_string()),
+        [126, 126, rest @ ..] => decode_big_order(rest, 6).map(|n| (n, &rest[6..])),
+        [126, rest @ ..] => decode_big_order(rest, 3).map(|n| (n, &rest[3..])),
+        [first, rest @ ..] if (63..126).contains(first) => Ok(((first - 63) as usize, rest)),
+        [first, ..] => Err(format!("unexpected character {:?}", *first as char)),
+    }
+}
+
+/// Reads a vertex count stored in `width` six-bit groups
+fn decode_big_order(bytes: &[u8], width: usize) -> Result<usize, String> {
+    if bytes.len() < width {
+        return Err("vertex count is cut short".to_string());
+    }
+    bytes[..width].iter().try_fold(0usize, |n, &b| {
+        if (63..=126).contains(&b) {
+            Ok((n << 6) | (b - 63) as usize)
+        } else {
+            Err(format!("unexpected character {:?}", b as char))
+        }
+    })
+}
+
+impl Graph {
+    /// Builds a graph from one graph6 string, naming the vertices 0, 1, 2, ...
+    ///
+    /// The upper triangle of the adjacency matrix is stored column by column,
+    /// six bits per printable character.
+    pub fn from_graph6(line: &str) -> Result<Graph, GraphError> {
+        let invalid = |reason: String| GraphError::InvalidFormat {
+            format: "graph6",
+            reason,
+        };
+        let line = line.trim();
+        let line = line.strip_prefix(GRAPH6_HEADER).unwrap_or(line);
+
+        let (n, body) = decode_order(line.as_bytes()).map_err(invalid)?;
+        Graph::check_vertex_limit(n)?;
+
+        let n_bits = n * n.saturating_sub(1) / 2;
+        let expected = n_bits / 6 + usize::from(n_bits % 6 != 0);
+        if body.len() != expected {
+            return Err(invalid(format!(
+                "expected {} characters of edge data for {} vertices, found {}",
+                expected,
+                n,
+                body.len()
+            )));
+        }
+        if let Some(&b) = body.iter().find(|b| !(63..=126).contains(*b)) {
+            return Err(invalid(format!("unexpected character {:?}", b as char)));
+        }
+        let bit = |k: usize| (body[k / 6] - 63) >> (5 - k % 6) & 1 == 1;
+
+        let mut graph = Graph::new();
+        graph.reserve_vertices(n);
+        graph.init_rep();
+        graph.n_vertices = n;
+        graph.vertices = (0..n).map(|i| i.to_string()).collect();
+
+        let mut k = 0;
+        for j in 1..n {
+            for i in 0..j {
+                if bit(k) {
+                    let (label_i, label_j) = (graph.vertices[i].clone(), graph.vertices[j].clone());
+                    graph.add_to_adj_list(i, &label_j);
+                    graph.add_to_adj_list(j, &label_i);
+                }
+                k += 1;
+            }
+        }
+        graph.make_adj_matrix();
+
+        Ok(graph)
+    }
+}
+
+/// Analyzes one graph6 graph per line, writing a CSV row of the configured metrics
+/// for each and keeping only the current graph in memory
+///
+/// Blank lines and a `>>graph6<<` header are ignored. Lines that cannot be decoded
+/// are skipped and listed in the summary; only I/O failures abort the stream. A
+/// graph still among the cfg.cache_entries most recently used ones is not computed
+/// again, its row repeating the remembered fields.
+pub fn analyze_graph6_stream<R: BufRead>(
+    mut reader: R,
+    cfg: &StreamConfig,
+) -> Result<StreamSummary, GraphError> {
+    let mut out = BufWriter::new(File::create(&cfg.output)?);
+    let mut summary = StreamSummary::default();
+    let mut cache = FieldCache::new(cfg.cache_entries);
+
+    let header: Vec<&str> = cfg.metrics.iter().map(StreamMetric::name).collect();
+    writeln!(out, "line,graph6,{}", header.join(","))?;
+
+    let mut decoder = LineDecoder::new(cfg.encoding);
+    let mut raw = Vec::new();
+    for i in 0.. {
+        raw.clear();
+        if reader.read_until(b'\n', &mut raw)? == 0 {
+            break;
+        }
+        let line = decoder.decode(&raw, i + 1)?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed == GRAPH6_HEADER {
+            continue;
+        }
+
+        let code = trimmed.strip_prefix(GRAPH6_HEADER).unwrap_or(trimmed);
+        if let Some(fields) = cache.get(code) {
+            writeln!(out, "{},{},{}", i + 1, code, fields.join(","))?;
+            summary.graphs += 1;
+            summary.cache_hits += 1;
+            continue;
+        }
+        match Graph::from_graph6(trimmed) {
+            Ok(graph) => {
+                let fields: Vec<String> = cfg.metrics.iter().map(|m| m.evaluate(&graph)).collect();
+                writeln!(out, "{},{},{}", i + 1, code, fields.join(","))?;
+                summary.graphs += 1;
+                summary.cache_misses += 1;
+                cache.insert(code, fields);
+            }
+            Err(e) => summary.malformed.push((i + 1, e.to_string())),
+        }
+    }
+
+    out.flush()?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::scratch_dir;
+    use std::fs;
+
+    /// Triangle, path, triangle, path, K4 and triangle again, with a blank line
+    const REPEATS: &str = ">>graph6<<Bw\nBg\nBw\n\nBg\nC~\nBw\n";
+
+    /// Runs the stream with a cache of the given size and returns the summary and the CSV
+    fn stream(cache_entries: usize, test: &str) -> (StreamSummary, String) {
+        let dir = scratch_dir("graph6", test);
+        let output = dir.join("out.csv").display().to_string();
+        let cfg = StreamConfig {
+            cache_entries,
+            ..StreamConfig::new(&output)
+        };
+        let summary = analyze_graph6_stream(REPEATS.as_bytes(), &cfg).unwrap();
+        let csv = fs::read_to_string(&output).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+        (summary, csv)
+    }
+
+    #[test]
+    fn repeated_graphs_hit_the_cache_with_the_same_rows() {
+        let (uncached, csv) = stream(0, "uncached");
+        assert_eq!((uncached.graphs, uncached.cache_hits, uncached.cache_misses), (6, 0, 6));
+        assert!(uncached.malformed.is_empty());
+        assert_eq!(csv.lines().count(), 7);
+        assert!(csv.lines().nth(1).unwrap().starts_with("1,Bw,"));
+
+        // Two entries: the triangle has left the cache by the time it comes back after K4
+        let (small, small_csv) = stream(2, "small");
+        assert_eq!((small.graphs, small.cache_hits, small.cache_misses), (6, 2, 4));
+        assert_eq!(small_csv, csv);
+
+        let (default, default_csv) = stream(DEFAULT_STREAM_CACHE_ENTRIES, "default");
+        assert_eq!((default.graphs, default.cache_hits, default.cache_misses), (6, 3, 3));
+        assert_eq!(default_csv, csv);
+    }
+}
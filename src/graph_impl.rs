@@ -1,8 +1,19 @@
 // src/graph_impl.rs - Core graph implementation (equivalent to graph.c)
-use crate::{Graph, AdjNode, MAX_VERTICES};
-use std::collections::VecDeque;
+use crate::{Graph, AdjNode, MATRIX_VERTEX_LIMIT};
+use crate::algo;
+use crate::dialect::{read_file_lines, Dialect, Encoding, ParseWarning};
+use crate::direction::Direction;
+use crate::error::GraphError;
+use crate::isomorphism::DEFAULT_AUTOMORPHISM_BUDGET;
+use crate::output::{write_section, ItemTotal, OutputConfig};
+use crate::profile::DialectProfile;
+use crate::vertex_ref::VertexRef;
+use crate::weights::{split_neighbor_token, MatrixStyle};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
+use std::io::Write;
+use std::sync::Arc;
 
 impl Graph {
     /// Gets the name of the file without extension from user input and copy into base_name
@@ -14,28 +25,68 @@ impl Graph {
         }
     }
 
+    /// Grows the matrix, list and counts so they hold at least n vertices
+    /// New cells start at 0, new lists empty; existing entries are kept
+    pub(crate) fn reserve_vertices(&mut self, n: usize) {
+        let capacity = self.adj_list.len();
+        if n <= capacity {
+            return;
+        }
+        // Growing at least twofold keeps adding vertices one at a time cheap
+        let new_capacity = n.max(capacity * 2);
+        for row in self.adj_matrix.iter_mut() {
+            row.resize(new_capacity, 0);
+        }
+        self.adj_matrix.resize(new_capacity, vec![0; new_capacity]);
+        self.adj_list.resize(new_capacity, None);
+        self.adj_count.resize(new_capacity, 0);
+    }
+
+    /// Checks that n vertices stay within MATRIX_VERTEX_LIMIT
+    pub(crate) fn check_vertex_limit(n: usize) -> Result<(), GraphError> {
+        if n > MATRIX_VERTEX_LIMIT {
+            return Err(GraphError::CapacityExceeded {
+                requested: n,
+                limit: MATRIX_VERTEX_LIMIT,
+            });
+        }
+        Ok(())
+    }
+
     /// Initialize the adjacency representations of the graph data structure
     pub fn init_rep(&mut self) {
-        for i in 0..MAX_VERTICES {
-            self.adj_count[i] = 0;
-            for j in 0..MAX_VERTICES {
-                self.adj_matrix[i][j] = 0;
-            }
+        for row in self.adj_matrix.iter_mut() {
+            row.fill(0);
         }
-        
-        for i in 0..MAX_VERTICES {
-            self.adj_list[i] = None;
+        self.adj_count.fill(0);
+        self.adj_list.fill(None);
+
+        self.coordinates.clear();
+        self.label_pool.clear();
+        self.edge_registry.clear();
+        self.invalidate_cache();
+    }
+
+    /// Returns the shared copy of a label, storing it the first time it is seen
+    pub(crate) fn intern_label(&mut self, label: &str) -> Arc<str> {
+        if let Some(shared) = self.label_pool.get(label) {
+            return Arc::clone(shared);
         }
+        let shared: Arc<str> = Arc::from(label);
+        self.label_pool.insert(Arc::clone(&shared));
+        shared
     }
 
     /// Creates a new node and set vertex as label of new node
-    pub fn create_node(vertex: String) -> Box<AdjNode> {
+    pub fn create_node(vertex: Arc<str>) -> Box<AdjNode> {
         Box::new(AdjNode::new(vertex))
     }
 
     /// Adds a new node into the adjacency list representation
-    pub fn add_to_adj_list(&mut self, vertex_idx: usize, adj_vertex: String) {
-        let new_node = Self::create_node(adj_vertex);
+    /// Nodes naming the same neighbor share one copy of its label
+    pub fn add_to_adj_list(&mut self, vertex_idx: usize, adj_vertex: &str) {
+        self.reserve_vertices(vertex_idx + 1);
+        let new_node = Self::create_node(self.intern_label(adj_vertex));
         
         if self.adj_list[vertex_idx].is_none() {
             self.adj_list[vertex_idx] = Some(new_node);
@@ -51,14 +102,14 @@ impl Graph {
         }
         
         self.adj_count[vertex_idx] += 1;
+        self.invalidate_cache();
     }
 
     /// Removes every node in the adjacency list
     pub fn free_adj_list(&mut self) {
-        for i in 0..MAX_VERTICES {
-            self.adj_list[i] = None;
-            self.adj_count[i] = 0;
-        }
+        self.adj_list.fill(None);
+        self.adj_count.fill(0);
+        self.invalidate_cache();
     }
 
     /// Searches through the known vertex labels in the graph and return the index of the target label
@@ -74,10 +125,16 @@ impl Graph {
     /// Creates the adjacency matrix representation of a graph
     pub fn make_adj_matrix(&mut self) {
         // Initialize all values in adjacency matrix to 0
-        for i in 0..MAX_VERTICES {
-            for j in 0..MAX_VERTICES {
-                self.adj_matrix[i][j] = 0;
-            }
+        self.reserve_vertices(self.n_vertices);
+        for row in self.adj_matrix.iter_mut() {
+            row.fill(0);
+        }
+
+        // Index of every label, the first vertex winning as in find_vertex_idx,
+        // so large graphs do not scan the vertices once per neighbor
+        let mut index: HashMap<&str, usize> = HashMap::with_capacity(self.vertices.len());
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            index.entry(vertex.as_str()).or_insert(i);
         }
 
         // Loop through all vertices
@@ -86,7 +143,7 @@ impl Graph {
             // Loop through all neighbors of a vertex
             while let Some(ref node) = current {
                 // Find index of vertex's neighbor on adjacency list
-                if let Some(adj_idx) = self.find_vertex_idx(&node.vertex) {
+                if let Some(&adj_idx) = index.get(&*node.vertex) {
                     // If index was found (edge exists between both vertex and neighbor),
                     // position on matrix is set to 1
                     self.adj_matrix[i][adj_idx] = 1;
@@ -95,26 +152,141 @@ impl Graph {
                 current = &node.next;
             }
         }
+
+        self.sync_edge_ids();
+        self.invalidate_cache();
+        self.debug_check_representations();
     }
 
     /// Reads information from input file and add to the graph data structure
-    pub fn read_input_file(&mut self, str_input_filename: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let file = File::open(str_input_filename)?;
-        let mut reader = BufReader::new(file);
-        let mut line = String::new();
+    pub fn read_input_file(&mut self, str_input_filename: &str) -> Result<(), GraphError> {
+        self.read_input_file_with(str_input_filename, Dialect::Strict)?;
+        Ok(())
+    }
+
+    /// Reads an input file under the given dialect and returns the warnings raised while parsing
+    /// Invalid UTF-8 is a GraphError::InvalidEncoding; see read_input_file_with_encoding
+    pub fn read_input_file_with(
+        &mut self,
+        str_input_filename: &str,
+        dialect: Dialect,
+    ) -> Result<Vec<ParseWarning>, GraphError> {
+        self.read_input_file_with_encoding(str_input_filename, dialect, Encoding::Strict)
+    }
+
+    /// Reads an input file under the given dialect, decoding its bytes as the encoding says
+    /// With Encoding::Lossy, a warning lists the lines and labels where invalid UTF-8 was replaced
+    pub fn read_input_file_with_encoding(
+        &mut self,
+        str_input_filename: &str,
+        dialect: Dialect,
+        encoding: Encoding,
+    ) -> Result<Vec<ParseWarning>, GraphError> {
+        let (lines, encoding_warning) = read_file_lines(str_input_filename, encoding)?;
+        let mut warnings: Vec<ParseWarning> = encoding_warning.into_iter().collect();
+        warnings.extend(self.read_input_lines(&lines, dialect)?);
+        if self.name.is_none() {
+            self.name = Some(Graph::get_base_filename(str_input_filename));
+        }
+        Ok(warnings)
+    }
+
+    /// Names the graph in the outputs, in place of the name of its file
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = Some(name.into());
+    }
+
+    /// Returns the name of the graph, or fallback when it has none
+    pub fn display_name<'a>(&'a self, fallback: &'a str) -> &'a str {
+        self.name.as_deref().unwrap_or(fallback)
+    }
+
+    /// Reads a graph from text in the input file format, under the given dialect
+    pub fn read_input_text_with(
+        &mut self,
+        text: &str,
+        dialect: Dialect,
+    ) -> Result<Vec<ParseWarning>, GraphError> {
+        let lines: Vec<String> = text.lines().map(str::to_string).collect();
+        self.read_input_lines(&lines, dialect)
+    }
+
+    /// Builds the graph from the lines of an input file
+    pub(crate) fn read_input_lines(
+        &mut self,
+        lines: &[String],
+        dialect: Dialect,
+    ) -> Result<Vec<ParseWarning>, GraphError> {
+        let mut warnings = Vec::new();
+
+        // Read number of vertices, or decide that the count line is missing
+        let first_line = lines.first().map(String::as_str).unwrap_or("");
+        let first_tokens: Vec<&str> = first_line.split_whitespace().collect();
+        let count = match first_tokens.as_slice() {
+            [token] => token.parse::<usize>().ok(),
+            _ => None,
+        };
+        if count.is_none() && dialect == Dialect::Strict {
+            return Err(GraphError::Parse {
+                line: 1,
+                reason: format!("expected the number of vertices, found \"{}\"", first_line.trim()),
+            });
+        }
+
+        let adjacency_lines: Vec<&str> = match count {
+            Some(n) => {
+                // Every vertex needs its line, so the count cannot claim more than the file
+                // holds; checked before anything is sized by it
+                let available = lines.len().saturating_sub(1);
+                if n > available {
+                    return Err(GraphError::Parse {
+                        line: 1,
+                        reason: format!("the count is {} but only {} adjacency lines follow", n, available),
+                    });
+                }
+                if let Some(i) = (1..=n).find(|&i| lines[i].trim().is_empty()) {
+                    return Err(GraphError::Parse {
+                        line: i + 1,
+                        reason: format!("expected the adjacency line of vertex {}, found an empty line", i),
+                    });
+                }
+                self.n_vertices = n;
+                lines[1..=n].iter().map(String::as_str).collect()
+            }
+            None => {
+                // A numeric first label may really be a count with stray tokens after it
+                if let Some(token) = first_tokens.first() {
+                    if token.parse::<usize>().is_ok() {
+                        warnings.push(ParseWarning::NumericFirstLabel(token.to_string()));
+                    }
+                }
+                let non_empty: Vec<&str> = lines
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|line| !line.trim().is_empty())
+                    .collect();
+                self.n_vertices = non_empty.len();
+                warnings.push(ParseWarning::CountInferred(self.n_vertices));
+                non_empty
+            }
+        };
 
-        // Read number of vertices
-        reader.read_line(&mut line)?;
-        self.n_vertices = line.trim().parse()?;
+        Graph::check_vertex_limit(self.n_vertices)?;
 
         // Initialize adjacency matrix representation
+        self.reserve_vertices(self.n_vertices);
         self.init_rep();
 
         // Read adjacency info from file and create adjacency list
-        for i in 0..self.n_vertices {
-            line.clear();
-            reader.read_line(&mut line)?;
-            let parts: Vec<&str> = line.trim().split_whitespace().collect();
+        // Weights written inline as B:5 are stored once the matrix tells which edges exist
+        // A token is only split into label and weight when its prefix labels a line
+        let labels: HashSet<&str> = adjacency_lines
+            .iter()
+            .filter_map(|line| line.split_whitespace().next())
+            .collect();
+        let mut inline_weights = Vec::new();
+        for (i, line) in adjacency_lines.iter().enumerate() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
             
             if parts.is_empty() {
                 continue;
@@ -124,16 +296,180 @@ impl Graph {
             self.adj_count[i] = 0;
 
             // Read adjacent vertices until -1
-            for j in 1..parts.len() {
-                if parts[j] == "-1" {
+            for part in parts.iter().skip(1) {
+                if *part == "-1" {
                     break;
                 }
-                self.add_to_adj_list(i, parts[j].to_string());
+                let (adj_vertex, weight) = split_neighbor_token(part, |label| labels.contains(label));
+                self.add_to_adj_list(i, adj_vertex);
+                if let Some(weight) = weight {
+                    inline_weights.push((i, adj_vertex.to_string(), weight));
+                }
             }
         }
 
         self.make_adj_matrix();
-        Ok(())
+
+        // Strict mode refuses files whose representations disagree
+        if dialect == Dialect::Strict {
+            self.check_invariants()?;
+        }
+
+        if dialect == Dialect::AutoDirection {
+            let profile = self.direction_profile();
+            let direction = profile.suggested().ok_or_else(|| GraphError::InvalidFormat {
+                format: "adjacency",
+                reason: format!(
+                    "cannot tell whether the graph is directed: {}; choose the direction explicitly",
+                    profile
+                ),
+            })?;
+            if direction == Direction::Undirected {
+                self.symmetrize();
+            }
+            warnings.push(ParseWarning::DirectionChosen {
+                direction,
+                reciprocal_pairs: profile.reciprocal_pairs,
+                one_way_pairs: profile.one_way_pairs,
+            });
+        }
+        if dialect == Dialect::Undirected {
+            self.symmetrize();
+        }
+
+        self.apply_inline_weights(&inline_weights)?;
+        self.debug_check_representations();
+
+        Ok(warnings)
+    }
+
+    /// Replaces the graph with the given rows of (vertex, neighbors), in input order
+    /// Weights and coordinates are cleared; EdgeIds stay on their index pairs
+    pub(crate) fn rebuild_from_rows(&mut self, rows: &[(String, Vec<String>)]) {
+        self.reserve_vertices(rows.len());
+        // The rows keep the vertex indices, so the EdgeIds outlive the reset
+        let edge_registry = std::mem::take(&mut self.edge_registry);
+        self.init_rep();
+        self.edge_registry = edge_registry;
+        self.vertices.clear();
+        self.n_vertices = rows.len();
+        for (i, (label, neighbors)) in rows.iter().enumerate() {
+            self.vertices.push(label.clone());
+            for neighbor in neighbors {
+                self.add_to_adj_list(i, neighbor);
+            }
+        }
+        self.make_adj_matrix();
+    }
+
+    /// Builds a graph from rows of (vertex, neighbors), checking the vertex limit
+    pub(crate) fn from_rows(rows: &[(String, Vec<String>)]) -> Result<Graph, GraphError> {
+        Graph::check_vertex_limit(rows.len())?;
+        let mut graph = Graph::new();
+        graph.rebuild_from_rows(rows);
+        Ok(graph)
+    }
+
+    /// Formats the graph in the input file format, one adjacency line per vertex in input order
+    /// Edges with a stored weight are written with it inline, as B:5
+    pub fn to_input_text(&self) -> String {
+        let mut text = format!("{}\n", self.n_vertices);
+        for (i, (vertex, adj_vertices)) in self.adjacency_rows().into_iter().enumerate() {
+            text.push_str(&vertex);
+            for adj_vertex in &adj_vertices {
+                text.push(' ');
+                text.push_str(&self.neighbor_token(i, adj_vertex));
+            }
+            text.push_str(" -1\n");
+        }
+        text
+    }
+
+    /// Formats the graph in the canonical form of the input format
+    ///
+    /// Vertices are sorted alphabetically, and so are the neighbors on each line;
+    /// tokens are separated by single spaces, every line ends with -1 and LF. Two
+    /// files describing the same graph produce the same bytes, whatever their line
+    /// and neighbor order, and the text reads back as the same graph.
+    pub fn to_canonical_text(&self) -> String {
+        let rows = self.adjacency_rows();
+        let mut text = format!("{}\n", self.n_vertices);
+        for idx in self.sort_vertices() {
+            let (vertex, adj_vertices) = &rows[idx];
+            let mut adj_vertices = adj_vertices.clone();
+            adj_vertices.sort();
+            text.push_str(vertex);
+            for adj_vertex in &adj_vertices {
+                text.push(' ');
+                text.push_str(&self.neighbor_token(idx, adj_vertex));
+            }
+            text.push_str(" -1\n");
+        }
+        text
+    }
+
+    /// Formats each edge of the canonical edge order as `A-B`, or `A->B` for an arc in one direction
+    fn edge_tokens(&self) -> Vec<(String, Option<f64>)> {
+        self.ordered_edges()
+            .into_iter()
+            .map(|edge| {
+                let link = if edge.directed { "->" } else { "-" };
+                (format!("{}{}{}", edge.source, link, edge.target), edge.weight)
+            })
+            .collect()
+    }
+
+    /// Formats the logical structure of the graph for snapshot tests
+    ///
+    /// The format is a stable guarantee: it changes only with a major release. It
+    /// holds the name (`-` when there is none), the vertex count, the labels in
+    /// alphabetical order, the edges in the canonical edge order, and a weights line
+    /// for the edges with an explicit weight when there are any:
+    ///
+    /// ```text
+    /// graph g
+    /// vertices 3: A B C
+    /// edges 2: A-B A->C
+    /// weights: A-B=2.5
+    /// ```
+    pub fn snapshot_string(&self) -> String {
+        let labels: Vec<&str> = self.sort_vertices().into_iter().map(|idx| self.vertices[idx].as_str()).collect();
+        let edges = self.edge_tokens();
+        let mut text = format!("graph {}\n", self.name.as_deref().unwrap_or("-"));
+        text.push_str(&format!("vertices {}:", self.n_vertices));
+        for label in labels {
+            text.push(' ');
+            text.push_str(label);
+        }
+        text.push_str(&format!("\nedges {}:", edges.len()));
+        for (edge, _) in &edges {
+            text.push(' ');
+            text.push_str(edge);
+        }
+        text.push('\n');
+        if edges.iter().any(|(_, weight)| weight.is_some()) {
+            text.push_str("weights:");
+            for (edge, weight) in &edges {
+                if let Some(weight) = weight {
+                    text.push_str(&format!(" {}={}", edge, weight));
+                }
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Turns a label into a part of a file name that stays in the directory of the base name
+    /// Path separators, and the characters Windows does not allow in file names, become _
+    pub fn file_name_infix(label: &str) -> String {
+        label
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+                c if c.is_control() => '_',
+                c => c,
+            })
+            .collect()
     }
 
     /// Sets the name of the output text file
@@ -142,19 +478,14 @@ impl Graph {
     }
 
     /// Sorts the index of the vertices in the graph by value of the label of each node into idx
+    /// The order is computed once and cached until the graph changes
     pub fn sort_vertices(&self) -> Vec<usize> {
-        let mut idx: Vec<usize> = (0..self.n_vertices).collect();
-
-        // Perform Bubble Sort on idx
-        for i in 0..self.n_vertices.saturating_sub(1) {
-            for j in 0..self.n_vertices.saturating_sub(1).saturating_sub(i) {
-                if self.vertices[idx[j]] > self.vertices[idx[j + 1]] {
-                    idx.swap(j, j + 1);
-                }
-            }
-        }
+        self.cached_sorted().to_vec()
+    }
 
-        idx
+    /// Performs the alphabetical sort behind sort_vertices without the cache
+    pub(crate) fn compute_sorted_vertices(&self) -> Vec<usize> {
+        algo::sorted_vertices(self)
     }
 
     /// To sort an array of integers that correspond to the indices of a graph's vertices (alias for sort_vertices)
@@ -163,14 +494,19 @@ impl Graph {
     }
 
     /// Prepares the output file of list of vertices and edges in the graph
-    pub fn produce_output_file1(&self, base_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn produce_output_file1(&self, base_name: &str) -> Result<(), GraphError> {
         let output_name = Self::get_output_filename(base_name, "-SET");
-        let mut fp = File::create(output_name)?;
-        
+        self.write_set(File::create(output_name)?, base_name)
+    }
+
+    /// Writes the vertex and edge sets of produce_output_file1, named after the graph
+    /// or, when it has no name, after fallback_name
+    pub fn write_set<W: Write>(&self, mut fp: W, fallback_name: &str) -> Result<(), GraphError> {
         let sorted_idx = self.sort_vertices();
+        let name = self.display_name(fallback_name);
 
         // Write vertices of graph
-        write!(fp, "V({})={{", base_name)?;
+        write!(fp, "V({})={{", name)?;
         for (i, &idx) in sorted_idx.iter().enumerate() {
             write!(fp, "{}", self.vertices[idx])?;
             if i < self.n_vertices - 1 {
@@ -179,20 +515,17 @@ impl Graph {
         }
         writeln!(fp, "}}")?;
 
-        // Write edges of graph
-        write!(fp, "E({})={{", base_name)?;
-        let mut edge_ctr = 0;
-        for i in 0..self.n_vertices {
-            for j in (i + 1)..self.n_vertices {
-                if self.adj_matrix[sorted_idx[i]][sorted_idx[j]] == 1 {
-                    if edge_ctr > 0 {
-                        write!(fp, ",")?;
-                    }
-                    write!(fp, "({},{})", self.vertices[sorted_idx[i]], self.vertices[sorted_idx[j]])?;
-                    edge_ctr += 1;
-                }
-            }
-        }
+        // Write edges of graph in the canonical edge order, each pair once from its
+        // alphabetically smaller endpoint as the set has always been written: self-loops
+        // and arcs listed only by the larger endpoint are left out
+        let ranks = self.alphabetical_ranks();
+        let edges: Vec<String> = self
+            .ordered_edges()
+            .into_iter()
+            .filter(|edge| ranks[edge.source_idx] < ranks[edge.target_idx])
+            .map(|edge| format!("({},{})", edge.source, edge.target))
+            .collect();
+        write!(fp, "E({})={{{}", name, edges.join(","))?;
         writeln!(fp, "}}")?;
 
         Ok(())
@@ -206,7 +539,7 @@ impl Graph {
         // Look through all neighbors of the vertex
         while let Some(ref node) = current {
             // Copy the vertex name of the neighbor into array
-            adj_vertices.push(node.vertex.clone());
+            adj_vertices.push(node.vertex.to_string());
             current = &node.next;
         }
         
@@ -215,10 +548,20 @@ impl Graph {
 
     /// Print the adjacency list of a node
     pub fn print_vertex_adjacency_list(
-        fp: &mut File,
+        fp: &mut impl Write,
+        vertex: &str,
+        adj_vertices: &[String],
+    ) -> Result<(), GraphError> {
+        Self::print_adjacency_line(fp, vertex, adj_vertices, "\\")
+    }
+
+    /// Print the adjacency list of a node, ending it with list_end instead of a backslash
+    fn print_adjacency_line(
+        fp: &mut impl Write,
         vertex: &str,
         adj_vertices: &[String],
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        list_end: &str,
+    ) -> Result<(), GraphError> {
         // Print first vertex
         write!(fp, "{}->", vertex)?;
         
@@ -227,24 +570,37 @@ impl Graph {
             write!(fp, "{}->", adj_vertex)?;
         }
         
-        write!(fp, "\\")?;
+        write!(fp, "{}", list_end)?;
         Ok(())
     }
 
     /// To create/write an output file that lists a graph's vertices and their degrees
-    pub fn produce_output_file2(&self, base_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn produce_output_file2(&self, base_name: &str) -> Result<(), GraphError> {
+        self.produce_output_file2_as(base_name, &DialectProfile::default())
+    }
+
+    /// Same as produce_output_file2, laid out as the profile says
+    pub fn produce_output_file2_as(
+        &self,
+        base_name: &str,
+        profile: &DialectProfile,
+    ) -> Result<(), GraphError> {
         // Get file name of output file
         let output_name = Self::get_output_filename(base_name, "-DEGREE");
-        
-        // Open output file
-        let mut fp = File::create(output_name)?;
-        
+        self.write_degrees(File::create(output_name)?, profile)
+    }
+
+    /// Writes the vertex degrees of produce_output_file2, laid out as the profile says
+    pub fn write_degrees<W: Write>(&self, mut fp: W, profile: &DialectProfile) -> Result<(), GraphError> {
         // Get indices of sorted vertex
         let sorted_idx = self.sort_vertices();
 
         // Print vertices in ascending order along with their degrees
         for (i, &idx) in sorted_idx.iter().enumerate() {
-            write!(fp, "{:<10}{}", self.vertices[idx], self.adj_count[idx])?;
+            match &profile.degree_separator {
+                Some(separator) => write!(fp, "{}{}{}", self.vertices[idx], separator, self.adj_count[idx])?,
+                None => write!(fp, "{:<width$}{}", self.vertices[idx], self.adj_count[idx], width = profile.column_width)?,
+            }
             if i < self.n_vertices - 1 {
                 writeln!(fp)?;
             }
@@ -253,48 +609,126 @@ impl Graph {
         Ok(())
     }
 
+    /// Returns each vertex with its neighbors as listed in the adjacency list, in input order
+    pub fn adjacency_rows(&self) -> Vec<(String, Vec<String>)> {
+        (0..self.n_vertices)
+            .map(|i| (self.vertices[i].clone(), self.collect_adjacent_vertices(&self.adj_list[i])))
+            .collect()
+    }
+
+    /// Writes one `vertex->neighbor->...->\` line per row, each line ending in the
+    /// list_end of the profile
+    fn write_adjacency_rows<W: Write>(
+        mut fp: W,
+        rows: &[(String, Vec<String>)],
+        profile: &DialectProfile,
+    ) -> Result<(), GraphError> {
+        for (vertex, adj_vertices) in rows {
+            Self::print_adjacency_line(&mut fp, vertex, adj_vertices, &profile.list_end)?;
+            writeln!(fp)?;
+        }
+        Ok(())
+    }
+
     /// To create/write an output file that visualizes an adjacency list representation of a graph
-    pub fn produce_output_file3(&self, base_name: &str) -> Result<(), Box<dyn std::error::Error>> {
-        // Get file name of output file
+    pub fn produce_output_file3(&self, base_name: &str) -> Result<(), GraphError> {
+        self.produce_output_file3_as(base_name, &DialectProfile::default())
+    }
+
+    /// Same as produce_output_file3, laid out as the profile says
+    pub fn produce_output_file3_as(
+        &self,
+        base_name: &str,
+        profile: &DialectProfile,
+    ) -> Result<(), GraphError> {
         let output_name = Self::get_output_filename(base_name, "-LIST");
-        
-        // Open output file
-        let mut fp = File::create(output_name)?;
+        self.write_list(File::create(output_name)?, profile)
+    }
 
+    /// Writes the adjacency list of produce_output_file3, laid out as the profile says
+    pub fn write_list<W: Write>(&self, fp: W, profile: &DialectProfile) -> Result<(), GraphError> {
         // Print vertices in their original input order (no sorting of vertices)
-        for i in 0..self.n_vertices {
-            // Collect all adjacent vertices from linked list
-            let adj_vertices = self.collect_adjacent_vertices(&self.adj_list[i]);
-            
-            // Print adjacency list for this vertex
-            Self::print_vertex_adjacency_list(&mut fp, &self.vertices[i], &adj_vertices)?;
-            writeln!(fp)?;
-        }
+        Self::write_adjacency_rows(fp, &self.adjacency_rows(), profile)
+    }
 
-        Ok(())
+    /// To create/write an output file with the adjacency list of a graph with both the
+    /// vertices and each vertex's neighbors sorted alphabetically
+    pub fn produce_output_file3_sorted(&self, base_name: &str) -> Result<(), GraphError> {
+        self.produce_output_file3_sorted_as(base_name, &DialectProfile::default())
+    }
+
+    /// Same as produce_output_file3_sorted, laid out as the profile says
+    pub fn produce_output_file3_sorted_as(
+        &self,
+        base_name: &str,
+        profile: &DialectProfile,
+    ) -> Result<(), GraphError> {
+        let output_name = Self::get_output_filename(base_name, "-LIST-SORTED");
+        self.write_list_sorted(File::create(output_name)?, profile)
+    }
+
+    /// Writes the sorted adjacency list of produce_output_file3_sorted, laid out as the
+    /// profile says
+    pub fn write_list_sorted<W: Write>(&self, fp: W, profile: &DialectProfile) -> Result<(), GraphError> {
+        let mut rows = self.adjacency_rows();
+        for (_, adj_vertices) in rows.iter_mut() {
+            adj_vertices.sort();
+        }
+        rows.sort_by(|a, b| a.0.cmp(&b.0));
+        Self::write_adjacency_rows(fp, &rows, profile)
     }
 
     /// To create/write an output file that visualizes an adjacency matrix representation of a graph
-    pub fn produce_output_file4(&self, base_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn produce_output_file4(&self, base_name: &str) -> Result<(), GraphError> {
+        self.produce_output_file4_as(base_name, &DialectProfile::default())
+    }
+
+    /// Same as produce_output_file4 with the cells chosen by a MatrixStyle
+    /// The legacy style writes exactly the bytes of produce_output_file4
+    pub fn produce_output_file4_with(&self, base_name: &str, style: MatrixStyle) -> Result<(), GraphError> {
+        let profile = DialectProfile {
+            matrix: style,
+            ..DialectProfile::default()
+        };
+        self.produce_output_file4_as(base_name, &profile)
+    }
+
+    /// Same as produce_output_file4, laid out as the profile says
+    pub fn produce_output_file4_as(
+        &self,
+        base_name: &str,
+        profile: &DialectProfile,
+    ) -> Result<(), GraphError> {
         let output_name = Self::get_output_filename(base_name, "-MATRIX");
-        let mut fp = File::create(output_name)?;
+        self.write_matrix(File::create(output_name)?, profile)
+    }
+
+    /// Writes the adjacency matrix of produce_output_file4, laid out as the profile says
+    pub fn write_matrix<W: Write>(&self, mut fp: W, profile: &DialectProfile) -> Result<(), GraphError> {
+        let width = profile.column_width;
 
-        write!(fp, "{:<10}", "")?;
+        write!(fp, "{:<width$}", profile.matrix_corner)?;
         
         // Print column vertices of matrix
         for i in 0..self.n_vertices {
-            write!(fp, "{:<10}", self.vertices[i])?;
+            write!(fp, "{:<width$}", self.vertices[i])?;
         }
         writeln!(fp)?;
 
         // Print rows of matrix
         for i in 0..self.n_vertices {
             // Print row vertex
-            write!(fp, "{:<10}", self.vertices[i])?;
+            write!(fp, "{:<width$}", self.vertices[i])?;
             
-            // Prints 1 if row vertex has an edge with column vertex, 0 otherwise
+            // Prints 1 if row vertex has an edge with column vertex, 0 otherwise,
+            // or the weight of the edge when the style shows weights
             for j in 0..self.n_vertices {
-                write!(fp, "{:<10}", self.adj_matrix[i][j])?;
+                if profile.matrix.show_weights {
+                    let cell = if self.adj_matrix[i][j] == 1 { self.weight_at(i, j) } else { 0.0 };
+                    write!(fp, "{:<width$}", cell)?;
+                } else {
+                    write!(fp, "{:<width$}", self.adj_matrix[i][j])?;
+                }
             }
             writeln!(fp)?;
         }
@@ -302,131 +736,265 @@ impl Graph {
         Ok(())
     }
 
+    /// To create/write the core output files -SET, -DEGREE, -LIST, -LIST-SORTED and
+    /// -MATRIX, laid out as the dialect of the configuration says
+    pub fn produce_core_outputs(&self, base_name: &str, config: &OutputConfig) -> Result<(), GraphError> {
+        let profile = config.dialect();
+        self.produce_output_file1(base_name)?;
+        self.produce_output_file2_as(base_name, profile)?;
+        self.produce_output_file3_as(base_name, profile)?;
+        self.produce_output_file3_sorted_as(base_name, profile)?;
+        self.produce_output_file4_as(base_name, profile)?;
+        Ok(())
+    }
+
+    /// Returns the indices of the neighbors of a vertex in alphabetical order of their labels
+    pub fn sorted_neighbors(&self, vertex_idx: usize) -> Vec<usize> {
+        algo::sorted_neighbors(self, vertex_idx)
+    }
+
     /// To traverse a graph at a given index using the Breadth First Search Algorithm (BFS)
     /// then storing the results in an array
+    /// Dense graphs are searched through the complement; see density_strategy
     pub fn bfs(&self, starting_index: usize) -> Vec<String> {
-        let mut visited = vec![false; self.n_vertices];
-        let mut queue = VecDeque::new();
-        let mut result = Vec::new();
-
-        // Put starting index in first index of queue
-        queue.push_back(starting_index);
-        // Visit starting index
-        visited[starting_index] = true;
-
-        // Loop runs until queue is empty
-        while let Some(current_vertex) = queue.pop_front() {
-            // Insert the frontmost, unvisited vertex stored in the queue
-            result.push(self.vertices[current_vertex].clone());
-
-            // Reset candidates counter
-            // Then find candidates (neighbors of current vertex that have not been visited)
-            let mut candidates = Vec::new();
-            for i in 0..self.n_vertices {
-                if self.adj_matrix[current_vertex][i] == 1 && !visited[i] {
-                    candidates.push(i);
-                    visited[i] = true;
-                }
-            }
-
-            // Perform Selection Sort on candidates array to arrange candidates in alphabetical order
-            candidates.sort_by(|&a, &b| self.vertices[a].cmp(&self.vertices[b]));
-
-            // Enqueue all candidates and repeat the loop until queue is empty
-            for candidate in candidates {
-                queue.push_back(candidate);
-            }
-        }
-
-        result
+        self.bfs_with(starting_index, self.density_strategy())
     }
 
     /// To traverse a graph at a given index using the Depth First Search Algorithm (DFS)
     /// then storing the results in an array
-    pub fn dfs(&self, previous_index: usize, result: &mut Vec<String>, visited: &mut Vec<bool>) {
-        // Visit indicated vertex with index of previous_index
-        // On first call of DFS, previous index is the starting index
-        visited[previous_index] = true;
-        result.push(self.vertices[previous_index].clone());
-
-        // Find candidates (neighbors of recently visited vertex that have not been visited yet)
-        let mut candidates = Vec::new();
-        for i in 0..self.n_vertices {
-            if self.adj_matrix[previous_index][i] == 1 && !visited[i] {
-                candidates.push(i);
-            }
-        }
-
-        // Perform Selection Sort on candidates array to arrange candidates in alphabetical order
-        candidates.sort_by(|&a, &b| self.vertices[a].cmp(&self.vertices[b]));
-
-        // Visit all candidates of recently visited vertex
-        for candidate in candidates {
-            if !visited[candidate] {
-                self.dfs(candidate, result, visited);
-            }
-        }
+    pub fn dfs(&self, previous_index: usize, result: &mut Vec<String>, visited: &mut [bool]) {
+        let mut order = Vec::new();
+        algo::dfs(self, previous_index, visited, &mut order);
+        result.extend(self.labels_of(&order));
     }
 
     /// To create/write an output file that prints the traversal order of a graph using 
     /// Breadth First Search (BFS)
-    pub fn produce_output_file5(&self, base_name: &str, start: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /// An unknown start is an error after the file is created, leaving it empty
+    pub fn produce_output_file5(&self, base_name: &str, start: &str) -> Result<(), GraphError> {
         let output_name = Self::get_output_filename(base_name, "-BFS");
-        
-        // Determine if starting vertex exists on the graph
-        let starting_idx = self.find_vertex_idx(start);
-        
-        let mut fp = File::create(output_name)?;
+        self.write_bfs(File::create(output_name)?, VertexRef::Label(start))
+    }
 
-        if let Some(starting_idx) = starting_idx {
-            // Perform BFS
-            let result = self.bfs(starting_idx);
-            
-            // Then print results
-            for (i, vertex) in result.iter().enumerate() {
-                write!(fp, "{}", vertex)?;
-                if i < result.len() - 1 {
-                    write!(fp, " ")?;
-                }
-            }
-            writeln!(fp)?;
-        } else {
-            return Err("Starting vertex not found".into());
-        }
+    /// Same as produce_output_file5, with the starting vertex given by its index
+    pub fn produce_output_file5_idx(&self, base_name: &str, start_idx: usize) -> Result<(), GraphError> {
+        let start = VertexRef::Index(self.resolve_vertex(VertexRef::Index(start_idx))?);
+        self.write_bfs(File::create(Self::get_output_filename(base_name, "-BFS"))?, start)
+    }
 
+    /// Writes the BFS order of produce_output_file5 from a start given by label or index,
+    /// space-separated on one line
+    pub fn write_bfs<W: Write>(&self, mut fp: W, start: VertexRef) -> Result<(), GraphError> {
+        let starting_idx = self.resolve_vertex(start)?;
+        writeln!(fp, "{}", self.bfs(starting_idx).join(" "))?;
         Ok(())
     }
 
     /// To create/write an output file that prints the traversal order of a graph using 
     /// Depth First Search (DFS)
-    pub fn produce_output_file6(&self, base_name: &str, start: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /// An unknown start is an error after the file is created, leaving it empty
+    pub fn produce_output_file6(&self, base_name: &str, start: &str) -> Result<(), GraphError> {
         let output_name = Self::get_output_filename(base_name, "-DFS");
-        
-        // Check if starting vertex exists on graph
-        let starting_idx = self.find_vertex_idx(start);
-        
-        let mut fp = File::create(output_name)?;
+        self.write_dfs(File::create(output_name)?, VertexRef::Label(start))
+    }
 
-        if let Some(starting_idx) = starting_idx {
-            // Initialize visited array
-            let mut visited = vec![false; MAX_VERTICES];
-            let mut result = Vec::new();
-            
-            // Perform DFS
-            self.dfs(starting_idx, &mut result, &mut visited);
-            
-            // Then print results
-            for (i, vertex) in result.iter().enumerate() {
-                write!(fp, "{}", vertex)?;
-                if i < result.len() - 1 {
-                    write!(fp, " ")?;
-                }
-            }
-            writeln!(fp)?;
+    /// Same as produce_output_file6, with the starting vertex given by its index
+    pub fn produce_output_file6_idx(&self, base_name: &str, start_idx: usize) -> Result<(), GraphError> {
+        let start = VertexRef::Index(self.resolve_vertex(VertexRef::Index(start_idx))?);
+        self.write_dfs(File::create(Self::get_output_filename(base_name, "-DFS"))?, start)
+    }
+
+    /// Writes the DFS order of produce_output_file6 from a start given by label or index,
+    /// space-separated on one line
+    pub fn write_dfs<W: Write>(&self, mut fp: W, start: VertexRef) -> Result<(), GraphError> {
+        let starting_idx = self.resolve_vertex(start)?;
+
+        // Initialize visited array
+        let mut visited = vec![false; self.n_vertices];
+        let mut result = Vec::new();
+
+        // Perform DFS
+        self.dfs(starting_idx, &mut result, &mut visited);
+        writeln!(fp, "{}", result.join(" "))?;
+        Ok(())
+    }
+
+    /// To create/write the BFS and DFS output files for a starting vertex given by label or index
+    pub fn produce_traversal_files(&self, base_name: &str, start: VertexRef) -> Result<(), GraphError> {
+        let start_idx = self.resolve_vertex(start)?;
+        self.produce_output_file5_idx(base_name, start_idx)?;
+        self.produce_output_file6_idx(base_name, start_idx)
+    }
+
+    /// To create/write the BFS and DFS output files of another starting vertex, with the
+    /// start as an infix (g-B-BFS.TXT, g-B-DFS.TXT) so that earlier runs are kept
+    /// The infix is the start as file_name_infix gives it, so a label such as ../x
+    /// cannot place the files outside the directory of base_name
+    pub fn produce_traversal_outputs(&self, base_name: &str, start: &str) -> Result<(), GraphError> {
+        let start_idx = self.require_vertex_idx(start)?;
+        let infixed = format!("{}-{}", base_name, Self::file_name_infix(start));
+        self.produce_traversal_files(&infixed, VertexRef::Index(start_idx))
+    }
+
+    /// To create/write output files with the BFS and DFS traversal orders from every
+    /// starting vertex, one line per start in alphabetical order
+    ///
+    /// With distinct_starts, only the alphabetically smallest vertex of each orbit is
+    /// used as a start, since symmetric starts give traversals of the same shape, and
+    /// each line is prefixed with the orbit it represents. If the automorphism search
+    /// exceeds its budget every vertex is used as a start instead.
+    pub fn produce_all_traversals(
+        &self,
+        base_name: &str,
+        distinct_starts: bool,
+    ) -> Result<(), GraphError> {
+        self.produce_all_traversals_with(base_name, distinct_starts, &OutputConfig::new())
+    }
+
+    /// Same as produce_all_traversals, cutting the files at the "bfs-all" and "dfs-all" limits
+    pub fn produce_all_traversals_with(
+        &self,
+        base_name: &str,
+        distinct_starts: bool,
+        config: &OutputConfig,
+    ) -> Result<(), GraphError> {
+        let orbits = if distinct_starts {
+            self.vertex_orbits_with_budget(DEFAULT_AUTOMORPHISM_BUDGET)
         } else {
-            return Err("Starting vertex not found".into());
-        }
+            None
+        };
+
+        // Each start comes with the orbit annotation of its line, if any
+        let starts: Vec<(usize, String)> = match orbits {
+            Some(orbits) => orbits
+                .iter()
+                .filter_map(|orbit| {
+                    let idx = self.find_vertex_idx(&orbit[0])?;
+                    Some((idx, format!("[orbit: {{{}}}] ", orbit.join(","))))
+                })
+                .collect(),
+            None => self.sort_vertices().into_iter().map(|idx| (idx, String::new())).collect(),
+        };
+        let total = ItemTotal::Exact(starts.len());
+
+        let mut bfs_fp = File::create(Self::get_output_filename(base_name, "-BFS-ALL"))?;
+        let bfs_lines = starts.iter().map(|(idx, annotation)| {
+            format!("{}{}: {}", annotation, self.vertices[*idx], self.bfs(*idx).join(" "))
+        });
+        write_section(&mut bfs_fp, bfs_lines, total, config.limit("bfs-all"))?;
+
+        let mut dfs_fp = File::create(Self::get_output_filename(base_name, "-DFS-ALL"))?;
+        let dfs_lines = starts.iter().map(|(idx, annotation)| {
+            let mut visited = vec![false; self.n_vertices];
+            let mut dfs_result = Vec::new();
+            self.dfs(*idx, &mut dfs_result, &mut visited);
+            format!("{}{}: {}", annotation, self.vertices[*idx], dfs_result.join(" "))
+        });
+        write_section(&mut dfs_fp, dfs_lines, total, config.limit("dfs-all"))?;
 
         Ok(())
     }
 }
+
+/// Shows the logical structure instead of the fixed-size matrix and boxed lists:
+/// name, vertex count, labels alphabetically and edges in the canonical edge order
+impl fmt::Debug for Graph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels: Vec<&str> = self.sort_vertices().into_iter().map(|idx| self.vertices[idx].as_str()).collect();
+        let edges = self.edge_tokens();
+        let mut debug = f.debug_struct("Graph");
+        debug
+            .field("name", &self.name)
+            .field("n_vertices", &self.n_vertices)
+            .field("vertices", &labels)
+            .field("edges", &edges.iter().map(|(edge, _)| edge.as_str()).collect::<Vec<_>>());
+        let weights: Vec<String> = edges
+            .iter()
+            .filter_map(|(edge, weight)| weight.map(|weight| format!("{}={}", edge, weight)))
+            .collect();
+        if !weights.is_empty() {
+            debug.field("weights", &weights);
+        }
+        debug.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use std::fs;
+
+    #[test]
+    fn infix_keeps_output_files_in_their_directory() {
+        assert_eq!(Graph::file_name_infix("B"), "B");
+        assert_eq!(Graph::file_name_infix("../x"), ".._x");
+        assert_eq!(Graph::file_name_infix("a:b*c?\"<>|\\\t"), "a_b_c_______");
+        assert_eq!(Graph::get_output_filename("dir/g", "-BFS"), "dir/g-BFS.TXT");
+    }
+
+    #[test]
+    fn set_lists_edges_with_the_smaller_label_first() {
+        let mut graph = Graph::new();
+        graph.read_input_text_with("3\nC B -1\nB C A -1\nA B -1\n", Dialect::Strict).unwrap();
+        let mut out = Vec::new();
+        graph.write_set(&mut out, "cba").unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "V(cba)={A,B,C}\nE(cba)={(A,B),(B,C)}\n");
+    }
+
+    #[test]
+    fn named_graphs_write_their_name() {
+        let mut graph = fixtures::triangle();
+        graph.set_name("tri");
+        let mut out = Vec::new();
+        graph.write_set(&mut out, "fallback").unwrap();
+        assert!(String::from_utf8(out).unwrap().starts_with("V(tri)={A,B,C}"));
+        assert_eq!(Graph::get_base_filename("data/g.txt"), "data/g");
+    }
+
+    /// The cycle V00-V01-...-V99-V00 in the input file format
+    fn hundred_cycle() -> String {
+        let mut text = String::from("100\n");
+        for i in 0..100 {
+            text.push_str(&format!("V{:02} V{:02} V{:02} -1\n", i, (i + 99) % 100, (i + 1) % 100));
+        }
+        text
+    }
+
+    #[test]
+    fn every_output_handles_a_hundred_vertices() {
+        let mut graph = Graph::new();
+        graph.read_input_text_with(&hundred_cycle(), Dialect::Strict).unwrap();
+        graph.check_invariants().unwrap();
+        let dir = fixtures::scratch_dir("graph", "hundred");
+        let base = dir.join("cycle").display().to_string();
+        graph.produce_output_file1(&base).unwrap();
+        graph.produce_output_file2(&base).unwrap();
+        graph.produce_output_file3(&base).unwrap();
+        graph.produce_output_file4(&base).unwrap();
+        graph.produce_output_file5(&base, "V00").unwrap();
+        graph.produce_output_file6(&base, "V00").unwrap();
+        let read = |suffix: &str| fs::read_to_string(format!("{}-{}.TXT", base, suffix)).unwrap();
+
+        let set = read("SET");
+        let edges = set.lines().nth(1).unwrap();
+        assert_eq!(set.lines().next().unwrap().matches(',').count(), 99);
+        assert_eq!(edges.matches("),(").count(), 99);
+        assert!(edges.ends_with("(V98,V99)}"));
+        assert!(read("DEGREE").lines().all(|line| line.ends_with(" 2")));
+        assert_eq!(read("DEGREE").lines().count(), 100);
+        assert_eq!(read("LIST").lines().nth(99), Some("V99->V98->V00->\\"));
+        let matrix = read("MATRIX");
+        assert_eq!(matrix.lines().count(), 101);
+        assert!(matrix.lines().skip(1).all(|row| row.split_whitespace().count() == 101));
+
+        let expected_bfs: Vec<String> = (0..100)
+            .map(|i| if i % 2 == 1 { (i + 1) / 2 } else { (100 - i / 2) % 100 })
+            .map(|i| format!("V{:02}", i))
+            .collect();
+        assert_eq!(read("BFS").trim_end(), expected_bfs.join(" "));
+        let expected_dfs: Vec<String> = (0..100).map(|i| format!("V{:02}", i)).collect();
+        assert_eq!(read("DFS").trim_end(), expected_dfs.join(" "));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+// src/graphml.rs - Export to GraphML
+//
+// The document declares the weight key only when the graph has stored weights,
+// then lists every vertex as a node, sorted, and the edges as the DOT export does:
+// a graph without one-way pairs is undirected with each edge once; any other graph
+// is directed, a pair listed both ways giving two edges. Node ids are the labels.
+use crate::error::GraphError;
+use crate::Graph;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+/// Escapes the five characters XML reserves, for use in text and in quoted attributes
+pub fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl Graph {
+    /// Writes the graph as a GraphML document, with the graph's name or "G" as graph id
+    ///
+    /// Edges with an explicit weight carry it as `weight` data; edges without one
+    /// take the key's default of 1, the weight every algorithm here gives them.
+    pub fn write_graphml<W: Write>(&self, mut w: W) -> Result<(), GraphError> {
+        let (directed, edges) = self.export_edges();
+        let weighted = self.has_weights();
+
+        writeln!(w, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(w, "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\"")?;
+        writeln!(w, "    xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"")?;
+        writeln!(
+            w,
+            "    xsi:schemaLocation=\"http://graphml.graphdrawing.org/xmlns \
+             http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd\">"
+        )?;
+        if weighted {
+            writeln!(w, "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\">")?;
+            writeln!(w, "    <default>1</default>")?;
+            writeln!(w, "  </key>")?;
+        }
+        let edgedefault = if directed { "directed" } else { "undirected" };
+        writeln!(
+            w,
+            "  <graph id=\"{}\" edgedefault=\"{}\">",
+            xml_escape(self.display_name("G")),
+            edgedefault
+        )?;
+        for &v in self.cached_sorted() {
+            writeln!(w, "    <node id=\"{}\"/>", xml_escape(&self.vertices[v]))?;
+        }
+        for edge in edges {
+            let (source, target) = (xml_escape(&edge.source), xml_escape(&edge.target));
+            match edge.weight {
+                Some(weight) => {
+                    writeln!(w, "    <edge source=\"{}\" target=\"{}\">", source, target)?;
+                    writeln!(w, "      <data key=\"weight\">{}</data>", weight)?;
+                    writeln!(w, "    </edge>")?;
+                }
+                None => writeln!(w, "    <edge source=\"{}\" target=\"{}\"/>", source, target)?,
+            }
+        }
+        writeln!(w, "  </graph>")?;
+        writeln!(w, "</graphml>")?;
+        w.flush()?;
+        Ok(())
+    }
+
+    /// To create/write the graph as a GraphML document, named base-GRAPH.graphml
+    pub fn produce_output_file_graphml(&self, base_name: &str) -> Result<(), GraphError> {
+        let fp = BufWriter::new(File::create(format!("{}-GRAPH.graphml", base_name))?);
+        self.write_graphml(fp)?;
+        Ok(())
+    }
+}
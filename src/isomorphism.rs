@@ -0,0 +1,337 @@
+// src/isomorphism.rs - Isomorphism and automorphism search by backtracking
+use crate::budget::{Budget, BudgetMeter};
+use crate::error::GraphError;
+use crate::output::{write_section, ItemTotal, OutputConfig};
+use crate::Graph;
+use std::fs::File;
+
+/// Default number of search steps the orbit computation may take before giving up
+pub const DEFAULT_AUTOMORPHISM_BUDGET: usize = 1_000_000;
+
+/// Backtracking search for adjacency-preserving bijections from g onto h
+struct Matcher<'a, 'm> {
+    g: &'a Graph,
+    h: &'a Graph,
+    /// Order in which the vertices of g are assigned
+    order: Vec<usize>,
+    /// Candidates of h for each vertex of g, in alphabetical order
+    candidates: Vec<Vec<usize>>,
+    map: Vec<Option<usize>>,
+    used: Vec<bool>,
+    limit: usize,
+    found: usize,
+    /// Steps taken so far against the budget of the search
+    budget: &'a mut BudgetMeter<'m>,
+    exhausted: bool,
+}
+
+impl<'a, 'm> Matcher<'a, 'm> {
+    /// Prepares a search where the pairs in fixed are mapped up front
+    fn new(
+        g: &'a Graph,
+        h: &'a Graph,
+        fixed: &[(usize, usize)],
+        limit: usize,
+        budget: &'a mut BudgetMeter<'m>,
+    ) -> Option<Self> {
+        if g.n_vertices != h.n_vertices {
+            return None;
+        }
+        let n = g.n_vertices;
+
+        // Vertices can only map onto vertices with the same in- and out-degree and loop
+        let signature = |graph: &Graph, v: usize| {
+            let out_degree = (0..n).filter(|&j| graph.adj_matrix[v][j] == 1).count();
+            let in_degree = (0..n).filter(|&j| graph.adj_matrix[j][v] == 1).count();
+            (out_degree, in_degree, graph.adj_matrix[v][v])
+        };
+        let h_sorted = h.sort_vertices();
+        let candidates: Vec<Vec<usize>> = (0..n)
+            .map(|v| {
+                let sig = signature(g, v);
+                h_sorted.iter().copied().filter(|&w| signature(h, w) == sig).collect()
+            })
+            .collect();
+
+        // Assign fixed vertices first, then always the vertex with the most assigned neighbors
+        let mut order: Vec<usize> = fixed.iter().map(|&(v, _)| v).collect();
+        let mut placed = vec![false; n];
+        for &v in &order {
+            placed[v] = true;
+        }
+        let g_sorted = g.sort_vertices();
+        while order.len() < n {
+            // Ties go to the alphabetically first vertex
+            let mut best: Option<(usize, usize)> = None;
+            for &v in g_sorted.iter().filter(|&&v| !placed[v]) {
+                let links = order
+                    .iter()
+                    .filter(|&&u| g.adj_matrix[u][v] == 1 || g.adj_matrix[v][u] == 1)
+                    .count();
+                if !matches!(best, Some((_, most)) if links <= most) {
+                    best = Some((v, links));
+                }
+            }
+            let (next, _) = best?;
+            placed[next] = true;
+            order.push(next);
+        }
+
+        let mut matcher = Matcher {
+            g,
+            h,
+            order,
+            candidates,
+            map: vec![None; n],
+            used: vec![false; n],
+            limit,
+            found: 0,
+            budget,
+            exhausted: false,
+        };
+        for &(v, w) in fixed {
+            if !matcher.consistent(v, w) {
+                return None;
+            }
+            matcher.map[v] = Some(w);
+            matcher.used[w] = true;
+        }
+        Some(matcher)
+    }
+
+    /// Checks that mapping v to w agrees with every vertex mapped so far
+    fn consistent(&self, v: usize, w: usize) -> bool {
+        if self.used[w] || !self.candidates[v].contains(&w) {
+            return false;
+        }
+        for (u, mapped) in self.map.iter().enumerate() {
+            if let Some(x) = *mapped {
+                if self.g.adj_matrix[u][v] != self.h.adj_matrix[x][w]
+                    || self.g.adj_matrix[v][u] != self.h.adj_matrix[w][x]
+                {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Extends the mapping from position depth of the order, reporting each complete mapping
+    fn extend(&mut self, depth: usize, visit: &mut dyn FnMut(&[usize])) {
+        if self.found >= self.limit || self.exhausted {
+            return;
+        }
+        if !self.budget.tick() {
+            self.exhausted = true;
+            return;
+        }
+        if depth == self.order.len() {
+            let complete: Vec<usize> = self.map.iter().map(|m| m.unwrap_or(0)).collect();
+            self.found += 1;
+            visit(&complete);
+            return;
+        }
+
+        let v = self.order[depth];
+        if self.map[v].is_some() {
+            self.extend(depth + 1, visit);
+            return;
+        }
+        for k in 0..self.candidates[v].len() {
+            let w = self.candidates[v][k];
+            if self.consistent(v, w) {
+                self.map[v] = Some(w);
+                self.used[w] = true;
+                self.extend(depth + 1, visit);
+                self.map[v] = None;
+                self.used[w] = false;
+                if self.found >= self.limit || self.exhausted {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Runs the isomorphism search and calls visit with each mapping (g index -> h index)
+/// Returns the number of mappings found, at most limit, or BudgetExceeded once the budget runs out
+pub(crate) fn search_isomorphisms(
+    g: &Graph,
+    h: &Graph,
+    fixed: &[(usize, usize)],
+    limit: usize,
+    budget: &mut BudgetMeter,
+    visit: &mut dyn FnMut(&[usize]),
+) -> Result<usize, GraphError> {
+    let Some(mut matcher) = Matcher::new(g, h, fixed, limit, budget) else {
+        return Ok(0);
+    };
+    matcher.extend(0, visit);
+    if matcher.exhausted {
+        Err(matcher.budget.exceeded())
+    } else {
+        Ok(matcher.found)
+    }
+}
+
+impl Graph {
+    /// Turns an index mapping into label pairs ordered by the source label
+    fn label_mapping(&self, other: &Graph, map: &[usize]) -> Vec<(String, String)> {
+        self.sort_vertices()
+            .into_iter()
+            .map(|v| (self.vertices[v].clone(), other.vertices[map[v]].clone()))
+            .collect()
+    }
+
+    /// Finds an isomorphism from this graph onto another, as label pairs
+    /// Returns None when the graphs are not isomorphic
+    pub fn is_isomorphic(&self, other: &Graph) -> Option<Vec<(String, String)>> {
+        self.is_isomorphic_within(other, &Budget::unlimited())
+            .unwrap_or_default()
+    }
+
+    /// Same as is_isomorphic, stopping with BudgetExceeded when the budget runs out
+    pub fn is_isomorphic_within(
+        &self,
+        other: &Graph,
+        budget: &Budget,
+    ) -> Result<Option<Vec<(String, String)>>, GraphError> {
+        let mut mapping = None;
+        search_isomorphisms(self, other, &[], 1, &mut budget.meter(), &mut |map| {
+            mapping = Some(self.label_mapping(other, map));
+        })?;
+        Ok(mapping)
+    }
+
+    /// Enumerates up to limit automorphisms of the graph as label mappings
+    pub fn automorphisms(&self, limit: usize) -> Vec<Vec<(String, String)>> {
+        self.automorphisms_within(limit, &Budget::unlimited())
+            .unwrap_or_default()
+    }
+
+    /// Same as automorphisms, stopping with BudgetExceeded when the budget runs out
+    pub fn automorphisms_within(
+        &self,
+        limit: usize,
+        budget: &Budget,
+    ) -> Result<Vec<Vec<(String, String)>>, GraphError> {
+        let mut result = Vec::new();
+        search_isomorphisms(self, self, &[], limit, &mut budget.meter(), &mut |map| {
+            result.push(self.label_mapping(self, map));
+        })?;
+        Ok(result)
+    }
+
+    /// Groups the vertices that some automorphism maps onto each other
+    /// Each orbit is sorted alphabetically and orbits are ordered by their smallest label
+    pub fn vertex_orbits(&self) -> Vec<Vec<String>> {
+        self.vertex_orbits_within(&Budget::unlimited())
+            .unwrap_or_default()
+    }
+
+    /// Same as vertex_orbits, giving up with None after the given number of search steps
+    pub fn vertex_orbits_with_budget(&self, budget: usize) -> Option<Vec<Vec<String>>> {
+        self.vertex_orbits_within(&Budget::nodes(budget as u64)).ok()
+    }
+
+    /// Same as vertex_orbits, stopping with BudgetExceeded when the budget runs out
+    pub fn vertex_orbits_within(&self, budget: &Budget) -> Result<Vec<Vec<String>>, GraphError> {
+        let mut meter = budget.meter();
+        let sorted = self.sort_vertices();
+        let mut orbit_of: Vec<Option<usize>> = vec![None; self.n_vertices];
+        let mut orbits: Vec<Vec<String>> = Vec::new();
+
+        for (pos, &v) in sorted.iter().enumerate() {
+            if orbit_of[v].is_some() {
+                continue;
+            }
+            let id = orbits.len();
+            orbit_of[v] = Some(id);
+            let mut orbit = vec![self.vertices[v].clone()];
+
+            // One automorphism sending v to w is enough to put w in v's orbit
+            for &w in &sorted[pos + 1..] {
+                if orbit_of[w].is_some() {
+                    continue;
+                }
+                if search_isomorphisms(self, self, &[(v, w)], 1, &mut meter, &mut |_| {})? > 0 {
+                    orbit_of[w] = Some(id);
+                    orbit.push(self.vertices[w].clone());
+                }
+            }
+            orbits.push(orbit);
+        }
+
+        Ok(orbits)
+    }
+
+    /// To create/write an output file that lists the vertex orbits of a graph in set notation
+    pub fn produce_output_file_orbits(&self, base_name: &str) -> Result<(), GraphError> {
+        self.produce_output_file_orbits_within(base_name, &Budget::unlimited())?;
+        Ok(())
+    }
+
+    /// Same as produce_output_file_orbits under a budget, returning the orbits it wrote
+    pub fn produce_output_file_orbits_within(
+        &self,
+        base_name: &str,
+        budget: &Budget,
+    ) -> Result<Vec<Vec<String>>, GraphError> {
+        self.produce_output_file_orbits_with(base_name, budget, &OutputConfig::new())
+    }
+
+    /// Same as produce_output_file_orbits_within, cutting the file at the "orbits" limit
+    pub fn produce_output_file_orbits_with(
+        &self,
+        base_name: &str,
+        budget: &Budget,
+        config: &OutputConfig,
+    ) -> Result<Vec<Vec<String>>, GraphError> {
+        let orbits = self.vertex_orbits_within(budget)?;
+        let output_name = Self::get_output_filename(base_name, "-ORBITS");
+        let mut fp = File::create(output_name)?;
+
+        let lines = orbits.iter().map(|orbit| format!("{{{}}}", orbit.join(",")));
+        write_section(&mut fp, lines, ItemTotal::Exact(orbits.len()), config.limit("orbits"))?;
+
+        Ok(orbits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::Dialect;
+
+    fn graph_from(text: &str) -> Graph {
+        let mut graph = Graph::new();
+        graph.read_input_text_with(text, Dialect::Strict).unwrap();
+        graph
+    }
+
+    fn strings(groups: &[&[&str]]) -> Vec<Vec<String>> {
+        groups.iter().map(|group| group.iter().map(|s| s.to_string()).collect()).collect()
+    }
+
+    #[test]
+    fn cycle_c5_has_ten_automorphisms_and_one_orbit() {
+        let c5 = graph_from("5\nA B E -1\nB A C -1\nC B D -1\nD C E -1\nE D A -1\n");
+        let automorphisms = c5.automorphisms(usize::MAX);
+        // Five rotations, each with or without a reflection
+        assert_eq!(automorphisms.len(), 10);
+        assert!(automorphisms.iter().any(|map| map.iter().all(|(u, v)| u == v)));
+        assert_eq!(c5.vertex_orbits(), strings(&[&["A", "B", "C", "D", "E"]]));
+        assert_eq!(c5.automorphisms(3).len(), 3);
+    }
+
+    #[test]
+    fn path_p4_has_two_automorphisms_and_two_orbits() {
+        let p4 = graph_from("4\nA B -1\nB A C -1\nC B D -1\nD C -1\n");
+        let automorphisms = p4.automorphisms(usize::MAX);
+        assert_eq!(automorphisms.len(), 2);
+        let reversal: Vec<(String, String)> =
+            [("A", "D"), ("B", "C"), ("C", "B"), ("D", "A")].map(|(u, v)| (u.to_string(), v.to_string())).to_vec();
+        assert!(automorphisms.contains(&reversal), "{:?}", automorphisms);
+        assert_eq!(p4.vertex_orbits(), strings(&[&["A", "D"], &["B", "C"]]));
+    }
+}
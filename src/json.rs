@@ -0,0 +1,51 @@
+// src/json.rs - Export to a node-link JSON document
+//
+// The document is one object with three keys, always in this order:
+//   {"directed":false,"vertices":["A","B","C"],"edges":[["A","B"],["B","C"]]}
+// Vertices are sorted alphabetically and edges follow export_edges as in the DOT
+// and GraphML exports, so the same graph always gives the same bytes. A graph
+// without one-way pairs is undirected with each edge once; any other graph is
+// directed, a pair listed both ways giving both arcs.
+use crate::error::{json_string, GraphError};
+use crate::Graph;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+impl Graph {
+    /// Writes the graph as a node-link JSON document, followed by a newline
+    pub fn write_json(&self, out: &mut dyn Write) -> io::Result<()> {
+        let vertices: Vec<String> = self
+            .cached_sorted()
+            .iter()
+            .map(|&v| json_string(&self.vertices[v]))
+            .collect();
+        let (directed, edges) = self.export_edges();
+        let edges: Vec<String> = edges
+            .iter()
+            .map(|edge| format!("[{},{}]", json_string(&edge.source), json_string(&edge.target)))
+            .collect();
+        writeln!(
+            out,
+            "{{\"directed\":{},\"vertices\":[{}],\"edges\":[{}]}}",
+            directed,
+            vertices.join(","),
+            edges.join(",")
+        )
+    }
+
+    /// Returns the graph as a node-link JSON document; see write_json
+    pub fn to_json(&self) -> String {
+        let mut out = Vec::new();
+        // Writing to a Vec cannot fail, and json_string only produces UTF-8
+        let _ = self.write_json(&mut out);
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    /// To create/write the graph as a node-link JSON document, named base-GRAPH.json
+    pub fn produce_output_file_json(&self, base_name: &str) -> Result<(), GraphError> {
+        let mut fp = BufWriter::new(File::create(format!("{}-GRAPH.json", base_name))?);
+        self.write_json(&mut fp)?;
+        fp.flush()?;
+        Ok(())
+    }
+}
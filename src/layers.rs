@@ -0,0 +1,94 @@
+// src/layers.rs - BFS levels of a start, listed and drawn layer by layer
+use crate::error::GraphError;
+use crate::Graph;
+use std::fs::File;
+use std::io::Write;
+
+impl Graph {
+    /// Returns the BFS level of every vertex from a start, in input order
+    /// The start is at level 0; None marks vertices that cannot be reached
+    pub fn bfs_levels(&self, start: &str) -> Result<Vec<Option<usize>>, GraphError> {
+        let start_idx = self.require_vertex_idx(start)?;
+        let (dist, _) = self.bfs_tree(start_idx);
+        Ok(dist)
+    }
+
+    /// Groups the vertices reachable from a start by BFS level, each layer sorted alphabetically
+    /// Layer k holds the vertices whose bfs_levels entry is k
+    pub fn bfs_layers(&self, start: &str) -> Result<Vec<Vec<String>>, GraphError> {
+        Ok(self.group_levels(&self.bfs_levels(start)?))
+    }
+
+    /// Groups labels by level, each group sorted alphabetically; unreached vertices are left out
+    fn group_levels(&self, levels: &[Option<usize>]) -> Vec<Vec<String>> {
+        let mut layers: Vec<Vec<String>> = Vec::new();
+        for idx in self.sort_vertices() {
+            if let Some(level) = levels[idx] {
+                if layers.len() <= level {
+                    layers.resize(level + 1, Vec::new());
+                }
+                layers[level].push(self.vertices[idx].clone());
+            }
+        }
+        layers
+    }
+
+    /// Counts the pairs joined, in either direction, between one layer and the next
+    fn edges_between(&self, upper: &[String], lower: &[String]) -> usize {
+        let indices = |layer: &[String]| -> Vec<usize> {
+            layer.iter().filter_map(|label| self.find_vertex_idx(label)).collect()
+        };
+        let lower = indices(lower);
+        indices(upper)
+            .iter()
+            .map(|&u| {
+                lower
+                    .iter()
+                    .filter(|&&v| self.adj_matrix[u][v] == 1 || self.adj_matrix[v][u] == 1)
+                    .count()
+            })
+            .sum()
+    }
+
+    /// To create/write an output file of the BFS layers of a start
+    ///
+    /// One `L<k>: {a,b,c}` line per layer is followed by a diagram with the vertices
+    /// of each layer centered on a row and the number of edges between consecutive
+    /// layers beside the connector. Vertices the start cannot reach end the file in
+    /// an `unreached: {...}` line.
+    pub fn produce_output_layers(&self, base_name: &str, start: &str) -> Result<(), GraphError> {
+        let levels = self.bfs_levels(start)?;
+        let layers = self.group_levels(&levels);
+        let output_name = Self::get_output_filename(base_name, "-LAYERS");
+        let mut fp = File::create(output_name)?;
+
+        for (k, layer) in layers.iter().enumerate() {
+            writeln!(fp, "L{}: {{{}}}", k, layer.join(","))?;
+        }
+
+        let rows: Vec<String> = layers.iter().map(|layer| layer.join(" ")).collect();
+        let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+        writeln!(fp)?;
+        for (k, row) in rows.iter().enumerate() {
+            if k > 0 {
+                let count = self.edges_between(&layers[k - 1], &layers[k]);
+                let noun = if count == 1 { "edge" } else { "edges" };
+                let connector = format!("{:^width$}", "|", width = width);
+                writeln!(fp, "{}  {} {}", connector.trim_end(), count, noun)?;
+            }
+            writeln!(fp, "{}", format!("{:^width$}", row, width = width).trim_end())?;
+        }
+
+        let unreached: Vec<&str> = self
+            .sort_vertices()
+            .into_iter()
+            .filter(|&idx| levels[idx].is_none())
+            .map(|idx| self.vertices[idx].as_str())
+            .collect();
+        if !unreached.is_empty() {
+            writeln!(fp, "unreached: {{{}}}", unreached.join(","))?;
+        }
+
+        Ok(())
+    }
+}
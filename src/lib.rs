@@ -1,14 +1,24 @@
 // src/lib.rs - Main library module
+use crate::cache::AnalysisCache;
+use crate::edge_id::EdgeRegistry;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Vertices Graph::new makes room for; larger graphs grow the representations as they are read
 pub const MAX_VERTICES: usize = 20;
+/// Most vertices a graph may have, since the dense adjacency matrix takes 4 bytes per
+/// ordered pair (400 MB at this size)
+pub const MATRIX_VERTEX_LIMIT: usize = 10_000;
 
 #[derive(Debug, Clone)]
 pub struct AdjNode {
-    pub vertex: String,
+    /// Label of the neighbor, shared with every other node naming the same vertex
+    pub vertex: Arc<str>,
     pub next: Option<Box<AdjNode>>,
 }
 
 impl AdjNode {
-    pub fn new(vertex: String) -> Self {
+    pub fn new(vertex: Arc<str>) -> Self {
         AdjNode {
             vertex,
             next: None,
@@ -16,13 +26,22 @@ impl AdjNode {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Debug is implemented by hand in graph_impl.rs, to show the logical structure
+#[derive(Clone)]
 pub struct Graph {
     pub vertices: Vec<String>,
     pub n_vertices: usize,
     pub adj_matrix: Vec<Vec<i32>>,
     pub adj_list: Vec<Option<Box<AdjNode>>>,
     pub adj_count: Vec<i32>,
+    pub coordinates: HashMap<usize, (f64, f64)>,
+    /// Name the outputs call the graph by; read_input_file sets it to the file stem
+    pub name: Option<String>,
+    pub(crate) cache: AnalysisCache,
+    /// One shared copy of every label used in the adjacency list
+    pub(crate) label_pool: HashSet<Arc<str>>,
+    /// EdgeIds of the edges and the attributes stored under them
+    pub(crate) edge_registry: EdgeRegistry,
 }
 
 impl Graph {
@@ -33,10 +52,95 @@ impl Graph {
             adj_matrix: vec![vec![0; MAX_VERTICES]; MAX_VERTICES],
             adj_list: vec![None; MAX_VERTICES],
             adj_count: vec![0; MAX_VERTICES],
+            coordinates: HashMap::new(),
+            name: None,
+            cache: AnalysisCache::default(),
+            label_pool: HashSet::new(),
+            edge_registry: EdgeRegistry::default(),
         }
     }
 }
 
+impl Default for Graph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // Include the implementation modules
+//
+// The prelude re-exports the stable core, covered by semver. Modules behind the
+// unstable-algorithms feature (on by default) may change in a minor release.
+pub mod algo;
+pub mod bipartite;
+mod brandes;
+pub mod budget;
+pub mod cache;
+#[cfg(feature = "unstable-algorithms")]
+pub mod centrality;
+pub mod cliques;
+#[cfg(feature = "unstable-algorithms")]
+pub mod communities;
+pub mod compare;
+pub mod components;
+pub mod coordinates;
+pub mod csv;
+pub mod cycles;
+pub mod dag;
+pub mod dedupe;
+pub mod density;
+pub mod dialect;
+pub mod diameter;
+pub mod digest;
+pub mod direction;
+pub mod disjoint_set;
+pub mod dot;
+pub mod edge_id;
+pub mod edge_list;
+pub mod edit;
+pub mod edges;
+pub mod error;
+#[cfg(feature = "unstable-algorithms")]
+pub mod examples;
+#[cfg(feature = "test-support")]
+pub mod fixtures;
+pub mod explain;
+pub mod flow;
+pub mod formats;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+#[cfg(feature = "unstable-algorithms")]
+pub mod generators;
+pub mod gomory_hu;
+pub mod graph6;
 pub mod graph_impl;
+pub mod graphml;
+pub mod isomorphism;
+pub mod json;
+pub mod layers;
+pub mod lint;
+pub mod memory;
+pub mod merge;
+pub mod metrics;
+pub mod minimize;
+pub mod motifs;
+pub mod mst;
+pub mod nearest;
+pub mod output;
+pub mod planarity;
+pub mod prelude;
+pub mod profile;
+pub mod reachability;
+pub mod require;
+pub mod rng;
+#[cfg(feature = "serde")]
+mod serde_impl;
+pub mod shortest_path;
+pub mod stats;
 pub mod subgraph_impl;
+pub mod suggest;
+pub mod tgf;
+pub mod validation;
+pub mod vertex_ref;
+pub mod view;
+pub mod weights;
@@ -0,0 +1,426 @@
+// src/lint.rs - Checking input files for mistakes, and fixing the safe ones
+//
+// The linter reads the raw text rather than a parsed graph, so it can point at
+// lines and see what the reader silently tolerates. Safe fixes change nothing a
+// reader could have meant differently: a byte order mark, CR line endings, a
+// missing -1 or count line, tokens after -1 and repeated neighbors. Everything
+// else is reported for a person to decide, including edges listed in one direction
+// only, which may be arcs of a directed graph rather than forgotten entries.
+use crate::dialect::Dialect;
+use crate::error::{file_error, GraphError};
+use crate::weights::split_neighbor_token;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// How serious a finding is; errors make strict reading fail or lose information
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// One problem found in an input file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    pub severity: Severity,
+    /// Line number counted from 1, or None for the file as a whole
+    pub line: Option<usize>,
+    pub message: String,
+    /// What would fix it; lint --fix applies it when safe is true
+    pub suggested_fix: Option<String>,
+    pub safe: bool,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        write!(f, "{}: {}", self.severity, self.message)?;
+        if let Some(fix) = &self.suggested_fix {
+            write!(f, " (fix: {})", fix)?;
+        }
+        Ok(())
+    }
+}
+
+/// Every finding of a file, in line order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintReport {
+    pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+    /// Counts the findings of error severity
+    pub fn error_count(&self) -> usize {
+        self.findings.iter().filter(|finding| finding.severity == Severity::Error).count()
+    }
+
+    /// Checks whether a safe fix is available for some finding
+    pub fn has_safe_fixes(&self) -> bool {
+        self.findings.iter().any(|finding| finding.safe)
+    }
+}
+
+impl fmt::Display for LintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for finding in &self.findings {
+            writeln!(f, "{}", finding)?;
+        }
+        Ok(())
+    }
+}
+
+/// One adjacency line as the linter sees it
+struct LintRow {
+    line: usize,
+    label: String,
+    /// Neighbor labels, without the inline weights of tokens such as B:5
+    neighbors: Vec<String>,
+    /// The ":5" of each weighted neighbor, empty for the others, kept for fix_text
+    weight_suffixes: Vec<String>,
+    terminated: bool,
+    /// Tokens after the -1, which the reader ignores
+    trailing: Vec<String>,
+}
+
+/// The file split into its parts, with the findings about its layout
+struct Parsed {
+    count: Option<usize>,
+    rows: Vec<LintRow>,
+    findings: Vec<LintFinding>,
+}
+
+fn finding(severity: Severity, line: Option<usize>, message: String, fix: Option<&str>, safe: bool) -> LintFinding {
+    LintFinding {
+        severity,
+        line,
+        message,
+        suggested_fix: fix.map(str::to_string),
+        safe,
+    }
+}
+
+/// Splits the text into the count and adjacency rows, noting BOM, CR and count problems
+fn parse(text: &str, dialect: Dialect) -> Parsed {
+    let mut findings = Vec::new();
+    let body = match text.strip_prefix('\u{feff}') {
+        Some(body) => {
+            findings.push(finding(
+                Severity::Warning,
+                Some(1),
+                "file starts with a byte order mark".to_string(),
+                Some("strip the byte order mark"),
+                true,
+            ));
+            body
+        }
+        None => text,
+    };
+
+    // Split on LF only, since lines() would hide the CR of CR LF endings
+    let mut lines: Vec<&str> = body.split('\n').collect();
+    if lines.last() == Some(&"") {
+        lines.pop();
+    }
+    let cr_lines: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.ends_with('\r'))
+        .map(|(i, _)| i + 1)
+        .collect();
+    if let Some(&first) = cr_lines.first() {
+        findings.push(finding(
+            Severity::Warning,
+            Some(first),
+            format!("{} lines end with CR LF", cr_lines.len()),
+            Some("use LF line endings"),
+            true,
+        ));
+    }
+    let lines: Vec<&str> = lines.iter().map(|line| line.trim_end_matches('\r')).collect();
+
+    let first_tokens: Vec<&str> = lines.first().map(|line| line.split_whitespace().collect()).unwrap_or_default();
+    let count = match first_tokens.as_slice() {
+        [token] => token.parse::<usize>().ok(),
+        _ => None,
+    };
+    let first_adjacency = usize::from(count.is_some());
+
+    let labels: HashSet<&str> = lines
+        .iter()
+        .skip(first_adjacency)
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+    let mut rows = Vec::new();
+    for (i, line) in lines.iter().enumerate().skip(first_adjacency) {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some((label, rest)) = tokens.split_first() else {
+            continue;
+        };
+        let end = rest.iter().position(|&token| token == "-1");
+        let (neighbors, weight_suffixes) = rest[..end.unwrap_or(rest.len())]
+            .iter()
+            .map(|token| {
+                let (neighbor, _) = split_neighbor_token(token, |label| labels.contains(label));
+                (neighbor.to_string(), token[neighbor.len()..].to_string())
+            })
+            .unzip();
+        rows.push(LintRow {
+            line: i + 1,
+            label: label.to_string(),
+            neighbors,
+            weight_suffixes,
+            terminated: end.is_some(),
+            trailing: end.map_or(Vec::new(), |end| rest[end + 1..].iter().map(|token| token.to_string()).collect()),
+        });
+    }
+
+    match count {
+        None => {
+            // Only the strict reader refuses the file; the others infer the same count
+            let severity = if dialect == Dialect::Strict { Severity::Error } else { Severity::Warning };
+            findings.push(finding(
+                severity,
+                Some(1),
+                "missing vertex count line".to_string(),
+                Some(&format!("insert the count {}", rows.len())),
+                true,
+            ));
+        }
+        Some(n) if n != rows.len() => findings.push(finding(
+            Severity::Error,
+            Some(1),
+            format!("count line says {} vertices but {} adjacency lines follow", n, rows.len()),
+            Some("correct the count, or add or remove adjacency lines"),
+            false,
+        )),
+        Some(_) => {}
+    }
+
+    Parsed {
+        count,
+        rows,
+        findings,
+    }
+}
+
+/// Checks whether a label is made of ASCII digits only
+fn is_number(label: &str) -> bool {
+    !label.is_empty() && label.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Lints the text of an input file
+pub fn lint_text(text: &str, dialect: Dialect) -> LintReport {
+    let Parsed { rows, mut findings, .. } = parse(text, dialect);
+    let mut first_line: HashMap<&str, usize> = HashMap::new();
+    for row in &rows {
+        first_line.entry(row.label.as_str()).or_insert(row.line);
+    }
+
+    for row in &rows {
+        let line = Some(row.line);
+        if first_line[row.label.as_str()] != row.line {
+            findings.push(finding(
+                Severity::Error,
+                line,
+                format!("vertex {} already has a line (line {})", row.label, first_line[row.label.as_str()]),
+                Some("merge the two lines"),
+                false,
+            ));
+        }
+        if !row.terminated {
+            findings.push(finding(
+                Severity::Warning,
+                line,
+                format!("line of {} does not end with -1", row.label),
+                Some("append -1"),
+                true,
+            ));
+        }
+        if !row.trailing.is_empty() {
+            findings.push(finding(
+                Severity::Warning,
+                line,
+                format!("tokens after -1 are ignored: {}", row.trailing.join(" ")),
+                Some("remove the tokens after -1"),
+                true,
+            ));
+        }
+
+        let mut seen: Vec<&str> = Vec::new();
+        for neighbor in &row.neighbors {
+            if seen.contains(&neighbor.as_str()) {
+                findings.push(finding(
+                    Severity::Warning,
+                    line,
+                    format!("{} lists {} more than once", row.label, neighbor),
+                    Some("remove the repeated neighbor"),
+                    true,
+                ));
+                continue;
+            }
+            seen.push(neighbor);
+
+            if *neighbor == row.label {
+                findings.push(finding(
+                    Severity::Info,
+                    line,
+                    format!("{} has a self-loop", row.label),
+                    None,
+                    false,
+                ));
+                continue;
+            }
+            match rows.iter().find(|other| other.label == *neighbor) {
+                None => findings.push(finding(
+                    Severity::Error,
+                    line,
+                    format!("{} lists {}, which has no line of its own", row.label, neighbor),
+                    Some(&format!("add a line for {} or remove it from the line of {}", neighbor, row.label)),
+                    false,
+                )),
+                Some(other) if !other.neighbors.contains(&row.label) => findings.push(finding(
+                    Severity::Warning,
+                    line,
+                    format!("{} lists {} but {} does not list {}", row.label, neighbor, neighbor, row.label),
+                    Some(&format!("add {} to the line of {}, unless the graph is directed", row.label, neighbor)),
+                    false,
+                )),
+                Some(_) => {}
+            }
+        }
+    }
+
+    let numeric: Vec<&str> = rows.iter().map(|row| row.label.as_str()).filter(|label| is_number(label)).collect();
+    let widths: Vec<usize> = numeric.iter().map(|label| label.len()).collect();
+    if widths.iter().min() != widths.iter().max() {
+        findings.push(finding(
+            Severity::Info,
+            None,
+            "numeric labels of different lengths sort as text, so 10 comes before 9".to_string(),
+            Some("pad the numeric labels with leading zeros to one width"),
+            false,
+        ));
+    }
+
+    findings.sort_by_key(|finding| finding.line.unwrap_or(usize::MAX));
+    LintReport { findings }
+}
+
+/// Lints an input file
+pub fn lint(path: &Path, dialect: Dialect) -> Result<LintReport, GraphError> {
+    let text = fs::read_to_string(path).map_err(file_error(&path.display().to_string()))?;
+    Ok(lint_text(&text, dialect))
+}
+
+/// Applies the safe fixes to the text of an input file and returns the corrected text
+///
+/// The result has LF endings and no byte order mark, a count line, -1 at the end
+/// of every line and no repeated neighbors. A count that disagrees with the lines
+/// is kept, and so is a neighbor listed in one direction only.
+pub fn fix_text(text: &str, dialect: Dialect) -> String {
+    let Parsed { count, mut rows, .. } = parse(text, dialect);
+    for row in rows.iter_mut() {
+        let mut unique: Vec<String> = Vec::with_capacity(row.neighbors.len());
+        let mut suffixes: Vec<String> = Vec::with_capacity(row.neighbors.len());
+        for (neighbor, suffix) in row.neighbors.drain(..).zip(row.weight_suffixes.drain(..)) {
+            if !unique.contains(&neighbor) {
+                unique.push(neighbor);
+                suffixes.push(suffix);
+            }
+        }
+        row.neighbors = unique;
+        row.weight_suffixes = suffixes;
+    }
+
+    let mut fixed = format!("{}\n", count.unwrap_or(rows.len()));
+    for row in &rows {
+        fixed.push_str(&row.label);
+        for (neighbor, suffix) in row.neighbors.iter().zip(&row.weight_suffixes) {
+            fixed.push(' ');
+            fixed.push_str(neighbor);
+            fixed.push_str(suffix);
+        }
+        fixed.push_str(" -1\n");
+    }
+    fixed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Graph;
+
+    /// BOM, CR LF endings, no count line, a repeated neighbor, an unterminated line,
+    /// tokens after -1 and a one-way edge, in one file
+    const MESSY: &str = "\u{feff}A B B C -1\r\nB A -1 junk\r\nC A D\r\nD -1\r\n";
+
+    fn messages(report: &LintReport) -> Vec<&str> {
+        report.findings.iter().map(|finding| finding.message.as_str()).collect()
+    }
+
+    #[test]
+    fn messy_file_findings() {
+        let report = lint_text(MESSY, Dialect::Strict);
+        assert_eq!(
+            messages(&report),
+            vec![
+                "file starts with a byte order mark",
+                "4 lines end with CR LF",
+                "missing vertex count line",
+                "A lists B more than once",
+                "tokens after -1 are ignored: junk",
+                "line of C does not end with -1",
+                "C lists D but D does not list C",
+            ]
+        );
+        assert_eq!(report.error_count(), 1);
+        assert!(report.has_safe_fixes());
+        assert!(report.findings.iter().filter(|finding| finding.message.contains("does not list")).all(|f| !f.safe));
+    }
+
+    #[test]
+    fn missing_count_is_only_an_error_when_strict() {
+        assert_eq!(lint_text("A -1\n", Dialect::Strict).error_count(), 1);
+        assert_eq!(lint_text("A -1\n", Dialect::InferCount).error_count(), 0);
+    }
+
+    #[test]
+    fn fixed_text_reads_strictly_and_keeps_one_way_edges() {
+        let fixed = fix_text(MESSY, Dialect::Strict);
+        assert_eq!(fixed, "4\nA B C -1\nB A -1\nC A D -1\nD -1\n");
+        let report = lint_text(&fixed, Dialect::Strict);
+        assert_eq!(messages(&report), vec!["C lists D but D does not list C"]);
+        assert!(!report.has_safe_fixes());
+        let mut graph = Graph::new();
+        graph.read_input_text_with(&fixed, Dialect::Directed).unwrap();
+        assert_eq!((graph.adj_matrix[2][3], graph.adj_matrix[3][2]), (1, 0));
+    }
+
+    #[test]
+    fn duplicate_lines_dangling_neighbors_and_numeric_widths() {
+        let report = lint_text("3\n9 10 -1\n10 9 X -1\n9 -1\n", Dialect::Strict);
+        assert_eq!(
+            messages(&report),
+            vec![
+                "10 lists X, which has no line of its own",
+                "vertex 9 already has a line (line 2)",
+                "numeric labels of different lengths sort as text, so 10 comes before 9",
+            ]
+        );
+    }
+}
@@ -0,0 +1,70 @@
+// src/memory.rs - Estimated heap usage of the graph representations
+use crate::edge_id::EdgeId;
+use crate::{AdjNode, Graph};
+use std::collections::HashSet;
+use std::mem::size_of;
+use std::sync::Arc;
+
+/// Approximate heap bytes held by each part of a graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryFootprint {
+    /// The vertex labels
+    pub labels: usize,
+    /// The adjacency matrix
+    pub matrix: usize,
+    /// The adjacency list nodes, their shared neighbor labels and the degree counts
+    pub adj_list: usize,
+    /// Weights, coordinates and the label pool
+    pub other: usize,
+}
+
+impl MemoryFootprint {
+    /// Returns the sum of all parts
+    pub fn total(&self) -> usize {
+        self.labels + self.matrix + self.adj_list + self.other
+    }
+}
+
+impl Graph {
+    /// Estimates the heap memory used by the graph
+    ///
+    /// Allocator overhead and the cached analysis results are not counted. A label
+    /// shared between several adjacency nodes is counted once.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        let labels = self.vertices.capacity() * size_of::<String>()
+            + self.vertices.iter().map(String::capacity).sum::<usize>();
+
+        let matrix = self.adj_matrix.capacity() * size_of::<Vec<i32>>()
+            + self
+                .adj_matrix
+                .iter()
+                .map(|row| row.capacity() * size_of::<i32>())
+                .sum::<usize>();
+
+        // Every distinct label allocation holds two reference counts and the bytes
+        let mut seen: HashSet<*const u8> = HashSet::new();
+        let mut adj_list = self.adj_list.capacity() * size_of::<Option<Box<AdjNode>>>()
+            + self.adj_count.capacity() * size_of::<i32>();
+        for head in &self.adj_list {
+            let mut current = head;
+            while let Some(node) = current {
+                adj_list += size_of::<AdjNode>();
+                if seen.insert(node.vertex.as_ptr()) {
+                    adj_list += 2 * size_of::<usize>() + node.vertex.len();
+                }
+                current = &node.next;
+            }
+        }
+
+        let other = self.edge_registry.weights_capacity() * size_of::<(EdgeId, [Option<f64>; 2])>()
+            + self.coordinates.capacity() * size_of::<(usize, (f64, f64))>()
+            + self.label_pool.capacity() * size_of::<Arc<str>>();
+
+        MemoryFootprint {
+            labels,
+            matrix,
+            adj_list,
+            other,
+        }
+    }
+}
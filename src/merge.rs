@@ -0,0 +1,126 @@
+// src/merge.rs - Merging graphs, with optional label namespaces
+use crate::dialect::Dialect;
+use crate::error::GraphError;
+use crate::Graph;
+use std::path::Path;
+
+/// Decides how the labels of a merged graph are named in the combined graph
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Namespace {
+    /// Labels are kept as they are; equal labels name the same vertex
+    #[default]
+    None,
+    /// Labels are prefixed with the source name, so `a` from file1.txt becomes `file1:a`
+    PrefixWithFile,
+    /// Labels are prefixed with the given string followed by `:`
+    Prefix(String),
+}
+
+impl Namespace {
+    /// Returns the label a vertex of the named source gets in the merged graph
+    pub fn apply(&self, source: &str, label: &str) -> String {
+        match self {
+            Namespace::None => label.to_string(),
+            Namespace::PrefixWithFile => format!("{}:{}", source, label),
+            Namespace::Prefix(prefix) => format!("{}:{}", prefix, label),
+        }
+    }
+}
+
+/// Records which label of which source each merged vertex came from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LabelMap {
+    /// (label in the merged graph, source name, original label), in merge order
+    pub entries: Vec<(String, String, String)>,
+}
+
+impl LabelMap {
+    /// Returns the source name and original label of a vertex in the merged graph
+    pub fn original(&self, merged: &str) -> Option<(&str, &str)> {
+        self.entries
+            .iter()
+            .find(|(label, _, _)| label == merged)
+            .map(|(_, source, original)| (source.as_str(), original.as_str()))
+    }
+
+    /// Returns the label a vertex of the given source has in the merged graph
+    pub fn merged(&self, source: &str, original: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(_, s, o)| s == source && o == original)
+            .map(|(label, _, _)| label.as_str())
+    }
+}
+
+impl Graph {
+    /// Adds the vertices and edges of another graph to this one
+    /// Labels of the other graph, and the neighbor references inside it, are renamed
+    /// by the namespace policy; a renamed label equal to an existing one joins that vertex.
+    /// The returned map records the renaming so that it can be reversed.
+    pub fn merge(&mut self, other: &Graph, source: &str, namespace: &Namespace) -> Result<LabelMap, GraphError> {
+        let mut rows = self.adjacency_rows();
+        let mut map = LabelMap::default();
+
+        // Position of every vertex of the other graph in the merged rows
+        let mut positions = Vec::with_capacity(other.n_vertices);
+        for (label, neighbors) in other.adjacency_rows() {
+            let merged = namespace.apply(source, &label);
+            let neighbors: Vec<String> = neighbors
+                .iter()
+                .map(|neighbor| namespace.apply(source, neighbor))
+                .collect();
+            map.entries.push((merged.clone(), source.to_string(), label));
+
+            match rows.iter().position(|(existing, _)| *existing == merged) {
+                Some(pos) => {
+                    let row = &mut rows[pos].1;
+                    for neighbor in neighbors {
+                        if !row.contains(&neighbor) {
+                            row.push(neighbor);
+                        }
+                    }
+                    positions.push(pos);
+                }
+                None => {
+                    positions.push(rows.len());
+                    rows.push((merged, neighbors));
+                }
+            }
+        }
+
+        Graph::check_vertex_limit(rows.len())?;
+
+        // Weights and coordinates of this graph keep their indices; the other graph's are moved
+        let mut coordinates = std::mem::take(&mut self.coordinates);
+        for (&v, &position) in &other.coordinates {
+            coordinates.entry(positions[v]).or_insert(position);
+        }
+
+        self.rebuild_from_rows(&rows);
+        for ((u, v), weight) in other.weight_entries() {
+            let (u, v) = (positions[u], positions[v]);
+            if self.stored_weight(u, v).is_none() {
+                self.store_weight(u, v, weight);
+            }
+        }
+        self.coordinates = coordinates;
+
+        Ok(map)
+    }
+
+    /// Reads a graph file and merges it into this one
+    /// The file name without directory or extension is the source name used by PrefixWithFile
+    pub fn merge_from_file(&mut self, path: &str, namespace: &Namespace) -> Result<LabelMap, GraphError> {
+        let mut other = Graph::new();
+        other
+            .read_input_file_with(path, Dialect::Strict)
+            .map_err(|error| GraphError::Io(error.to_string()))?;
+
+        let file_name = Path::new(path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(path);
+        let source = Graph::get_base_filename(file_name);
+        self.merge(&other, &source, namespace)
+    }
+}
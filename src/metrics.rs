@@ -0,0 +1,254 @@
+// src/metrics.rs - Per-vertex metrics computed together and written as one CSV table
+//
+// Edges count in either direction for every metric but degree, strength and
+// pagerank, which follow the adjacency lines as written. Work shared by several
+// metrics is done once per call: a single BFS from every vertex feeds both
+// closeness and eccentricity.
+use crate::csv::csv_field;
+use crate::error::GraphError;
+use crate::Graph;
+use std::fs::File;
+use std::io::Write;
+
+/// Damping factor of PageRank, the chance of following an edge rather than jumping
+pub const PAGERANK_DAMPING: f64 = 0.85;
+/// PageRank stops once no score moves by more than this in an iteration
+const PAGERANK_TOLERANCE: f64 = 1e-10;
+/// PageRank stops after this many iterations even if the scores still move
+const PAGERANK_MAX_ITERATIONS: usize = 1_000;
+
+/// A number computed for every vertex
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexMetric {
+    /// Entries in the vertex's adjacency line
+    Degree,
+    /// Sum of the weights of the vertex's edges; needs weights
+    Strength,
+    /// Largest k such that the vertex lies in the k-core
+    Coreness,
+    /// Share of the pairs of neighbors that are joined themselves
+    Clustering,
+    /// Reached vertices less one over the sum of their hop distances; 0 when none are reached
+    Closeness,
+    /// Exact Brandes betweenness, as Graph::betweenness
+    Betweenness,
+    PageRank,
+    /// Largest hop distance to a vertex of the same component
+    Eccentricity,
+    /// Number of the connected component, as Graph::component_ids
+    Component,
+}
+
+impl VertexMetric {
+    /// Every metric, in the column order used by default
+    pub const ALL: [VertexMetric; 9] = [
+        VertexMetric::Degree,
+        VertexMetric::Strength,
+        VertexMetric::Coreness,
+        VertexMetric::Clustering,
+        VertexMetric::Closeness,
+        VertexMetric::Betweenness,
+        VertexMetric::PageRank,
+        VertexMetric::Eccentricity,
+        VertexMetric::Component,
+    ];
+
+    /// Returns the CSV column name, which is also the name accepted by from_name
+    pub fn name(&self) -> &'static str {
+        match self {
+            VertexMetric::Degree => "degree",
+            VertexMetric::Strength => "strength",
+            VertexMetric::Coreness => "coreness",
+            VertexMetric::Clustering => "clustering",
+            VertexMetric::Closeness => "closeness",
+            VertexMetric::Betweenness => "betweenness",
+            VertexMetric::PageRank => "pagerank",
+            VertexMetric::Eccentricity => "eccentricity",
+            VertexMetric::Component => "component",
+        }
+    }
+
+    /// Parses a metric from its column name
+    pub fn from_name(name: &str) -> Option<VertexMetric> {
+        VertexMetric::ALL.into_iter().find(|metric| metric.name() == name)
+    }
+
+    /// Whether every value of the metric is a whole number
+    fn is_count(&self) -> bool {
+        matches!(
+            self,
+            VertexMetric::Degree | VertexMetric::Coreness | VertexMetric::Eccentricity | VertexMetric::Component
+        )
+    }
+
+    /// Formats a value as a CSV field: whole numbers bare, the rest to 6 decimals
+    pub fn format(&self, value: f64) -> String {
+        if self.is_count() {
+            format!("{}", value as u64)
+        } else {
+            format!("{:.6}", value)
+        }
+    }
+}
+
+/// The metrics of one vertex, in the order they were asked for
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexMetricsRow {
+    pub label: String,
+    pub values: Vec<f64>,
+}
+
+impl Graph {
+    /// Vertices joined to v in either direction, without v itself
+    fn undirected_neighbors(&self, v: usize) -> Vec<usize> {
+        (0..self.n_vertices).filter(|&u| u != v && self.linked(u, v)).collect()
+    }
+
+    /// Core number of every vertex, by repeatedly peeling a vertex of least remaining degree
+    fn core_numbers(&self, neighbors: &[Vec<usize>]) -> Vec<usize> {
+        let n = self.n_vertices;
+        let mut degree: Vec<usize> = neighbors.iter().map(Vec::len).collect();
+        let mut removed = vec![false; n];
+        let mut core = vec![0; n];
+        let mut k = 0;
+        for _ in 0..n {
+            let Some(v) = (0..n).filter(|&v| !removed[v]).min_by_key(|&v| degree[v]) else {
+                break;
+            };
+            k = k.max(degree[v]);
+            core[v] = k;
+            removed[v] = true;
+            for &u in &neighbors[v] {
+                if !removed[u] {
+                    degree[u] -= 1;
+                }
+            }
+        }
+        core
+    }
+
+    /// Local clustering coefficient of a vertex; 0 with fewer than two neighbors
+    fn clustering_of(&self, neighbors: &[Vec<usize>], v: usize) -> f64 {
+        let around = &neighbors[v];
+        let k = around.len();
+        if k < 2 {
+            return 0.0;
+        }
+        let mut links = 0;
+        for (i, &a) in around.iter().enumerate() {
+            links += around[i + 1..].iter().filter(|&&b| self.linked(a, b)).count();
+        }
+        links as f64 / (k * (k - 1) / 2) as f64
+    }
+
+    /// PageRank of every vertex by power iteration; a vertex without out-edges spreads
+    /// its score over every vertex
+    fn pagerank(&self) -> Vec<f64> {
+        let n = self.n_vertices;
+        if n == 0 {
+            return Vec::new();
+        }
+        let out: Vec<Vec<usize>> = (0..n)
+            .map(|v| (0..n).filter(|&u| self.adj_matrix[v][u] == 1).collect())
+            .collect();
+        let mut rank = vec![1.0 / n as f64; n];
+        for _ in 0..PAGERANK_MAX_ITERATIONS {
+            let dangling: f64 = (0..n).filter(|&v| out[v].is_empty()).map(|v| rank[v]).sum();
+            let base = (1.0 - PAGERANK_DAMPING + PAGERANK_DAMPING * dangling) / n as f64;
+            let mut next = vec![base; n];
+            for v in 0..n {
+                for &u in &out[v] {
+                    next[u] += PAGERANK_DAMPING * rank[v] / out[v].len() as f64;
+                }
+            }
+            let moved = rank.iter().zip(&next).map(|(a, b)| (a - b).abs()).fold(0.0, f64::max);
+            rank = next;
+            if moved <= PAGERANK_TOLERANCE {
+                break;
+            }
+        }
+        rank
+    }
+
+    /// Computes the requested metrics of every vertex, one row per vertex in alphabetical order
+    ///
+    /// The values of each row follow the order of `which`; a metric asked for twice
+    /// gets two columns. Strength needs edge weights and fails with InvalidParameter
+    /// naming it when the graph has none.
+    pub fn vertex_metrics(&self, which: &[VertexMetric]) -> Result<Vec<VertexMetricsRow>, GraphError> {
+        let n = self.n_vertices;
+        if which.contains(&VertexMetric::Strength) && !self.has_weights() {
+            return Err(GraphError::InvalidParameter {
+                name: "strength",
+                reason: "strength sums edge weights, and the graph has none; attach them first".to_string(),
+            });
+        }
+
+        let wants = |metric: VertexMetric| which.contains(&metric);
+        let neighbors: Vec<Vec<usize>> = if wants(VertexMetric::Coreness) || wants(VertexMetric::Clustering) {
+            (0..n).map(|v| self.undirected_neighbors(v)).collect()
+        } else {
+            Vec::new()
+        };
+
+        // One BFS per vertex gives both its closeness and its eccentricity
+        let (mut closeness, mut eccentricity) = (Vec::new(), Vec::new());
+        if wants(VertexMetric::Closeness) || wants(VertexMetric::Eccentricity) {
+            for v in 0..n {
+                let hops: Vec<usize> = self.undirected_hops(v).into_iter().flatten().collect();
+                let total: usize = hops.iter().sum();
+                closeness.push(if total == 0 { 0.0 } else { (hops.len() - 1) as f64 / total as f64 });
+                eccentricity.push(hops.iter().copied().max().unwrap_or(0) as f64);
+            }
+        }
+
+        let mut columns: Vec<Vec<f64>> = Vec::with_capacity(which.len());
+        for &metric in which {
+            let column = match metric {
+                VertexMetric::Degree => self.cached_degrees().iter().map(|&d| d as f64).collect(),
+                VertexMetric::Strength => (0..n)
+                    .map(|v| (0..n).filter(|&u| self.adj_matrix[v][u] == 1).map(|u| self.weight_at(v, u)).sum())
+                    .collect(),
+                VertexMetric::Coreness => self.core_numbers(&neighbors).iter().map(|&k| k as f64).collect(),
+                VertexMetric::Clustering => (0..n).map(|v| self.clustering_of(&neighbors, v)).collect(),
+                VertexMetric::Closeness => closeness.clone(),
+                VertexMetric::Betweenness => self.brandes_scores(&(0..n).collect::<Vec<_>>(), 1.0),
+                VertexMetric::PageRank => self.pagerank(),
+                VertexMetric::Eccentricity => eccentricity.clone(),
+                VertexMetric::Component => self.cached_component_ids().iter().map(|&id| id as f64).collect(),
+            };
+            columns.push(column);
+        }
+
+        Ok(self
+            .sort_vertices()
+            .into_iter()
+            .map(|v| VertexMetricsRow {
+                label: self.vertices[v].clone(),
+                values: columns.iter().map(|column| column[v]).collect(),
+            })
+            .collect())
+    }
+
+    /// To create/write a CSV table of per-vertex metrics, named base-METRICS.csv
+    /// The header reads `vertex` and then the metric names in the order of `which`,
+    /// followed by one row per vertex in alphabetical order, labels quoted as csv_field does
+    pub fn produce_output_file_metrics(
+        &self,
+        base_name: &str,
+        which: &[VertexMetric],
+    ) -> Result<(), GraphError> {
+        let rows = self.vertex_metrics(which)?;
+        let mut fp = File::create(format!("{}-METRICS.csv", base_name))?;
+
+        let header: Vec<&str> = std::iter::once("vertex").chain(which.iter().map(|metric| metric.name())).collect();
+        writeln!(fp, "{}", header.join(","))?;
+        for row in rows {
+            let values = which.iter().zip(&row.values).map(|(metric, &value)| metric.format(value));
+            let fields: Vec<String> = std::iter::once(csv_field(&row.label)).chain(values).collect();
+            writeln!(fp, "{}", fields.join(","))?;
+        }
+
+        Ok(())
+    }
+}
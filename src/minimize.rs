@@ -0,0 +1,131 @@
+// src/minimize.rs - Shrinking a graph to a small one that still shows a behavior
+//
+// Delta debugging: drop chunks of vertices, then chunks of edges, halving the
+// chunk size down to single items whenever no chunk can go, and start over
+// until a whole round removes nothing. Each candidate graph costs one predicate
+// call, counted as one step against the budget.
+use crate::budget::{Budget, BudgetMeter};
+use crate::Graph;
+use std::collections::{HashMap, HashSet};
+
+/// Removes chunks of items while the predicate holds on what is left
+/// Returns whether anything was removed and whether the budget ran out
+fn shrink<T: Clone>(
+    items: &mut Vec<T>,
+    meter: &mut BudgetMeter,
+    mut holds: impl FnMut(&[T]) -> bool,
+) -> (bool, bool) {
+    let mut removed = false;
+    let mut chunk = (items.len() / 2).max(1);
+    while !items.is_empty() {
+        let mut removed_now = false;
+        let mut start = 0;
+        while start < items.len() {
+            let end = (start + chunk).min(items.len());
+            let candidate: Vec<T> = items[..start].iter().chain(&items[end..]).cloned().collect();
+            if !meter.tick_checked() {
+                return (removed, true);
+            }
+            if holds(&candidate) {
+                *items = candidate;
+                removed_now = true;
+            } else {
+                start = end;
+            }
+        }
+        removed |= removed_now;
+        if !removed_now {
+            if chunk == 1 {
+                break;
+            }
+            chunk /= 2;
+        }
+    }
+    (removed, false)
+}
+
+impl Graph {
+    /// Returns the undirected pairs of vertex indices joined by an adjacency list entry, loops included
+    fn edge_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for i in 0..self.n_vertices {
+            for adj_vertex in self.collect_adjacent_vertices(&self.adj_list[i]) {
+                if let Some(j) = self.find_vertex_idx(&adj_vertex) {
+                    let pair = (i.min(j), i.max(j));
+                    if !pairs.contains(&pair) {
+                        pairs.push(pair);
+                    }
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Builds the graph of the kept vertices and edges, with their weights and coordinates
+    fn restricted(&self, vertices: &[usize], edges: &[(usize, usize)]) -> Graph {
+        let new_idx: HashMap<usize, usize> = vertices.iter().enumerate().map(|(new, &old)| (old, new)).collect();
+        let edges: HashSet<(usize, usize)> = edges.iter().copied().collect();
+        let kept = |i: usize, j: usize| new_idx.contains_key(&j) && edges.contains(&(i.min(j), i.max(j)));
+
+        let rows: Vec<(String, Vec<String>)> = vertices
+            .iter()
+            .map(|&i| {
+                let neighbors = self
+                    .collect_adjacent_vertices(&self.adj_list[i])
+                    .into_iter()
+                    .filter(|adj_vertex| matches!(self.find_vertex_idx(adj_vertex), Some(j) if kept(i, j)))
+                    .collect();
+                (self.vertices[i].clone(), neighbors)
+            })
+            .collect();
+
+        let mut graph = Graph::new();
+        graph.rebuild_from_rows(&rows);
+        for ((i, j), weight) in self.weight_entries() {
+            if let (Some(&u), Some(&v)) = (new_idx.get(&i), new_idx.get(&j)) {
+                if kept(i, j) {
+                    graph.store_weight(u, v, weight);
+                }
+            }
+        }
+        for (&i, &point) in &self.coordinates {
+            if let Some(&u) = new_idx.get(&i) {
+                graph.coordinates.insert(u, point);
+            }
+        }
+        graph
+    }
+
+    /// Shrinks the graph to a small one on which the predicate still holds
+    ///
+    /// Vertices and then edges are removed in chunks, down to one at a time, for as
+    /// long as the predicate keeps holding; vertices keep their labels and surviving
+    /// edges their direction and weight. When the budget runs out the smallest graph
+    /// found so far is returned. A predicate that does not hold on the graph itself
+    /// leaves nothing to preserve, and the graph is returned unchanged.
+    pub fn minimize(&self, predicate: impl Fn(&Graph) -> bool, budget: Budget) -> Graph {
+        if !predicate(self) {
+            return self.clone();
+        }
+        let mut meter = budget.meter();
+        let mut vertices: Vec<usize> = (0..self.n_vertices).collect();
+        let mut edges = self.edge_pairs();
+
+        loop {
+            let (fewer_vertices, out) = shrink(&mut vertices, &mut meter, |vertices| {
+                predicate(&self.restricted(vertices, &edges))
+            });
+            edges.retain(|&(i, j)| vertices.contains(&i) && vertices.contains(&j));
+            if out {
+                break;
+            }
+            let (fewer_edges, out) = shrink(&mut edges, &mut meter, |edges| {
+                predicate(&self.restricted(&vertices, edges))
+            });
+            if out || (!fewer_vertices && !fewer_edges) {
+                break;
+            }
+        }
+        self.restricted(&vertices, &edges)
+    }
+}
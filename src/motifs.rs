@@ -0,0 +1,222 @@
+// src/motifs.rs - Counts of the connected 3- and 4-vertex induced subgraphs
+//
+// Edges count in either direction and self-loops are ignored. The counts come from
+// degrees, triangles per edge and common neighbors per pair: each formula first
+// counts the copies of a shape as a subgraph, not necessarily induced, and the
+// copies inside denser shapes are then subtracted, from the clique down. Small
+// graphs can be checked against motif_spectrum_exhaustive, which looks at every
+// set of 3 and 4 vertices.
+use crate::error::GraphError;
+use crate::Graph;
+use std::fs::File;
+use std::io::Write;
+
+/// Largest graph motif_spectrum_exhaustive looks at, about 1.6 million 4-vertex sets
+pub const MOTIF_EXHAUSTIVE_LIMIT: usize = 80;
+
+/// Number of induced copies of each connected graph on 3 and 4 vertices
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MotifSpectrum {
+    /// Three vertices joined by two edges
+    pub path3: u64,
+    pub triangle: u64,
+    /// Four vertices in a line
+    pub path4: u64,
+    /// One vertex joined to three others that are not joined to each other
+    pub star: u64,
+    /// Four vertices in a ring without chords
+    pub cycle4: u64,
+    /// A triangle with an edge hanging off one corner
+    pub tailed_triangle: u64,
+    /// Four vertices with every edge but one
+    pub diamond: u64,
+    pub clique4: u64,
+}
+
+impl MotifSpectrum {
+    /// Every class as (name, count), the 3-vertex classes first and then by edge count
+    pub fn classes(&self) -> [(&'static str, u64); 8] {
+        [
+            ("3-path", self.path3),
+            ("triangle", self.triangle),
+            ("4-path", self.path4),
+            ("3-star", self.star),
+            ("4-cycle", self.cycle4),
+            ("tailed-triangle", self.tailed_triangle),
+            ("diamond", self.diamond),
+            ("4-clique", self.clique4),
+        ]
+    }
+}
+
+/// Number of ways to choose k of n, for k up to 3
+fn choose(n: u64, k: u64) -> u64 {
+    match k {
+        0 => 1,
+        1 => n,
+        2 => n * n.saturating_sub(1) / 2,
+        _ => n * n.saturating_sub(1) * n.saturating_sub(2) / 6,
+    }
+}
+
+impl Graph {
+    /// Neighbors of every vertex with edges in either direction and without self-loops
+    fn simple_neighbors(&self) -> Vec<Vec<usize>> {
+        (0..self.n_vertices)
+            .map(|v| (0..self.n_vertices).filter(|&u| u != v && self.linked(u, v)).collect())
+            .collect()
+    }
+
+    /// Counts the triangles, with edges in either direction and self-loops ignored
+    pub fn triangle_count(&self) -> u64 {
+        let neighbors = self.simple_neighbors();
+        let mut triangles = 0;
+        for (u, around) in neighbors.iter().enumerate() {
+            for (i, &v) in around.iter().enumerate() {
+                if v < u {
+                    continue;
+                }
+                triangles += around[i + 1..]
+                    .iter()
+                    .filter(|&&w| w > u && self.linked(v, w))
+                    .count() as u64;
+            }
+        }
+        triangles
+    }
+
+    /// Counts every connected induced subgraph on 3 and 4 vertices by counting formulas
+    ///
+    /// Takes time about the sum of the squared degrees times the largest degree, far
+    /// below looking at every 4-vertex set on sparse graphs.
+    pub fn motif_spectrum(&self) -> MotifSpectrum {
+        let n = self.n_vertices;
+        let neighbors = self.simple_neighbors();
+        let degree: Vec<u64> = neighbors.iter().map(|around| around.len() as u64).collect();
+
+        // Triangles on each edge u < v and at each vertex, and the 4-cliques over each edge
+        let mut triangles_at = vec![0u64; n];
+        let (mut wedges, mut stars, mut paths_ni, mut diamonds_ni, mut cliques_x6) = (0, 0, 0, 0, 0);
+        let mut triangles_x3 = 0;
+        for u in 0..n {
+            wedges += choose(degree[u], 2);
+            stars += choose(degree[u], 3);
+            for &v in &neighbors[u] {
+                if v < u {
+                    continue;
+                }
+                let common: Vec<usize> = neighbors[u].iter().copied().filter(|&w| self.linked(v, w)).collect();
+                let on_edge = common.len() as u64;
+                triangles_x3 += on_edge;
+                triangles_at[u] += on_edge;
+                triangles_at[v] += on_edge;
+                paths_ni += (degree[u] - 1) * (degree[v] - 1);
+                diamonds_ni += choose(on_edge, 2);
+                for (i, &a) in common.iter().enumerate() {
+                    cliques_x6 += common[i + 1..].iter().filter(|&&b| self.linked(a, b)).count() as u64;
+                }
+            }
+        }
+        let triangle = triangles_x3 / 3;
+        // Every triangle at a vertex was counted once for each of its two edges there
+        let tailed_ni: u64 = (0..n).map(|v| triangles_at[v] / 2 * degree[v].saturating_sub(2)).sum();
+        let paths_ni = paths_ni - 3 * triangle;
+
+        // Each 4-cycle has two pairs of opposite corners, each with both others in common
+        let mut cycles_x2 = 0;
+        let mut common = vec![0u64; n];
+        for u in 0..n {
+            for &w in &neighbors[u] {
+                for &v in &neighbors[w] {
+                    if v > u {
+                        common[v] += 1;
+                    }
+                }
+            }
+            for shared in &mut common[u + 1..] {
+                cycles_x2 += choose(*shared, 2);
+                *shared = 0;
+            }
+        }
+
+        let clique4 = cliques_x6 / 6;
+        let diamond = diamonds_ni - 6 * clique4;
+        let cycle4 = cycles_x2 / 2 - diamond - 3 * clique4;
+        let tailed_triangle = tailed_ni - 4 * diamond - 12 * clique4;
+        MotifSpectrum {
+            path3: wedges - 3 * triangle,
+            triangle,
+            path4: paths_ni - 4 * cycle4 - 2 * tailed_triangle - 6 * diamond - 12 * clique4,
+            star: stars - tailed_triangle - 2 * diamond - 4 * clique4,
+            cycle4,
+            tailed_triangle,
+            diamond,
+            clique4,
+        }
+    }
+
+    /// Counts the same classes as motif_spectrum by classifying every set of 3 and 4
+    /// vertices; None for graphs of more than MOTIF_EXHAUSTIVE_LIMIT vertices
+    pub fn motif_spectrum_exhaustive(&self) -> Option<MotifSpectrum> {
+        let n = self.n_vertices;
+        if n > MOTIF_EXHAUSTIVE_LIMIT {
+            return None;
+        }
+        let joined = |a: usize, b: usize| self.linked(a, b) as usize;
+        let mut spectrum = MotifSpectrum::default();
+        for a in 0..n {
+            for b in a + 1..n {
+                for c in b + 1..n {
+                    match joined(a, b) + joined(a, c) + joined(b, c) {
+                        2 => spectrum.path3 += 1,
+                        3 => spectrum.triangle += 1,
+                        _ => {}
+                    }
+                    for d in c + 1..n {
+                        let set = [a, b, c, d];
+                        let degrees: Vec<usize> = set
+                            .iter()
+                            .map(|&x| set.iter().filter(|&&y| y != x).map(|&y| joined(x, y)).sum())
+                            .collect();
+                        let edges = degrees.iter().sum::<usize>() / 2;
+                        let max = degrees.iter().copied().max().unwrap_or(0);
+                        let isolated = degrees.contains(&0);
+                        match (edges, max) {
+                            (3, 3) => spectrum.star += 1,
+                            (3, _) if !isolated => spectrum.path4 += 1,
+                            (4, 2) => spectrum.cycle4 += 1,
+                            (4, _) => spectrum.tailed_triangle += 1,
+                            (5, _) => spectrum.diamond += 1,
+                            (6, _) => spectrum.clique4 += 1,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        Some(spectrum)
+    }
+
+    /// To create/write an output file of the motif spectrum
+    /// One `name count` line per class of MotifSpectrum::classes is followed by the
+    /// check of the triangles against triangle_count and, for graphs of up to
+    /// MOTIF_EXHAUSTIVE_LIMIT vertices, of every class against a count of every set
+    pub fn produce_output_file_motifs(&self, base_name: &str) -> Result<(), GraphError> {
+        let spectrum = self.motif_spectrum();
+        let output_name = Self::get_output_filename(base_name, "-MOTIFS");
+        let mut fp = File::create(output_name)?;
+
+        for (name, count) in spectrum.classes() {
+            writeln!(fp, "{} {}", name, count)?;
+        }
+        let triangles = self.triangle_count();
+        let verdict = if triangles == spectrum.triangle { "ok" } else { "MISMATCH" };
+        writeln!(fp, "triangle check: {} (triangle_count {})", verdict, triangles)?;
+        if let Some(exhaustive) = self.motif_spectrum_exhaustive() {
+            let verdict = if exhaustive == spectrum { "ok" } else { "MISMATCH" };
+            writeln!(fp, "exhaustive check: {}", verdict)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+// src/mst.rs - Minimum spanning trees of weighted graphs
+//
+// The edges are taken as undirected: a pair listed either way is one edge, and a
+// pair listed both ways with different weights counts with the lighter one. Edges
+// without a stored weight weigh 1, so an unweighted graph gives a spanning tree
+// with the fewest edges possible, one per vertex but the first of each component.
+use crate::disjoint_set::DisjointSet;
+use crate::error::GraphError;
+use crate::Graph;
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::Write;
+
+/// Sums the weights of a list of edges, such as the one returned by mst_prim
+pub fn total_weight(edges: &[(String, String, f64)]) -> f64 {
+    edges.iter().map(|(_, _, weight)| weight).sum()
+}
+
+impl Graph {
+    /// Weight of the undirected edge between u and v, the lighter of its two directions
+    pub(crate) fn undirected_weight(&self, u: usize, v: usize) -> f64 {
+        match (self.adj_matrix[u][v] == 1, self.adj_matrix[v][u] == 1) {
+            (true, true) => self.weight_at(u, v).min(self.weight_at(v, u)),
+            (true, false) => self.weight_at(u, v),
+            _ => self.weight_at(v, u),
+        }
+    }
+
+    /// Compares two candidate edges (weight, a, b) by weight, then by the label of a,
+    /// then by the label of b
+    fn lighter_edge(&self, a: (f64, usize, usize), b: (f64, usize, usize)) -> Ordering {
+        a.0.total_cmp(&b.0)
+            .then_with(|| self.vertices[a.1].cmp(&self.vertices[b.1]))
+            .then_with(|| self.vertices[a.2].cmp(&self.vertices[b.2]))
+    }
+
+    /// Builds a minimum spanning forest by Prim's algorithm, growing from `start`
+    ///
+    /// Returns the chosen edges as (tree endpoint, added vertex, weight), in the order
+    /// they were added; total_weight sums them. Of equally light edges the one whose
+    /// tree endpoint, and then added vertex, comes first alphabetically is taken, so
+    /// the result is the same on every run.
+    ///
+    /// A disconnected graph gives a spanning forest rather than an error: once the
+    /// component of `start` is spanned, the tree grows again from the alphabetically
+    /// first vertex not reached yet, so the forest has n minus the number of
+    /// components edges. Fails with UnknownVertex when `start` is not a label.
+    pub fn mst_prim(&self, start: &str) -> Result<Vec<(String, String, f64)>, GraphError> {
+        let start = self.require_vertex_idx(start)?;
+        let n = self.n_vertices;
+        let mut in_tree = vec![false; n];
+        // The lightest known edge joining each vertex to the tree, as (weight, tree endpoint)
+        let mut best: Vec<Option<(f64, usize)>> = vec![None; n];
+        let mut edges = Vec::with_capacity(n.saturating_sub(1));
+
+        let roots = std::iter::once(start).chain(self.sort_vertices());
+        for root in roots {
+            if in_tree[root] {
+                continue;
+            }
+            let mut next = Some(root);
+            while let Some(u) = next {
+                in_tree[u] = true;
+                if let Some((weight, from)) = best[u] {
+                    edges.push((self.vertices[from].clone(), self.vertices[u].clone(), weight));
+                }
+                for v in 0..n {
+                    if in_tree[v] || !self.linked(u, v) {
+                        continue;
+                    }
+                    let weight = self.undirected_weight(u, v);
+                    let improves = match best[v] {
+                        None => true,
+                        Some((known, from)) => self.lighter_edge((weight, u, v), (known, from, v)).is_lt(),
+                    };
+                    if improves {
+                        best[v] = Some((weight, u));
+                    }
+                }
+                next = (0..n)
+                    .filter(|&v| !in_tree[v])
+                    .filter_map(|v| best[v].map(|(weight, from)| (weight, from, v)))
+                    .min_by(|&a, &b| self.lighter_edge(a, b))
+                    .map(|(_, _, v)| v);
+            }
+        }
+
+        Ok(edges)
+    }
+
+    /// Builds a minimum spanning forest by Kruskal's algorithm
+    ///
+    /// Every edge is considered once, lightest first, and kept when it joins two trees
+    /// of a DisjointSet. Edges are given as (u, v, weight) with u before v alphabetically
+    /// and sorted by weight, then by u, then by v, which also settles ties, so the
+    /// result is the same on every run. A disconnected graph gives one tree per
+    /// component; self-loops never join two trees and are skipped.
+    pub fn mst_kruskal(&self) -> Vec<(String, String, f64)> {
+        let n = self.n_vertices;
+        let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+        for u in 0..n {
+            for v in u + 1..n {
+                if self.linked(u, v) {
+                    let (a, b) = if self.vertices[u] < self.vertices[v] { (u, v) } else { (v, u) };
+                    candidates.push((self.undirected_weight(a, b), a, b));
+                }
+            }
+        }
+        candidates.sort_by(|&x, &y| self.lighter_edge(x, y));
+
+        let mut trees = DisjointSet::new(n);
+        candidates
+            .into_iter()
+            .filter(|&(_, a, b)| trees.union(a, b))
+            .map(|(weight, a, b)| (self.vertices[a].clone(), self.vertices[b].clone(), weight))
+            .collect()
+    }
+
+    /// To create/write an output file of the minimum spanning forest grown from `start`
+    /// The edges are written as `MST(name)={(A,B),(B,C)}` the way -SET writes its edges,
+    /// each with the smaller label first and sorted, followed by `weight: 3`, their total
+    pub fn produce_output_file_mst(&self, base_name: &str, start: &str) -> Result<(), GraphError> {
+        let edges = self.mst_prim(start)?;
+        let output_name = Self::get_output_filename(base_name, "-MST");
+        let mut fp = File::create(output_name)?;
+
+        let mut pairs: Vec<(&str, &str)> = edges
+            .iter()
+            .map(|(a, b, _)| if a <= b { (a.as_str(), b.as_str()) } else { (b.as_str(), a.as_str()) })
+            .collect();
+        pairs.sort_unstable();
+        let pairs: Vec<String> = pairs.iter().map(|(a, b)| format!("({},{})", a, b)).collect();
+        writeln!(fp, "MST({})={{{}}}", self.display_name(base_name), pairs.join(","))?;
+        writeln!(fp, "weight: {}", total_weight(&edges))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use std::fs;
+
+    #[test]
+    fn mst_file_lists_each_edge_smaller_label_first() {
+        // From D, Prim's algorithm adds D-C, then C-B, then B-A
+        let graph = fixtures::weighted_square();
+        let edges = graph.mst_prim("D").unwrap();
+        let added: Vec<(&str, &str)> = edges.iter().map(|(a, b, _)| (a.as_str(), b.as_str())).collect();
+        assert_eq!(added, [("D", "C"), ("C", "B"), ("B", "A")]);
+        assert_eq!(total_weight(&edges), 6.0);
+
+        let dir = fixtures::scratch_dir("mst", "golden");
+        let base = dir.join("weighted_square").display().to_string();
+        graph.produce_output_file_mst(&base, "D").unwrap();
+        // The file names the graph after the base path, the fixture name in the golden file
+        let written = fs::read_to_string(format!("{}-MST.TXT", base)).unwrap().replace(&base, "weighted_square");
+        assert_eq!(written, include_str!("../tests/golden/weighted_square-MST.TXT"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
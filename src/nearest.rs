@@ -0,0 +1,87 @@
+// src/nearest.rs - Nearest-source assignment by one BFS from many sources
+use crate::error::GraphError;
+use crate::Graph;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Write;
+
+impl Graph {
+    /// Finds, for every vertex reachable from a source, its hop distance to the nearest
+    /// source and that source, as (vertex, distance, source) in alphabetical order of vertex
+    ///
+    /// One BFS is seeded with all sources at distance 0. A vertex as close to several
+    /// sources goes to the alphabetically first of them. Edges are followed in their
+    /// direction, from the sources outward, like the -BFS traversal.
+    pub fn multi_source_bfs(&self, sources: &[&str]) -> Result<Vec<(String, usize, String)>, GraphError> {
+        if sources.is_empty() {
+            return Err(GraphError::InvalidParameter {
+                name: "sources",
+                reason: "at least one source is needed".to_string(),
+            });
+        }
+        let mut source_idx = sources
+            .iter()
+            .map(|source| self.require_vertex_idx(source))
+            .collect::<Result<Vec<_>, _>>()?;
+        source_idx.sort_by(|&a, &b| self.vertices[a].cmp(&self.vertices[b]));
+        source_idx.dedup();
+
+        let n = self.n_vertices;
+        let mut distance: Vec<Option<usize>> = vec![None; n];
+        let mut owner: Vec<usize> = vec![usize::MAX; n];
+        let mut queue = VecDeque::new();
+        for &source in &source_idx {
+            distance[source] = Some(0);
+            owner[source] = source;
+            queue.push_back(source);
+        }
+
+        // A vertex is dequeued only after its whole previous level, so its owner is final by then
+        while let Some(current) = queue.pop_front() {
+            let next_distance = distance[current].map(|d| d + 1);
+            for next in 0..n {
+                if self.adj_matrix[current][next] != 1 {
+                    continue;
+                }
+                if distance[next].is_none() {
+                    distance[next] = next_distance;
+                    owner[next] = owner[current];
+                    queue.push_back(next);
+                } else if distance[next] == next_distance
+                    && self.vertices[owner[current]] < self.vertices[owner[next]]
+                {
+                    owner[next] = owner[current];
+                }
+            }
+        }
+
+        Ok(self
+            .sort_vertices()
+            .into_iter()
+            .filter_map(|v| {
+                let d = distance[v]?;
+                Some((self.vertices[v].clone(), d, self.vertices[owner[v]].clone()))
+            })
+            .collect())
+    }
+
+    /// To create/write an output file that assigns every vertex its nearest source
+    /// Lines read `vertex distance source`; unreachable vertices follow as `vertex -`
+    pub fn produce_output_file_nearest(&self, base_name: &str, sources: &[&str]) -> Result<(), GraphError> {
+        let assignment = self.multi_source_bfs(sources)?;
+        let output_name = Self::get_output_filename(base_name, "-NEAREST");
+        let mut fp = File::create(output_name)?;
+
+        for (vertex, distance, source) in &assignment {
+            writeln!(fp, "{} {} {}", vertex, distance, source)?;
+        }
+        for idx in self.sort_vertices() {
+            let vertex = &self.vertices[idx];
+            if !assignment.iter().any(|(reached, _, _)| reached == vertex) {
+                writeln!(fp, "{} -", vertex)?;
+            }
+        }
+
+        Ok(())
+    }
+}
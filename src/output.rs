@@ -0,0 +1,197 @@
+// src/output.rs - Limits on how much of an enumeration an output section writes
+//
+// A section is one enumeration output, named by its file suffix in lowercase
+// without the dash: "bfs-all", "dfs-all", "orbits". A limited section stops
+// writing items at its limit and ends with the line
+// `... truncated: k of N items shown`, so everything before it parses as usual.
+// The configuration also carries the DialectProfile of the core output files.
+use crate::error::GraphError;
+use crate::profile::DialectProfile;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Names of the sections the writers limit, as given to --limit SECTION=N
+pub const SECTIONS: &[&str] = &["bfs-all", "dfs-all", "orbits"];
+
+/// Caps on the items and bytes one output section writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SectionLimit {
+    pub max_items: usize,
+    pub max_bytes: u64,
+}
+
+impl SectionLimit {
+    /// A limit that never truncates
+    pub fn unlimited() -> Self {
+        SectionLimit {
+            max_items: usize::MAX,
+            max_bytes: u64::MAX,
+        }
+    }
+
+    /// A limit on the number of items only
+    pub fn items(max_items: usize) -> Self {
+        SectionLimit {
+            max_items,
+            ..SectionLimit::unlimited()
+        }
+    }
+}
+
+impl Default for SectionLimit {
+    fn default() -> Self {
+        SectionLimit::unlimited()
+    }
+}
+
+/// The number of items in an enumeration, or a lower bound when counting was cut short
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemTotal {
+    Exact(usize),
+    AtLeast(usize),
+}
+
+impl ItemTotal {
+    fn count(self) -> usize {
+        match self {
+            ItemTotal::Exact(n) | ItemTotal::AtLeast(n) => n,
+        }
+    }
+}
+
+impl fmt::Display for ItemTotal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemTotal::Exact(n) => write!(f, "{}", n),
+            ItemTotal::AtLeast(n) => write!(f, "at least {}", n),
+        }
+    }
+}
+
+/// Settings of the writers: a SectionLimit per section name, and the DialectProfile
+/// laying out the core output files
+#[derive(Debug, Clone, Default)]
+pub struct OutputConfig {
+    limits: HashMap<String, SectionLimit>,
+    dialect: DialectProfile,
+}
+
+impl OutputConfig {
+    /// A configuration without limits
+    pub fn new() -> Self {
+        OutputConfig::default()
+    }
+
+    /// Adds a limit on one section
+    pub fn with_limit(mut self, section: &str, limit: SectionLimit) -> Self {
+        self.set_limit(section, limit);
+        self
+    }
+
+    pub fn set_limit(&mut self, section: &str, limit: SectionLimit) {
+        self.limits.insert(section.to_string(), limit);
+    }
+
+    /// Returns the limit of a section, unlimited when none was set
+    pub fn limit(&self, section: &str) -> SectionLimit {
+        self.limits.get(section).copied().unwrap_or_default()
+    }
+
+    /// Lays out the core output files as the profile says
+    pub fn with_dialect(mut self, dialect: DialectProfile) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Returns the profile of the core output files, the default one unless set
+    pub fn dialect(&self) -> &DialectProfile {
+        &self.dialect
+    }
+
+    /// Applies a command-line limit of the form SECTION=MAX_ITEMS, like orbits=1000
+    /// A section not in SECTIONS is an error, so a misspelled one cannot go unnoticed
+    pub fn apply_limit_spec(&mut self, spec: &str) -> Result<(), GraphError> {
+        let invalid = |reason: String| GraphError::InvalidParameter { name: "limit", reason };
+        let (section, items) = spec
+            .split_once('=')
+            .ok_or_else(|| invalid(format!("expected SECTION=N, got {}", spec)))?;
+        if !SECTIONS.contains(&section) {
+            return Err(invalid(format!(
+                "unknown section {}; expected one of {}",
+                section,
+                SECTIONS.join(", ")
+            )));
+        }
+        let items = items
+            .parse()
+            .map_err(|_| invalid(format!("{} is not a number of items", items)))?;
+        self.set_limit(section, SectionLimit::items(items));
+        Ok(())
+    }
+}
+
+/// Writes the lines of a section up to its limit, then the truncation marker if lines were left out
+/// Returns the number of lines written; lines past the limit are never generated
+pub fn write_section<I>(out: &mut dyn Write, lines: I, total: ItemTotal, limit: SectionLimit) -> io::Result<usize>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut shown = 0;
+    let mut bytes: u64 = 0;
+    for line in lines {
+        let size = line.len() as u64 + 1;
+        if shown >= limit.max_items || bytes + size > limit.max_bytes {
+            break;
+        }
+        writeln!(out, "{}", line)?;
+        shown += 1;
+        bytes += size;
+    }
+    if shown < total.count() || matches!(total, ItemTotal::AtLeast(_)) {
+        writeln!(out, "... truncated: {} of {} items shown", shown, total)?;
+    }
+    Ok(shown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(count: usize, total: ItemTotal, limit: SectionLimit) -> String {
+        let mut out = Vec::new();
+        write_section(&mut out, (1..=count).map(|i| format!("line {}", i)), total, limit).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn limit_specs() {
+        let mut config = OutputConfig::new();
+        config.apply_limit_spec("orbits=3").unwrap();
+        assert_eq!(config.limit("orbits"), SectionLimit::items(3));
+        assert_eq!(config.limit("bfs-all"), SectionLimit::unlimited());
+
+        let error = config.apply_limit_spec("bfs_all=3").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid parameter limit: unknown section bfs_all; expected one of bfs-all, dfs-all, orbits"
+        );
+        assert!(config.apply_limit_spec("orbits").is_err());
+        assert!(config.apply_limit_spec("orbits=many").is_err());
+    }
+
+    #[test]
+    fn sections_are_cut_at_items_and_bytes() {
+        assert_eq!(section(2, ItemTotal::Exact(2), SectionLimit::items(5)), "line 1\nline 2\n");
+        assert_eq!(
+            section(4, ItemTotal::Exact(4), SectionLimit::items(2)),
+            "line 1\nline 2\n... truncated: 2 of 4 items shown\n"
+        );
+        let bytes = SectionLimit { max_items: usize::MAX, max_bytes: 10 };
+        assert_eq!(section(3, ItemTotal::Exact(3), bytes), "line 1\n... truncated: 1 of 3 items shown\n");
+        assert_eq!(
+            section(2, ItemTotal::AtLeast(2), SectionLimit::unlimited()),
+            "line 1\nline 2\n... truncated: 2 of at least 2 items shown\n"
+        );
+    }
+}
@@ -0,0 +1,488 @@
+// src/planarity.rs - Planarity testing with Kuratowski witnesses
+use crate::budget::{Budget, BudgetMeter};
+use crate::error::GraphError;
+use crate::Graph;
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt;
+
+/// Number of embedding steps is_planar may spend before giving up
+pub const DEFAULT_PLANARITY_BUDGET: usize = 1_000_000;
+
+/// The two forbidden graphs of Kuratowski's theorem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kuratowski {
+    K5,
+    K33,
+}
+
+/// Outcome of a planarity check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanarityResult {
+    Planar,
+    /// The graph contains a subdivision of the witness, made of these edges
+    NonPlanar {
+        witness: Kuratowski,
+        subdivision: Vec<(String, String)>,
+    },
+    /// The step budget ran out before an answer was found
+    BudgetExceeded,
+}
+
+impl fmt::Display for Kuratowski {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kuratowski::K5 => write!(f, "K5"),
+            Kuratowski::K33 => write!(f, "K3,3"),
+        }
+    }
+}
+
+impl fmt::Display for PlanarityResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanarityResult::Planar => write!(f, "yes"),
+            PlanarityResult::NonPlanar { witness, .. } => {
+                write!(f, "no (contains a {} subdivision)", witness)
+            }
+            PlanarityResult::BudgetExceeded => write!(f, "unknown (budget exceeded)"),
+        }
+    }
+}
+
+/// Simple undirected adjacency used by the planarity routines
+type Adjacency = Vec<BTreeSet<usize>>;
+
+/// Splits the edges of a graph into biconnected blocks using Tarjan's edge stack
+fn blocks(adj: &Adjacency) -> Vec<Vec<(usize, usize)>> {
+    let n = adj.len();
+    let mut disc = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut time = 0;
+    let mut edge_stack = Vec::new();
+    let mut result = Vec::new();
+
+    for root in 0..n {
+        if disc[root] != usize::MAX {
+            continue;
+        }
+        disc[root] = time;
+        low[root] = time;
+        time += 1;
+
+        // Iterative DFS frames: (vertex, parent, remaining neighbors)
+        let mut stack: Vec<(usize, usize, Vec<usize>)> =
+            vec![(root, usize::MAX, adj[root].iter().copied().collect())];
+        while let Some(frame) = stack.last_mut() {
+            let (u, parent) = (frame.0, frame.1);
+            if let Some(v) = frame.2.pop() {
+                if disc[v] == usize::MAX {
+                    edge_stack.push((u, v));
+                    disc[v] = time;
+                    low[v] = time;
+                    time += 1;
+                    stack.push((v, u, adj[v].iter().copied().collect()));
+                } else if v != parent && disc[v] < disc[u] {
+                    edge_stack.push((u, v));
+                    low[u] = low[u].min(disc[v]);
+                }
+            } else {
+                stack.pop();
+                if let Some(parent_frame) = stack.last() {
+                    let p = parent_frame.0;
+                    low[p] = low[p].min(low[u]);
+                    // p separates u's subtree, which closes a block
+                    if low[u] >= disc[p] {
+                        let mut block = Vec::new();
+                        while let Some(edge) = edge_stack.pop() {
+                            block.push(edge);
+                            if edge == (p, u) {
+                                break;
+                            }
+                        }
+                        result.push(block);
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Finds a cycle in a biconnected block with at least three vertices
+fn find_cycle(adj: &Adjacency, start: usize) -> Vec<usize> {
+    // DFS until an edge closes back onto the current path
+    let mut pred = vec![usize::MAX; adj.len()];
+    let mut on_path = vec![false; adj.len()];
+    let mut stack: Vec<(usize, Vec<usize>)> = vec![(start, adj[start].iter().rev().copied().collect())];
+    on_path[start] = true;
+    pred[start] = start;
+
+    while let Some(frame) = stack.last_mut() {
+        let u = frame.0;
+        match frame.1.pop() {
+            Some(v) if on_path[v] && v != pred[u] => {
+                let mut cycle = vec![u];
+                let mut current = u;
+                while current != v {
+                    current = pred[current];
+                    cycle.push(current);
+                }
+                cycle.reverse();
+                return cycle;
+            }
+            Some(v) if pred[v] == usize::MAX => {
+                pred[v] = u;
+                on_path[v] = true;
+                stack.push((v, adj[v].iter().rev().copied().collect()));
+            }
+            Some(_) => {}
+            None => {
+                on_path[u] = false;
+                stack.pop();
+            }
+        }
+    }
+
+    Vec::new()
+}
+
+/// Tests a biconnected block for planarity with the Demoucron-Malgrange-Pertuiset
+/// path addition algorithm
+/// Returns None when the step budget runs out
+fn block_is_planar(adj: &Adjacency, edge_total: usize, steps: &mut BudgetMeter) -> Option<bool> {
+    let n = adj.len();
+    let Some(start) = (0..n).find(|&v| !adj[v].is_empty()) else {
+        return Some(true);
+    };
+
+    let cycle = find_cycle(adj, start);
+    let mut in_h = vec![false; n];
+    let mut h_edges: BTreeSet<(usize, usize)> = BTreeSet::new();
+    for (k, &v) in cycle.iter().enumerate() {
+        in_h[v] = true;
+        let w = cycle[(k + 1) % cycle.len()];
+        h_edges.insert((v.min(w), v.max(w)));
+    }
+    let mut faces: Vec<Vec<usize>> = vec![cycle.clone(), cycle];
+
+    while h_edges.len() < edge_total {
+        if !steps.tick() {
+            return None;
+        }
+
+        // Fragments: a chord between embedded vertices, or a component of the
+        // unembedded vertices with the embedded vertices it attaches to
+        let mut fragments: Vec<(BTreeSet<usize>, Vec<usize>)> = Vec::new();
+        for u in 0..n {
+            if !in_h[u] {
+                continue;
+            }
+            for &v in &adj[u] {
+                if u < v && in_h[v] && !h_edges.contains(&(u, v)) {
+                    fragments.push((BTreeSet::from([u, v]), Vec::new()));
+                }
+            }
+        }
+        let mut seen = vec![false; n];
+        for root in 0..n {
+            if in_h[root] || seen[root] || adj[root].is_empty() {
+                continue;
+            }
+            let mut members = Vec::new();
+            let mut attachments = BTreeSet::new();
+            let mut queue = VecDeque::from([root]);
+            seen[root] = true;
+            while let Some(u) = queue.pop_front() {
+                members.push(u);
+                for &v in &adj[u] {
+                    if in_h[v] {
+                        attachments.insert(v);
+                    } else if !seen[v] {
+                        seen[v] = true;
+                        queue.push_back(v);
+                    }
+                }
+            }
+            fragments.push((attachments, members));
+        }
+
+        // Pick a fragment with the fewest admissible faces
+        let mut choice: Option<(usize, usize, usize)> = None;
+        for (k, (attachments, _)) in fragments.iter().enumerate() {
+            let admissible: Vec<usize> = (0..faces.len())
+                .filter(|&f| attachments.iter().all(|a| faces[f].contains(a)))
+                .collect();
+            if admissible.is_empty() {
+                return Some(false);
+            }
+            let fewer = match choice {
+                None => true,
+                Some((_, _, count)) => admissible.len() < count,
+            };
+            if fewer {
+                choice = Some((k, admissible[0], admissible.len()));
+            }
+        }
+        let Some((k, face_idx, _)) = choice else {
+            break;
+        };
+
+        let path = fragment_path(adj, &in_h, &fragments[k]);
+        for window in path.windows(2) {
+            let (u, v) = (window[0], window[1]);
+            h_edges.insert((u.min(v), u.max(v)));
+        }
+        for &v in &path {
+            in_h[v] = true;
+        }
+
+        // Split the face along the new path
+        let face = faces.swap_remove(face_idx);
+        let a = path[0];
+        let b = path[path.len() - 1];
+        let i = face.iter().position(|&v| v == a).unwrap_or(0);
+        let j = face.iter().position(|&v| v == b).unwrap_or(0);
+        let len = face.len();
+        let walk = |from: usize, to: usize| {
+            let mut part = Vec::new();
+            let mut k = from;
+            loop {
+                part.push(face[k]);
+                if k == to {
+                    break;
+                }
+                k = (k + 1) % len;
+            }
+            part
+        };
+        let interior = &path[1..path.len() - 1];
+        let mut first = walk(i, j);
+        first.extend(interior.iter().rev());
+        let mut second = walk(j, i);
+        second.extend(interior.iter());
+        faces.push(first);
+        faces.push(second);
+    }
+
+    Some(true)
+}
+
+/// Finds a path through a fragment between two of its attachment vertices
+fn fragment_path(adj: &Adjacency, in_h: &[bool], fragment: &(BTreeSet<usize>, Vec<usize>)) -> Vec<usize> {
+    let (attachments, members) = fragment;
+    let mut ends = attachments.iter().copied();
+    let a = ends.next().unwrap_or(0);
+    if members.is_empty() {
+        return vec![a, ends.next().unwrap_or(a)];
+    }
+
+    // BFS from a through the fragment until reaching a vertex adjacent to another attachment
+    let member_set: BTreeSet<usize> = members.iter().copied().collect();
+    let mut pred = vec![usize::MAX; adj.len()];
+    let mut queue = VecDeque::new();
+    for &v in &adj[a] {
+        if member_set.contains(&v) && pred[v] == usize::MAX {
+            pred[v] = a;
+            queue.push_back(v);
+        }
+    }
+    while let Some(u) = queue.pop_front() {
+        if let Some(&b) = adj[u].iter().find(|&&w| in_h[w] && w != a && attachments.contains(&w)) {
+            let mut path = vec![b, u];
+            let mut current = u;
+            while pred[current] != a {
+                current = pred[current];
+                path.push(current);
+            }
+            path.push(a);
+            path.reverse();
+            return path;
+        }
+        for &v in &adj[u] {
+            if member_set.contains(&v) && pred[v] == usize::MAX {
+                pred[v] = u;
+                queue.push_back(v);
+            }
+        }
+    }
+
+    vec![a]
+}
+
+/// Tests a whole graph for planarity by testing each biconnected block
+fn adjacency_is_planar(adj: &Adjacency, steps: &mut BudgetMeter) -> Option<bool> {
+    let n = adj.len();
+    let edges: usize = adj.iter().map(|s| s.len()).sum::<usize>() / 2;
+    // Euler's formula bounds the edges of a simple planar graph
+    if n >= 3 && edges > 3 * n - 6 {
+        return Some(false);
+    }
+
+    for block in blocks(adj) {
+        if block.len() < 3 {
+            continue;
+        }
+        let mut block_adj: Adjacency = vec![BTreeSet::new(); n];
+        for &(u, v) in &block {
+            block_adj[u].insert(v);
+            block_adj[v].insert(u);
+        }
+        if !block_is_planar(&block_adj, block.len(), steps)? {
+            return Some(false);
+        }
+    }
+
+    Some(true)
+}
+
+impl Graph {
+    /// Builds the simple undirected adjacency of the graph, ignoring self-loops
+    fn planarity_adjacency(&self) -> Adjacency {
+        (0..self.n_vertices)
+            .map(|i| {
+                (0..self.n_vertices)
+                    .filter(|&j| i != j && (self.adj_matrix[i][j] == 1 || self.adj_matrix[j][i] == 1))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Determines whether the graph can be drawn in the plane without crossing edges
+    pub fn is_planar(&self) -> PlanarityResult {
+        self.is_planar_with_budget(DEFAULT_PLANARITY_BUDGET)
+    }
+
+    /// Same as is_planar, giving up after the given number of embedding steps
+    ///
+    /// A nonplanar graph is shrunk to a minimal nonplanar subgraph by deleting every
+    /// edge that can go while the rest stays nonplanar. What remains is a
+    /// subdivision of K5 (five branch vertices of degree 4) or of K3,3 (six branch
+    /// vertices of degree 3), which is reported as the witness.
+    pub fn is_planar_with_budget(&self, budget: usize) -> PlanarityResult {
+        self.is_planar_within(&Budget::nodes(budget as u64))
+            .unwrap_or(PlanarityResult::BudgetExceeded)
+    }
+
+    /// Same as is_planar, stopping with BudgetExceeded when the budget runs out
+    pub fn is_planar_within(&self, budget: &Budget) -> Result<PlanarityResult, GraphError> {
+        let mut steps = budget.meter();
+        let mut adj = self.planarity_adjacency();
+
+        match adjacency_is_planar(&adj, &mut steps) {
+            None => return Err(steps.exceeded()),
+            Some(true) => return Ok(PlanarityResult::Planar),
+            Some(false) => {}
+        }
+
+        // Drop edges in alphabetical order while the graph stays nonplanar
+        let order = self.sort_vertices();
+        for &u in &order {
+            for &v in &order {
+                if self.vertices[u] >= self.vertices[v] || !adj[u].contains(&v) {
+                    continue;
+                }
+                adj[u].remove(&v);
+                adj[v].remove(&u);
+                match adjacency_is_planar(&adj, &mut steps) {
+                    None => return Err(steps.exceeded()),
+                    Some(true) => {
+                        adj[u].insert(v);
+                        adj[v].insert(u);
+                    }
+                    Some(false) => {}
+                }
+            }
+        }
+
+        let branch_vertices = adj.iter().filter(|s| s.len() >= 3).count();
+        let witness = if branch_vertices == 5 {
+            Kuratowski::K5
+        } else {
+            Kuratowski::K33
+        };
+        let mut subdivision = Vec::new();
+        for &u in &order {
+            for &v in &order {
+                if self.vertices[u] < self.vertices[v] && adj[u].contains(&v) {
+                    subdivision.push((self.vertices[u].clone(), self.vertices[v].clone()));
+                }
+            }
+        }
+
+        Ok(PlanarityResult::NonPlanar {
+            witness,
+            subdivision,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::Dialect;
+    use crate::fixtures;
+    use std::collections::BTreeMap;
+
+    fn graph_from(text: &str) -> Graph {
+        let mut graph = Graph::new();
+        graph.read_input_text_with(text, Dialect::Strict).unwrap();
+        graph
+    }
+
+    /// The complete graph on the labels
+    fn complete(labels: &[&str]) -> Graph {
+        let mut text = format!("{}\n", labels.len());
+        for u in labels {
+            let others: Vec<&str> = labels.iter().copied().filter(|v| v != u).collect();
+            text += &format!("{} {} -1\n", u, others.join(" "));
+        }
+        graph_from(&text)
+    }
+
+    /// Checks that the subdivision only uses edges of the graph and has the branch
+    /// vertices of the witness: five of degree 4 for K5, six of degree 3 for K3,3,
+    /// every other vertex of degree 2
+    fn assert_witness(graph: &Graph, expected: Kuratowski) {
+        let PlanarityResult::NonPlanar { witness, subdivision } = graph.is_planar() else {
+            panic!("expected a nonplanar graph");
+        };
+        assert_eq!(witness, expected);
+        let mut degree: BTreeMap<&str, usize> = BTreeMap::new();
+        for (u, v) in &subdivision {
+            let (i, j) = (graph.find_vertex_idx(u).unwrap(), graph.find_vertex_idx(v).unwrap());
+            assert_eq!(graph.adj_matrix[i][j], 1, "{}-{} is no edge", u, v);
+            *degree.entry(u).or_default() += 1;
+            *degree.entry(v).or_default() += 1;
+        }
+        let (branch_count, branch_degree) = match expected {
+            Kuratowski::K5 => (5, 4),
+            Kuratowski::K33 => (6, 3),
+        };
+        assert_eq!(degree.values().filter(|&&d| d == branch_degree).count(), branch_count, "{:?}", degree);
+        assert!(degree.values().all(|&d| d == branch_degree || d == 2), "{:?}", degree);
+    }
+
+    #[test]
+    fn k5_is_its_own_witness() {
+        let k5 = complete(&["A", "B", "C", "D", "E"]);
+        assert_witness(&k5, Kuratowski::K5);
+        // Splitting an edge leaves a subdivision of K5
+        let split = graph_from("6\nA C D E X -1\nB C D E X -1\nC A B D E -1\nD A B C E -1\nE A B C D -1\nX A B -1\n");
+        assert_witness(&split, Kuratowski::K5);
+    }
+
+    #[test]
+    fn k33_and_petersen_give_k33_witnesses() {
+        let k33 = graph_from("6\nA X Y Z -1\nB X Y Z -1\nC X Y Z -1\nX A B C -1\nY A B C -1\nZ A B C -1\n");
+        assert_witness(&k33, Kuratowski::K33);
+        assert_witness(&fixtures::petersen(), Kuratowski::K33);
+    }
+
+    #[test]
+    fn planar_graphs_have_no_witness() {
+        assert_eq!(complete(&["A", "B", "C", "D"]).is_planar(), PlanarityResult::Planar);
+        assert_eq!(fixtures::two_cliques_bridge().is_planar(), PlanarityResult::Planar);
+        assert_eq!(complete(&["A", "B", "C", "D", "E"]).is_planar_with_budget(1), PlanarityResult::BudgetExceeded);
+    }
+}
@@ -0,0 +1,17 @@
+// src/prelude.rs - The stable core of the public API, for a single glob import
+//
+// `use graph_analysis::prelude::*;` brings in the graph, its errors and the types
+// its main methods take and return. Everything re-exported here follows semver:
+// it is only removed or changed incompatibly in a major release. Modules behind
+// the unstable-algorithms feature are deliberately left out.
+pub use crate::budget::Budget;
+pub use crate::components::ComponentsIndex;
+pub use crate::dialect::{Dialect, ParseWarning};
+pub use crate::direction::{Direction, DirectionProfile};
+pub use crate::edges::EdgeView;
+pub use crate::error::GraphError;
+pub use crate::formats::GraphFormat;
+pub use crate::output::{ItemTotal, OutputConfig, SectionLimit};
+pub use crate::stats::GraphStats;
+pub use crate::vertex_ref::VertexRef;
+pub use crate::Graph;
@@ -0,0 +1,166 @@
+// src/profile.rs - Output dialects: the small layout choices of the core output files
+//
+// A DialectProfile bundles how -DEGREE, -LIST, -LIST-SORTED and -MATRIX are laid
+// out, so that a grader expecting slightly different files needs a profile rather
+// than a fork. The default profile writes the files exactly as the legacy writers
+// do. Profiles can also be read from a key=value file:
+//
+//   # Our grader, like course-ps2 but with wider matrix columns
+//   base = course-ps2
+//   name = grader
+//   column_width = 12
+//
+// `base` must come first when given. Values are taken as written after the `=`,
+// trimmed; `\t` stands for a tab and `\\` for a backslash, and surrounding double
+// quotes keep leading or trailing spaces.
+use crate::error::{file_error, GraphError};
+use crate::weights::MatrixStyle;
+use std::fs;
+use std::path::Path;
+
+/// Names of the built-in profiles, as given to --dialect
+pub const BUILT_IN_PROFILES: &[&str] = &["default", "course-ps2"];
+
+/// Layout of the core output files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialectProfile {
+    pub name: String,
+    /// Written after the last neighbor of every -LIST line, `\` by default
+    pub list_end: String,
+    /// Written between a label and its degree in -DEGREE; None pads the label to column_width
+    pub degree_separator: Option<String>,
+    /// Top-left cell of the -MATRIX header row, blank by default
+    pub matrix_corner: String,
+    pub matrix: MatrixStyle,
+    /// Width each -DEGREE label and -MATRIX cell is padded to
+    pub column_width: usize,
+}
+
+impl Default for DialectProfile {
+    fn default() -> Self {
+        DialectProfile {
+            name: "default".to_string(),
+            list_end: "\\".to_string(),
+            degree_separator: None,
+            matrix_corner: String::new(),
+            matrix: MatrixStyle::legacy(),
+            column_width: 10,
+        }
+    }
+}
+
+/// Reads the escapes of a profile value: surrounding quotes, `\t` and `\\`
+fn unescape(value: &str) -> String {
+    let value = value
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .unwrap_or(value);
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('t')) => {
+                text.push('\t');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                text.push('\\');
+                chars.next();
+            }
+            _ => text.push(c),
+        }
+    }
+    text
+}
+
+impl DialectProfile {
+    /// The layout a course grader expects: -LIST lines end in -1 as in the input
+    /// format, -DEGREE separates label and degree with a tab, and the -MATRIX
+    /// header row starts with the word MATRIX
+    pub fn course_ps2() -> Self {
+        DialectProfile {
+            name: "course-ps2".to_string(),
+            list_end: "-1".to_string(),
+            degree_separator: Some("\t".to_string()),
+            matrix_corner: "MATRIX".to_string(),
+            ..DialectProfile::default()
+        }
+    }
+
+    /// Returns the built-in profile of the given name; see BUILT_IN_PROFILES
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(DialectProfile::default()),
+            "course-ps2" => Some(DialectProfile::course_ps2()),
+            _ => None,
+        }
+    }
+
+    /// Reads a profile from key=value lines, starting from the default profile
+    ///
+    /// Keys are base, name, list_end, degree_separator (`pad` to pad the label),
+    /// matrix_corner, matrix_weights (true or false) and column_width. Blank lines
+    /// and lines starting with # are skipped. An unknown key, a malformed line or
+    /// a bad value is an error naming its line.
+    pub fn parse(text: &str) -> Result<Self, GraphError> {
+        let mut profile = DialectProfile::default();
+        let mut seen_key = false;
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: String| GraphError::InvalidFormat {
+                format: "dialect profile",
+                reason: format!("line {}: {}", i + 1, reason),
+            };
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("expected KEY = VALUE, found \"{}\"", line)))?;
+            let (key, value) = (key.trim(), unescape(value.trim()));
+            match key {
+                "base" if seen_key => return Err(invalid("base must come before every other key".to_string())),
+                "base" => {
+                    profile = DialectProfile::named(&value)
+                        .ok_or_else(|| invalid(format!("no built-in profile named {}", value)))?;
+                }
+                "name" => profile.name = value,
+                "list_end" => profile.list_end = value,
+                "degree_separator" if value == "pad" => profile.degree_separator = None,
+                "degree_separator" => profile.degree_separator = Some(value),
+                "matrix_corner" => profile.matrix_corner = value,
+                "matrix_weights" => {
+                    profile.matrix.show_weights = value
+                        .parse()
+                        .map_err(|_| invalid(format!("matrix_weights is true or false, not {}", value)))?;
+                }
+                "column_width" => {
+                    profile.column_width = value
+                        .parse()
+                        .map_err(|_| invalid(format!("column_width is a number, not {}", value)))?;
+                }
+                _ => return Err(invalid(format!("unknown key {}", key))),
+            }
+            seen_key = true;
+        }
+        Ok(profile)
+    }
+
+    /// Returns the built-in profile named spec, or else reads the profile file at that path
+    pub fn load(spec: &str) -> Result<Self, GraphError> {
+        if let Some(profile) = DialectProfile::named(spec) {
+            return Ok(profile);
+        }
+        if !Path::new(spec).is_file() {
+            return Err(GraphError::InvalidParameter {
+                name: "dialect",
+                reason: format!(
+                    "{} is neither a built-in profile ({}) nor a profile file",
+                    spec,
+                    BUILT_IN_PROFILES.join(", ")
+                ),
+            });
+        }
+        DialectProfile::parse(&fs::read_to_string(spec).map_err(file_error(spec))?)
+    }
+}
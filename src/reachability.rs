@@ -0,0 +1,264 @@
+// src/reachability.rs - Precomputed reachability closure and its binary export
+//
+// File layout (all integers little-endian):
+//   magic "GRCH", version byte, vertex count n (u32),
+//   n labels as (length u32, UTF-8 bytes),
+//   n bitset rows of ceil(n / 8) bytes, bit j of row i set when i reaches j,
+//   FNV-1a checksum (u32) of every preceding byte.
+use crate::algo;
+use crate::error::{file_error, GraphError};
+use crate::Graph;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"GRCH";
+const FORMAT_VERSION: u8 = 1;
+const FORMAT_NAME: &str = "reachability";
+
+/// Computes the 32-bit FNV-1a hash of the given bytes
+fn fnv1a(bytes: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811c_9dc5;
+    for &byte in bytes {
+        hash ^= u32::from(byte);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+/// Returns the number of bytes in one bitset row for n vertices
+fn row_len(n: usize) -> usize {
+    let full = n / 8;
+    if full * 8 < n {
+        full + 1
+    } else {
+        full
+    }
+}
+
+fn invalid(reason: &str) -> GraphError {
+    GraphError::InvalidFormat {
+        format: FORMAT_NAME,
+        reason: reason.to_string(),
+    }
+}
+
+impl Graph {
+    /// Computes which vertices each vertex can reach along the arcs of the adjacency matrix
+    /// Every vertex reaches itself, so closure[i][i] is always true
+    pub fn transitive_closure(&self) -> Vec<Vec<bool>> {
+        (0..self.n_vertices)
+            .map(|i| {
+                let mut row = vec![false; self.n_vertices];
+                for j in algo::bfs(self, i) {
+                    row[j] = true;
+                }
+                row
+            })
+            .collect()
+    }
+
+    /// To write the reachability closure in the compact binary format read by ReachabilityIndex
+    pub fn export_reachability(&self, path: &Path) -> Result<(), GraphError> {
+        let n = self.n_vertices;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&(n as u32).to_le_bytes());
+        for label in &self.vertices[..n] {
+            bytes.extend_from_slice(&(label.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(label.as_bytes());
+        }
+
+        let width = row_len(n);
+        for row in self.transitive_closure() {
+            let mut bits = vec![0u8; width];
+            for (j, _) in row.iter().enumerate().filter(|(_, &reached)| reached) {
+                bits[j / 8] |= 1 << (j % 8);
+            }
+            bytes.extend_from_slice(&bits);
+        }
+
+        let checksum = fnv1a(&bytes);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+}
+
+/// Reachability queries answered from an exported file without building a Graph
+#[derive(Debug, Clone)]
+pub struct ReachabilityIndex {
+    labels: Vec<String>,
+    index: HashMap<String, usize>,
+    row_len: usize,
+    bits: Vec<u8>,
+}
+
+impl ReachabilityIndex {
+    /// Reads a file written by Graph::export_reachability, checking its version and checksum
+    pub fn load(path: &Path) -> Result<ReachabilityIndex, GraphError> {
+        Self::from_bytes(&fs::read(path).map_err(file_error(&path.display().to_string()))?)
+    }
+
+    /// Parses the contents of an exported reachability file
+    pub fn from_bytes(bytes: &[u8]) -> Result<ReachabilityIndex, GraphError> {
+        if bytes.len() < MAGIC.len() + 1 + 4 + 4 || &bytes[..4] != MAGIC {
+            return Err(invalid("not a reachability file"));
+        }
+        let (body, stored) = bytes.split_at(bytes.len() - 4);
+        let stored = u32::from_le_bytes([stored[0], stored[1], stored[2], stored[3]]);
+        if fnv1a(body) != stored {
+            return Err(invalid("checksum mismatch; the file is corrupted"));
+        }
+        if body[4] != FORMAT_VERSION {
+            return Err(invalid(&format!(
+                "unsupported version {}; expected {}",
+                body[4], FORMAT_VERSION
+            )));
+        }
+
+        let mut pos = 5;
+        let read_u32 = |pos: &mut usize| -> Result<usize, GraphError> {
+            let field = body
+                .get(*pos..*pos + 4)
+                .ok_or_else(|| invalid("file ends inside the header"))?;
+            *pos += 4;
+            Ok(u32::from_le_bytes([field[0], field[1], field[2], field[3]]) as usize)
+        };
+
+        let n = read_u32(&mut pos)?;
+        let mut labels = Vec::with_capacity(n.min(body.len()));
+        for _ in 0..n {
+            let len = read_u32(&mut pos)?;
+            let raw = body
+                .get(pos..pos + len)
+                .ok_or_else(|| invalid("file ends inside a label"))?;
+            let label = std::str::from_utf8(raw).map_err(|_| invalid("label is not valid UTF-8"))?;
+            labels.push(label.to_string());
+            pos += len;
+        }
+
+        let width = row_len(n);
+        let bits = &body[pos..];
+        if bits.len() != n * width {
+            return Err(invalid(&format!(
+                "expected {} bytes of reachability rows, found {}",
+                n * width,
+                bits.len()
+            )));
+        }
+
+        let index = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.clone(), i))
+            .collect();
+        Ok(ReachabilityIndex {
+            labels,
+            index,
+            row_len: width,
+            bits: bits.to_vec(),
+        })
+    }
+
+    /// Returns the vertex labels in the order of the exported graph
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Checks whether a can reach b; None when either label is unknown
+    pub fn can_reach(&self, a: &str, b: &str) -> Option<bool> {
+        let i = *self.index.get(a)?;
+        let j = *self.index.get(b)?;
+        Some(self.bits[i * self.row_len + j / 8] & (1 << (j % 8)) != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, scratch_dir};
+
+    /// The bytes export_reachability writes for a graph
+    fn exported(graph: &Graph, test: &str) -> Vec<u8> {
+        let path = scratch_dir("reachability", test).join("graph.reach");
+        graph.export_reachability(&path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        bytes
+    }
+
+    /// Replaces the trailing checksum with the one of the changed body
+    fn reseal(bytes: &mut [u8]) {
+        let end = bytes.len() - 4;
+        let checksum = fnv1a(&bytes[..end]);
+        bytes[end..].copy_from_slice(&checksum.to_le_bytes());
+    }
+
+    fn reason(result: Result<ReachabilityIndex, GraphError>) -> String {
+        match result {
+            Err(GraphError::InvalidFormat { format, reason }) => {
+                assert_eq!(format, FORMAT_NAME);
+                reason
+            }
+            other => panic!("expected InvalidFormat, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn loaded_index_answers_like_the_closure() {
+        for (test, graph) in [("petersen", fixtures::petersen()), ("pair", fixtures::disconnected_pair())] {
+            let path = scratch_dir("reachability", test).join("graph.reach");
+            graph.export_reachability(&path).unwrap();
+            let index = ReachabilityIndex::load(&path).unwrap();
+            fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+            assert_eq!(index.labels(), &graph.vertices[..graph.n_vertices]);
+            let closure = graph.transitive_closure();
+            for (i, a) in index.labels().iter().enumerate() {
+                for (j, b) in index.labels().iter().enumerate() {
+                    assert_eq!(index.can_reach(a, b), Some(closure[i][j]), "{} to {}", a, b);
+                }
+            }
+        }
+        let index = ReachabilityIndex::from_bytes(&exported(&fixtures::disconnected_pair(), "pair-bits")).unwrap();
+        assert_eq!(index.can_reach("A", "B"), Some(true));
+        assert_eq!(index.can_reach("A", "C"), Some(false));
+        assert_eq!(index.can_reach("A", "Z"), None);
+    }
+
+    #[test]
+    fn damaged_files_are_rejected() {
+        let bytes = exported(&fixtures::two_cliques_bridge(), "damaged");
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(reason(ReachabilityIndex::from_bytes(&bad_magic)), "not a reachability file");
+        assert_eq!(reason(ReachabilityIndex::from_bytes(&bytes[..6])), "not a reachability file");
+
+        let mut bad_version = bytes.clone();
+        bad_version[4] = FORMAT_VERSION + 1;
+        reseal(&mut bad_version);
+        assert_eq!(
+            reason(ReachabilityIndex::from_bytes(&bad_version)),
+            "unsupported version 2; expected 1"
+        );
+
+        let mut flipped = bytes.clone();
+        let last_row = flipped.len() - 5;
+        flipped[last_row] ^= 1;
+        assert_eq!(
+            reason(ReachabilityIndex::from_bytes(&flipped)),
+            "checksum mismatch; the file is corrupted"
+        );
+
+        let mut truncated = bytes[..bytes.len() - 5].to_vec();
+        truncated.extend_from_slice(&[0; 4]);
+        reseal(&mut truncated);
+        assert_eq!(
+            reason(ReachabilityIndex::from_bytes(&truncated)),
+            "expected 8 bytes of reachability rows, found 7"
+        );
+    }
+}
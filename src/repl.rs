@@ -0,0 +1,417 @@
+// src/repl.rs - Interactive query loop over one loaded graph
+//
+// The same commands run from scripts: a script has one command per line, blank
+// lines and # comments are skipped, and each command is echoed after a "> " prompt
+// so the output reads like the interactive session would.
+use graph_analysis::components::ComponentsIndex;
+use graph_analysis::planarity::PlanarityResult;
+use graph_analysis::Graph;
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+/// Scripts may source other scripts this many levels deep, which stops a script sourcing itself
+const MAX_SOURCE_DEPTH: usize = 16;
+
+/// Whether the loop should keep reading commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    Quit,
+    /// The command could not be answered and wrote an `Error:` line; an interactive
+    /// session carries on, a script does what its onerror policy says
+    Failed,
+}
+
+/// What a script does when one of its commands fails, chosen with `set onerror`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnError {
+    Stop,
+    Continue,
+}
+
+impl OnError {
+    fn from_name(name: &str) -> Option<OnError> {
+        match name {
+            "stop" => Some(OnError::Stop),
+            "continue" => Some(OnError::Continue),
+            _ => None,
+        }
+    }
+}
+
+/// Splits a command line into words
+/// Quotes keep spaces inside a word, a backslash outside single quotes takes the
+/// next character as it is, and a # at the start of a word begins a comment
+pub fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"') | None, '\\') => match chars.next() {
+                Some(next) => {
+                    word.push(next);
+                    in_word = true;
+                }
+                None => return Err("line ends with a backslash".to_string()),
+            },
+            (Some(_), c) => word.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, '#') if !in_word => break,
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if let Some(q) = quote {
+        return Err(format!("unterminated {} quote", q));
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Answers commands about one graph, one line at a time
+pub struct Repl<'a> {
+    graph: &'a Graph,
+    /// Built on the first query that needs components; the graph never changes under it
+    components: Option<ComponentsIndex>,
+    /// Commands entered at the top level that did not fail, in order; the lines of a
+    /// sourced script are represented by its source command
+    history: Vec<String>,
+    on_error: OnError,
+    /// Number of scripts being sourced inside one another
+    depth: usize,
+    /// Whether source and save-session may touch the file system
+    file_access: bool,
+}
+
+impl<'a> Repl<'a> {
+    pub fn new(graph: &'a Graph) -> Self {
+        Repl {
+            graph,
+            components: None,
+            history: Vec::new(),
+            on_error: OnError::Stop,
+            depth: 0,
+            file_access: true,
+        }
+    }
+
+    /// A REPL without source and save-session, for answering clients that must not
+    /// read or write the files of the machine it runs on
+    pub fn without_file_access(graph: &'a Graph) -> Self {
+        Repl {
+            file_access: false,
+            ..Repl::new(graph)
+        }
+    }
+
+    /// Returns the components index of the graph, building it the first time
+    fn components_index(&mut self) -> &mut ComponentsIndex {
+        let graph = self.graph;
+        self.components.get_or_insert_with(|| graph.components_index())
+    }
+
+    /// Prints the list of commands
+    fn print_help(out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "Commands:")?;
+        writeln!(out, "  neighbors LABEL   list the neighbors of a vertex")?;
+        writeln!(out, "  path FROM TO      shortest path between two vertices")?;
+        writeln!(out, "  stats             structural summary")?;
+        writeln!(out, "  components        connected components, one per line")?;
+        writeln!(out, "  planar            planarity check with a Kuratowski witness")?;
+        writeln!(out, "  explain A B       why the edge A-B does or does not appear in each output")?;
+        writeln!(out, "  whatif A B ...    components left after removing vertices, without changing the graph")?;
+        #[cfg(feature = "unstable-algorithms")]
+        writeln!(out, "  why LABEL         why a vertex ranks where it does in the centrality report")?;
+        writeln!(out, "  history           commands entered so far, numbered")?;
+        writeln!(out, "  save-session FILE write the commands entered so far as a script")?;
+        writeln!(out, "  source FILE       run the commands of a script")?;
+        writeln!(out, "  set onerror stop|continue  whether a script stops at a failed command")?;
+        writeln!(out, "  help              this list")?;
+        writeln!(out, "  quit              leave")
+    }
+
+    /// Runs a single command line and writes its answer
+    /// Labels with spaces are quoted, as in `neighbors "New York"`
+    pub fn execute(&mut self, line: &str, out: &mut dyn Write) -> io::Result<Flow> {
+        let words = match split_words(line) {
+            Ok(words) => words,
+            Err(reason) => {
+                writeln!(out, "Error: {}", reason)?;
+                return Ok(Flow::Failed);
+            }
+        };
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let flow = self.dispatch(&words, line, out)?;
+        if flow != Flow::Failed && !words.is_empty() && self.depth == 0 {
+            self.history.push(line.trim().to_string());
+        }
+        Ok(flow)
+    }
+
+    /// Runs the commands of a script file, echoing each one after a prompt
+    ///
+    /// Under `set onerror stop`, the default, the first failed command ends the script
+    /// with a `Stopped at FILE line N` line; under continue every line runs. Either
+    /// way the script fails when any of its commands did, and quit ends it at once.
+    pub fn run_script(&mut self, path: &str, out: &mut dyn Write) -> io::Result<Flow> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => {
+                writeln!(out, "Error: {}: {}", path, e)?;
+                return Ok(Flow::Failed);
+            }
+        };
+
+        let mut failed = false;
+        for (number, line) in text.lines().enumerate() {
+            // Blank lines and comments are not commands, so they are not echoed either
+            if split_words(line).is_ok_and(|words| words.is_empty()) {
+                continue;
+            }
+            writeln!(out, "> {}", line.trim())?;
+            match self.execute(line, out)? {
+                Flow::Continue => {}
+                Flow::Quit => return Ok(Flow::Quit),
+                Flow::Failed => {
+                    failed = true;
+                    if self.on_error == OnError::Stop {
+                        writeln!(out, "Stopped at {} line {}", path, number + 1)?;
+                        return Ok(Flow::Failed);
+                    }
+                }
+            }
+        }
+        Ok(if failed { Flow::Failed } else { Flow::Continue })
+    }
+
+    /// Writes the history as a script that source can run again
+    fn save_session(&self, path: &str) -> io::Result<()> {
+        let mut fp = fs::File::create(path)?;
+        for entry in &self.history {
+            writeln!(fp, "{}", entry)?;
+        }
+        Ok(())
+    }
+
+    /// Answers one command given as words
+    fn dispatch(&mut self, words: &[&str], line: &str, out: &mut dyn Write) -> io::Result<Flow> {
+        let graph = self.graph;
+
+        match words {
+            [] => {}
+            ["quit"] | ["exit"] => return Ok(Flow::Quit),
+            ["help"] => Self::print_help(out)?,
+            ["history"] => {
+                for (number, entry) in self.history.iter().enumerate() {
+                    writeln!(out, "{:>4}  {}", number + 1, entry)?;
+                }
+            }
+            ["set", "onerror", policy] => match OnError::from_name(policy) {
+                Some(policy) => self.on_error = policy,
+                None => {
+                    writeln!(out, "Error: onerror expects stop or continue, got {}", policy)?;
+                    return Ok(Flow::Failed);
+                }
+            },
+            ["source" | "save-session", ..] if !self.file_access => {
+                writeln!(out, "Error: {} is not available here", words[0])?;
+                return Ok(Flow::Failed);
+            }
+            ["save-session", path] => match self.save_session(path) {
+                Ok(()) => writeln!(out, "Saved {} commands to {}", self.history.len(), path)?,
+                Err(e) => {
+                    writeln!(out, "Error: {}: {}", path, e)?;
+                    return Ok(Flow::Failed);
+                }
+            },
+            ["source", path] => {
+                if self.depth >= MAX_SOURCE_DEPTH {
+                    writeln!(out, "Error: {}: scripts nested more than {} deep", path, MAX_SOURCE_DEPTH)?;
+                    return Ok(Flow::Failed);
+                }
+                self.depth += 1;
+                let flow = self.run_script(path, out);
+                self.depth -= 1;
+                return flow;
+            }
+            ["stats"] => write!(out, "{}", graph.stats())?,
+            ["planar"] => {
+                let result = graph.is_planar();
+                writeln!(out, "planar: {}", result)?;
+                if let PlanarityResult::NonPlanar { subdivision, .. } = &result {
+                    let edges: Vec<String> =
+                        subdivision.iter().map(|(a, b)| format!("({},{})", a, b)).collect();
+                    writeln!(out, "witness edges: {}", edges.join(","))?;
+                }
+            }
+            ["neighbors", label] => match graph.find_vertex_idx(label) {
+                Some(idx) => {
+                    let neighbors: Vec<&str> = graph
+                        .sorted_neighbors(idx)
+                        .into_iter()
+                        .map(|i| graph.vertices[i].as_str())
+                        .collect();
+                    writeln!(out, "{}", neighbors.join(" "))?;
+                }
+                None => {
+                    writeln!(out, "Error: {}", graph.unknown_vertex(label))?;
+                    return Ok(Flow::Failed);
+                }
+            },
+            ["components"] => {
+                let index = self.components_index();
+                writeln!(out, "components: {}", index.component_count())?;
+                for component in index.components() {
+                    writeln!(out, "{{{}}}", component.join(","))?;
+                }
+            }
+            // Vertices in different components cannot reach each other, whatever the algorithm
+            ["path", from, to]
+                if self
+                    .components_index()
+                    .same_component(from, to)
+                    .is_ok_and(|same| !same) =>
+            {
+                writeln!(out, "{} is unreachable from {}", to, from)?
+            }
+            ["whatif", labels @ ..] if !labels.is_empty() => write!(out, "{}", graph.what_if_remove(labels))?,
+            ["explain", a, b] => write!(out, "{}", graph.explain_edge(a, b))?,
+            #[cfg(feature = "unstable-algorithms")]
+            ["why", label] => match graph.explain_centrality(label) {
+                Ok(explanation) => write!(out, "{}", explanation)?,
+                Err(e) => {
+                    writeln!(out, "Error: {}", e)?;
+                    return Ok(Flow::Failed);
+                }
+            },
+            ["path", from, to] => match graph.shortest_path(from, to) {
+                Ok((algorithm, Some((cost, path)))) => {
+                    writeln!(out, "{} (cost {}, {})", path.join("->"), cost, algorithm)?
+                }
+                Ok((_, None)) => writeln!(out, "{} is unreachable from {}", to, from)?,
+                Err(e) => {
+                    writeln!(out, "Error: {}", e)?;
+                    return Ok(Flow::Failed);
+                }
+            },
+            _ => {
+                writeln!(out, "Unknown command: {} (type help)", line.trim())?;
+                return Ok(Flow::Failed);
+            }
+        }
+
+        Ok(Flow::Continue)
+    }
+
+    /// Reads commands until quit or end of input, prompting before each one
+    pub fn run(&mut self, input: &mut dyn BufRead, out: &mut dyn Write) -> io::Result<()> {
+        let mut line = String::new();
+        loop {
+            write!(out, "> ")?;
+            out.flush()?;
+            line.clear();
+            // A failed command has already said so; the session carries on
+            if input.read_line(&mut line)? == 0 || self.execute(&line, out)? == Flow::Quit {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph_analysis::fixtures::scratch_dir;
+    use graph_analysis::prelude::Dialect;
+
+    const SQUARE: &str = "4\nA B D -1\nB A C -1\nC B D -1\nD A C -1\n";
+    const SCRIPT: &str = include_str!("../tests/golden/repl-script.txt");
+
+    fn square() -> Graph {
+        let mut graph = Graph::new();
+        graph.read_input_text_with(SQUARE, Dialect::Strict).unwrap();
+        graph
+    }
+
+    /// Runs the golden script under an onerror policy, the script path written as SCRIPT
+    fn transcript(policy: &str) -> (Flow, String) {
+        let dir = scratch_dir("repl", policy);
+        let script = dir.join("script.txt");
+        fs::write(&script, SCRIPT).unwrap();
+        let path = script.display().to_string();
+        let graph = square();
+        let mut repl = Repl::new(&graph);
+        let mut out = Vec::new();
+        repl.execute(&format!("set onerror {}", policy), &mut out).unwrap();
+        let flow = repl.run_script(&path, &mut out).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+        (flow, String::from_utf8(out).unwrap().replace(&path, "SCRIPT"))
+    }
+
+    #[test]
+    fn script_stops_at_the_failed_command() {
+        let (flow, text) = transcript("stop");
+        assert_eq!(flow, Flow::Failed);
+        assert_eq!(text, include_str!("../tests/golden/repl-onerror-stop.txt"));
+    }
+
+    #[test]
+    fn script_continues_past_the_failed_command() {
+        let (flow, text) = transcript("continue");
+        assert_eq!(flow, Flow::Failed);
+        assert_eq!(text, include_str!("../tests/golden/repl-onerror-continue.txt"));
+    }
+
+    #[test]
+    fn saved_session_sources_back() {
+        let dir = scratch_dir("repl", "session");
+        let saved = dir.join("session.txt").display().to_string();
+        let graph = square();
+        let mut repl = Repl::new(&graph);
+        let mut out = Vec::new();
+        for line in ["neighbors A", "neighbors Q", "path B D", &format!("save-session \"{}\"", saved)] {
+            repl.execute(line, &mut out).unwrap();
+        }
+        // The failed command is left out of the history, and so out of the session
+        assert_eq!(fs::read_to_string(&saved).unwrap(), "neighbors A\npath B D\n");
+
+        let mut out = Vec::new();
+        assert_eq!(Repl::new(&graph).execute(&format!("source {}", saved), &mut out).unwrap(), Flow::Continue);
+        assert_eq!(String::from_utf8(out).unwrap(), "> neighbors A\nB D\n> path B D\nB->A->D (cost 2, BFS)\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn file_commands_are_refused_without_file_access() {
+        let graph = square();
+        let mut out = Vec::new();
+        assert_eq!(Repl::without_file_access(&graph).execute("source x", &mut out).unwrap(), Flow::Failed);
+        assert_eq!(String::from_utf8(out).unwrap(), "Error: source is not available here\n");
+    }
+
+    #[test]
+    fn words_split_on_quotes_and_escapes() {
+        assert_eq!(split_words(r#"neighbors "New York" # note"#).unwrap(), ["neighbors", "New York"]);
+        assert_eq!(split_words(r"path San\ Jose 'a\b'").unwrap(), ["path", "San Jose", r"a\b"]);
+        assert_eq!(split_words(r#"x "" y"#).unwrap(), ["x", "", "y"]);
+        assert!(split_words("neighbors \"open").is_err());
+        assert!(split_words("trailing \\").is_err());
+    }
+}
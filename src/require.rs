@@ -0,0 +1,203 @@
+// src/require.rs - Structural requirements checked all at once, for pipeline validation
+use crate::error::GraphError;
+use crate::Graph;
+use std::fmt;
+
+/// A structural property the graph must have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Requirement {
+    Connected,
+    /// No cycle when edges are taken as undirected; self-loops count as cycles
+    Acyclic,
+    MaxDegree(usize),
+    MinDegree(usize),
+    /// The number of vertices lies in the inclusive range
+    VertexCountBetween(usize, usize),
+    Bipartite,
+    ContainsVertices(Vec<String>),
+}
+
+/// Why a requirement does not hold, with the evidence found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequirementFailure {
+    /// The graph falls apart into these components
+    Disconnected { components: Vec<Vec<String>> },
+    /// A cycle, listed in order around it
+    Cycle { cycle: Vec<String> },
+    DegreeAbove { vertex: String, degree: usize, max: usize },
+    DegreeBelow { vertex: String, degree: usize, min: usize },
+    VertexCountOutside { count: usize, min: usize, max: usize },
+    /// A cycle of odd length, which no two-coloring can cover
+    OddCycle { cycle: Vec<String> },
+    MissingVertices(Vec<String>),
+}
+
+impl Requirement {
+    /// Parses the command-line form of a requirement: `connected`, `acyclic`, `bipartite`,
+    /// `max-degree=N`, `min-degree=N`, `vertex-count=MIN..MAX` or `contains=A,B,...`
+    pub fn parse(spec: &str) -> Result<Requirement, GraphError> {
+        let invalid = |reason: &str| GraphError::InvalidFormat {
+            format: "requirement",
+            reason: format!("{}: {}", spec, reason),
+        };
+        let number = |value: &str| value.trim().parse::<usize>().map_err(|_| invalid("expected a number"));
+
+        let (name, value) = match spec.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (spec, None),
+        };
+        match (name, value) {
+            ("connected", None) => Ok(Requirement::Connected),
+            ("acyclic", None) => Ok(Requirement::Acyclic),
+            ("bipartite", None) => Ok(Requirement::Bipartite),
+            ("max-degree", Some(value)) => Ok(Requirement::MaxDegree(number(value)?)),
+            ("min-degree", Some(value)) => Ok(Requirement::MinDegree(number(value)?)),
+            ("vertex-count", Some(value)) => {
+                let (min, max) = value.split_once("..").ok_or_else(|| invalid("expected MIN..MAX"))?;
+                Ok(Requirement::VertexCountBetween(number(min)?, number(max)?))
+            }
+            ("contains", Some(value)) => Ok(Requirement::ContainsVertices(
+                value.split(',').filter(|label| !label.is_empty()).map(str::to_string).collect(),
+            )),
+            _ => Err(invalid(
+                "expected connected, acyclic, bipartite, max-degree=N, min-degree=N, vertex-count=MIN..MAX or contains=A,B",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Requirement::Connected => write!(f, "connected"),
+            Requirement::Acyclic => write!(f, "acyclic"),
+            Requirement::MaxDegree(max) => write!(f, "max-degree={}", max),
+            Requirement::MinDegree(min) => write!(f, "min-degree={}", min),
+            Requirement::VertexCountBetween(min, max) => write!(f, "vertex-count={}..{}", min, max),
+            Requirement::Bipartite => write!(f, "bipartite"),
+            Requirement::ContainsVertices(labels) => write!(f, "contains={}", labels.join(",")),
+        }
+    }
+}
+
+impl fmt::Display for RequirementFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let set = |labels: &[String]| format!("{{{}}}", labels.join(","));
+        match self {
+            RequirementFailure::Disconnected { components } => {
+                let parts: Vec<String> = components.iter().map(|c| set(c)).collect();
+                write!(f, "not connected: {} components {}", components.len(), parts.join(" "))
+            }
+            RequirementFailure::Cycle { cycle } => {
+                write!(f, "not acyclic: cycle {}->{}", cycle.join("->"), cycle[0])
+            }
+            RequirementFailure::DegreeAbove { vertex, degree, max } => {
+                write!(f, "vertex {} has degree {}, above the maximum {}", vertex, degree, max)
+            }
+            RequirementFailure::DegreeBelow { vertex, degree, min } => {
+                write!(f, "vertex {} has degree {}, below the minimum {}", vertex, degree, min)
+            }
+            RequirementFailure::VertexCountOutside { count, min, max } => {
+                write!(f, "{} vertices, outside {}..{}", count, min, max)
+            }
+            RequirementFailure::OddCycle { cycle } => write!(
+                f,
+                "not bipartite: odd cycle {}->{}",
+                cycle.join("->"),
+                cycle[0]
+            ),
+            RequirementFailure::MissingVertices(labels) => write!(f, "missing vertices {}", set(labels)),
+        }
+    }
+}
+
+impl Graph {
+    /// Checks whether u and v are joined in either direction
+    pub(crate) fn linked(&self, u: usize, v: usize) -> bool {
+        self.adj_matrix[u][v] == 1 || self.adj_matrix[v][u] == 1
+    }
+
+    /// Evaluates a single requirement, listing every failure it finds
+    fn requirement_failures(&self, requirement: &Requirement) -> Vec<RequirementFailure> {
+        let sorted = self.sort_vertices();
+        let degrees = self.cached_degrees();
+        match requirement {
+            Requirement::Connected => {
+                let components = self.connected_components();
+                if components.len() > 1 {
+                    vec![RequirementFailure::Disconnected { components }]
+                } else {
+                    Vec::new()
+                }
+            }
+            // find_cycle closes the cycle on its first vertex; the failure lists each vertex once
+            Requirement::Acyclic => self
+                .find_cycle()
+                .map(|mut cycle| {
+                    cycle.pop();
+                    RequirementFailure::Cycle { cycle }
+                })
+                .into_iter()
+                .collect(),
+            Requirement::MaxDegree(max) => sorted
+                .iter()
+                .filter(|&&v| degrees[v] > *max)
+                .map(|&v| RequirementFailure::DegreeAbove {
+                    vertex: self.vertices[v].clone(),
+                    degree: degrees[v],
+                    max: *max,
+                })
+                .collect(),
+            Requirement::MinDegree(min) => sorted
+                .iter()
+                .filter(|&&v| degrees[v] < *min)
+                .map(|&v| RequirementFailure::DegreeBelow {
+                    vertex: self.vertices[v].clone(),
+                    degree: degrees[v],
+                    min: *min,
+                })
+                .collect(),
+            Requirement::VertexCountBetween(min, max) => {
+                if (*min..=*max).contains(&self.n_vertices) {
+                    Vec::new()
+                } else {
+                    vec![RequirementFailure::VertexCountOutside {
+                        count: self.n_vertices,
+                        min: *min,
+                        max: *max,
+                    }]
+                }
+            }
+            Requirement::Bipartite => self
+                .find_odd_cycle()
+                .map(|cycle| RequirementFailure::OddCycle { cycle })
+                .into_iter()
+                .collect(),
+            Requirement::ContainsVertices(labels) => {
+                let missing: Vec<String> = labels
+                    .iter()
+                    .filter(|label| self.find_vertex_idx(label).is_none())
+                    .cloned()
+                    .collect();
+                if missing.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![RequirementFailure::MissingVertices(missing)]
+                }
+            }
+        }
+    }
+
+    /// Checks every condition and returns all failures, not just the first
+    pub fn require(&self, conditions: &[Requirement]) -> Result<(), Vec<RequirementFailure>> {
+        let failures: Vec<RequirementFailure> = conditions
+            .iter()
+            .flat_map(|condition| self.requirement_failures(condition))
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+}
@@ -0,0 +1,111 @@
+// src/serde_impl.rs - Serialize and Deserialize for Graph (enabled by the serde feature)
+//
+// A graph is written in a flattened form instead of its fields: the labels in
+// input order, the neighbor labels of each vertex as the adjacency list holds
+// them, and the weights, edge attributes and coordinates by vertex index. The matrix, the counts,
+// the cache and the linked lists stay implementation details; deserializing
+// rebuilds them from the rows as reading a file does, so the representations
+// agree afterwards. EdgeIds are assigned afresh, and the weights and attributes
+// are stored under them, so each must name an edge of the rows.
+use crate::Graph;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// The serialized form of a Graph
+#[derive(Serialize, Deserialize)]
+struct GraphRepr {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    vertices: Vec<String>,
+    /// Neighbor labels of each vertex, in the order of vertices
+    adjacency: Vec<Vec<String>>,
+    /// (from, to, weight) by vertex index, sorted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    weights: Vec<(usize, usize, f64)>,
+    /// (from, to, attributes) by vertex index, in the order of ordered_edges
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    attributes: Vec<(usize, usize, BTreeMap<String, String>)>,
+    /// (vertex, x, y) by vertex index, sorted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    coordinates: Vec<(usize, f64, f64)>,
+}
+
+impl Serialize for Graph {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (vertices, adjacency) = self.adjacency_rows().into_iter().unzip();
+        let weights: Vec<(usize, usize, f64)> = self.weight_entries().into_iter().map(|((u, v), w)| (u, v, w)).collect();
+        let attributes = self
+            .ordered_edges()
+            .into_iter()
+            .filter(|edge| !edge.attrs.is_empty())
+            .map(|edge| (edge.source_idx, edge.target_idx, edge.attrs))
+            .collect();
+        let mut coordinates: Vec<(usize, f64, f64)> =
+            self.coordinates.iter().map(|(&v, &(x, y))| (v, x, y)).collect();
+        coordinates.sort_by_key(|&(v, _, _)| v);
+        GraphRepr {
+            name: self.name.clone(),
+            vertices,
+            adjacency,
+            weights,
+            attributes,
+            coordinates,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Graph {
+    /// Fails when the lists and labels differ in number, an index is out of range, a
+    /// weight or attribute names two vertices without an edge between them, or the
+    /// graph exceeds the vertex limit
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = GraphRepr::deserialize(deserializer)?;
+        if repr.adjacency.len() != repr.vertices.len() {
+            return Err(D::Error::custom(format!(
+                "{} vertices but {} adjacency lists",
+                repr.vertices.len(),
+                repr.adjacency.len()
+            )));
+        }
+        let n = repr.vertices.len();
+        let out_of_range = repr
+            .weights
+            .iter()
+            .map(|&(u, v, _)| u.max(v))
+            .chain(repr.attributes.iter().map(|(u, v, _)| *u.max(v)))
+            .chain(repr.coordinates.iter().map(|&(v, _, _)| v))
+            .find(|&index| index >= n);
+        if let Some(index) = out_of_range {
+            return Err(D::Error::custom(format!("vertex index {} out of range for {} vertices", index, n)));
+        }
+
+        let rows: Vec<(String, Vec<String>)> = repr.vertices.into_iter().zip(repr.adjacency).collect();
+        let mut graph = Graph::from_rows(&rows).map_err(D::Error::custom)?;
+        for (u, v, w) in repr.weights {
+            if graph.adj_matrix[u][v] != 1 {
+                return Err(D::Error::custom(format!(
+                    "weight on {} -> {}, which is not an edge",
+                    graph.vertices[u], graph.vertices[v]
+                )));
+            }
+            graph.store_weight(u, v, w);
+        }
+        for (u, v, attrs) in repr.attributes {
+            let id = graph.find_edge_idx(u, v).ok_or_else(|| {
+                D::Error::custom(format!(
+                    "attributes on {} - {}, which is not an edge",
+                    graph.vertices[u], graph.vertices[v]
+                ))
+            })?;
+            for (key, value) in &attrs {
+                graph.set_edge_attr(id, key, value);
+            }
+        }
+        graph.coordinates = repr.coordinates.into_iter().map(|(v, x, y)| (v, (x, y))).collect();
+        graph.name = repr.name;
+        graph.invalidate_cache();
+        Ok(graph)
+    }
+}
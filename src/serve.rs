@@ -0,0 +1,170 @@
+// src/serve.rs - Read-only query server answering REPL commands over TCP
+//
+// Line protocol: the client sends one REPL command per line; the answer is the
+// REPL output followed by a blank line. quit closes the connection, and the
+// commands that read or write files are refused. Every connection gets its own
+// thread, and all of them share one loaded graph.
+use crate::repl::{Flow, Repl};
+use graph_analysis::Graph;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Longest command line a client may send, in bytes; a longer one closes the connection
+const MAX_LINE: u64 = 64 * 1024;
+
+/// Failed accepts in a row after which serve stops and returns the last error
+const MAX_ACCEPT_FAILURES: u32 = 10;
+
+/// How long to wait after the given number of failed accepts in a row: 10 ms,
+/// doubling with each failure up to one second
+fn accept_backoff(failures: u32) -> Duration {
+    let millis = 10u64 << failures.saturating_sub(1).min(7);
+    Duration::from_millis(millis.min(1000))
+}
+
+/// Answers the commands of one client until it sends quit or disconnects
+fn serve_client(graph: &Graph, stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    let mut repl = Repl::without_file_access(graph);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = (&mut reader).take(MAX_LINE).read_line(&mut line)?;
+        if read == 0 {
+            return Ok(());
+        }
+        if read as u64 == MAX_LINE && !line.ends_with('\n') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("command line longer than {} bytes", MAX_LINE),
+            ));
+        }
+        let mut answer = Vec::new();
+        if repl.execute(&line, &mut answer)? == Flow::Quit {
+            return Ok(());
+        }
+        answer.push(b'\n');
+        writer.write_all(&answer)?;
+        writer.flush()?;
+    }
+}
+
+/// Accepts clients on the listener forever, one thread per connection
+/// A failed accept is logged and skipped, so one bad connection does not stop the server.
+/// Each failure in a row waits longer before the next accept, so a lasting error such as
+/// running out of file descriptors does not spin the loop, and MAX_ACCEPT_FAILURES of
+/// them in a row end it with the last error
+pub fn serve(graph: Arc<Graph>, listener: TcpListener) -> io::Result<()> {
+    let mut failures = 0;
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => {
+                failures = 0;
+                stream
+            }
+            Err(e) => {
+                failures += 1;
+                if failures == MAX_ACCEPT_FAILURES {
+                    return Err(e);
+                }
+                eprintln!("Connection not accepted: {}", e);
+                thread::sleep(accept_backoff(failures));
+                continue;
+            }
+        };
+        let graph = Arc::clone(&graph);
+        thread::spawn(move || {
+            let peer = stream.peer_addr().map(|addr| addr.to_string()).unwrap_or_default();
+            if let Err(e) = serve_client(&graph, stream) {
+                eprintln!("Connection {} closed: {}", peer, e);
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph_analysis::prelude::Dialect;
+
+    const SQUARE: &str = "4\nA B D -1\nB A C -1\nC B D -1\nD A C -1\n";
+
+    /// Starts a server for the square on an ephemeral port and returns its address
+    fn start_server() -> (Arc<Graph>, std::net::SocketAddr) {
+        let mut graph = Graph::new();
+        graph.read_input_text_with(SQUARE, Dialect::Strict).unwrap();
+        let graph = Arc::new(graph);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shared = Arc::clone(&graph);
+        thread::spawn(move || serve(shared, listener));
+        (graph, addr)
+    }
+
+    /// Sends one command and reads its answer, up to the blank line ending it
+    fn ask(reader: &mut BufReader<TcpStream>, writer: &mut TcpStream, command: &str) -> String {
+        writeln!(writer, "{}", command).unwrap();
+        let mut answer = String::new();
+        loop {
+            let mut line = String::new();
+            assert!(reader.read_line(&mut line).unwrap() > 0, "connection closed after {:?}", answer);
+            if line == "\n" {
+                return answer;
+            }
+            answer.push_str(&line);
+        }
+    }
+
+    #[test]
+    fn two_clients_get_the_library_answers() {
+        let (graph, addr) = start_server();
+        let clients: Vec<_> = ["A", "C"]
+            .into_iter()
+            .map(|from| {
+                let graph = Arc::clone(&graph);
+                thread::spawn(move || {
+                    let mut writer = TcpStream::connect(addr).unwrap();
+                    let mut reader = BufReader::new(writer.try_clone().unwrap());
+                    for _ in 0..20 {
+                        let (_, route) = graph.shortest_path(from, "B").unwrap();
+                        let (cost, path) = route.unwrap();
+                        assert_eq!(
+                            ask(&mut reader, &mut writer, &format!("path {} B", from)),
+                            format!("{} (cost {}, BFS)\n", path.join("->"), cost)
+                        );
+                        assert_eq!(ask(&mut reader, &mut writer, "stats"), graph.stats().to_string());
+                    }
+                    assert_eq!(ask(&mut reader, &mut writer, "neighbors D"), "A C\n");
+                    writeln!(writer, "quit").unwrap();
+                    let mut rest = String::new();
+                    reader.read_to_string(&mut rest).unwrap();
+                    assert!(rest.is_empty());
+                })
+            })
+            .collect();
+        for client in clients {
+            client.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn failed_accepts_back_off_up_to_a_second() {
+        let waits: Vec<u64> = (1..=9).map(|failures| accept_backoff(failures).as_millis() as u64).collect();
+        assert_eq!(waits, [10, 20, 40, 80, 160, 320, 640, 1000, 1000]);
+        assert_eq!(accept_backoff(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn file_commands_are_refused() {
+        let (_, addr) = start_server();
+        let mut writer = TcpStream::connect(addr).unwrap();
+        let mut reader = BufReader::new(writer.try_clone().unwrap());
+        assert_eq!(ask(&mut reader, &mut writer, "save-session /tmp/x"), "Error: save-session is not available here\n");
+        assert_eq!(ask(&mut reader, &mut writer, "neighbors Q"), "Error: vertex Q not found\n");
+    }
+}
@@ -0,0 +1,444 @@
+// src/shortest_path.rs - Shortest paths over unweighted, 0/1-weighted and nonnegative graphs
+use crate::algo::{self, SearchTree};
+use crate::error::GraphError;
+use crate::weights::WeightProfile;
+use crate::Graph;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+
+/// The cost of a path and the labels along it, from source to target
+pub type Route = (f64, Vec<String>);
+
+/// The algorithm used to answer a shortest path query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathAlgorithm {
+    Bfs,
+    ZeroOneBfs,
+    UniformCost,
+    BellmanFord,
+}
+
+impl PathAlgorithm {
+    /// Parses the name used by the --algorithm flag
+    pub fn from_name(name: &str) -> Option<PathAlgorithm> {
+        match name {
+            "bfs" => Some(PathAlgorithm::Bfs),
+            "0-1-bfs" => Some(PathAlgorithm::ZeroOneBfs),
+            "uniform-cost" => Some(PathAlgorithm::UniformCost),
+            "bellman-ford" => Some(PathAlgorithm::BellmanFord),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PathAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PathAlgorithm::Bfs => "BFS",
+            PathAlgorithm::ZeroOneBfs => "0-1 BFS",
+            PathAlgorithm::UniformCost => "uniform-cost search",
+            PathAlgorithm::BellmanFord => "Bellman-Ford",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Counters describing the work done by a search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchStats {
+    /// Number of vertices taken off the queue and expanded
+    pub expansions: usize,
+}
+
+impl Graph {
+    /// Returns the position of every vertex in alphabetical order, indexed by vertex index
+    pub(crate) fn alphabetical_ranks(&self) -> Vec<usize> {
+        algo::alphabetical_ranks(self)
+    }
+
+    /// Follows a predecessor array back from target and returns the labels from the source to target
+    pub(crate) fn reconstruct_path(&self, pred: &[Option<usize>], target: usize) -> Vec<String> {
+        self.labels_of(&algo::reconstruct_path(pred, target))
+    }
+
+    /// Maps vertex indices to their labels
+    pub(crate) fn labels_of(&self, indices: &[usize]) -> Vec<String> {
+        indices.iter().map(|&i| self.vertices[i].clone()).collect()
+    }
+
+    /// Looks up the index of a label or reports it as unknown
+    pub(crate) fn require_vertex_idx(&self, label: &str) -> Result<usize, GraphError> {
+        self.find_vertex_idx(label)
+            .ok_or_else(|| self.unknown_vertex(label))
+    }
+
+    /// Checks every edge weight against a predicate and reports the first edge that fails it
+    pub(crate) fn validate_weights(
+        &self,
+        is_valid: impl Fn(f64) -> bool,
+        reason: &'static str,
+    ) -> Result<(), GraphError> {
+        algo::validate_weights(self, is_valid, reason)
+    }
+
+    /// Computes hop distances and the BFS tree from a starting index, ignoring weights
+    pub(crate) fn bfs_tree(&self, start: usize) -> SearchTree<usize> {
+        algo::bfs_tree(self, start)
+    }
+
+    /// Finds a path with the fewest edges between two labels, ignoring weights
+    /// Returns the labels from `from` to `to`, or None when either label is unknown or
+    /// `to` cannot be reached. Ties go the way of bfs(), which visits neighbors alphabetically.
+    pub fn find_path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let start = self.find_vertex_idx(from)?;
+        let target = self.find_vertex_idx(to)?;
+        let (dist, pred) = self.bfs_tree(start);
+        dist[target].map(|_| self.reconstruct_path(&pred, target))
+    }
+
+    /// Runs 0/1 BFS from a starting index and returns the distances and predecessors
+    fn zero_one_bfs_tree(&self, start: usize) -> Result<SearchTree<u64>, GraphError> {
+        algo::zero_one_bfs_tree(self, start)
+    }
+
+    /// Computes the distance from a starting index to every vertex in a graph whose
+    /// weights are all 0 or 1, using a deque instead of a priority queue
+    /// Returns None for vertices that cannot be reached
+    pub fn zero_one_bfs(&self, start: usize) -> Result<Vec<Option<u64>>, GraphError> {
+        let (dist, _) = self.zero_one_bfs_tree(start)?;
+        Ok(dist)
+    }
+
+    /// Runs a best-first search guided by a heuristic estimate of the remaining cost
+    /// With a zero heuristic this is uniform-cost search (Dijkstra's algorithm)
+    fn best_first_search(
+        &self,
+        start: usize,
+        target: usize,
+        heuristic: impl Fn(usize) -> f64,
+    ) -> (Option<Route>, SearchStats) {
+        let (found, stats) = algo::best_first_search(self, start, target, heuristic);
+        (found.map(|(cost, path)| (cost, self.labels_of(&path))), stats)
+    }
+
+    /// Finds a minimum cost path between two labels with uniform-cost search
+    /// Returns the cost and the labels along the path, or None if to is unreachable
+    pub fn uniform_cost_search(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<Option<Route>, GraphError> {
+        Ok(self.uniform_cost_search_with_stats(from, to)?.0)
+    }
+
+    /// Same as uniform_cost_search, also returning how many vertices were expanded
+    pub fn uniform_cost_search_with_stats(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<(Option<Route>, SearchStats), GraphError> {
+        let start = self.require_vertex_idx(from)?;
+        let target = self.require_vertex_idx(to)?;
+        self.validate_weights(|w| w >= 0.0, "uniform-cost search requires nonnegative weights")?;
+
+        Ok(self.best_first_search(start, target, |_| 0.0))
+    }
+
+    /// Finds a minimum cost path between two labels with A* search
+    ///
+    /// The heuristic estimates the remaining cost from a label to `to`. The result
+    /// is only guaranteed to be a minimum cost path when the heuristic is
+    /// admissible, i.e. it never overestimates the true remaining cost.
+    pub fn astar(
+        &self,
+        from: &str,
+        to: &str,
+        heuristic: impl Fn(&str) -> f64,
+    ) -> Result<Option<Route>, GraphError> {
+        Ok(self.astar_with_stats(from, to, heuristic)?.0)
+    }
+
+    /// Same as astar, also returning how many vertices were expanded
+    pub fn astar_with_stats(
+        &self,
+        from: &str,
+        to: &str,
+        heuristic: impl Fn(&str) -> f64,
+    ) -> Result<(Option<Route>, SearchStats), GraphError> {
+        let start = self.require_vertex_idx(from)?;
+        let target = self.require_vertex_idx(to)?;
+        self.validate_weights(|w| w >= 0.0, "A* search requires nonnegative weights")?;
+
+        Ok(self.best_first_search(start, target, |idx| heuristic(&self.vertices[idx])))
+    }
+
+    /// Runs Bellman-Ford from a starting index and returns the distances and predecessors,
+    /// or the negative cycle reachable from the start
+    fn bellman_ford_tree(&self, start: usize) -> Result<SearchTree<f64>, GraphError> {
+        algo::bellman_ford_tree(self, start)
+    }
+
+    /// Computes the distance from a starting label to every vertex with Bellman-Ford,
+    /// which accepts negative weights
+    /// Returns the distances in input order (None when unreachable), or
+    /// GraphError::NegativeCycle when a negative cycle is reachable from the start.
+    /// In an undirected graph every negative edge forms such a cycle with itself.
+    pub fn bellman_ford(&self, start: &str) -> Result<Vec<(String, Option<f64>)>, GraphError> {
+        let start_idx = self.require_vertex_idx(start)?;
+        let (dist, _) = self.bellman_ford_tree(start_idx)?;
+        Ok(self.vertices[..self.n_vertices]
+            .iter()
+            .cloned()
+            .zip(dist)
+            .collect())
+    }
+
+    /// Computes a shortest route from a starting index to every vertex with Dijkstra's algorithm
+    /// Returns the routes in input order, None for vertices that cannot be reached;
+    /// weights must be nonnegative, and equal routes are chosen alphabetically
+    pub fn dijkstra(&self, start_idx: usize) -> Result<Vec<Option<Route>>, GraphError> {
+        if start_idx >= self.n_vertices {
+            return Err(GraphError::InvalidParameter {
+                name: "start_idx",
+                reason: format!("{} is not below the {} vertices", start_idx, self.n_vertices),
+            });
+        }
+        self.validate_weights(|w| w >= 0.0, "Dijkstra's algorithm requires nonnegative weights")?;
+        let (dist, pred) = algo::dijkstra_tree(self, start_idx);
+        Ok(dist
+            .iter()
+            .enumerate()
+            .map(|(idx, d)| d.map(|d| (d, self.reconstruct_path(&pred, idx))))
+            .collect())
+    }
+
+    /// To create/write an output file of the shortest route from a start to every vertex
+    /// The first line names the algorithm, as `algorithm: Bellman-Ford`; the lines after
+    /// it read `vertex distance A->B->vertex` in alphabetical order of the vertices, and
+    /// unreachable vertices read `vertex INF`. Every route is found by shortest_path_with,
+    /// so its errors, such as NegativeCycle, leave no file behind
+    pub fn produce_output_file_shortest(
+        &self,
+        base_name: &str,
+        start: &str,
+        algorithm: PathAlgorithm,
+    ) -> Result<(), GraphError> {
+        self.require_vertex_idx(start)?;
+        let routes = self
+            .sort_vertices()
+            .into_iter()
+            .map(|idx| Ok((idx, self.shortest_path_with(start, &self.vertices[idx], algorithm)?)))
+            .collect::<Result<Vec<_>, GraphError>>()?;
+        let output_name = Self::get_output_filename(base_name, "-SHORTPATH");
+        let mut fp = File::create(output_name)?;
+
+        writeln!(fp, "algorithm: {}", algorithm)?;
+        for (idx, route) in routes {
+            match route {
+                Some((distance, path)) => writeln!(fp, "{} {} {}", self.vertices[idx], distance, path.join("->"))?,
+                None => writeln!(fp, "{} INF", self.vertices[idx])?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Picks the cheapest shortest path algorithm the graph's weights allow
+    pub fn choose_path_algorithm(&self) -> PathAlgorithm {
+        match self.weight_profile() {
+            WeightProfile::Unweighted => PathAlgorithm::Bfs,
+            WeightProfile::ZeroOne => PathAlgorithm::ZeroOneBfs,
+            WeightProfile::NonNegative => PathAlgorithm::UniformCost,
+            WeightProfile::Negative => PathAlgorithm::BellmanFord,
+        }
+    }
+
+    /// Finds a shortest path between two labels using the cheapest algorithm the
+    /// graph's weights allow, and reports which algorithm was used
+    pub fn shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<(PathAlgorithm, Option<Route>), GraphError> {
+        let algorithm = self.choose_path_algorithm();
+        let route = self.shortest_path_with(from, to, algorithm)?;
+        Ok((algorithm, route))
+    }
+
+    /// Finds a shortest path between two labels with a specific algorithm
+    pub fn shortest_path_with(
+        &self,
+        from: &str,
+        to: &str,
+        algorithm: PathAlgorithm,
+    ) -> Result<Option<Route>, GraphError> {
+        let start = self.require_vertex_idx(from)?;
+        let target = self.require_vertex_idx(to)?;
+
+        match algorithm {
+            PathAlgorithm::Bfs => {
+                let (dist, pred) = self.bfs_tree(start);
+                Ok(dist[target].map(|d| (d as f64, self.reconstruct_path(&pred, target))))
+            }
+            PathAlgorithm::ZeroOneBfs => {
+                let (dist, pred) = self.zero_one_bfs_tree(start)?;
+                Ok(dist[target].map(|d| (d as f64, self.reconstruct_path(&pred, target))))
+            }
+            PathAlgorithm::UniformCost => self.uniform_cost_search(from, to),
+            PathAlgorithm::BellmanFord => {
+                let (dist, pred) = self.bellman_ford_tree(start)?;
+                Ok(dist[target].map(|d| (d, self.reconstruct_path(&pred, target))))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::Dialect;
+    use crate::fixtures::scratch_dir;
+    use std::fs;
+
+    fn graph_from(text: &str, dialect: Dialect) -> Graph {
+        let mut graph = Graph::new();
+        graph.read_input_text_with(text, dialect).unwrap();
+        graph
+    }
+
+    fn with_weights(mut graph: Graph, weights: &[(&str, &str, f64)]) -> Graph {
+        for &(u, v, weight) in weights {
+            graph.set_edge_weight(u, v, weight).unwrap();
+        }
+        graph
+    }
+
+    /// Arcs A->B 4, A->C 2, C->B -3 and B->D 1, listed one way only
+    fn negative_arc() -> Graph {
+        with_weights(
+            graph_from("4\nA B C -1\nB D -1\nC B -1\nD -1\n", Dialect::Directed),
+            &[("A", "B", 4.0), ("A", "C", 2.0), ("C", "B", -3.0), ("B", "D", 1.0)],
+        )
+    }
+
+    #[test]
+    fn bellman_ford_takes_the_negative_arc() {
+        let graph = negative_arc();
+        // By hand: C = 2, B = min(4, 2 - 3) = -1, D = B + 1 = 0
+        let expected = [("A", 0.0), ("B", -1.0), ("C", 2.0), ("D", 0.0)].map(|(v, d)| (v.to_string(), Some(d)));
+        assert_eq!(graph.bellman_ford("A").unwrap(), expected);
+        assert_eq!(
+            graph.shortest_path("A", "D").unwrap(),
+            (PathAlgorithm::BellmanFord, Some((0.0, vec!["A", "C", "B", "D"].into_iter().map(String::from).collect())))
+        );
+        // Nothing leads back to A
+        assert_eq!(graph.bellman_ford("D").unwrap()[0], ("A".to_string(), None));
+    }
+
+    /// B->C->D->B weighs 1 + 1 - 3 = -1; A leads into it and E into A
+    fn negative_cycle() -> Graph {
+        with_weights(
+            graph_from("5\nA B -1\nB C -1\nC D -1\nD B -1\nE A -1\n", Dialect::Directed),
+            &[("B", "C", 1.0), ("C", "D", 1.0), ("D", "B", -3.0)],
+        )
+    }
+
+    /// Checks that a result is the negative cycle through B, C and D
+    fn assert_cycle_bcd<T: fmt::Debug>(result: Result<T, GraphError>) {
+        match result {
+            Err(GraphError::NegativeCycle { cycle }) => {
+                let mut members = cycle.clone();
+                members.sort();
+                members.dedup();
+                assert_eq!(members, ["B", "C", "D"], "{:?}", cycle);
+                assert!(cycle.len() == 3 || (cycle.len() == 4 && cycle[0] == cycle[3]), "{:?}", cycle);
+            }
+            other => panic!("expected a negative cycle, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn bellman_ford_names_the_reachable_negative_cycle() {
+        let graph = negative_cycle();
+        for start in ["A", "E", "C"] {
+            assert_cycle_bcd(graph.bellman_ford(start));
+        }
+        // An undirected negative edge is a negative cycle by itself
+        let edge = with_weights(graph_from("2\nA B -1\nB A -1\n", Dialect::Strict), &[("A", "B", -1.0)]);
+        assert!(matches!(edge.bellman_ford("A"), Err(GraphError::NegativeCycle { .. })));
+    }
+
+    /// A random geometric graph: points drawn in the unit square by a small LCG,
+    /// joined when closer than `radius`, each edge weighing its length
+    fn random_geometric(n: usize, radius: f64, seed: u64) -> (Graph, Vec<(f64, f64)>) {
+        let mut state = seed;
+        let mut next = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let points: Vec<(f64, f64)> = (0..n).map(|_| (next(), next())).collect();
+        let length = |i: usize, j: usize| (points[i].0 - points[j].0).hypot(points[i].1 - points[j].1);
+        let mut text = format!("{}\n", n);
+        for i in 0..n {
+            text += &format!("P{}", i);
+            for j in (0..n).filter(|&j| j != i && length(i, j) < radius) {
+                text += &format!(" P{}", j);
+            }
+            text += " -1\n";
+        }
+        let mut graph = graph_from(&text, Dialect::Strict);
+        for i in 0..n {
+            for j in (i + 1..n).filter(|&j| length(i, j) < radius) {
+                graph.set_edge_weight(&format!("P{}", i), &format!("P{}", j), length(i, j)).unwrap();
+            }
+        }
+        (graph, points)
+    }
+
+    #[test]
+    fn astar_agrees_with_dijkstra_on_random_geometric_graphs() {
+        for seed in 1..=5 {
+            let (graph, points) = random_geometric(40, 0.3, seed);
+            let point = |label: &str| points[label[1..].parse::<usize>().unwrap()];
+            for (from, to) in [("P0", "P39"), ("P3", "P17"), ("P25", "P8")] {
+                // The straight line to the target never overestimates: A* is exact
+                let target = point(to);
+                let straight = |label: &str| {
+                    let p = point(label);
+                    (p.0 - target.0).hypot(p.1 - target.1)
+                };
+                let (astar, astar_stats) = graph.astar_with_stats(from, to, straight).unwrap();
+                let (dijkstra, dijkstra_stats) = graph.uniform_cost_search_with_stats(from, to).unwrap();
+                match (astar, dijkstra) {
+                    (Some((a, _)), Some((d, _))) => assert!((a - d).abs() < 1e-9, "seed {}: {} vs {}", seed, a, d),
+                    (a, d) => assert_eq!(a, d, "seed {}: {} to {}", seed, from, to),
+                }
+                assert!(astar_stats.expansions <= dijkstra_stats.expansions);
+            }
+        }
+    }
+
+    #[test]
+    fn shortpath_file_names_its_algorithm() {
+        let dir = scratch_dir("shortest", "algorithm");
+        let base = dir.join("g").display().to_string();
+        negative_arc().produce_output_file_shortest(&base, "A", PathAlgorithm::BellmanFord).unwrap();
+        assert_eq!(
+            fs::read_to_string(format!("{}-SHORTPATH.TXT", base)).unwrap(),
+            "algorithm: Bellman-Ford\nA 0 A\nB -1 A->C->B\nC 2 A->C\nD 0 A->C->B->D\n"
+        );
+        // Uniform-cost search refuses the negative arc instead of writing wrong distances
+        assert!(negative_arc().produce_output_file_shortest(&base, "A", PathAlgorithm::UniformCost).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn shortpath_file_reports_the_negative_cycle() {
+        let dir = scratch_dir("shortest", "negative-cycle");
+        let base = dir.join("g").display().to_string();
+        assert_cycle_bcd(negative_cycle().produce_output_file_shortest(&base, "E", PathAlgorithm::BellmanFord));
+        assert!(!dir.join("g-SHORTPATH.TXT").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,199 @@
+// src/stats.rs - Structural summary of a graph
+use crate::density::DensityStrategy;
+use crate::error::GraphError;
+use crate::planarity::PlanarityResult;
+use crate::Graph;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+
+/// Basic structural numbers describing a graph
+/// The optional fields are only filled in by Graph::stats, which pays for the
+/// more expensive analyses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphStats {
+    /// Name of the graph, when it has one
+    pub name: Option<String>,
+    pub n_vertices: usize,
+    pub n_edges: usize,
+    pub n_components: usize,
+    pub min_degree: usize,
+    pub max_degree: usize,
+    /// Whether the algorithms with two forms run directly or on the complement
+    pub strategy: DensityStrategy,
+    pub edge_connectivity: Option<usize>,
+    pub vertex_connectivity: Option<usize>,
+    pub planarity: Option<PlanarityResult>,
+    /// Lower and upper bound on the diameter, equal when it is known exactly
+    pub diameter: Option<(usize, usize)>,
+}
+
+impl fmt::Display for GraphStats {
+    /// Writes one `name: value` line per statistic
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            writeln!(f, "graph: {}", name)?;
+        }
+        writeln!(f, "vertices: {}", self.n_vertices)?;
+        writeln!(f, "edges: {}", self.n_edges)?;
+        writeln!(f, "components: {}", self.n_components)?;
+        writeln!(f, "min degree: {}", self.min_degree)?;
+        writeln!(f, "max degree: {}", self.max_degree)?;
+        writeln!(f, "strategy: {}", self.strategy)?;
+        if let Some(edge_connectivity) = self.edge_connectivity {
+            writeln!(f, "edge connectivity: {}", edge_connectivity)?;
+        }
+        if let Some(vertex_connectivity) = self.vertex_connectivity {
+            writeln!(f, "vertex connectivity: {}", vertex_connectivity)?;
+        }
+        if let Some(planarity) = &self.planarity {
+            writeln!(f, "planar: {}", planarity)?;
+        }
+        match self.diameter {
+            Some((lower, upper)) if lower == upper => writeln!(f, "diameter: {}", lower)?,
+            Some((lower, upper)) => writeln!(
+                f,
+                "diameter: {} to {} (bounds from BFS sweeps; the graph is too large for the exact value)",
+                lower, upper
+            )?,
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+impl Graph {
+    /// Counts the undirected edges in the adjacency matrix, each unordered pair once
+    pub fn edge_count(&self) -> usize {
+        let mut count = 0;
+        for i in 0..self.n_vertices {
+            for j in i..self.n_vertices {
+                if self.adj_matrix[i][j] == 1 || self.adj_matrix[j][i] == 1 {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Returns the length of the shortest cycle, or None if the graph has no cycle
+    ///
+    /// Edges are treated as undirected. A BFS from every vertex finds the shortest
+    /// cycle through it as the first non-tree edge closing back into the tree.
+    pub fn girth(&self) -> Option<usize> {
+        let n = self.n_vertices;
+        let linked = |u: usize, v: usize| self.adj_matrix[u][v] == 1 || self.adj_matrix[v][u] == 1;
+        let mut best: Option<usize> = None;
+
+        for root in 0..n {
+            if linked(root, root) {
+                return Some(1);
+            }
+            let mut dist: Vec<Option<usize>> = vec![None; n];
+            let mut parent: Vec<Option<usize>> = vec![None; n];
+            let mut queue = VecDeque::from([root]);
+            dist[root] = Some(0);
+
+            while let Some(u) = queue.pop_front() {
+                let du = dist[u].unwrap_or(0);
+                for v in 0..n {
+                    if v == u || !linked(u, v) {
+                        continue;
+                    }
+                    match dist[v] {
+                        None => {
+                            dist[v] = Some(du + 1);
+                            parent[v] = Some(u);
+                            queue.push_back(v);
+                        }
+                        Some(dv) if parent[u] != Some(v) => {
+                            let length = du + dv + 1;
+                            if !matches!(best, Some(b) if b <= length) {
+                                best = Some(length);
+                            }
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Iterates over the degree of every vertex, in input order
+    pub(crate) fn stats_degrees(&self) -> impl Iterator<Item = usize> + Clone + '_ {
+        self.cached_degrees().iter().copied()
+    }
+
+    /// Computes the parts of the summary that take linear time or less
+    fn basic_stats(&self) -> GraphStats {
+        GraphStats {
+            name: self.name.clone(),
+            n_vertices: self.n_vertices,
+            n_edges: self.edge_count(),
+            n_components: self.connected_components().len(),
+            min_degree: self.stats_degrees().min().unwrap_or(0),
+            max_degree: self.stats_degrees().max().unwrap_or(0),
+            strategy: self.density_strategy(),
+            edge_connectivity: None,
+            vertex_connectivity: None,
+            planarity: None,
+            diameter: None,
+        }
+    }
+
+    /// Computes the structural summary of the graph
+    pub fn stats(&self) -> GraphStats {
+        GraphStats {
+            edge_connectivity: Some(self.edge_connectivity()),
+            vertex_connectivity: Some(self.vertex_connectivity()),
+            planarity: Some(self.is_planar()),
+            diameter: Some(self.diameter_approx()),
+            ..self.basic_stats()
+        }
+    }
+
+    /// To create/write an output file that lists the structural summary of a graph
+    pub fn produce_output_file_stats(&self, base_name: &str) -> Result<(), GraphError> {
+        let output_name = Self::get_output_filename(base_name, "-STATS");
+        let mut fp = File::create(output_name)?;
+        write!(fp, "{}", self.stats())?;
+        Ok(())
+    }
+
+    /// Formats a one-line summary of the graph, e.g.
+    /// `Loaded g.txt: 12 vertices, 17 edges, 2 components, max degree 5`
+    /// A graph name other than the default, the path without its extension, follows
+    /// the file, as `Loaded g.txt (roads): ...`
+    pub fn summary_line(&self, name: &str) -> String {
+        // The load summary skips the max-flow based numbers to stay cheap
+        let stats = self.basic_stats();
+        let shown = match &self.name {
+            Some(graph_name) if name.is_empty() => graph_name.clone(),
+            Some(graph_name) if *graph_name != Graph::get_base_filename(name) => format!("{} ({})", name, graph_name),
+            _ => name.to_string(),
+        };
+        format!(
+            "Loaded {}: {} {}, {} {}, {} {}, max degree {}",
+            shown,
+            stats.n_vertices,
+            plural(stats.n_vertices, "vertex", "vertices"),
+            stats.n_edges,
+            plural(stats.n_edges, "edge", "edges"),
+            stats.n_components,
+            plural(stats.n_components, "component", "components"),
+            stats.max_degree
+        )
+    }
+}
+
+/// Picks the singular or plural form of a noun for a count
+fn plural(count: usize, singular: &'static str, plural: &'static str) -> &'static str {
+    if count == 1 {
+        singular
+    } else {
+        plural
+    }
+}
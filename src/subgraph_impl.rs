@@ -1,8 +1,67 @@
 // src/subgraph_impl.rs - Subgraph detection functionality (equivalent to 6-Bonus.c)
+use crate::algo;
+use crate::edge_list::is_edge_list_path;
+use crate::error::GraphError;
 use crate::Graph;
+use std::fmt;
 use std::fs::File;
 use std::io::{self, Write};
 
+/// How much of graph H was found in graph G, from Graph::subgraph_report
+///
+/// Vertices are matched by label. An edge of H counts as matched when G joins the
+/// same labels in every direction H does; an undirected edge and a self-loop count
+/// once, as in Graph::ordered_edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubgraphReport {
+    pub vertices_matched: usize,
+    pub vertices_total: usize,
+    pub edges_matched: usize,
+    pub edges_total: usize,
+}
+
+/// Share of matched items as a percentage; nothing to match counts as fully matched
+fn percent(matched: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        matched as f64 * 100.0 / total as f64
+    }
+}
+
+impl SubgraphReport {
+    /// Checks whether every vertex and every edge of H was found
+    pub fn is_subgraph(&self) -> bool {
+        self.vertices_matched == self.vertices_total && self.edges_matched == self.edges_total
+    }
+
+    /// Percentage of the vertices of H found in G
+    pub fn vertex_percent(&self) -> f64 {
+        percent(self.vertices_matched, self.vertices_total)
+    }
+
+    /// Percentage of the edges of H found in G
+    pub fn edge_percent(&self) -> f64 {
+        percent(self.edges_matched, self.edges_total)
+    }
+}
+
+/// Written as `3/4 vertices (75.0%), 4/5 edges (80.0%)`
+impl fmt::Display for SubgraphReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} vertices ({:.1}%), {}/{} edges ({:.1}%)",
+            self.vertices_matched,
+            self.vertices_total,
+            self.vertex_percent(),
+            self.edges_matched,
+            self.edges_total,
+            self.edge_percent()
+        )
+    }
+}
+
 impl Graph {
     /// To remove the file extension from a file's name
     pub fn remove_file_extension(file_name: &str) -> String {
@@ -24,7 +83,7 @@ impl Graph {
         graph_g: &Graph,
         graph_h: &Graph,
         sorted_indices: &[usize],
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), GraphError> {
         for &i in sorted_indices {
             // Check if vertex in graph H exists on the graph G
             let vertex_found = graph_g.find_vertex_idx(&graph_h.vertices[i]);
@@ -64,27 +123,17 @@ impl Graph {
     }
 
     /// To write on a file if an edge in the second graph exists in the first graph or not
+    /// Edges of graph H are listed in the canonical order of Graph::ordered_edges
     pub fn write_edges_status(
         file: &mut File,
         graph_g: &Graph,
         graph_h: &Graph,
-        sorted_indices: &[usize],
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // Loop through all vertices in graph H
-        for i in 0..graph_h.n_vertices {
-            for j in (i + 1)..graph_h.n_vertices {
-                // Determine two vertices
-                let idx1 = sorted_indices[i];
-                let idx2 = sorted_indices[j];
-
-                // If edge exists on graph H
-                if graph_h.adj_matrix[idx1][idx2] == 1 {
-                    // Check if edge also exists on graph G
-                    let status = graph_g.get_edge_status(graph_h, idx1, idx2);
-                    // Then print the corresponding status
-                    writeln!(file, "({},{}) {}", graph_h.vertices[idx1], graph_h.vertices[idx2], status)?;
-                }
-            }
+    ) -> Result<(), GraphError> {
+        for edge in graph_h.ordered_edges().iter().filter(|edge| !edge.is_loop()) {
+            // Check if edge also exists on graph G
+            let status = graph_g.get_edge_status(graph_h, edge.source_idx, edge.target_idx);
+            // Then print the corresponding status
+            writeln!(file, "({},{}) {}", edge.source, edge.target, status)?;
         }
         Ok(())
     }
@@ -105,15 +154,7 @@ impl Graph {
     /// To check if all vertices in graph H exist in graph G
     /// Returns true if all vertices exist, false otherwise
     pub fn all_vertices_exist(&self, graph_h: &Graph) -> bool {
-        // Loop through all vertices in graph H
-        for i in 0..graph_h.n_vertices {
-            // If vertex in graph H cannot be found in graph G
-            // return false
-            if !self.find_vertex(&graph_h.vertices[i]) {
-                return false;
-            }
-        }
-        true
+        algo::all_vertices_exist(self, graph_h)
     }
 
     /// Checks if a specific edge from graph H exists in graph G
@@ -145,39 +186,36 @@ impl Graph {
     /// Determines if all edges in graph H exist in graph G
     /// Returns true if all edges within graph H are also present in graph G, false otherwise
     pub fn all_edges_exist(&self, graph_h: &Graph) -> bool {
-        // Loop through all vertices in graph H
-        for h_index1 in 0..graph_h.n_vertices {
-            // Loop through all vertices in graph H
-            for h_index2 in 0..graph_h.n_vertices {
-                // If an edge exists between both vertices in graph H
-                if graph_h.adj_matrix[h_index1][h_index2] == 1 {
-                    // Check if the edge also exists in graph G
-                    // If it doesn't exist, return false
-                    if !self.check_edge_exists(graph_h, h_index1, h_index2) {
-                        return false;
-                    }
-                }
-            }
+        algo::all_edges_exist(self, graph_h)
+    }
+
+    /// Counts the vertices and edges of graph H that are found in graph G
+    /// The report is a subgraph exactly when check_is_subgraph holds
+    pub fn subgraph_report(&self, graph_h: &Graph) -> SubgraphReport {
+        let vertices_matched = (0..graph_h.n_vertices)
+            .filter(|&i| self.find_vertex_idx(&graph_h.vertices[i]).is_some())
+            .count();
+        let edges = graph_h.ordered_edges();
+        let edges_matched = edges
+            .iter()
+            .filter(|edge| {
+                let (Some(u), Some(v)) = (self.find_vertex_idx(&edge.source), self.find_vertex_idx(&edge.target)) else {
+                    return false;
+                };
+                self.adj_matrix[u][v] == 1 && (edge.directed || self.adj_matrix[v][u] == 1)
+            })
+            .count();
+        SubgraphReport {
+            vertices_matched,
+            vertices_total: graph_h.n_vertices,
+            edges_matched,
+            edges_total: edges.len(),
         }
-        true
     }
 
     /// Determines if graph H is a subgraph of graph G
     pub fn check_is_subgraph(&self, graph_h: &Graph) -> bool {
-        // Check if all vertices on graph H exist on graph G
-        let all_vertices_found = self.all_vertices_exist(graph_h);
-        
-        // If all vertices exist on graphs H and G, check if the edges on H also exist on G
-        // If some or all vertices on graph H don't exist on graph G, 
-        // some or all edges on H cannot be found on G
-        let all_edges_found = if all_vertices_found {
-            self.all_edges_exist(graph_h)
-        } else {
-            false
-        };
-
-        // If all vertices and edges in graph H exist on graph G, H is a subgraph of G
-        all_vertices_found && all_edges_found
+        algo::is_subgraph(self, graph_h)
     }
 
     /// To write on a file if a graph is a subgraph of another graph
@@ -186,7 +224,7 @@ impl Graph {
         base_g: &str,
         base_h: &str,
         is_subgraph: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), GraphError> {
         if is_subgraph {
             writeln!(file, "{} is a subgraph of {}.", base_h, base_g)?;
         } else {
@@ -195,61 +233,124 @@ impl Graph {
         Ok(())
     }
 
+    /// Checks whether graph H is too large to be a subgraph of this graph, which
+    /// usually means the two inputs were given in the wrong order
+    pub fn suggest_swap(&self, graph_h: &Graph) -> bool {
+        graph_h.n_vertices > self.n_vertices || graph_h.edge_count() > self.edge_count()
+    }
+
     /// To create/write the output file
     pub fn produce_subgraph_output(
         &self,
         str_file_g: &str,
         str_file_h: &str,
         graph_h: &Graph,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let str_base_g = Self::remove_file_extension(str_file_g);
-        let str_base_h = Self::remove_file_extension(str_file_h);
+    ) -> Result<(), GraphError> {
+        self.produce_subgraph_output_with(str_file_g, str_file_h, graph_h, false)
+    }
+
+    /// To create/write the output file, optionally checking the graphs in swapped roles
+    ///
+    /// With swapped, the file is still named after G and H in the order given, but
+    /// it reports whether G is a subgraph of H and the conclusion notes the swap.
+    pub fn produce_subgraph_output_with(
+        &self,
+        str_file_g: &str,
+        str_file_h: &str,
+        graph_h: &Graph,
+        swapped: bool,
+    ) -> Result<(), GraphError> {
+        // Graph names take the place of the file stems, in the file name and the conclusion
+        let str_base_g = self.display_name(&Self::remove_file_extension(str_file_g)).to_string();
+        let str_base_h = graph_h.display_name(&Self::remove_file_extension(str_file_h)).to_string();
         let str_output_filename = Self::create_output_filename(&str_base_g, &str_base_h);
 
         let mut file = File::create(str_output_filename)?;
 
-        // Write output file
-        let sorted_indices = graph_h.sort_vertices_alphabetically();
-        Self::write_vertices_status(&mut file, self, graph_h, &sorted_indices)?;
-        Self::write_edges_status(&mut file, self, graph_h, &sorted_indices)?;
+        // The container is the graph checked against, the candidate the possible subgraph
+        let (container, candidate, base_container, base_candidate) = if swapped {
+            (graph_h, self, &str_base_h, &str_base_g)
+        } else {
+            (self, graph_h, &str_base_g, &str_base_h)
+        };
 
-        let is_subgraph = self.check_is_subgraph(graph_h);
-        Self::write_subgraph_conclusion(&mut file, &str_base_g, &str_base_h, is_subgraph)?;
+        // Write output file
+        let sorted_indices = candidate.sort_vertices_alphabetically();
+        Self::write_vertices_status(&mut file, container, candidate, &sorted_indices)?;
+        Self::write_edges_status(&mut file, container, candidate)?;
+
+        if swapped {
+            writeln!(
+                file,
+                "Inputs swapped: {} is larger than {}, so {} was checked against {}.",
+                str_base_h, str_base_g, str_base_g, str_base_h
+            )?;
+        }
+        let is_subgraph = container.check_is_subgraph(candidate);
+        Self::write_subgraph_conclusion(&mut file, base_container, base_candidate, is_subgraph)?;
+        // On failure, how close the candidate came
+        if !is_subgraph {
+            writeln!(file, "Matched: {}", container.subgraph_report(candidate))?;
+        }
 
         Ok(())
     }
 
+    /// To print why a graph file could not be read, with a hint when the file is inconsistent
+    fn report_read_error(str_file: &str, error: &GraphError) {
+        match error {
+            GraphError::Inconsistent(violation) => {
+                println!("File {} is inconsistent: {}", str_file, violation);
+                if let Some(hint) = violation.hint() {
+                    println!("Hint: {}", hint);
+                }
+            }
+            GraphError::FileNotFound(_) => println!("File {} not found.", str_file),
+            _ => println!("File {} could not be read: {}", str_file, error),
+        }
+    }
+
     /// To read the files of both graphs and get their respective details
     /// Returns Ok((graph_g, graph_h)) if both files were successfully read, Err otherwise
+    /// A file named *.edges is read as an edge list, as everywhere else
     pub fn read_both_graph_files(
         str_file_g: &str,
         str_file_h: &str,
-    ) -> Result<(Graph, Graph), Box<dyn std::error::Error>> {
+    ) -> Result<(Graph, Graph), GraphError> {
         let mut graph_g = Graph::new();
         let mut graph_h = Graph::new();
 
         // If either or both files cannot be read, return error
-        match graph_g.read_input_file(str_file_g) {
+        match graph_g.read_graph_file(str_file_g) {
             Ok(_) => {},
-            Err(_) => {
-                println!("File {} not found.", str_file_g);
-                return Err("File not found".into());
+            Err(e) => {
+                Self::report_read_error(str_file_g, &e);
+                return Err(e);
             }
         }
 
-        match graph_h.read_input_file(str_file_h) {
+        match graph_h.read_graph_file(str_file_h) {
             Ok(_) => {},
-            Err(_) => {
-                println!("File {} not found.", str_file_h);
-                return Err("File not found".into());
+            Err(e) => {
+                Self::report_read_error(str_file_h, &e);
+                return Err(e);
             }
         }
 
         Ok((graph_g, graph_h))
     }
 
+    /// Reads one graph file of the subgraph check, as an edge list when it is named *.edges
+    fn read_graph_file(&mut self, str_file: &str) -> Result<(), GraphError> {
+        if is_edge_list_path(str_file) {
+            self.read_edge_list(str_file)
+        } else {
+            self.read_input_file(str_file)
+        }
+    }
+
     /// To get the file names of the two graphs to be used
-    pub fn get_input_filenames() -> Result<(String, String), Box<dyn std::error::Error>> {
+    pub fn get_input_filenames() -> Result<(String, String), GraphError> {
         println!("Input first graph filename: ");
         let mut str_file_g = String::new();
         io::stdin().read_line(&mut str_file_g)?;
@@ -267,13 +368,12 @@ impl Graph {
 /// To read the files of two graphs, determine if the program continues,
 /// find out if one graph is a subgraph of the other, and produce an output file.
 /// Returns Ok(()) on success
-pub fn main_subgraph() -> Result<(), Box<dyn std::error::Error>> {
-    let (str_file_g, str_file_h) = Graph::get_input_filenames()?;
-    
-    match Graph::read_both_graph_files(&str_file_g, &str_file_h) {
-        Ok((graph_g, graph_h)) => {
-            graph_g.produce_subgraph_output(&str_file_g, &str_file_h, &graph_h)?;
-            
+pub fn main_subgraph() -> Result<(), GraphError> {
+    match main_subgraph_with(&mut |_, _| false) {
+        Ok(summaries) => {
+            for line in summaries {
+                println!("{}", line);
+            }
             // Note: In Rust, we don't need to manually free memory as it's handled automatically
             println!("Subgraph analysis completed successfully!");
         }
@@ -284,3 +384,71 @@ pub fn main_subgraph() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Same as main_subgraph, asking confirm_swap(base_g, base_h) whether to check the
+/// graphs in swapped roles when H is larger than G
+/// Returns the summary lines of G and H for the caller to show, rather than printing them
+pub fn main_subgraph_with(
+    confirm_swap: &mut dyn FnMut(&str, &str) -> bool,
+) -> Result<Vec<String>, GraphError> {
+    let (str_file_g, str_file_h) = Graph::get_input_filenames()?;
+    let (graph_g, graph_h) = Graph::read_both_graph_files(&str_file_g, &str_file_h)?;
+    let summaries = vec![graph_g.summary_line(&str_file_g), graph_h.summary_line(&str_file_h)];
+
+    let swapped = graph_g.suggest_swap(&graph_h)
+        && confirm_swap(
+            graph_g.display_name(&Graph::remove_file_extension(&str_file_g)),
+            graph_h.display_name(&Graph::remove_file_extension(&str_file_h)),
+        );
+    graph_g.produce_subgraph_output_with(&str_file_g, &str_file_h, &graph_h, swapped)?;
+
+    Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::Dialect;
+    use crate::fixtures;
+    use std::fs;
+
+    fn path_abc() -> Graph {
+        let mut graph = Graph::new();
+        graph.read_input_text_with("3\nA B -1\nB A C -1\nC B -1\n", Dialect::Strict).unwrap();
+        graph
+    }
+
+    #[test]
+    fn one_missing_edge_is_counted() {
+        let report = path_abc().subgraph_report(&fixtures::triangle());
+        assert_eq!(
+            report,
+            SubgraphReport { vertices_matched: 3, vertices_total: 3, edges_matched: 2, edges_total: 3 }
+        );
+        assert!(!report.is_subgraph());
+        assert_eq!(report.to_string(), "3/3 vertices (100.0%), 2/3 edges (66.7%)");
+        assert!(!path_abc().check_is_subgraph(&fixtures::triangle()));
+    }
+
+    #[test]
+    fn a_subgraph_matches_fully() {
+        let report = fixtures::triangle().subgraph_report(&path_abc());
+        assert!(report.is_subgraph());
+        assert_eq!((report.vertex_percent(), report.edge_percent()), (100.0, 100.0));
+        assert!(fixtures::triangle().check_is_subgraph(&path_abc()));
+        assert!(path_abc().suggest_swap(&fixtures::petersen()));
+    }
+
+    #[test]
+    fn edge_list_files_are_read_as_edge_lists() {
+        let dir = fixtures::scratch_dir("subgraph", "edge-lists");
+        let (g, h) = (dir.join("g.txt"), dir.join("h.edges"));
+        fs::write(&g, "3\nA B C -1\nB A C -1\nC A B -1\n").unwrap();
+        fs::write(&h, "A B\nB C\n").unwrap();
+        let (graph_g, graph_h) =
+            Graph::read_both_graph_files(&g.display().to_string(), &h.display().to_string()).unwrap();
+        assert!(graph_g.check_is_subgraph(&graph_h));
+        assert_eq!(graph_h.subgraph_report(&graph_g).edges_matched, 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,103 @@
+// src/suggest.rs - "Did you mean" suggestions for labels that do not exist
+use crate::error::GraphError;
+use crate::Graph;
+
+/// Largest edit distance at which a label is still suggested
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+/// Number of suggestions attached to an UnknownVertex error
+pub const DEFAULT_SUGGESTIONS: usize = 3;
+
+/// Counts the insertions, deletions, substitutions and adjacent transpositions
+/// turning a into b (optimal string alignment distance)
+pub(crate) fn osa_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(d[i - 2][j - 2] + 1);
+            }
+            d[i][j] = best;
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Returns up to k of the labels resembling query, the closest first, as closest_labels does
+/// For label sets kept outside a Graph, such as a ComponentsIndex or an edge list file
+pub(crate) fn closest_among<'a, I>(labels: I, query: &str, k: usize) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let query = query.to_lowercase();
+    let max_distance = (query.chars().count() / 2).min(MAX_SUGGESTION_DISTANCE);
+    let mut close: Vec<(usize, &String)> = labels
+        .into_iter()
+        .map(|label| (osa_distance(&query, &label.to_lowercase()), label))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect();
+    close.sort();
+    close.dedup();
+    close.into_iter().take(k).map(|(_, label)| label.clone()).collect()
+}
+
+impl Graph {
+    /// Returns up to k labels resembling query, the closest first
+    ///
+    /// Labels are compared case-insensitively, so a label differing from the query
+    /// only in case ranks first. Short queries allow fewer edits than long ones, and
+    /// labels at the same distance are ordered alphabetically.
+    pub fn closest_labels(&self, query: &str, k: usize) -> Vec<String> {
+        closest_among(&self.vertices[..self.n_vertices], query, k)
+    }
+
+    /// The UnknownVertex error for a label, with suggestions from this graph
+    pub fn unknown_vertex(&self, label: &str) -> GraphError {
+        GraphError::UnknownVertex {
+            label: label.to_string(),
+            suggestions: self.closest_labels(label, DEFAULT_SUGGESTIONS),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::Dialect;
+
+    #[test]
+    fn osa_counts_transpositions_once() {
+        assert_eq!(osa_distance("kitten", "sitting"), 3);
+        assert_eq!(osa_distance("ab", "ba"), 1);
+        assert_eq!(osa_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn suggestions_rank_case_first_then_distance_then_label() {
+        let labels: Vec<String> = ["Alpha", "alpah", "Alpine", "Beta", "alpha2"].map(String::from).to_vec();
+        assert_eq!(closest_among(&labels, "ALPHA", 3), ["Alpha", "alpah", "alpha2"]);
+        assert_eq!(closest_among(&labels, "ALPHA", 1), ["Alpha"]);
+        // One letter allows no edit at all
+        assert!(closest_among(&labels, "B", 3).is_empty());
+    }
+
+    #[test]
+    fn unknown_vertex_carries_the_suggestions() {
+        let mut graph = Graph::new();
+        graph.read_input_text_with("3\nParis -1\nParma -1\nLyon -1\n", Dialect::Strict).unwrap();
+        assert_eq!(
+            graph.unknown_vertex("paris"),
+            GraphError::UnknownVertex {
+                label: "paris".to_string(),
+                suggestions: vec!["Paris".to_string(), "Parma".to_string()],
+            }
+        );
+    }
+}
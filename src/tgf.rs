@@ -0,0 +1,40 @@
+// src/tgf.rs - Export to the Trivial Graph Format, as read by yEd
+//
+// The file lists one `id label` line per vertex, ids 1 to n in input order, then
+// a line holding only `#`, then one `source target` line per edge, by id. TGF has
+// no notion of direction: edges follow export_edges, so a graph without one-way
+// pairs gives each edge once and any other graph gives every arc, a pair listed
+// both ways giving two lines. Every line ends with a newline.
+use crate::error::GraphError;
+use crate::Graph;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+impl Graph {
+    /// Writes the graph in the Trivial Graph Format
+    ///
+    /// Edges with an explicit weight carry it as their label, after the two ids.
+    pub fn write_tgf<W: Write>(&self, mut w: W) -> Result<(), GraphError> {
+        let (_, edges) = self.export_edges();
+        for (i, vertex) in self.vertices[..self.n_vertices].iter().enumerate() {
+            writeln!(w, "{} {}", i + 1, vertex)?;
+        }
+        writeln!(w, "#")?;
+        for edge in edges {
+            let (source, target) = (edge.source_idx + 1, edge.target_idx + 1);
+            match edge.weight {
+                Some(weight) => writeln!(w, "{} {} {}", source, target, weight)?,
+                None => writeln!(w, "{} {}", source, target)?,
+            }
+        }
+        w.flush()?;
+        Ok(())
+    }
+
+    /// To create/write the graph in the Trivial Graph Format, named base-GRAPH.tgf
+    pub fn produce_output_file_tgf(&self, base_name: &str) -> Result<(), GraphError> {
+        let fp = BufWriter::new(File::create(format!("{}-GRAPH.tgf", base_name))?);
+        self.write_tgf(fp)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,389 @@
+// src/ui.rs - The interactive menus of the binary
+//
+// Every prompt reads from and writes to the streams handed down from the menu,
+// so a whole session can be driven from a script. The end of input answers any
+// prompt by backing out, so a closed stdin never leaves a menu spinning.
+use crate::cli::{find_command, Command};
+use graph_analysis::dialect::Dialect;
+use graph_analysis::direction::Direction;
+use graph_analysis::edge_list::is_edge_list_path;
+use graph_analysis::output::OutputConfig;
+use graph_analysis::Graph;
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+
+/// A menu handler, given the input to read answers from and the output to prompt on
+pub type Handler = fn(&mut dyn BufRead, &mut dyn Write) -> Result<(), Box<dyn Error>>;
+
+/// What choosing a menu item does
+pub enum Action {
+    Run(Handler),
+    /// Opens a menu of its own, returning here when it is left
+    Submenu(fn() -> Menu),
+    /// Runs a subcommand of the CLI with arguments asked for at the prompt
+    Command(&'static Command),
+}
+
+/// One choice of a menu
+pub struct MenuItem {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub action: Action,
+}
+
+/// A titled list of choices, shown until the exit key or the end of input
+pub struct Menu {
+    pub title: &'static str,
+    pub items: Vec<MenuItem>,
+    /// Key leaving the menu, and what it is called
+    pub exit: (&'static str, &'static str),
+}
+
+impl Menu {
+    /// Writes the title and one line per item
+    pub fn render(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", self.title)?;
+        for item in &self.items {
+            writeln!(out, "{}. {}", item.key, item.description)?;
+        }
+        writeln!(out, "{}. {}", self.exit.0, self.exit.1)
+    }
+
+    /// Shows the menu and runs the chosen items until it is left
+    /// A failing item is reported and the menu shown again
+    pub fn run(&self, input: &mut dyn BufRead, out: &mut dyn Write) -> io::Result<()> {
+        loop {
+            self.render(out)?;
+            let keys: Vec<&str> = self.items.iter().map(|item| item.key).collect();
+            let Some(choice) = ask(input, out, &format!("Select option ({} or {}): ", keys.join(", "), self.exit.0))?
+            else {
+                return Ok(());
+            };
+            if choice == self.exit.0 {
+                return Ok(());
+            }
+            let Some(item) = self.items.iter().find(|item| item.key == choice) else {
+                writeln!(out, "Invalid choice {}.", choice)?;
+                continue;
+            };
+            let result = match &item.action {
+                Action::Run(handler) => handler(input, out),
+                Action::Submenu(build) => build().run(input, out).map_err(Into::into),
+                Action::Command(command) => run_command(command, input, out),
+            };
+            if let Err(error) = result {
+                writeln!(out, "Error: {}", error)?;
+            }
+        }
+    }
+}
+
+/// The menu shown when the binary runs without arguments
+pub fn main_menu() -> Menu {
+    Menu {
+        title: "Graph Analysis Program",
+        items: vec![
+            MenuItem {
+                key: "1",
+                description: "Graph Traversal",
+                action: Action::Run(graph_traversal),
+            },
+            MenuItem {
+                key: "2",
+                description: "Subgraph Detection",
+                action: Action::Run(subgraph_detection),
+            },
+            MenuItem {
+                key: "3",
+                description: "More Tools",
+                action: Action::Submenu(tools_menu),
+            },
+        ],
+        exit: ("0", "Exit"),
+    }
+}
+
+/// Keys of generated menu items, in order
+const ITEM_KEYS: &[&str] = &["1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+/// Subcommands of the CLI worth reaching from the menu; batch analysis is graph6
+const TOOL_COMMANDS: &[&str] = &["repl", "graph6", "convert", "generate"];
+
+/// The second-level menu, one item per CLI subcommand in TOOL_COMMANDS
+pub fn tools_menu() -> Menu {
+    let items = TOOL_COMMANDS
+        .iter()
+        .filter_map(|&name| find_command(name))
+        .zip(ITEM_KEYS)
+        .map(|(command, &key)| MenuItem {
+            key,
+            // The first line of the summary is enough to pick the command by
+            description: command.summary.lines().next().unwrap_or(command.name),
+            action: Action::Command(command),
+        })
+        .collect();
+    Menu {
+        title: "More Tools",
+        items,
+        exit: ("0", "Back"),
+    }
+}
+
+/// Writes a prompt line and reads the trimmed answer, or None at the end of input
+fn ask(input: &mut dyn BufRead, out: &mut dyn Write, prompt: &str) -> io::Result<Option<String>> {
+    writeln!(out, "{}", prompt)?;
+    out.flush()?;
+    let mut answer = String::new();
+    if input.read_line(&mut answer)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(answer.trim().to_string()))
+}
+
+/// Asks for the arguments of a subcommand and runs it
+fn run_command(command: &Command, input: &mut dyn BufRead, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    let prompt = format!("Arguments for {}: {}", command.name, command.args);
+    let Some(line) = ask(input, out, &prompt)? else {
+        return Ok(());
+    };
+    let args: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+    command.execute(&args).map_err(Into::into)
+}
+
+/// Reads a graph, writes its outputs and traversals from one or more starts
+fn graph_traversal(input: &mut dyn BufRead, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    let Some(str_input_filename) = ask(input, out, "Input filename: ")? else {
+        return Ok(());
+    };
+
+    let mut graph = Graph::new();
+    let read = if is_edge_list_path(&str_input_filename) {
+        graph.read_edge_list(&str_input_filename).map(|()| Vec::new())
+    } else {
+        graph.read_input_file_with(&str_input_filename, Dialect::InferCount)
+    };
+    let warnings = match read {
+        Ok(warnings) => warnings,
+        Err(_) => {
+            writeln!(out, "File {} not found.", str_input_filename)?;
+            return Ok(());
+        }
+    };
+    for warning in &warnings {
+        writeln!(out, "Warning: {}", warning)?;
+    }
+
+    let profile = graph.direction_profile();
+    let direction = if profile.one_way_pairs > 0 {
+        writeln!(out, "Direction: {}", profile)?;
+        confirm_direction(input, out, profile.suggested())?
+    } else {
+        Direction::Undirected
+    };
+    if direction == Direction::Undirected {
+        graph.symmetrize();
+    }
+
+    // A directed graph lists its edges once, so only an undirected one must pass the handshake check
+    let checked = match direction {
+        Direction::Undirected => graph.check_invariants(),
+        Direction::Directed => Ok(()),
+    };
+    if let Err(violation) = checked {
+        writeln!(out, "Error: {}", violation)?;
+        if let Some(hint) = violation.hint() {
+            writeln!(out, "Hint: {}", hint)?;
+        }
+        return Ok(());
+    }
+
+    writeln!(out, "{}", graph.summary_line(&str_input_filename))?;
+
+    let Some(str_start_vertex) = ask(input, out, "Input starting label: ")? else {
+        return Ok(());
+    };
+
+    let str_base_filename = Graph::get_base_filename(&str_input_filename);
+    let write_components = graph.connected_components().len() > 1
+        && confirm_components(input, out, &str_base_filename)?;
+
+    graph.produce_core_outputs(&str_base_filename, &OutputConfig::new())?;
+    if write_components {
+        graph.produce_output_file_components(&str_base_filename)?;
+    }
+
+    // The start may be a label or #N for the vertex at index N in input order
+    let (start, warning) = graph.parse_vertex_ref(&str_start_vertex);
+    if let Some(warning) = warning {
+        writeln!(out, "Warning: {}", warning)?;
+    }
+    match graph.resolve_vertex(start) {
+        Ok(_) => graph.produce_traversal_files(&str_base_filename, start)?,
+        Err(error) => writeln!(out, "Error: {}", error)?,
+    }
+
+    // Further starts only rewrite the traversal files, under a -<start> infix
+    while let Some(str_next_vertex) = ask_another_start(input, out)? {
+        let (next, warning) = graph.parse_vertex_ref(&str_next_vertex);
+        if let Some(warning) = warning {
+            writeln!(out, "Warning: {}", warning)?;
+        }
+        match graph.resolve_vertex(next) {
+            Ok(idx) => {
+                let label = graph.vertices[idx].clone();
+                graph.produce_traversal_outputs(&str_base_filename, &label)?;
+                let infix = Graph::file_name_infix(&label);
+                writeln!(out, "Wrote {}-{}-BFS.TXT and {}-{}-DFS.TXT", str_base_filename, infix, str_base_filename, infix)?;
+            }
+            Err(error) => writeln!(out, "Error: {}", error)?,
+        }
+    }
+
+    graph.free_adj_list();
+    writeln!(out, "All output files generated successfully!")?;
+    Ok(())
+}
+
+/// Reads two graphs and writes whether the second is a subgraph of the first
+fn subgraph_detection(input: &mut dyn BufRead, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    let Some(str_file_g) = ask(input, out, "Input first graph filename: ")? else {
+        return Ok(());
+    };
+    let Some(str_file_h) = ask(input, out, "Input second graph filename: ")? else {
+        return Ok(());
+    };
+
+    let (graph_g, graph_h) = match Graph::read_both_graph_files(&str_file_g, &str_file_h) {
+        Ok(graphs) => graphs,
+        Err(e) => {
+            writeln!(out, "Error: {}", e)?;
+            return Ok(());
+        }
+    };
+    writeln!(out, "{}", graph_g.summary_line(&str_file_g))?;
+    writeln!(out, "{}", graph_h.summary_line(&str_file_h))?;
+
+    let swapped = graph_g.suggest_swap(&graph_h)
+        && confirm_swap(
+            input,
+            out,
+            graph_g.display_name(&Graph::remove_file_extension(&str_file_g)),
+            graph_h.display_name(&Graph::remove_file_extension(&str_file_h)),
+        )?;
+    graph_g.produce_subgraph_output_with(&str_file_g, &str_file_h, &graph_h, swapped)?;
+
+    writeln!(out, "Subgraph analysis completed successfully!")?;
+    Ok(())
+}
+
+/// Asks for another starting vertex; N, an empty answer or the end of input stop
+fn ask_another_start(input: &mut dyn BufRead, out: &mut dyn Write) -> io::Result<Option<String>> {
+    match ask(input, out, "Another starting vertex? [label/N]")?.as_deref() {
+        None | Some("" | "n" | "N" | "no") => Ok(None),
+        Some(label) => Ok(Some(label.to_string())),
+    }
+}
+
+/// Asks whether to read the graph as undirected, defaulting to the suggested direction
+/// Without a suggestion the question is repeated until answered
+fn confirm_direction(
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+    suggested: Option<Direction>,
+) -> io::Result<Direction> {
+    let choices = match suggested {
+        Some(Direction::Undirected) => "[Y/n]",
+        Some(Direction::Directed) => "[y/N]",
+        None => "[y/n]",
+    };
+    let prompt = format!("Read as undirected, adding the missing reverse entries? {}", choices);
+    loop {
+        let Some(answer) = ask(input, out, &prompt)? else {
+            return Ok(suggested.unwrap_or(Direction::Directed));
+        };
+        match (answer.as_str(), suggested) {
+            ("y" | "Y" | "yes", _) => return Ok(Direction::Undirected),
+            ("n" | "N" | "no", _) => return Ok(Direction::Directed),
+            ("", Some(direction)) => return Ok(direction),
+            _ => {}
+        }
+    }
+}
+
+/// Asks whether to list the components of a disconnected graph in -COMPONENTS.TXT
+fn confirm_components(input: &mut dyn BufRead, out: &mut dyn Write, base_name: &str) -> io::Result<bool> {
+    let prompt = format!("The graph is disconnected \u{2014} also write {}-COMPONENTS.TXT? [y/N]", base_name);
+    Ok(matches!(ask(input, out, &prompt)?.as_deref(), Some("y" | "Y" | "yes")))
+}
+
+/// Asks whether to check G against H instead when H is too large to fit in G
+fn confirm_swap(input: &mut dyn BufRead, out: &mut dyn Write, base_g: &str, base_h: &str) -> io::Result<bool> {
+    let prompt = format!(
+        "{} is larger than {} \u{2014} swap and check {} \u{2287} {} instead? [y/N]",
+        base_h, base_g, base_h, base_g
+    );
+    Ok(matches!(ask(input, out, &prompt)?.as_deref(), Some("y" | "Y" | "yes")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph_analysis::fixtures::scratch_dir;
+    use std::fs;
+
+    /// Runs the main menu on a scripted input and returns everything it wrote
+    fn session(script: &str) -> String {
+        let mut out = Vec::new();
+        main_menu().run(&mut script.as_bytes(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn traversal_session_writes_every_file() {
+        let dir = scratch_dir("ui", "traversal");
+        let input = dir.join("g.txt");
+        fs::write(&input, "3\nA B -1\nB A C -1\nC B -1\n").unwrap();
+        let transcript = session(&format!("1\n{}\nA\nC\nN\n0\n", input.display()));
+
+        assert!(transcript.contains("Input starting label: "), "{}", transcript);
+        assert!(transcript.ends_with("All output files generated successfully!\nGraph Analysis Program\n1. Graph Traversal\n\
+                                      2. Subgraph Detection\n3. More Tools\n0. Exit\nSelect option (1, 2, 3 or 0): \n"));
+        for suffix in ["SET", "DEGREE", "LIST", "LIST-SORTED", "MATRIX", "BFS", "DFS", "C-BFS", "C-DFS"] {
+            assert!(dir.join(format!("g-{}.TXT", suffix)).is_file(), "g-{}.TXT missing", suffix);
+        }
+        assert!(!dir.join("g-COMPONENTS.TXT").exists());
+        assert_eq!(fs::read_to_string(dir.join("g-BFS.TXT")).unwrap(), "A B C\n");
+        assert_eq!(fs::read_to_string(dir.join("g-C-BFS.TXT")).unwrap(), "C B A\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn disconnected_graph_asks_for_components() {
+        let dir = scratch_dir("ui", "components");
+        let input = dir.join("g.txt");
+        fs::write(&input, "4\nA B -1\nB A -1\nC D -1\nD C -1\n").unwrap();
+        let transcript = session(&format!("1\n{}\nA\ny\nN\n0\n", input.display()));
+        assert!(transcript.contains("-COMPONENTS.TXT? [y/N]"), "{}", transcript);
+        assert!(dir.join("g-COMPONENTS.TXT").is_file());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_file_and_bad_choice_return_to_the_menu() {
+        let transcript = session("7\n1\nno-such-file.txt\n");
+        assert!(transcript.contains("Invalid choice 7."));
+        assert!(transcript.contains("File no-such-file.txt not found."));
+        // The end of input leaves the menu
+        assert_eq!(transcript.matches("Graph Analysis Program").count(), 3);
+    }
+
+    #[test]
+    fn tools_menu_reaches_the_cli_subcommands() {
+        let transcript = session("3\n0\n0\n");
+        let tools = tools_menu();
+        assert!(!tools.items.is_empty());
+        for item in &tools.items {
+            assert!(transcript.contains(&format!("{}. {}", item.key, item.description)));
+        }
+    }
+}
@@ -0,0 +1,171 @@
+// src/validation.rs - Consistency checks across the graph representations
+use crate::Graph;
+use std::fmt;
+
+/// A disagreement between the adjacency list, the degree counts and the adjacency matrix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// adj_count of a vertex differs from the length of its adjacency list
+    CountMismatch {
+        vertex: String,
+        adj_count: i32,
+        list_len: usize,
+    },
+    /// The degree sum is not twice the number of edges found in the matrix, plus its
+    /// self-loops once each; matrix_edges counts the loops among the edges
+    HandshakeMismatch {
+        sum_counts: usize,
+        matrix_edges: usize,
+        loops: usize,
+    },
+    /// A cached analysis result no longer matches the graph
+    StaleCache { entry: &'static str },
+}
+
+impl InvariantViolation {
+    /// Returns a short suggestion on what is likely wrong with the input file
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            InvariantViolation::HandshakeMismatch { .. } => Some(
+                "your file probably lists some edges in only one direction, \
+                 or names a neighbor that has no line of its own",
+            ),
+            InvariantViolation::StaleCache { .. } => Some(
+                "a field of the graph was changed directly; call invalidate_cache afterwards",
+            ),
+            InvariantViolation::CountMismatch { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::CountMismatch {
+                vertex,
+                adj_count,
+                list_len,
+            } => write!(
+                f,
+                "vertex {} has degree count {} but {} adjacency list entries",
+                vertex, adj_count, list_len
+            ),
+            InvariantViolation::HandshakeMismatch {
+                sum_counts,
+                matrix_edges,
+                loops,
+            } => write!(
+                f,
+                "handshake lemma violated: degrees sum to {} but the matrix holds {} edges and {} self-loops, \
+                 which need a sum of {} (2\u{b7}edges + loops)",
+                sum_counts,
+                matrix_edges - loops,
+                loops,
+                2 * (matrix_edges - loops) + loops
+            ),
+            InvariantViolation::StaleCache { entry } => {
+                write!(f, "cached {} is out of date", entry)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvariantViolation {}
+
+impl Graph {
+    /// Verifies that the adjacency list, adj_count and adj_matrix describe the same graph
+    ///
+    /// The handshake lemma requires the degree sum to equal twice the number of
+    /// undirected edges in the matrix. Self-loops are listed once in the input
+    /// format, so they count once on both sides. Any cached analysis result must
+    /// also agree with a fresh computation.
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        // Every degree count must match the length of its linked list
+        for i in 0..self.n_vertices {
+            let list_len = self.collect_adjacent_vertices(&self.adj_list[i]).len();
+            if self.adj_count[i] < 0 || self.adj_count[i] as usize != list_len {
+                return Err(InvariantViolation::CountMismatch {
+                    vertex: self.vertices.get(i).cloned().unwrap_or_default(),
+                    adj_count: self.adj_count[i],
+                    list_len,
+                });
+            }
+        }
+
+        let sum_counts: usize = self.adj_count[..self.n_vertices]
+            .iter()
+            .map(|&count| count as usize)
+            .sum();
+
+        // Count each unordered pair once, whichever direction the matrix has it in
+        let mut pair_edges = 0;
+        let mut loops = 0;
+        for i in 0..self.n_vertices {
+            if self.adj_matrix[i][i] == 1 {
+                loops += 1;
+            }
+            for j in (i + 1)..self.n_vertices {
+                if self.adj_matrix[i][j] == 1 || self.adj_matrix[j][i] == 1 {
+                    pair_edges += 1;
+                }
+            }
+        }
+
+        if sum_counts != 2 * pair_edges + loops {
+            return Err(InvariantViolation::HandshakeMismatch {
+                sum_counts,
+                matrix_edges: pair_edges + loops,
+                loops,
+            });
+        }
+
+        // Cached results must describe the current graph
+        if let Some(entry) = self.stale_cache_entries().first() {
+            return Err(InvariantViolation::StaleCache { entry });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::Dialect;
+    use crate::error::GraphError;
+    use crate::fixtures;
+
+    #[test]
+    fn fixtures_and_self_loops_are_consistent() {
+        fixtures::petersen().check_invariants().unwrap();
+        let mut graph = Graph::new();
+        graph.read_input_text_with("2\nA A B -1\nB A -1\n", Dialect::Strict).unwrap();
+        graph.check_invariants().unwrap();
+    }
+
+    #[test]
+    fn dangling_neighbor_breaks_the_handshake() {
+        let mut graph = Graph::new();
+        let error = graph.read_input_text_with("2\nA B C -1\nB A -1\n", Dialect::Strict).unwrap_err();
+        let GraphError::Inconsistent(violation) = error else {
+            panic!("expected an inconsistent graph, got {:?}", error);
+        };
+        assert_eq!(violation, InvariantViolation::HandshakeMismatch { sum_counts: 3, matrix_edges: 1, loops: 0 });
+        assert_eq!(
+            violation.to_string(),
+            "handshake lemma violated: degrees sum to 3 but the matrix holds 1 edges and 0 self-loops, \
+             which need a sum of 2 (2\u{b7}edges + loops)"
+        );
+        assert!(violation.hint().unwrap().contains("one direction"));
+    }
+
+    #[test]
+    fn count_mismatch_names_the_vertex() {
+        let mut graph = fixtures::triangle();
+        graph.adj_count[1] += 1;
+        assert_eq!(
+            graph.check_invariants(),
+            Err(InvariantViolation::CountMismatch { vertex: "B".to_string(), adj_count: 3, list_len: 2 })
+        );
+    }
+}
@@ -0,0 +1,59 @@
+// src/vertex_ref.rs - Referring to a vertex by label or by position
+use crate::dialect::ParseWarning;
+use crate::error::GraphError;
+use crate::Graph;
+use std::fmt;
+
+/// A vertex given either by its label or by its index in input order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexRef<'a> {
+    Label(&'a str),
+    Index(usize),
+}
+
+impl<'a> VertexRef<'a> {
+    /// Parses user input, reading `#N` as index N and anything else as a label
+    pub fn parse(input: &'a str) -> VertexRef<'a> {
+        match input.strip_prefix('#').map(str::parse::<usize>) {
+            Some(Ok(index)) => VertexRef::Index(index),
+            _ => VertexRef::Label(input),
+        }
+    }
+}
+
+impl fmt::Display for VertexRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VertexRef::Label(label) => write!(f, "{}", label),
+            VertexRef::Index(index) => write!(f, "#{}", index),
+        }
+    }
+}
+
+impl Graph {
+    /// Returns the index of a referenced vertex, checking that it exists
+    pub fn resolve_vertex(&self, vertex: VertexRef) -> Result<usize, GraphError> {
+        match vertex {
+            VertexRef::Label(label) => self.require_vertex_idx(label),
+            VertexRef::Index(index) if index < self.n_vertices => Ok(index),
+            VertexRef::Index(index) => Err(GraphError::IndexOutOfRange {
+                index,
+                n_vertices: self.n_vertices,
+            }),
+        }
+    }
+
+    /// Parses user input as a vertex reference for this graph
+    /// Input such as `#3` that is also a label of the graph refers to that label,
+    /// with a warning about the ambiguity
+    pub fn parse_vertex_ref<'a>(&self, input: &'a str) -> (VertexRef<'a>, Option<ParseWarning>) {
+        let vertex = VertexRef::parse(input);
+        if matches!(vertex, VertexRef::Index(_)) && self.find_vertex_idx(input).is_some() {
+            return (
+                VertexRef::Label(input),
+                Some(ParseWarning::AmbiguousVertexRef(input.to_string())),
+            );
+        }
+        (vertex, None)
+    }
+}
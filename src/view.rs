@@ -0,0 +1,212 @@
+// src/view.rs - Masked views of a graph, read without copying it
+//
+// A GraphView keeps the vertices and edges of a graph that survive its masks and
+// numbers the kept vertices 0..vertex_count() in input order, so it is the induced
+// subgraph a copy would give, index for index. It implements GraphRef, so every
+// generic algorithm in algo.rs runs on it directly.
+use crate::algo::{self, GraphRef};
+use crate::error::GraphError;
+use crate::Graph;
+use std::collections::HashSet;
+
+/// A subset of a graph's vertices and edges, borrowed from the graph
+#[derive(Debug, Clone)]
+pub struct GraphView<'a> {
+    graph: &'a Graph,
+    /// Graph index of every kept vertex, in input order; the position is the view index
+    kept: Vec<usize>,
+    /// View index of every graph vertex, None when masked out
+    position: Vec<Option<usize>>,
+    /// Masked edges as graph index pairs, both directions listed
+    removed_edges: HashSet<(usize, usize)>,
+}
+
+impl Graph {
+    /// Returns a view of the whole graph, to be narrowed with the GraphView methods
+    pub fn view(&self) -> GraphView<'_> {
+        GraphView {
+            graph: self,
+            kept: (0..self.n_vertices).collect(),
+            position: (0..self.n_vertices).map(Some).collect(),
+            removed_edges: HashSet::new(),
+        }
+    }
+}
+
+impl<'a> GraphView<'a> {
+    /// Keeps only the graph vertices accepted by keep
+    fn retain(mut self, keep: impl Fn(usize) -> bool) -> Self {
+        self.kept.retain(|&idx| keep(idx));
+        self.position = vec![None; self.graph.n_vertices];
+        for (pos, &idx) in self.kept.iter().enumerate() {
+            self.position[idx] = Some(pos);
+        }
+        self
+    }
+
+    /// Returns the graph index of a label kept in the view
+    fn require_kept(&self, label: &str) -> Result<usize, GraphError> {
+        let idx = self.graph.require_vertex_idx(label)?;
+        if self.position[idx].is_none() {
+            return Err(GraphError::InvalidParameter {
+                name: "label",
+                reason: format!("{} is masked out of the view", label),
+            });
+        }
+        Ok(idx)
+    }
+
+    /// Masks out vertices and every edge touching them
+    /// Every label must be a vertex of the graph; masking one twice changes nothing
+    pub fn without_vertices(self, labels: &[&str]) -> Result<Self, GraphError> {
+        let masked: HashSet<usize> = labels
+            .iter()
+            .map(|label| self.graph.require_vertex_idx(label))
+            .collect::<Result<_, _>>()?;
+        Ok(self.retain(|idx| !masked.contains(&idx)))
+    }
+
+    /// Masks out edges in both directions, leaving their endpoints in the view
+    /// Every label must be a vertex of the graph; pairs that are not joined are ignored
+    pub fn without_edges(mut self, edges: &[(&str, &str)]) -> Result<Self, GraphError> {
+        for &(u, v) in edges {
+            let i = self.graph.require_vertex_idx(u)?;
+            let j = self.graph.require_vertex_idx(v)?;
+            self.removed_edges.insert((i, j));
+            self.removed_edges.insert((j, i));
+        }
+        Ok(self)
+    }
+
+    /// Keeps only the connected component of a vertex, as the view's edges connect it
+    pub fn only_component_of(self, label: &str) -> Result<Self, GraphError> {
+        let idx = self.require_kept(label)?;
+        let ids = algo::component_ids(&self);
+        let component_of: Vec<Option<usize>> = self.position.iter().map(|pos| pos.map(|pos| ids[pos])).collect();
+        let target = component_of[idx];
+        Ok(self.retain(|v| component_of[v] == target))
+    }
+
+    /// The graph the view reads from
+    pub fn graph(&self) -> &'a Graph {
+        self.graph
+    }
+
+    /// Returns the graph index of the vertex at a view index
+    pub fn graph_index(&self, idx: usize) -> usize {
+        self.kept[idx]
+    }
+
+    /// Copies the view into a graph of its own, with the weights of its edges
+    pub fn to_graph(&self) -> Graph {
+        let rows: Vec<(String, Vec<String>)> = (0..self.vertex_count())
+            .map(|i| {
+                let neighbors = self.neighbors(i).map(|j| self.label(j).to_string()).collect();
+                (self.label(i).to_string(), neighbors)
+            })
+            .collect();
+        let mut graph = Graph::new();
+        graph.rebuild_from_rows(&rows);
+        for i in 0..self.vertex_count() {
+            for j in self.neighbors(i) {
+                if let Some(weight) = self.graph.stored_weight(self.kept[i], self.kept[j]) {
+                    graph.store_weight(i, j, weight);
+                }
+            }
+        }
+        graph
+    }
+}
+
+/// Neighbors of a vertex in a view, read off the graph's matrix row through the masks
+pub struct ViewNeighbors<'v> {
+    view: &'v GraphView<'v>,
+    from: usize,
+    pos: usize,
+}
+
+impl Iterator for ViewNeighbors<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.pos < self.view.kept.len() {
+            let pos = self.pos;
+            self.pos += 1;
+            if self.view.has_edge(self.from, pos) {
+                return Some(pos);
+            }
+        }
+        None
+    }
+}
+
+impl<'a> GraphRef for GraphView<'a> {
+    type Neighbors<'v> = ViewNeighbors<'v> where Self: 'v;
+
+    fn vertex_count(&self) -> usize {
+        self.kept.len()
+    }
+
+    fn label(&self, idx: usize) -> &str {
+        &self.graph.vertices[self.kept[idx]]
+    }
+
+    fn index_of(&self, label: &str) -> Option<usize> {
+        self.graph.find_vertex_idx(label).and_then(|idx| self.position[idx])
+    }
+
+    fn neighbors(&self, idx: usize) -> ViewNeighbors<'_> {
+        ViewNeighbors {
+            view: self,
+            from: idx,
+            pos: 0,
+        }
+    }
+
+    fn has_edge(&self, i: usize, j: usize) -> bool {
+        let (u, v) = (self.kept[i], self.kept[j]);
+        self.graph.adj_matrix[u][v] == 1 && !self.removed_edges.contains(&(u, v))
+    }
+
+    fn weight(&self, i: usize, j: usize) -> f64 {
+        self.graph.weight_at(self.kept[i], self.kept[j])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algo::{self, GraphRef};
+    use crate::fixtures;
+
+    fn labels<G: GraphRef>(g: &G, order: &[usize]) -> Vec<String> {
+        order.iter().map(|&idx| g.label(idx).to_string()).collect()
+    }
+
+    #[test]
+    fn view_without_a_vertex_is_the_induced_subgraph() {
+        let graph = fixtures::two_cliques_bridge();
+        let view = graph.view().without_vertices(&["D"]).unwrap();
+        let mut copy = graph.clone();
+        copy.remove_vertex("D").unwrap();
+
+        assert_eq!(view.vertex_count(), copy.vertex_count());
+        for idx in 0..view.vertex_count() {
+            assert_eq!(view.label(idx), copy.label(idx));
+            assert_eq!(labels(&view, &algo::bfs(&view, idx)), labels(&copy, &algo::bfs(&copy, idx)));
+        }
+        assert_eq!(algo::component_ids(&view), algo::component_ids(&copy));
+        assert_eq!(view.to_graph().adjacency_rows(), copy.adjacency_rows());
+        assert_eq!(view.graph_index(3), 4);
+    }
+
+    #[test]
+    fn masked_edges_split_components() {
+        let graph = fixtures::two_cliques_bridge();
+        let view = graph.view().without_edges(&[("E", "D")]).unwrap().only_component_of("B").unwrap();
+        assert_eq!(labels(&view, &(0..view.vertex_count()).collect::<Vec<_>>()), ["A", "B", "C", "D"]);
+        assert!(view.has_edge(3, 0));
+        assert_eq!(view.neighbors(3).count(), 3);
+        assert!(graph.view().without_vertices(&["Z"]).is_err());
+        assert!(graph.view().without_vertices(&["A"]).unwrap().only_component_of("A").is_err());
+    }
+}
@@ -0,0 +1,379 @@
+// src/weights.rs - Optional edge weights stored alongside the adjacency matrix
+//
+// The matrix only ever holds 0 and 1; weights live in the sparse Graph::weights map
+// and the legacy writers never read it, so -SET, -DEGREE, -LIST and -MATRIX stay
+// byte-identical whether or not a graph has weights. Writers show weights only
+// when asked, as with MatrixStyle::show_weights. Weights come from a CSV file
+// (apply_weights_csv) or inline in the input file, a neighbor written as B:5.
+use crate::dialect::{read_file_lines, Encoding, ParseWarning};
+use crate::error::GraphError;
+use crate::Graph;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Summary of the kinds of weights present in a graph, ordered from the most to the least restrictive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WeightProfile {
+    /// Every edge has the implicit weight 1
+    Unweighted,
+    /// Every edge weight is either 0 or 1
+    ZeroOne,
+    /// Every edge weight is nonnegative
+    NonNegative,
+    /// At least one edge weight is negative
+    Negative,
+}
+
+/// How the -MATRIX writer fills its cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MatrixStyle {
+    /// Write the weight of each edge (1 when none was set) instead of 1; cells without an edge stay 0
+    pub show_weights: bool,
+}
+
+impl MatrixStyle {
+    /// The 0/1 cells of the legacy -MATRIX file
+    pub fn legacy() -> Self {
+        MatrixStyle::default()
+    }
+
+    /// Cells holding the edge weights
+    pub fn weighted() -> Self {
+        MatrixStyle { show_weights: true }
+    }
+}
+
+/// What apply_weights_csv does with a row naming two vertices that are not joined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingEdgePolicy {
+    /// Fail without applying anything
+    #[default]
+    Error,
+    /// Skip the row and list it in the report
+    Ignore,
+    /// Add the edge, undirected, with the row's weight
+    AddEdge,
+}
+
+impl MissingEdgePolicy {
+    /// Looks a policy up by its command-line name: error, ignore or add
+    pub fn from_name(name: &str) -> Option<MissingEdgePolicy> {
+        match name {
+            "error" => Some(MissingEdgePolicy::Error),
+            "ignore" => Some(MissingEdgePolicy::Ignore),
+            "add" => Some(MissingEdgePolicy::AddEdge),
+            _ => None,
+        }
+    }
+}
+
+/// What apply_weights_csv did, edges given as (source, target) labels
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeightReport {
+    /// Number of distinct edges that received a weight
+    pub weighted: usize,
+    /// Rows naming missing edges that the Ignore policy skipped
+    pub ignored: Vec<(String, String)>,
+    /// Edges the AddEdge policy created
+    pub added: Vec<(String, String)>,
+    /// Edges named by more than one row; the last row wins
+    pub duplicates: Vec<(String, String)>,
+    /// Edges no row named, left at the default weight 1
+    pub unweighted: Vec<(String, String)>,
+    /// Lines of the file where Encoding::Lossy replaced invalid UTF-8
+    pub encoding: Option<ParseWarning>,
+}
+
+/// Splits a neighbor token of the input format into its label and inline weight
+///
+/// "B:5" and "B:2.5" name B with a weight when B is a vertex and "B:5" is not, so a
+/// label such as "t10:30" stays a label as long as some line is labeled with it. A
+/// token whose text after the last colon is not a number, such as "x:y", is a plain
+/// label. The weight is not checked here, so "B:inf" gives an infinite weight for
+/// the reader to refuse.
+pub(crate) fn split_neighbor_token(token: &str, is_vertex: impl Fn(&str) -> bool) -> (&str, Option<f64>) {
+    if is_vertex(token) {
+        return (token, None);
+    }
+    match token.rsplit_once(':') {
+        Some((label, weight)) if is_vertex(label) => match weight.parse::<f64>() {
+            Ok(weight) => (label, Some(weight)),
+            Err(_) => (token, None),
+        },
+        _ => (token, None),
+    }
+}
+
+impl fmt::Display for WeightReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs = |edges: &[(String, String)]| {
+            edges
+                .iter()
+                .map(|(a, b)| format!("({},{})", a, b))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        if let Some(warning) = &self.encoding {
+            writeln!(f, "Warning: {}", warning)?;
+        }
+        writeln!(f, "weighted edges: {}", self.weighted)?;
+        for (a, b) in &self.duplicates {
+            writeln!(f, "Warning: edge ({},{}) has several rows; the last one was used", a, b)?;
+        }
+        if !self.added.is_empty() {
+            writeln!(f, "added edges: {}", pairs(&self.added))?;
+        }
+        if !self.ignored.is_empty() {
+            writeln!(f, "ignored rows for missing edges: {}", pairs(&self.ignored))?;
+        }
+        if !self.unweighted.is_empty() {
+            writeln!(f, "unweighted edges (weight 1): {}", pairs(&self.unweighted))?;
+        }
+        Ok(())
+    }
+}
+
+impl Graph {
+    /// Stores the weights written inline in the input file, as (vertex, neighbor, weight)
+    ///
+    /// Each weight applies to the edge from the vertex to the neighbor and, unless the
+    /// neighbor's line gives one of its own, to the way back. Neighbors that are not
+    /// vertices never reach the matrix, so their weights are dropped.
+    pub(crate) fn apply_inline_weights(&mut self, inline: &[(usize, String, f64)]) -> Result<(), GraphError> {
+        let mut explicit = HashSet::new();
+        let mut resolved = Vec::with_capacity(inline.len());
+        for (i, label, weight) in inline {
+            if !weight.is_finite() {
+                return Err(GraphError::InvalidWeight {
+                    from: self.vertices[*i].clone(),
+                    to: label.clone(),
+                    weight: *weight,
+                    reason: "weights must be finite",
+                });
+            }
+            if let Some(j) = self.find_vertex_idx(label) {
+                explicit.insert((*i, j));
+                resolved.push((*i, j, *weight));
+            }
+        }
+        for (i, j, weight) in resolved {
+            self.store_weight(i, j, weight);
+            if self.adj_matrix[j][i] == 1 && !explicit.contains(&(j, i)) {
+                self.store_weight(j, i, weight);
+            }
+        }
+        self.invalidate_cache();
+        Ok(())
+    }
+
+    /// Formats a neighbor as in the input format, with its inline weight when one is stored
+    pub(crate) fn neighbor_token(&self, i: usize, adj_vertex: &str) -> String {
+        let weight = if self.has_weights() {
+            self.find_vertex_idx(adj_vertex).and_then(|j| self.stored_weight(i, j))
+        } else {
+            None
+        };
+        match weight {
+            Some(weight) => format!("{}:{}", adj_vertex, weight),
+            None => adj_vertex.to_string(),
+        }
+    }
+
+    /// Returns the weight of the edge from index i to index j, defaulting to 1 when none was set
+    pub fn weight_at(&self, i: usize, j: usize) -> f64 {
+        self.stored_weight(i, j).unwrap_or(1.0)
+    }
+
+    /// Returns the weight stored for the edge from index i to index j
+    /// Weights live with the EdgeId of the edge; this looks the id up by the pair
+    pub(crate) fn stored_weight(&self, i: usize, j: usize) -> Option<f64> {
+        self.edge_registry.weight(i, j)
+    }
+
+    /// Stores the weight of the edge from index i to index j under its EdgeId
+    pub(crate) fn store_weight(&mut self, i: usize, j: usize, weight: f64) {
+        self.edge_registry.set_weight(i, j, weight);
+    }
+
+    /// Checks whether any edge has a stored weight
+    pub fn has_weights(&self) -> bool {
+        self.edge_registry.has_weights()
+    }
+
+    /// Lists the stored weights as ((from, to), weight) by vertex index, sorted
+    pub fn weight_entries(&self) -> Vec<((usize, usize), f64)> {
+        let mut entries: Vec<((usize, usize), f64)> = self.edge_registry.weights().collect();
+        entries.sort_by_key(|&(pair, _)| pair);
+        entries
+    }
+
+    /// Returns the weight explicitly stored for the edge from a to b
+    /// None when there is no such edge, or the edge has the implicit weight 1
+    pub fn weight(&self, a: &str, b: &str) -> Option<f64> {
+        let i = self.find_vertex_idx(a)?;
+        let j = self.find_vertex_idx(b)?;
+        if self.adj_matrix[i][j] == 1 {
+            self.stored_weight(i, j)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the weight of the edge between two labels, or None if there is no such edge
+    /// edge_weight_by_id looks the edge up by its EdgeId instead
+    pub fn edge_weight(&self, u: &str, v: &str) -> Option<f64> {
+        let i = self.find_vertex_idx(u)?;
+        let j = self.find_vertex_idx(v)?;
+        if self.adj_matrix[i][j] == 1 {
+            Some(self.weight_at(i, j))
+        } else {
+            None
+        }
+    }
+
+    /// Sets the weight of the edge from u to v, and of the way back when it is listed
+    /// No edge from u to v is an InvalidParameter error, storing nothing
+    pub fn set_edge_weight(&mut self, u: &str, v: &str, weight: f64) -> Result<(), GraphError> {
+        let i = self.require_vertex_idx(u)?;
+        let j = self.require_vertex_idx(v)?;
+        if self.adj_matrix[i][j] == 0 {
+            return Err(GraphError::InvalidParameter {
+                name: "edge",
+                reason: format!("there is no edge from {} to {}", u, v),
+            });
+        }
+        self.store_weight(i, j, weight);
+        if self.adj_matrix[j][i] == 1 {
+            self.store_weight(j, i, weight);
+        }
+        self.invalidate_cache();
+        Ok(())
+    }
+
+    /// Classifies the weights of all edges present in the adjacency matrix
+    pub fn weight_profile(&self) -> WeightProfile {
+        let mut profile = WeightProfile::Unweighted;
+        for ((i, j), weight) in self.edge_registry.weights() {
+            // Weights left over on cells without an edge do not count
+            if i >= self.n_vertices || j >= self.n_vertices || self.adj_matrix[i][j] != 1 {
+                continue;
+            }
+            let kind = if weight < 0.0 {
+                WeightProfile::Negative
+            } else if weight == 1.0 {
+                WeightProfile::Unweighted
+            } else if weight == 0.0 {
+                WeightProfile::ZeroOne
+            } else {
+                WeightProfile::NonNegative
+            };
+            profile = profile.max(kind);
+        }
+        profile
+    }
+
+    /// Attaches weights from a CSV file of `a,b,weight` rows to the edges of the graph
+    ///
+    /// Blank lines and lines starting with `#` are skipped, and so is a first line
+    /// whose weight column is not a number, taken as a header. Weights apply to both
+    /// directions of an edge. Rows naming vertices that are not joined
+    /// are handled by the policy; rows naming unknown vertices are always an error.
+    /// Invalid UTF-8 is an error; see apply_weights_csv_with_encoding
+    pub fn apply_weights_csv(&mut self, path: &str, policy: MissingEdgePolicy) -> Result<WeightReport, GraphError> {
+        self.apply_weights_csv_with_encoding(path, policy, Encoding::Strict)
+    }
+
+    /// Attaches weights from a CSV file as apply_weights_csv does, decoding it under the
+    /// given encoding; the report notes the lines where Lossy replaced invalid UTF-8
+    pub fn apply_weights_csv_with_encoding(
+        &mut self,
+        path: &str,
+        policy: MissingEdgePolicy,
+        encoding: Encoding,
+    ) -> Result<WeightReport, GraphError> {
+        let (lines, encoding_warning) = read_file_lines(path, encoding)?;
+        let invalid = |line: usize, reason: String| GraphError::InvalidFormat {
+            format: "weights CSV",
+            reason: format!("line {}: {}", line + 1, reason),
+        };
+
+        // Every row is checked before anything changes, so an error leaves the graph as it was
+        let mut rows: Vec<(usize, usize, f64)> = Vec::new();
+        let mut first = true;
+        for (line_no, line) in lines.iter().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let header_allowed = std::mem::replace(&mut first, false);
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [a, b, weight] = fields.as_slice() else {
+                return Err(invalid(line_no, format!("expected a,b,weight, found \"{}\"", line)));
+            };
+            let weight: f64 = match weight.parse() {
+                Ok(weight) => weight,
+                Err(_) if header_allowed => continue,
+                Err(_) => return Err(invalid(line_no, format!("{} is not a number", weight))),
+            };
+            if !weight.is_finite() {
+                return Err(GraphError::InvalidWeight {
+                    from: a.to_string(),
+                    to: b.to_string(),
+                    weight,
+                    reason: "weights must be finite",
+                });
+            }
+            let i = self.require_vertex_idx(a)?;
+            let j = self.require_vertex_idx(b)?;
+            let joined = self.adj_matrix[i][j] == 1 || self.adj_matrix[j][i] == 1;
+            if !joined && policy == MissingEdgePolicy::Error {
+                return Err(invalid(line_no, format!("{} and {} are not joined by an edge", a, b)));
+            }
+            rows.push((i, j, weight));
+        }
+
+        let mut report = WeightReport {
+            encoding: encoding_warning,
+            ..WeightReport::default()
+        };
+        let mut seen: HashSet<(usize, usize)> = HashSet::new();
+        let mut repeated: HashSet<(usize, usize)> = HashSet::new();
+        let mut added = false;
+        for (i, j, weight) in rows {
+            let pair = (i.min(j), i.max(j));
+            let labels = (self.vertices[i].clone(), self.vertices[j].clone());
+            if self.adj_matrix[i][j] == 0 && self.adj_matrix[j][i] == 0 {
+                if policy == MissingEdgePolicy::Ignore {
+                    report.ignored.push(labels);
+                    continue;
+                }
+                self.add_to_adj_list(i, &labels.1);
+                if i != j {
+                    self.add_to_adj_list(j, &labels.0);
+                }
+                self.adj_matrix[i][j] = 1;
+                self.adj_matrix[j][i] = 1;
+                report.added.push(labels.clone());
+                added = true;
+            }
+            if !seen.insert(pair) && repeated.insert(pair) {
+                report.duplicates.push(labels);
+            }
+            self.store_weight(i, j, weight);
+            self.store_weight(j, i, weight);
+        }
+        if added {
+            self.make_adj_matrix();
+        }
+        self.invalidate_cache();
+
+        report.weighted = seen.len();
+        report.unweighted = self
+            .ordered_edges()
+            .into_iter()
+            .filter(|edge| !seen.contains(&(edge.source_idx.min(edge.target_idx), edge.source_idx.max(edge.target_idx))))
+            .map(|edge| (edge.source, edge.target))
+            .collect();
+        Ok(report)
+    }
+}
@@ -0,0 +1,50 @@
+// tests/api_surface.rs - The stable API, as the prelude promises it
+//
+// Every name of the prelude is imported explicitly, so removing or renaming one
+// fails to compile. The test runs with and without the unstable-algorithms
+// feature; betweenness goes through vertex_metrics, which is stable, rather than
+// the centrality module behind the feature.
+use graph_analysis::metrics::VertexMetric;
+use graph_analysis::prelude::{
+    Budget, ComponentsIndex, Dialect, Direction, DirectionProfile, EdgeView, Graph, GraphError, GraphFormat,
+    GraphStats, ItemTotal, OutputConfig, ParseWarning, SectionLimit, VertexRef,
+};
+
+const PATH: &str = "3\nA B -1\nB A C -1\nC B -1\n";
+
+fn path_graph() -> Graph {
+    let mut graph = Graph::new();
+    let warnings: Vec<ParseWarning> = graph.read_input_text_with(PATH, Dialect::Strict).unwrap();
+    assert!(warnings.is_empty());
+    graph
+}
+
+#[test]
+fn prelude_types_are_reachable() {
+    let graph = path_graph();
+    let stats: GraphStats = graph.stats();
+    assert_eq!((stats.n_vertices, stats.n_edges), (3, 2));
+    let edges: Vec<EdgeView> = graph.ordered_edges();
+    assert_eq!(edges.len(), 2);
+    let profile: DirectionProfile = graph.direction_profile();
+    assert_eq!(profile.one_way_pairs, 0);
+    let mut index: ComponentsIndex = graph.components_index();
+    assert_eq!(index.same_component("A", "C"), Ok(true));
+    assert_eq!(graph.resolve_vertex(VertexRef::parse("#1")), Ok(1));
+    assert_eq!(GraphFormat::from_name("dimacs"), Some(GraphFormat::Dimacs));
+    let _: Option<(Budget, OutputConfig, SectionLimit, ItemTotal, Direction)> = None;
+}
+
+#[test]
+fn errors_keep_their_codes() {
+    let graph = path_graph();
+    let error: GraphError = graph.resolve_vertex(VertexRef::parse("Z")).unwrap_err();
+    assert_eq!(error.code(), "E_UNKNOWN_VERTEX");
+}
+
+#[test]
+fn betweenness_is_stable_through_vertex_metrics() {
+    let rows = path_graph().vertex_metrics(&[VertexMetric::Betweenness]).unwrap();
+    let scores: Vec<(&str, f64)> = rows.iter().map(|row| (row.label.as_str(), row.values[0])).collect();
+    assert_eq!(scores, vec![("A", 0.0), ("B", 1.0), ("C", 0.0)]);
+}
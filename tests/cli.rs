@@ -0,0 +1,71 @@
+// tests/cli.rs - The main binary, run as a process: exit codes, JSON errors, output files
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+const PATH: &str = "3\nA B -1\nB A C -1\nC B -1\n";
+const TWO_PARTS: &str = "4\nA B -1\nB A -1\nC D -1\nD C -1\n";
+
+fn run_main(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_main"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn missing_file_is_a_json_error() {
+    let dir = common::scratch_dir("cli", "missing");
+    let output = run_main(&dir, &["--json", "path", "missing.txt", "A", "B"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr).trim_end(),
+        r#"{"code":"E_FILE_NOT_FOUND","message":"file missing.txt not found","path":"missing.txt"}"#
+    );
+}
+
+#[test]
+fn unknown_vertex_is_a_json_error() {
+    let dir = common::scratch_dir("cli", "start");
+    fs::write(dir.join("g.txt"), PATH).unwrap();
+    let output = run_main(&dir, &["--json", "path", "g.txt", "Q", "A"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.starts_with(r#"{"code":"E_UNKNOWN_VERTEX","#), "{}", stderr);
+    assert!(stderr.contains(r#""label":"Q""#), "{}", stderr);
+}
+
+#[test]
+fn errors_without_json_are_plain_text() {
+    let dir = common::scratch_dir("cli", "plain");
+    let output = run_main(&dir, &["path", "missing.txt", "A", "B"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stderr).trim_end(), "Error: file missing.txt not found");
+}
+
+#[test]
+fn traverse_writes_components_only_when_asked() {
+    let dir = common::scratch_dir("cli", "components");
+    fs::write(dir.join("g.txt"), TWO_PARTS).unwrap();
+    let output = run_main(&dir, &["traverse", "g.txt", "--start", "A"]);
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    for suffix in ["BFS", "DFS", "DEGREE", "LIST", "LIST-SORTED", "MATRIX", "SET"] {
+        assert!(dir.join(format!("g-{}.TXT", suffix)).is_file(), "g-{}.TXT missing", suffix);
+    }
+    assert!(!dir.join("g-COMPONENTS.TXT").exists());
+
+    let output = run_main(&dir, &["traverse", "g.txt", "--start", "A", "--components"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(dir.join("g-COMPONENTS.TXT").is_file());
+}
+
+#[test]
+fn unknown_command_fails() {
+    let dir = common::scratch_dir("cli", "unknown");
+    let output = run_main(&dir, &["frobnicate"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown command frobnicate"));
+}
@@ -0,0 +1,3 @@
+// tests/common/mod.rs - Helpers shared by the integration tests; each test file
+// that needs them declares `mod common;`
+pub use graph_analysis::fixtures::scratch_dir;
@@ -0,0 +1,96 @@
+// tests/fixtures.rs - The reference graphs of graph_analysis::fixtures (test-support feature)
+//
+// The fixtures are a contract for downstream tests, so their shape and their output
+// files are pinned here: a change to one of them has to change this file and its
+// golden files in tests/golden too.
+mod common;
+
+use graph_analysis::fixtures;
+use graph_analysis::shortest_path::PathAlgorithm;
+use std::fs;
+
+/// Where the golden files of the six legacy outputs of each fixture live, written
+/// by the program of the baseline commit af08210 from the fixture text, started at A
+const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+const LEGACY_SUFFIXES: [&str; 6] = ["-SET", "-DEGREE", "-LIST", "-MATRIX", "-BFS", "-DFS"];
+
+#[test]
+fn every_fixture_keeps_the_invariants() {
+    for graph in [
+        fixtures::triangle(),
+        fixtures::petersen(),
+        fixtures::two_cliques_bridge(),
+        fixtures::disconnected_pair(),
+        fixtures::weighted_square(),
+    ] {
+        graph.check_invariants().unwrap();
+    }
+}
+
+#[test]
+fn fixtures_have_their_documented_shape() {
+    let shape = |graph: graph_analysis::Graph| {
+        let stats = graph.stats();
+        (stats.n_vertices, stats.n_edges, stats.n_components, stats.min_degree, stats.max_degree)
+    };
+    assert_eq!(shape(fixtures::triangle()), (3, 3, 1, 2, 2));
+    assert_eq!(shape(fixtures::petersen()), (10, 15, 1, 3, 3));
+    assert_eq!(shape(fixtures::two_cliques_bridge()), (8, 13, 1, 3, 4));
+    assert_eq!(shape(fixtures::disconnected_pair()), (4, 2, 2, 1, 1));
+    assert_eq!(shape(fixtures::weighted_square()), (4, 4, 1, 2, 2));
+}
+
+#[test]
+fn petersen_has_girth_five_and_is_not_bipartite() {
+    let petersen = fixtures::petersen();
+    assert_eq!(petersen.girth(), Some(5));
+    assert!(petersen.is_bipartite().is_none());
+    assert!(fixtures::weighted_square().is_bipartite().is_some());
+}
+
+#[test]
+fn disconnected_pair_has_two_components() {
+    assert_eq!(
+        fixtures::disconnected_pair().connected_components(),
+        vec![vec!["A".to_string(), "B".to_string()], vec!["C".to_string(), "D".to_string()]]
+    );
+}
+
+#[test]
+fn weighted_square_goes_the_cheap_way_round() {
+    let (algorithm, route) = fixtures::weighted_square().shortest_path("A", "D").unwrap();
+    assert_eq!(algorithm, PathAlgorithm::UniformCost);
+    let (cost, path) = route.unwrap();
+    assert_eq!(cost, 4.0);
+    assert_eq!(path, vec!["A".to_string(), "D".to_string()]);
+    let (cost, path) = fixtures::weighted_square().shortest_path("A", "C").unwrap().1.unwrap();
+    assert_eq!(cost, 3.0);
+    assert_eq!(path, vec!["A".to_string(), "B".to_string(), "C".to_string()]);
+}
+
+#[test]
+fn every_fixture_writes_its_golden_outputs() {
+    let dir = common::scratch_dir("fixtures", "golden");
+    for (name, graph) in [
+        ("triangle", fixtures::triangle()),
+        ("petersen", fixtures::petersen()),
+        ("two_cliques_bridge", fixtures::two_cliques_bridge()),
+        ("disconnected_pair", fixtures::disconnected_pair()),
+        ("weighted_square", fixtures::weighted_square()),
+    ] {
+        let base = dir.join(name).display().to_string();
+        graph.produce_output_file1(&base).unwrap();
+        graph.produce_output_file2(&base).unwrap();
+        graph.produce_output_file3(&base).unwrap();
+        graph.produce_output_file4(&base).unwrap();
+        graph.produce_output_file5(&base, "A").unwrap();
+        graph.produce_output_file6(&base, "A").unwrap();
+        for suffix in LEGACY_SUFFIXES {
+            // -SET names the graph after the base path, the input file name in the golden files
+            let written = fs::read_to_string(format!("{}{}.TXT", base, suffix)).unwrap().replace(&base, name);
+            let golden = fs::read_to_string(format!("{}/{}{}.TXT", GOLDEN, name, suffix)).unwrap();
+            assert_eq!(written, golden, "{}{}.TXT differs", name, suffix);
+        }
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,20 @@
+// tests/fuzz_corpus.rs - Replays tests/corpus through the fuzz harnesses (fuzz feature)
+//
+// Each file of the corpus once made a parser panic or produce an inconsistent
+// graph; the harnesses assert the graph invariants, so a regression panics here.
+#![cfg(feature = "fuzz")]
+use graph_analysis::fuzz::{fuzz_adjacency_parser, fuzz_graph6_parser, run_corpus};
+
+const CORPUS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus");
+
+#[test]
+fn adjacency_corpus_replays_cleanly() {
+    let count = run_corpus(format!("{}/adjacency", CORPUS), fuzz_adjacency_parser).unwrap();
+    assert_eq!(count, 10);
+}
+
+#[test]
+fn graph6_corpus_replays_cleanly() {
+    let count = run_corpus(format!("{}/graph6", CORPUS), fuzz_graph6_parser).unwrap();
+    assert_eq!(count, 6);
+}
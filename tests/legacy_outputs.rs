@@ -0,0 +1,78 @@
+// tests/legacy_outputs.rs - The legacy output files, byte for byte, with and without weights
+//
+// The golden files of the weighted_square fixture were regenerated from the baseline
+// commit af08210, by running its program on the fixture text started at A. A graph
+// with weights must still write the same -SET, -DEGREE, -LIST, -MATRIX, -BFS and
+// -DFS files; only the weighted matrix style shows them.
+mod common;
+
+use graph_analysis::dialect::Dialect;
+use graph_analysis::fixtures;
+use graph_analysis::weights::MatrixStyle;
+use graph_analysis::Graph;
+use std::fs;
+
+const LEGACY: [(&str, &str); 6] = [
+    ("-SET", include_str!("golden/weighted_square-SET.TXT")),
+    ("-DEGREE", include_str!("golden/weighted_square-DEGREE.TXT")),
+    ("-LIST", include_str!("golden/weighted_square-LIST.TXT")),
+    ("-MATRIX", include_str!("golden/weighted_square-MATRIX.TXT")),
+    ("-BFS", include_str!("golden/weighted_square-BFS.TXT")),
+    ("-DFS", include_str!("golden/weighted_square-DFS.TXT")),
+];
+
+/// The square of the weighted_square fixture, read without its weights
+fn unweighted_square() -> Graph {
+    let mut graph = Graph::new();
+    graph.read_input_text_with(fixtures::text::WEIGHTED_SQUARE, Dialect::Strict).unwrap();
+    graph
+}
+
+/// Writes the six legacy files, traversals started at A, and checks them against the golden files
+fn assert_legacy_outputs(graph: Graph, test: &str) {
+    let dir = common::scratch_dir("legacy", test);
+    let base = dir.join("weighted_square").display().to_string();
+    graph.produce_output_file1(&base).unwrap();
+    graph.produce_output_file2(&base).unwrap();
+    graph.produce_output_file3(&base).unwrap();
+    graph.produce_output_file4(&base).unwrap();
+    graph.produce_output_file5(&base, "A").unwrap();
+    graph.produce_output_file6(&base, "A").unwrap();
+    for (suffix, golden) in LEGACY {
+        // -SET names the graph after the base path, the input file name in the golden files
+        let written = fs::read_to_string(format!("{}{}.TXT", base, suffix)).unwrap().replace(&base, "weighted_square");
+        assert_eq!(written, golden, "weighted_square{}.TXT differs", suffix);
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn unweighted_outputs_match_the_golden_files() {
+    assert_legacy_outputs(unweighted_square(), "unweighted");
+}
+
+#[test]
+fn weights_leave_the_legacy_outputs_alone() {
+    let mut graph = fixtures::weighted_square();
+    graph.set_edge_weight("C", "D", -3.0).unwrap();
+    assert_legacy_outputs(graph, "weighted");
+}
+
+#[test]
+fn weighted_matrix_style_shows_the_weights() {
+    let mut graph = unweighted_square();
+    graph.set_edge_weight("A", "B", 1.5).unwrap();
+    assert_eq!(graph.weight("B", "A"), Some(1.5));
+    assert_eq!(graph.weight("A", "D"), None);
+
+    let dir = common::scratch_dir("legacy", "matrix-style");
+    let base = dir.join("weighted_square").display().to_string();
+    graph.produce_output_file4_with(&base, MatrixStyle::legacy()).unwrap();
+    assert_eq!(fs::read_to_string(format!("{}-MATRIX.TXT", base)).unwrap(), LEGACY[3].1);
+    graph.produce_output_file4_with(&base, MatrixStyle::weighted()).unwrap();
+    let weighted = fs::read_to_string(format!("{}-MATRIX.TXT", base)).unwrap();
+    let rows: Vec<Vec<&str>> = weighted.lines().map(|line| line.split_whitespace().collect()).collect();
+    assert_eq!(rows[1], ["A", "0", "1.5", "0", "1"]);
+    assert_eq!(rows[2], ["B", "1.5", "0", "1", "0"]);
+    fs::remove_dir_all(dir).unwrap();
+}
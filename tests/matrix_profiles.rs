@@ -0,0 +1,42 @@
+// tests/matrix_profiles.rs - The -MATRIX output of each built-in profile, and reading it back
+use graph_analysis::prelude::*;
+use graph_analysis::profile::DialectProfile;
+use graph_analysis::Graph;
+
+const STAR: &str = "3\nA B C -1\nB A -1\nC A -1\n";
+
+fn star() -> Graph {
+    let mut graph = Graph::new();
+    graph.read_input_text_with(STAR, Dialect::Strict).unwrap();
+    graph
+}
+
+fn matrix_of(profile: &DialectProfile) -> String {
+    let mut out = Vec::new();
+    star().write_matrix(&mut out, profile).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn default_matrix_matches_golden_file() {
+    assert_eq!(matrix_of(&DialectProfile::default()), include_str!("golden/star-default-MATRIX.TXT"));
+}
+
+#[test]
+fn course_ps2_matrix_matches_golden_file() {
+    assert_eq!(matrix_of(&DialectProfile::course_ps2()), include_str!("golden/star-course-ps2-MATRIX.TXT"));
+}
+
+#[test]
+fn default_matrix_reads_back() {
+    let text = include_str!("golden/star-default-MATRIX.TXT");
+    let graph = Graph::from_adjacency_matrix(text, Direction::Undirected).unwrap();
+    assert_eq!(graph.adjacency_rows(), star().adjacency_rows());
+}
+
+#[test]
+fn course_ps2_matrix_reads_back_past_the_corner_word() {
+    let text = include_str!("golden/star-course-ps2-MATRIX.TXT");
+    let graph = Graph::from_adjacency_matrix(text, Direction::Undirected).unwrap();
+    assert_eq!(graph.adjacency_rows(), star().adjacency_rows());
+}
@@ -0,0 +1,48 @@
+// tests/serde_round_trip.rs - Graph through Serialize and Deserialize (serde feature)
+#![cfg(feature = "serde")]
+use graph_analysis::prelude::*;
+
+fn weighted_graph() -> Graph {
+    let mut graph = Graph::new();
+    graph
+        .read_input_text_with("3\nA B:2 C -1\nB A:2 -1\nC A -1\n", Dialect::Strict)
+        .unwrap();
+    graph.set_coordinates("B", 1.0, 2.5).unwrap();
+    let id = graph.find_edge("A", "C").unwrap();
+    graph.set_edge_attr(id, "color", "red");
+    graph.set_name("triangle");
+    graph
+}
+
+#[test]
+fn round_trip_keeps_rows_weights_attributes_and_coordinates() {
+    let graph = weighted_graph();
+    let json = serde_json::to_string(&graph).unwrap();
+    let back: Graph = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.adjacency_rows(), graph.adjacency_rows());
+    assert_eq!(back.weight_entries(), graph.weight_entries());
+    assert_eq!(back.coordinates_of("B"), Some((1.0, 2.5)));
+    assert_eq!(back.edge_attr(back.find_edge("A", "C").unwrap(), "color"), Some("red"));
+    assert_eq!(back.display_name("x"), "triangle");
+    assert_eq!(serde_json::to_string(&back).unwrap(), json);
+}
+
+#[test]
+fn weight_on_a_non_edge_is_refused() {
+    let json = r#"{"vertices":["A","B"],"adjacency":[[],[]],"weights":[[0,1,2.0]]}"#;
+    let error = serde_json::from_str::<Graph>(json).unwrap_err();
+    assert!(error.to_string().contains("not an edge"), "{}", error);
+}
+
+#[test]
+fn attributes_on_a_non_edge_are_refused() {
+    let json = r#"{"vertices":["A","B"],"adjacency":[[],[]],"attributes":[[0,1,{"k":"v"}]]}"#;
+    let error = serde_json::from_str::<Graph>(json).unwrap_err();
+    assert!(error.to_string().contains("not an edge"), "{}", error);
+}
+
+#[test]
+fn mismatched_lists_are_refused() {
+    let json = r#"{"vertices":["A","B"],"adjacency":[[]]}"#;
+    assert!(serde_json::from_str::<Graph>(json).is_err());
+}